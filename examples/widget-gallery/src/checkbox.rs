@@ -1,5 +1,5 @@
 use floem::{
-    reactive::create_signal,
+    reactive::create_rw_signal,
     view::View,
     views::Decorators,
     widgets::{checkbox, labeled_checkbox},
@@ -9,39 +9,24 @@ use crate::form::{form, form_item};
 
 pub fn checkbox_view() -> impl View {
     let width = 160.0;
-    let (is_checked, set_is_checked) = create_signal(true);
+    let is_checked = create_rw_signal(true);
     form({
         (
             form_item("Checkbox:".to_string(), width, move || {
-                checkbox(is_checked)
-                    .style(|s| s.margin(5.0))
-                    .on_click_stop(move |_| {
-                        set_is_checked.update(|checked| *checked = !*checked);
-                    })
+                checkbox(is_checked).style(|s| s.margin(5.0))
             }),
             form_item("Disabled Checkbox:".to_string(), width, move || {
                 checkbox(is_checked)
                     .style(|s| s.margin(5.0))
-                    .on_click_stop(move |_| {
-                        set_is_checked.update(|checked| *checked = !*checked);
-                    })
                     .disabled(|| true)
             }),
             form_item("Labelled Checkbox:".to_string(), width, move || {
-                labeled_checkbox(is_checked, || "Check me!").on_click_stop(move |_| {
-                    set_is_checked.update(|checked| *checked = !*checked);
-                })
+                labeled_checkbox(is_checked, || "Check me!")
             }),
             form_item(
                 "Disabled Labelled Checkbox:".to_string(),
                 width,
-                move || {
-                    labeled_checkbox(is_checked, || "Check me!")
-                        .on_click_stop(move |_| {
-                            set_is_checked.update(|checked| *checked = !*checked);
-                        })
-                        .disabled(|| true)
-                },
+                move || labeled_checkbox(is_checked, || "Check me!").disabled(|| true),
             ),
         )
     })
@@ -1,7 +1,7 @@
 use floem::{
     cosmic_text::Weight,
     peniko::Color,
-    reactive::create_signal,
+    reactive::{create_rw_signal, create_signal},
     style::JustifyContent,
     view::View,
     views::{
@@ -43,13 +43,11 @@ fn enhanced_list() -> impl View {
             move || long_list.get().enumerate(),
             move |(_, item)| *item,
             move |(index, item)| {
-                let (is_checked, set_is_checked) = create_signal(true);
+                let is_checked = create_rw_signal(true);
                 container({
                     stack({
                         (
-                            checkbox(is_checked).on_click_stop(move |_| {
-                                set_is_checked.update(|checked: &mut bool| *checked = !*checked);
-                            }),
+                            checkbox(is_checked),
                             label(move || item.to_string())
                                 .style(|s| s.height(32.0).font_size(22.0)),
                             container({
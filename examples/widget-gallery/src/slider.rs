@@ -1,3 +1,3 @@
-// slider::slider(|| 0.)
+// slider::slider(value, 0.0..=100.0)
 //     .style(|s| s.height(15).width(200))
-//     .on_change_pct(move |val| set_slider.set(val)),
+//     .on_change_complete(move |val| set_slider.set(val)),
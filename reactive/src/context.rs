@@ -1,9 +1,15 @@
 use std::any::{Any, TypeId};
 
-use crate::runtime::RUNTIME;
+use crate::{
+    id::Id,
+    runtime::{Runtime, RUNTIME},
+};
 
-/// Try to retrieve a stored Context value in the reactive system.
-/// You can store a Context value anywhere, and retrieve it from anywhere afterwards.
+/// Try to retrieve a Context value of type `T` provided with [provide_context](provide_context).
+///
+/// The value provided in the nearest Scope is used, starting at the current Scope and going
+/// up through its parent Scopes. Values provided with [provide_global_context] or outside
+/// of all Scopes are found from anywhere, including Scopes which aren't a child of any Scope.
 pub fn use_context<T>() -> Option<T>
 where
     T: Clone + 'static,
@@ -11,24 +17,73 @@ where
     let ty = TypeId::of::<T>();
     RUNTIME.with(|runtime| {
         let contexts = runtime.contexts.borrow();
-        let context = contexts
-            .get(&ty)
-            .and_then(|val| val.downcast_ref::<T>())
-            .cloned();
-        context
+        let find = |scope| {
+            contexts
+                .get(&scope)
+                .and_then(|contexts| contexts.get(&ty))
+                .and_then(|val| val.downcast_ref::<T>())
+                .cloned()
+        };
+        let mut scope = Some(*runtime.current_scope.borrow());
+        while let Some(id) = scope {
+            if let Some(context) = find(id) {
+                return Some(context);
+            }
+            scope = id.parent();
+        }
+        find(runtime.root_scope)
     })
 }
 
-/// Sets a context value to be stored in the reative system.
-/// The stored context value can be retrieved from anywhere by using [use_context](use_context)
+/// Sets a Context value in the current Scope, which can be retrieved with
+/// [use_context](use_context) in the Scope and its child Scopes. This value shadows the
+/// values of the same type provided in the parent Scopes.
+///
+/// The value is dropped when the Scope is disposed. So views built in a child Scope, like the
+/// children of a `dyn_container`, can provide values to the views below them.
+///
+/// ```rust
+/// # use floem_reactive::{provide_context, use_context, with_scope, Scope};
+/// provide_context(1);
+/// let child = Scope::current().create_child();
+/// with_scope(child, || {
+///     provide_context(2);
+///     assert_eq!(use_context::<i32>(), Some(2));
+/// });
+/// assert_eq!(use_context::<i32>(), Some(1));
+/// ```
 pub fn provide_context<T>(value: T)
 where
     T: Clone + 'static,
 {
-    let id = value.type_id();
-
     RUNTIME.with(|runtime| {
-        let mut contexts = runtime.contexts.borrow_mut();
-        contexts.insert(id, Box::new(value) as Box<dyn Any>);
+        let scope = *runtime.current_scope.borrow();
+        provide_in(runtime, scope, value);
     });
 }
+
+/// Sets a Context value which can be retrieved with [use_context](use_context) from any
+/// Scope, unless a Scope shadows it with [provide_context](provide_context). This is the
+/// same as providing the value outside of all Scopes, and is used for app-global state.
+///
+/// ```rust
+/// # use floem_reactive::{provide_global_context, use_context, with_scope, Scope};
+/// with_scope(Scope::new(), || provide_global_context(1));
+/// assert_eq!(use_context::<i32>(), Some(1));
+/// with_scope(Scope::new(), || assert_eq!(use_context::<i32>(), Some(1)));
+/// ```
+pub fn provide_global_context<T>(value: T)
+where
+    T: Clone + 'static,
+{
+    RUNTIME.with(|runtime| provide_in(runtime, runtime.root_scope, value));
+}
+
+fn provide_in<T: 'static>(runtime: &Runtime, scope: Id, value: T) {
+    let id = value.type_id();
+    let mut contexts = runtime.contexts.borrow_mut();
+    contexts
+        .entry(scope)
+        .or_default()
+        .insert(id, Box::new(value) as Box<dyn Any>);
+}
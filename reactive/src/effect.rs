@@ -121,25 +121,36 @@ pub fn untrack<T>(f: impl FnOnce() -> T) -> T {
     result
 }
 
+/// Run `f` while deferring effects triggered by signal updates until it returns, so each
+/// effect runs at most once for the whole batch.
+///
+/// Nested batches are merged into the outermost one, which is the only one that runs the
+/// pending effects.
 pub fn batch<T>(f: impl FnOnce() -> T) -> T {
-    let already_batching = RUNTIME.with(|runtime| {
-        let batching = runtime.batching.get();
-        if !batching {
-            runtime.batching.set(true);
-        }
-
-        batching
-    });
+    struct BatchGuard {
+        already_batching: bool,
+    }
 
-    let result = f();
-    if !already_batching {
-        RUNTIME.with(|runtime| {
-            runtime.batching.set(false);
-            runtime.run_pending_effects();
-        });
+    impl Drop for BatchGuard {
+        fn drop(&mut self) {
+            if !self.already_batching {
+                RUNTIME.with(|runtime| {
+                    runtime.batching.set(false);
+                    // Don't run effects while unwinding, but don't leave the runtime batching either
+                    if std::thread::panicking() {
+                        runtime.pending_effects.take();
+                    } else {
+                        runtime.run_pending_effects();
+                    }
+                });
+            }
+        }
     }
 
-    result
+    let already_batching = RUNTIME.with(|runtime| runtime.batching.replace(true));
+    let _guard = BatchGuard { already_batching };
+
+    f()
 }
 
 pub(crate) fn run_initial_effect(effect: Rc<dyn EffectTrait>) {
@@ -160,7 +171,7 @@ pub(crate) fn run_initial_effect(effect: Rc<dyn EffectTrait>) {
 
 pub(crate) fn run_effect(effect: Rc<dyn EffectTrait>) {
     let effect_id = effect.id();
-    effect_id.dispose();
+    effect_id.dispose_for_rerun();
 
     observer_clean_up(&effect);
 
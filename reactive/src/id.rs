@@ -26,20 +26,62 @@ impl Id {
     /// Make this Id a child of the current Scope
     pub(crate) fn set_scope(&self) {
         RUNTIME.with(|runtime| {
-            let scope = runtime.current_scope.borrow();
+            let scope = *runtime.current_scope.borrow();
+            self.set_parent(scope);
+        });
+    }
+
+    /// Make this Id a child of `parent`
+    pub(crate) fn set_parent(&self, parent: Id) {
+        RUNTIME.with(|runtime| {
             let mut children = runtime.children.borrow_mut();
-            let children = children.entry(*scope).or_default();
+            let children = children.entry(parent).or_default();
             children.insert(*self);
+            runtime.parents.borrow_mut().insert(*self, parent);
+        });
+    }
+
+    /// The Scope this Id is a child of
+    pub(crate) fn parent(&self) -> Option<Id> {
+        RUNTIME.with(|runtime| runtime.parents.borrow().get(self).copied())
+    }
+
+    /// Run `f` when this Id is disposed
+    pub(crate) fn add_cleanup(&self, f: Box<dyn FnOnce()>) {
+        RUNTIME.with(|runtime| {
+            runtime
+                .cleanups
+                .borrow_mut()
+                .entry(*self)
+                .or_default()
+                .push(f)
         });
     }
 
     /// Dispose the relevant resources that's linking to this Id, and the all the children
-    /// and grandchildren.
+    /// and grandchildren. This Id is also removed from its parent.
     pub(crate) fn dispose(&self) {
-        if let Ok((children, signal)) = RUNTIME.try_with(|runtime| {
+        self.dispose_for_rerun();
+        let _ = RUNTIME.try_with(|runtime| {
+            let parent = runtime.parents.borrow_mut().remove(self);
+            if let Some(parent) = parent {
+                if let Some(children) = runtime.children.borrow_mut().get_mut(&parent) {
+                    children.remove(self);
+                }
+            }
+        });
+    }
+
+    /// Dispose the resources and children of this Id like [`Id::dispose`], but keep it as a
+    /// child of its parent. Effects do this before running again.
+    pub(crate) fn dispose_for_rerun(&self) {
+        if let Ok((children, signal, cleanups)) = RUNTIME.try_with(|runtime| {
+            let contexts = runtime.contexts.borrow_mut().remove(self);
+            drop(contexts);
             (
                 runtime.children.borrow_mut().remove(self),
                 runtime.signals.borrow_mut().remove(self),
+                runtime.cleanups.borrow_mut().remove(self),
             )
         }) {
             if let Some(children) = children {
@@ -53,6 +95,51 @@ impl Id {
                     observer_clean_up(&effect);
                 }
             }
+
+            if let Some(cleanups) = cleanups {
+                for cleanup in cleanups {
+                    cleanup();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{create_effect, create_rw_signal, runtime::RUNTIME, scope::Scope, with_scope};
+
+    #[test]
+    fn disposed_scopes_are_removed_from_their_parent() {
+        let scope = Scope::new();
+        let children: Vec<Scope> = (0..3).map(|_| scope.create_child()).collect();
+        for child in &children {
+            child.dispose();
         }
+        RUNTIME.with(|runtime| {
+            assert!(runtime.children.borrow()[&scope.0].is_empty());
+            let parents = runtime.parents.borrow();
+            assert!(children.iter().all(|child| !parents.contains_key(&child.0)));
+        });
+        scope.dispose();
+    }
+
+    #[test]
+    fn effects_keep_their_parent_when_they_run_again() {
+        let scope = Scope::new();
+        let count = create_rw_signal(0);
+        let parents = Rc::new(RefCell::new(Vec::new()));
+        with_scope(scope, || {
+            let parents = parents.clone();
+            create_effect(move |_| {
+                count.track();
+                parents.borrow_mut().push(Scope::current().parent());
+            });
+        });
+        count.set(1);
+        assert_eq!(*parents.borrow(), [Some(scope), Some(scope)]);
+        scope.dispose();
     }
 }
@@ -75,6 +75,19 @@ impl<T> Memo<T> {
 pub fn create_memo<T>(f: impl Fn(Option<&T>) -> T + 'static) -> Memo<T>
 where
     T: PartialEq + 'static,
+{
+    create_memo_with_eq(f, |old, new| old == new)
+}
+
+/// Create a Memo like [`create_memo`], but which uses `eq` to decide if the computed value
+/// is different with the last computed value. The value is still recomputed on every upstream
+/// change, but subscribers are only triggered when `eq` returns `false`.
+pub fn create_memo_with_eq<T>(
+    f: impl Fn(Option<&T>) -> T + 'static,
+    eq: impl Fn(&T, &T) -> bool + 'static,
+) -> Memo<T>
+where
+    T: 'static,
 {
     let cx = Scope::current();
     let (getter, setter) = create_signal(None::<T>);
@@ -83,7 +96,8 @@ where
         cx.track();
         let (is_different, new_value) = getter.with_untracked(|value| {
             let new_value = f(value.as_ref());
-            (Some(&new_value) != value.as_ref(), new_value)
+            let is_different = !value.as_ref().is_some_and(|value| eq(value, &new_value));
+            (is_different, new_value)
         });
         if is_different {
             setter.set(Some(new_value));
@@ -13,6 +13,12 @@ use crate::{
     signal::Signal,
 };
 
+/// The functions run when a Scope is disposed
+type Cleanups = Vec<Box<dyn FnOnce()>>;
+
+/// The context values provided in a Scope, by their type
+type Contexts = HashMap<TypeId, Box<dyn Any>>;
+
 thread_local! {
     pub(crate) static RUNTIME: Runtime = Runtime::new();
 }
@@ -22,9 +28,15 @@ thread_local! {
 pub(crate) struct Runtime {
     pub(crate) current_effect: RefCell<Option<Rc<dyn EffectTrait>>>,
     pub(crate) current_scope: RefCell<Id>,
+    /// The Scope which is current outside of all other Scopes
+    pub(crate) root_scope: Id,
     pub(crate) children: RefCell<HashMap<Id, HashSet<Id>>>,
+    /// The parent of each child Scope, Signal and Effect
+    pub(crate) parents: RefCell<HashMap<Id, Id>>,
     pub(crate) signals: RefCell<HashMap<Id, Signal>>,
-    pub(crate) contexts: RefCell<HashMap<TypeId, Box<dyn Any>>>,
+    pub(crate) cleanups: RefCell<HashMap<Id, Cleanups>>,
+    /// The context values provided in each Scope
+    pub(crate) contexts: RefCell<HashMap<Id, Contexts>>,
     pub(crate) batching: Cell<bool>,
     pub(crate) pending_effects: RefCell<SmallVec<[Rc<dyn EffectTrait>; 10]>>,
 }
@@ -37,11 +49,15 @@ impl Default for Runtime {
 
 impl Runtime {
     pub(crate) fn new() -> Self {
+        let root_scope = Id::next();
         Self {
             current_effect: RefCell::new(None),
-            current_scope: RefCell::new(Id::next()),
+            current_scope: RefCell::new(root_scope),
+            root_scope,
             children: RefCell::new(HashMap::new()),
+            parents: Default::default(),
             signals: Default::default(),
+            cleanups: Default::default(),
             contexts: Default::default(),
             batching: Cell::new(false),
             pending_effects: RefCell::new(SmallVec::new()),
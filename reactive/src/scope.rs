@@ -3,7 +3,7 @@ use std::{any::Any, cell::RefCell, collections::HashMap, fmt, rc::Rc};
 use crate::{
     create_effect,
     id::Id,
-    memo::{create_memo, Memo},
+    memo::{create_memo, create_memo_with_eq, Memo},
     runtime::RUNTIME,
     signal::{create_rw_signal, create_signal, ReadSignal, RwSignal, Signal, WriteSignal},
     trigger::{create_trigger, Trigger},
@@ -13,7 +13,7 @@ use crate::{
 /// Every Signal has a Scope created explictly or implicity,
 /// and when you Dispose the Scope, it will clean up all the Signals
 /// that belong to the Scope and all the child Scopes
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Scope(pub(crate) Id);
 
 impl Default for Scope {
@@ -42,14 +42,16 @@ impl Scope {
         RUNTIME.with(|runtime| Scope(*runtime.current_scope.borrow()))
     }
 
+    /// The Scope this Scope is a child of. Effects are a child of the Scope they were
+    /// created in.
+    pub fn parent(&self) -> Option<Scope> {
+        self.0.parent().map(Scope)
+    }
+
     /// Create a child Scope of this Scope
     pub fn create_child(&self) -> Scope {
         let child = Id::next();
-        RUNTIME.with(|runtime| {
-            let mut children = runtime.children.borrow_mut();
-            let children = children.entry(self.0).or_default();
-            children.insert(child);
-        });
+        child.set_parent(self.0);
         Scope(child)
     }
 
@@ -77,6 +79,18 @@ impl Scope {
         with_scope(self, || create_memo(f))
     }
 
+    /// Create a Memo with a custom equality function under this Scope
+    pub fn create_memo_with_eq<T>(
+        self,
+        f: impl Fn(Option<&T>) -> T + 'static,
+        eq: impl Fn(&T, &T) -> bool + 'static,
+    ) -> Memo<T>
+    where
+        T: 'static,
+    {
+        with_scope(self, || create_memo_with_eq(f, eq))
+    }
+
     /// Create a Trigger under this Scope
     pub fn create_trigger(self) -> Trigger {
         with_scope(self, create_trigger)
@@ -107,6 +121,12 @@ impl Scope {
         tracker.subscribe();
     }
 
+    /// Run `f` when this Scope is disposed, after its child Scopes and Signals have been
+    /// disposed. This can be used to release resources which aren't Signals, like timers.
+    pub fn on_cleanup(&self, f: impl FnOnce() + 'static) {
+        self.0.add_cleanup(Box::new(f));
+    }
+
     /// Dispose this Scope, and it will cleanup all the Signals and child Scope
     /// of this Scope.
     pub fn dispose(&self) {
@@ -114,6 +134,21 @@ impl Scope {
     }
 }
 
+/// Run `f` when the current Scope is disposed. Within an effect, this is before the effect
+/// runs again or when it's disposed.
+///
+/// ```rust
+/// # use floem_reactive::{on_cleanup, Scope};
+/// let scope = Scope::new();
+/// scope.create_effect(|_| {
+///     on_cleanup(|| println!("cleaning up"));
+/// });
+/// scope.dispose();
+/// ```
+pub fn on_cleanup(f: impl FnOnce() + 'static) {
+    Scope::current().on_cleanup(f);
+}
+
 /// Runs the given code with the given Scope
 pub fn with_scope<T>(scope: Scope, f: impl FnOnce() -> T) -> T
 where
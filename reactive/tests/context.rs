@@ -0,0 +1,78 @@
+use floem_reactive::{
+    create_effect, create_rw_signal, provide_context, provide_global_context, use_context,
+    with_scope, Scope,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+struct Theme(&'static str);
+
+#[test]
+fn context_is_shadowed_in_child_scopes() {
+    let outer = Scope::new();
+    let inner = outer.create_child();
+    let sibling = outer.create_child();
+
+    with_scope(outer, || provide_context(Theme("light")));
+    with_scope(inner, || {
+        assert_eq!(use_context::<Theme>(), Some(Theme("light")));
+        provide_context(Theme("dark"));
+        assert_eq!(use_context::<Theme>(), Some(Theme("dark")));
+    });
+    with_scope(sibling, || {
+        assert_eq!(use_context::<Theme>(), Some(Theme("light")));
+    });
+    with_scope(inner.create_child(), || {
+        assert_eq!(use_context::<Theme>(), Some(Theme("dark")));
+    });
+    assert_eq!(use_context::<Theme>(), None);
+
+    // The value of the inner scope is dropped with it, as is its link to the outer scope
+    inner.dispose();
+    with_scope(inner, || {
+        assert_eq!(use_context::<Theme>(), None);
+    });
+    outer.dispose();
+}
+
+#[test]
+fn context_is_found_when_effects_run_again() {
+    let scope = Scope::new();
+    let count = create_rw_signal(0);
+    let seen = create_rw_signal(Vec::new());
+    with_scope(scope, || {
+        provide_context(Theme("light"));
+        create_effect(move |_| {
+            count.track();
+            let theme = use_context::<Theme>();
+            seen.update(|seen| seen.push(theme));
+        });
+    });
+
+    count.set(1);
+    assert_eq!(
+        seen.get_untracked(),
+        vec![Some(Theme("light")), Some(Theme("light"))]
+    );
+    scope.dispose();
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Service(u32);
+
+#[test]
+fn global_context_is_found_in_unrelated_scopes() {
+    let window = Scope::new();
+    let other_window = Scope::new();
+    with_scope(window, || provide_global_context(Service(1)));
+    with_scope(other_window, || {
+        assert_eq!(use_context::<Service>(), Some(Service(1)));
+        provide_context(Service(2));
+        assert_eq!(use_context::<Service>(), Some(Service(2)));
+    });
+    assert_eq!(use_context::<Service>(), Some(Service(1)));
+
+    // The global value isn't dropped with the scope it was provided in
+    window.dispose();
+    other_window.dispose();
+    assert_eq!(use_context::<Service>(), Some(Service(1)));
+}
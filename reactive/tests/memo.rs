@@ -0,0 +1,40 @@
+use std::{cell::Cell, rc::Rc};
+
+use floem_reactive::{create_effect, create_memo_with_eq, create_rw_signal};
+
+#[test]
+fn memo_with_eq_suppresses_equal_values() {
+    let point = create_rw_signal((0, 0));
+    let computed = Rc::new(Cell::new(0));
+
+    let x = create_memo_with_eq(
+        {
+            let computed = computed.clone();
+            move |_| {
+                computed.set(computed.get() + 1);
+                point.get()
+            }
+        },
+        |old, new| old.0 == new.0,
+    );
+
+    let count = Rc::new(Cell::new(0));
+    create_effect({
+        let count = count.clone();
+        move |_| {
+            x.track();
+            count.set(count.get() + 1);
+        }
+    });
+    assert_eq!(count.get(), 1);
+
+    // Changing the ignored field recomputes the memo without notifying
+    point.set((0, 1));
+    assert_eq!(computed.get(), 2);
+    assert_eq!(count.get(), 1);
+    assert_eq!(x.get_untracked(), (0, 0));
+
+    point.set((1, 1));
+    assert_eq!(count.get(), 2);
+    assert_eq!(x.get_untracked(), (1, 1));
+}
@@ -0,0 +1,70 @@
+use std::{cell::RefCell, rc::Rc};
+
+use floem_reactive::{create_effect, create_rw_signal, on_cleanup, with_scope, Scope};
+
+#[test]
+fn cleanup_runs_after_child_scopes_are_disposed() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let scope = Scope::new();
+    let child = scope.create_child();
+
+    scope.on_cleanup({
+        let log = log.clone();
+        move || log.borrow_mut().push("parent")
+    });
+    with_scope(child, || {
+        on_cleanup({
+            let log = log.clone();
+            move || log.borrow_mut().push("child")
+        });
+    });
+    assert!(log.borrow().is_empty());
+
+    scope.dispose();
+    assert_eq!(*log.borrow(), vec!["child", "parent"]);
+
+    // Each cleanup only runs once
+    scope.dispose();
+    assert_eq!(log.borrow().len(), 2);
+}
+
+#[test]
+fn cleanup_runs_before_effect_reruns() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let count = create_rw_signal(0);
+    let scope = Scope::new();
+
+    scope.create_effect({
+        let log = log.clone();
+        move |_| {
+            let value = count.get();
+            log.borrow_mut().push(format!("run {value}"));
+            let log = log.clone();
+            on_cleanup(move || log.borrow_mut().push(format!("cleanup {value}")));
+        }
+    });
+    count.set(1);
+    assert_eq!(*log.borrow(), vec!["run 0", "cleanup 0", "run 1"]);
+
+    scope.dispose();
+    assert_eq!(
+        *log.borrow(),
+        vec!["run 0", "cleanup 0", "run 1", "cleanup 1"]
+    );
+}
+
+#[test]
+fn effects_are_children_of_their_scope() {
+    let scope = Scope::new();
+    let child = scope.create_child();
+    assert_eq!(child.parent(), Some(scope));
+    assert_eq!(scope.parent(), None);
+
+    let parent = Rc::new(RefCell::new(None));
+    with_scope(child, || {
+        let parent = parent.clone();
+        create_effect(move |_| *parent.borrow_mut() = Scope::current().parent());
+    });
+    assert_eq!(*parent.borrow(), Some(child));
+    scope.dispose();
+}
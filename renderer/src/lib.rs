@@ -22,6 +22,14 @@ pub struct Img<'a> {
 pub trait Renderer {
     fn begin(&mut self, capture: bool);
 
+    /// Begins a frame which only repaints `damage`, given in logical pixels, keeping the rest
+    /// of the previous frame. Returns `false` if the renderer can't keep the previous frame, in
+    /// which case [`Renderer::begin`] must be used instead. The renderer may still reuse work
+    /// from the previous frame outside of `damage` then.
+    fn begin_damaged(&mut self, _damage: Rect) -> bool {
+        false
+    }
+
     fn transform(&mut self, transform: Affine);
 
     fn set_z_index(&mut self, z_index: i32);
@@ -31,6 +39,25 @@ pub trait Renderer {
 
     fn clear_clip(&mut self);
 
+    /// Paint everything drawn until the matching [`Renderer::pop_layer`] into a layer,
+    /// which is then blended onto what's below it with `alpha` as a whole. Layers can be nested.
+    /// The layer only covers the part of `bounds` which isn't clipped away by the current clip,
+    /// so nothing is painted outside of it. It's painted in the coordinates of the current
+    /// transform and then transformed as a whole, so views in it are rotated and scaled like
+    /// shapes, including their text and images.
+    ///
+    /// Layers are blended in the order they're painted. What's painted before a layer is below
+    /// it and what's painted after it is above it, so z-indexes only order shapes within the
+    /// same layer.
+    fn push_layer(&mut self, alpha: f32, bounds: Rect);
+
+    /// Blend the layer started by the last [`Renderer::push_layer`].
+    fn pop_layer(&mut self);
+
+    /// Blur what's already painted inside a [`Shape`], with a gaussian blur whose standard
+    /// deviation is `radius`. Pixels up to 3 times `radius` outside of the shape are sampled.
+    fn blur_backdrop(&mut self, shape: &impl Shape, radius: f64);
+
     /// Stroke a [`Shape`].
     fn stroke<'b>(&mut self, shape: &impl Shape, brush: impl Into<BrushRef<'b>>, width: f64);
 
@@ -0,0 +1,449 @@
+//! Describing views to assistive technology like screen readers.
+//!
+//! Views are described by a [`Role`], a name and their state. The built-in widgets describe
+//! themselves, and any view can be described with [`Decorators::role`],
+//! [`Decorators::accessible_name`] and [`Decorators::accessible_checked`]. Views showing text,
+//! like labels, are named by their text, and buttons, checkboxes and the like are named by the
+//! text of their children unless they have a name.
+//!
+//! With the `accesskit` feature, each window exposes an accessibility tree of its views to the
+//! platform with [AccessKit](https://accesskit.dev), which is updated as the views change.
+//! Assistive technology can focus views which are `keyboard_navigatable` and click views with a
+//! click handler.
+//!
+//! [`Decorators::role`]: crate::views::Decorators::role
+//! [`Decorators::accessible_name`]: crate::views::Decorators::accessible_name
+//! [`Decorators::accessible_checked`]: crate::views::Decorators::accessible_checked
+
+#[cfg(feature = "accesskit")]
+pub(crate) use adapter::AccessibilityAdapter;
+
+/// What kind of control a view is, for assistive technology.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Role {
+    Button,
+    CheckBox,
+    RadioButton,
+    RadioGroup,
+    /// An on and off toggle, like a toggle button.
+    Switch,
+    Slider,
+    /// Text which isn't editable.
+    Label,
+    TextInput,
+    MultilineTextInput,
+    List,
+    ListItem,
+    Image,
+    Link,
+    /// A view grouping other views.
+    Group,
+}
+
+impl Role {
+    /// Whether views of the role are named by the text of their children if they don't have a
+    /// name.
+    #[cfg_attr(not(feature = "accesskit"), allow(dead_code))]
+    fn named_by_content(self) -> bool {
+        matches!(
+            self,
+            Role::Button
+                | Role::CheckBox
+                | Role::RadioButton
+                | Role::Switch
+                | Role::ListItem
+                | Role::Link
+        )
+    }
+}
+
+/// How a view is described to assistive technology.
+#[derive(Default)]
+#[cfg_attr(not(feature = "accesskit"), allow(dead_code))]
+pub(crate) struct AccessInfo {
+    pub(crate) role: Option<Role>,
+    pub(crate) name: Option<String>,
+    pub(crate) checked: Option<bool>,
+}
+
+pub(crate) enum AccessUpdate {
+    Role(Role),
+    Name(String),
+    Checked(bool),
+}
+
+impl AccessInfo {
+    pub(crate) fn update(&mut self, update: AccessUpdate) {
+        match update {
+            AccessUpdate::Role(role) => self.role = Some(role),
+            AccessUpdate::Name(name) => self.name = Some(name),
+            AccessUpdate::Checked(checked) => self.checked = Some(checked),
+        }
+    }
+}
+
+#[cfg(feature = "accesskit")]
+mod adapter {
+    use std::{collections::HashSet, sync::Mutex};
+
+    use accesskit::{
+        Action, ActionRequest, Affine, Checked, Node, NodeBuilder, NodeClassSet, NodeId, Rect,
+        Tree, TreeUpdate,
+    };
+    use winit::{
+        event::WindowEvent,
+        event_loop::EventLoopProxy,
+        window::{Window, WindowId},
+    };
+
+    use super::Role;
+    use crate::{
+        app::UserEvent, context::AppState, event::EventListener, id::Id, view::View, Application,
+    };
+
+    impl Role {
+        fn to_accesskit(self) -> accesskit::Role {
+            match self {
+                Role::Button => accesskit::Role::Button,
+                Role::CheckBox => accesskit::Role::CheckBox,
+                Role::RadioButton => accesskit::Role::RadioButton,
+                Role::RadioGroup => accesskit::Role::RadioGroup,
+                Role::Switch => accesskit::Role::Switch,
+                Role::Slider => accesskit::Role::Slider,
+                Role::Label => accesskit::Role::StaticText,
+                Role::TextInput => accesskit::Role::TextInput,
+                Role::MultilineTextInput => accesskit::Role::MultilineTextInput,
+                Role::List => accesskit::Role::List,
+                Role::ListItem => accesskit::Role::ListItem,
+                Role::Image => accesskit::Role::Image,
+                Role::Link => accesskit::Role::Link,
+                Role::Group => accesskit::Role::Group,
+            }
+        }
+    }
+
+    /// Sends the actions requested by assistive technology to the event loop, as they can
+    /// come from other threads.
+    struct ActionHandler {
+        window_id: WindowId,
+        proxy: Mutex<EventLoopProxy<UserEvent>>,
+    }
+
+    impl accesskit::ActionHandler for ActionHandler {
+        fn do_action(&mut self, request: ActionRequest) {
+            if let Ok(proxy) = self.proxy.lock() {
+                let _ = proxy.send_event(UserEvent::Accessibility {
+                    window_id: self.window_id,
+                    request,
+                });
+            }
+        }
+    }
+
+    /// Connects the accessibility tree of a window to the platform.
+    pub(crate) struct AccessibilityAdapter {
+        adapter: platform::Adapter,
+        classes: NodeClassSet,
+    }
+
+    impl AccessibilityAdapter {
+        /// Create the adapter for `window`, which must not have been shown yet.
+        pub(crate) fn new(window: &Window, root: Id) -> Option<Self> {
+            let mut proxy = None;
+            Application::with_event_loop_proxy(|event_loop_proxy| {
+                proxy = Some(event_loop_proxy.clone())
+            });
+            let action_handler = ActionHandler {
+                window_id: window.id(),
+                proxy: Mutex::new(proxy?),
+            };
+            let root = NodeId(root.to_raw());
+            let adapter = platform::Adapter::new(
+                window,
+                // The views are added by the next update
+                move || {
+                    let node =
+                        NodeBuilder::new(accesskit::Role::Window).build(&mut NodeClassSet::new());
+                    TreeUpdate {
+                        nodes: vec![(root, node)],
+                        tree: Some(Tree::new(root)),
+                        focus: root,
+                    }
+                },
+                action_handler,
+            )?;
+            Some(Self {
+                adapter,
+                classes: NodeClassSet::new(),
+            })
+        }
+
+        pub(crate) fn process_event(&self, window: &Window, event: &WindowEvent) {
+            self.adapter.process_event(window, event);
+        }
+
+        /// Send the tree of `root` to the platform, if assistive technology is using it.
+        /// `scale` is the number of physical pixels per logical pixel.
+        pub(crate) fn update(&mut self, root: &dyn View, app_state: &AppState, scale: f64) {
+            let classes = &mut self.classes;
+            self.adapter.update_if_active(|| {
+                let mut builder = TreeBuilder {
+                    app_state,
+                    classes,
+                    nodes: Vec::new(),
+                    ids: HashSet::new(),
+                };
+                let (root_id, _) = builder.add(root, Some(scale));
+                let focus = app_state
+                    .focus
+                    .map(|id| NodeId(id.to_raw()))
+                    .filter(|id| builder.ids.contains(id))
+                    .unwrap_or(root_id);
+                TreeUpdate {
+                    nodes: builder.nodes,
+                    tree: Some(Tree::new(root_id)),
+                    focus,
+                }
+            });
+        }
+    }
+
+    struct TreeBuilder<'a> {
+        app_state: &'a AppState,
+        classes: &'a mut NodeClassSet,
+        nodes: Vec<(NodeId, Node)>,
+        ids: HashSet<NodeId>,
+    }
+
+    impl TreeBuilder<'_> {
+        /// Add the node of `view` and its descendants, returning its id and the text of it and
+        /// its descendants. `scale` is set for the root.
+        fn add(&mut self, view: &dyn View, scale: Option<f64>) -> (NodeId, String) {
+            let id = view.id();
+            let node_id = NodeId(id.to_raw());
+            let mut children = Vec::new();
+            let text = view.accessible_text();
+            let mut content = text.clone().unwrap_or_default();
+            view.for_each_child(&mut |child| {
+                if !self.app_state.is_hidden(child.id()) {
+                    let (child_id, child_content) = self.add(child, None);
+                    children.push(child_id);
+                    if !child_content.is_empty() {
+                        if !content.is_empty() {
+                            content.push(' ');
+                        }
+                        content.push_str(&child_content);
+                    }
+                }
+                false
+            });
+
+            let view_state = self.app_state.view_states.get(&id);
+            let info = view_state.map(|view_state| &view_state.accessibility);
+            let role = info
+                .and_then(|info| info.role)
+                .or_else(|| text.as_ref().map(|_| Role::Label));
+
+            let mut node = NodeBuilder::new(match (scale, role) {
+                (Some(_), _) => accesskit::Role::Window,
+                (None, Some(role)) => role.to_accesskit(),
+                (None, None) => accesskit::Role::GenericContainer,
+            });
+            if let Some(scale) = scale {
+                node.set_transform(Affine::scale(scale));
+            }
+            if let Some(view_state) = view_state {
+                let rect = view_state.layout_rect;
+                node.set_bounds(Rect::new(rect.x0, rect.y0, rect.x1, rect.y1));
+                if view_state
+                    .event_listeners
+                    .contains_key(&EventListener::Click)
+                {
+                    node.add_action(Action::Default);
+                }
+            }
+            let name = info.and_then(|info| info.name.clone());
+            match role {
+                Some(Role::TextInput | Role::MultilineTextInput) => {
+                    if let Some(text) = text {
+                        node.set_value(text);
+                    }
+                    if let Some(name) = name {
+                        node.set_name(name);
+                    }
+                }
+                Some(role) if role.named_by_content() => {
+                    let name = name.unwrap_or_else(|| content.clone());
+                    if !name.is_empty() {
+                        node.set_name(name);
+                    }
+                }
+                _ => {
+                    if let Some(name) = name.or(text) {
+                        node.set_name(name);
+                    }
+                }
+            }
+            if let Some(checked) = info.and_then(|info| info.checked) {
+                node.set_checked(if checked {
+                    Checked::True
+                } else {
+                    Checked::False
+                });
+            }
+            if self.app_state.is_disabled(&id) {
+                node.set_disabled();
+            } else if self.app_state.keyboard_navigable.contains(&id) {
+                node.add_action(Action::Focus);
+            }
+            node.set_children(children);
+
+            self.nodes.push((node_id, node.build(self.classes)));
+            self.ids.insert(node_id);
+            (node_id, content)
+        }
+    }
+
+    /// The platform adapters, which are driven through the raw window handle so they don't
+    /// depend on a particular winit.
+    #[cfg(target_os = "windows")]
+    mod platform {
+        use accesskit::TreeUpdate;
+        use accesskit_windows::{SubclassingAdapter, HWND};
+        use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+        use winit::{event::WindowEvent, window::Window};
+
+        use super::ActionHandler;
+
+        pub(super) struct Adapter {
+            adapter: SubclassingAdapter,
+        }
+
+        impl Adapter {
+            pub(super) fn new(
+                window: &Window,
+                source: impl 'static + FnOnce() -> TreeUpdate + Send,
+                action_handler: ActionHandler,
+            ) -> Option<Self> {
+                let RawWindowHandle::Win32(handle) = window.raw_window_handle() else {
+                    return None;
+                };
+                let hwnd = HWND(handle.hwnd as isize);
+                let adapter = SubclassingAdapter::new(hwnd, source, Box::new(action_handler));
+                Some(Self { adapter })
+            }
+
+            pub(super) fn update_if_active(&self, update: impl FnOnce() -> TreeUpdate) {
+                if let Some(events) = self.adapter.update_if_active(update) {
+                    events.raise();
+                }
+            }
+
+            pub(super) fn process_event(&self, _window: &Window, _event: &WindowEvent) {}
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    mod platform {
+        use accesskit::TreeUpdate;
+        use accesskit_macos::SubclassingAdapter;
+        use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+        use winit::{event::WindowEvent, window::Window};
+
+        use super::ActionHandler;
+
+        pub(super) struct Adapter {
+            adapter: SubclassingAdapter,
+        }
+
+        impl Adapter {
+            pub(super) fn new(
+                window: &Window,
+                source: impl 'static + FnOnce() -> TreeUpdate + Send,
+                action_handler: ActionHandler,
+            ) -> Option<Self> {
+                let RawWindowHandle::AppKit(handle) = window.raw_window_handle() else {
+                    return None;
+                };
+                // SAFETY: `ns_view` is the live view of `window`, which outlives the adapter as
+                // both are owned by the window handle.
+                let adapter = unsafe {
+                    SubclassingAdapter::new(handle.ns_view, source, Box::new(action_handler))
+                };
+                Some(Self { adapter })
+            }
+
+            pub(super) fn update_if_active(&self, update: impl FnOnce() -> TreeUpdate) {
+                if let Some(events) = self.adapter.update_if_active(update) {
+                    events.raise();
+                }
+            }
+
+            pub(super) fn process_event(&self, _window: &Window, _event: &WindowEvent) {}
+        }
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    mod platform {
+        use accesskit::{Rect, TreeUpdate};
+        use winit::{event::WindowEvent, window::Window};
+
+        use super::ActionHandler;
+
+        pub(super) struct Adapter {
+            adapter: accesskit_unix::Adapter,
+        }
+
+        impl Adapter {
+            pub(super) fn new(
+                _window: &Window,
+                source: impl 'static + FnOnce() -> TreeUpdate + Send,
+                action_handler: ActionHandler,
+            ) -> Option<Self> {
+                let adapter =
+                    accesskit_unix::Adapter::new(source, false, Box::new(action_handler))?;
+                Some(Self { adapter })
+            }
+
+            pub(super) fn update_if_active(&self, update: impl FnOnce() -> TreeUpdate) {
+                self.adapter.update_if_active(update);
+            }
+
+            /// AT-SPI needs to be told where the window is and whether it's focused.
+            pub(super) fn process_event(&self, window: &Window, event: &WindowEvent) {
+                match event {
+                    WindowEvent::Moved(_) | WindowEvent::Resized(_) => {
+                        let outer = window.outer_position().unwrap_or_default();
+                        let outer_size = window.outer_size();
+                        let inner = window.inner_position().unwrap_or_default();
+                        let inner_size = window.inner_size();
+                        self.adapter.set_root_window_bounds(
+                            Rect::new(
+                                outer.x as f64,
+                                outer.y as f64,
+                                outer.x as f64 + outer_size.width as f64,
+                                outer.y as f64 + outer_size.height as f64,
+                            ),
+                            Rect::new(
+                                inner.x as f64,
+                                inner.y as f64,
+                                inner.x as f64 + inner_size.width as f64,
+                                inner.y as f64 + inner_size.height as f64,
+                            ),
+                        );
+                    }
+                    WindowEvent::Focused(focused) => {
+                        self.adapter.update_window_focus_state(*focused);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
@@ -1,24 +1,35 @@
 use std::{
-    path::PathBuf,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
     sync::atomic::AtomicU64,
     time::{Duration, Instant},
 };
 
-use floem_reactive::Scope;
+use floem_reactive::{
+    create_effect, create_signal, untrack, with_scope, ReadSignal, Scope, WriteSignal,
+};
 use kurbo::{Point, Size, Vec2};
-use winit::window::ResizeDirection;
+use peniko::Color;
+use winit::window::{CursorGrabMode, ResizeDirection};
 
 use crate::{
     app::{add_app_update_event, AppUpdateEvent},
     ext_event::create_ext_action,
     file::{FileDialogOptions, FileInfo},
     id::Id,
+    keyboard::{KeyCombination, ShortcutId},
     menu::Menu,
+    style_class,
     update::{UpdateMessage, CENTRAL_UPDATE_MESSAGES},
     view::View,
+    views::{h_stack_from_iter, label, v_stack, Decorators},
+    widgets::button,
     window_handle::{get_current_view, set_current_view},
 };
 
+style_class!(pub ModalScrimClass);
+
 fn add_update_message(msg: UpdateMessage) {
     let current_view = get_current_view();
     CENTRAL_UPDATE_MESSAGES.with(|msgs| {
@@ -56,8 +67,46 @@ pub fn update_window_scale(window_scale: f64) {
 
 pub(crate) struct Timer {
     pub(crate) token: TimerToken,
-    pub(crate) action: Box<dyn FnOnce(TimerToken)>,
+    pub(crate) action: Box<dyn FnMut(TimerToken)>,
     pub(crate) deadline: Instant,
+    /// If set, the timer is re-armed with this interval after each fire.
+    pub(crate) interval: Option<Duration>,
+}
+
+impl Timer {
+    /// Computes the next deadline of an interval timer.
+    ///
+    /// The deadline advances by whole intervals from the previous deadline, so the
+    /// time spent running the action doesn't accumulate as lag. Ticks that were missed
+    /// because the loop fell behind are coalesced into the next one.
+    pub(crate) fn next_deadline(&self, interval: Duration, now: Instant) -> Instant {
+        if interval.is_zero() {
+            return now;
+        }
+        let behind = now.saturating_duration_since(self.deadline);
+        let missed = behind.as_nanos() / interval.as_nanos();
+        let ticks = u32::try_from(missed + 1).unwrap_or(u32::MAX);
+        let next = saturating_add(
+            self.deadline,
+            interval.checked_mul(ticks).unwrap_or(Duration::MAX),
+        );
+        // Far too many ticks of a short interval were missed to count them
+        if next > now {
+            next
+        } else {
+            saturating_add(now, interval)
+        }
+    }
+}
+
+/// Adds `duration` to `instant`, or as much of it as can be represented.
+fn saturating_add(instant: Instant, mut duration: Duration) -> Instant {
+    loop {
+        if let Some(instant) = instant.checked_add(duration) {
+            return instant;
+        }
+        duration /= 2;
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
@@ -84,62 +133,464 @@ impl TimerToken {
     }
 }
 
+thread_local! {
+    /// The scope each pending timer was started in and the scopes above it
+    static TIMER_SCOPES: RefCell<HashMap<TimerToken, Vec<Scope>>> = Default::default();
+}
+
+/// Remember the scopes `token` is started within, and return the current scope.
+fn scope_timer(token: TimerToken) -> Scope {
+    let current = Scope::current();
+    let mut scopes = Vec::new();
+    let mut scope = Some(current);
+    while let Some(parent) = scope {
+        scopes.push(parent);
+        scope = parent.parent();
+    }
+    TIMER_SCOPES.with(|timers| timers.borrow_mut().insert(token, scopes));
+    current
+}
+
+/// Stop tracking the scopes of `token`, once it has fired or been cleared.
+fn remove_scope_timer(token: TimerToken) {
+    TIMER_SCOPES.with(|timers| timers.borrow_mut().remove(&token));
+}
+
+/// Cancel the pending timers started within `scope` or its child scopes when it's disposed.
+/// This is done for the scopes owned by views, so the timers stop when the view is removed,
+/// but not for the scopes of effects, which are disposed every time they run again.
+pub(crate) fn cancel_timers_with(scope: Scope) {
+    scope.on_cleanup(move || {
+        let tokens: Vec<TimerToken> = TIMER_SCOPES.with(|timers| {
+            timers
+                .borrow()
+                .iter()
+                .filter(|(_, scopes)| scopes.contains(&scope))
+                .map(|(token, _)| *token)
+                .collect()
+        });
+        for token in tokens {
+            clear_timer(token);
+        }
+    });
+}
+
+/// Execute a callback after a specified duration
+///
+/// The timer is cancelled if it's started within the scope of a view, like one built with
+/// [`scoped`](crate::views::scoped()) or [`dyn_container`](crate::views::dyn_container()),
+/// and the view is removed before it fires. Effects running again don't cancel it. The
+/// callback runs in the current scope, so it sees the context values provided there.
 pub fn exec_after(duration: Duration, action: impl FnOnce(TimerToken) + 'static) -> TimerToken {
     let view = get_current_view();
+    let token = TimerToken::next();
+    let scope = scope_timer(token);
+    let mut action = Some(action);
+    let action = move |token| {
+        if let Some(action) = action.take() {
+            remove_scope_timer(token);
+            let current_view = get_current_view();
+            set_current_view(view);
+            with_scope(scope, || action(token));
+            set_current_view(current_view);
+        }
+    };
+
+    let deadline = Instant::now() + duration;
+    add_app_update_event(AppUpdateEvent::RequestTimer {
+        timer: Timer {
+            token,
+            action: Box::new(action),
+            deadline,
+            interval: None,
+        },
+    });
+    token
+}
+
+/// Execute a callback repeatedly, every `interval`, until the timer is cancelled.
+///
+/// The first call happens after one `interval` has elapsed. The timer is also cancelled when
+/// the view it's started in is removed, as with [`exec_after`].
+pub fn exec_interval(
+    interval: Duration,
+    mut action: impl FnMut(TimerToken) + 'static,
+) -> TimerToken {
+    let view = get_current_view();
+    let token = TimerToken::next();
+    let scope = scope_timer(token);
     let action = move |token| {
         let current_view = get_current_view();
         set_current_view(view);
-        action(token);
+        with_scope(scope, || action(token));
         set_current_view(current_view);
     };
 
-    let token = TimerToken::next();
-    let deadline = Instant::now() + duration;
+    let deadline = Instant::now() + interval;
     add_app_update_event(AppUpdateEvent::RequestTimer {
         timer: Timer {
             token,
             action: Box::new(action),
             deadline,
+            interval: Some(interval),
         },
     });
     token
 }
 
-pub fn open_file(
-    options: FileDialogOptions,
-    file_info_action: impl Fn(Option<FileInfo>) + 'static,
-) {
-    let send = create_ext_action(Scope::new(), move |path: Option<PathBuf>| {
-        file_info_action(path.map(|path| FileInfo { path, format: None }))
+/// Cancel a timer scheduled with [`exec_after`] or [`exec_interval`].
+///
+/// The timer's action is guaranteed not to run after this is called, even if its deadline
+/// has already passed.
+pub fn clear_timer(token: TimerToken) {
+    remove_scope_timer(token);
+    add_app_update_event(AppUpdateEvent::CancelTimer { token });
+}
+
+/// Create a signal which follows the value of `source`, but only updates once `source`
+/// hasn't changed for `duration`.
+///
+/// The signal starts out with the current value of `source`.
+pub fn create_debounced_signal<T: 'static>(
+    source: impl Fn() -> T + 'static,
+    duration: Duration,
+) -> ReadSignal<T> {
+    let (read, write) = create_signal(untrack(&source));
+    let timer = Rc::new(Cell::new(None));
+
+    create_effect(move |prev: Option<()>| {
+        let value = source();
+        // The first run only subscribes to `source`
+        if prev.is_none() {
+            return;
+        }
+
+        if let Some(token) = timer.take() {
+            clear_timer(token);
+        }
+        let token = exec_after(duration, {
+            let timer = timer.clone();
+            move |_| {
+                timer.set(None);
+                write.try_set(value);
+            }
+        });
+        timer.set(Some(token));
     });
-    std::thread::spawn(move || {
-        let mut dialog = rfd::FileDialog::new();
-        if let Some(path) = options.starting_directory.as_ref() {
-            dialog = dialog.set_directory(path);
+
+    read
+}
+
+/// Create a signal which follows the value of `source`, but updates at most once per `duration`.
+///
+/// A change is applied immediately if there was no update in the last `duration`, otherwise
+/// the latest value is applied when `duration` has passed.
+pub fn create_throttled_signal<T: 'static>(
+    source: impl Fn() -> T + 'static,
+    duration: Duration,
+) -> ReadSignal<T> {
+    let (read, write) = create_signal(untrack(&source));
+    let throttling = Rc::new(Cell::new(false));
+    let pending = Rc::new(RefCell::new(None));
+    // The timers run in the scope of the signal rather than the one of the effect, which is
+    // disposed when it reruns
+    let scope = Scope::current();
+
+    fn schedule<T: 'static>(
+        scope: Scope,
+        duration: Duration,
+        throttling: Rc<Cell<bool>>,
+        pending: Rc<RefCell<Option<T>>>,
+        write: WriteSignal<T>,
+    ) {
+        throttling.set(true);
+        let action = move |_: TimerToken| {
+            let value = pending.borrow_mut().take();
+            match value {
+                Some(value) => {
+                    write.try_set(value);
+                    schedule(scope, duration, throttling, pending, write);
+                }
+                None => throttling.set(false),
+            }
+        };
+        with_scope(scope, || {
+            exec_after(duration, action);
+        });
+    }
+
+    create_effect(move |prev: Option<()>| {
+        let value = source();
+        // The first run only subscribes to `source`
+        if prev.is_none() {
+            return;
         }
-        let path = if options.select_directories {
-            dialog.pick_folder()
+
+        if throttling.get() {
+            *pending.borrow_mut() = Some(value);
         } else {
-            dialog.pick_file()
-        };
-        send(path);
+            write.try_set(value);
+            schedule(scope, duration, throttling.clone(), pending.clone(), write);
+        }
     });
+
+    read
 }
 
+/// Show a file dialog for `options` on another thread, and pass the result of `show` to
+/// `action` once it's closed. Modal dialogs are parented to the current window.
+fn spawn_file_dialog<T: Send + 'static>(
+    options: FileDialogOptions,
+    show: impl FnOnce(rfd::FileDialog) -> T + Send + 'static,
+    action: impl FnOnce(T) + 'static,
+) {
+    let send = create_ext_action(Scope::new(), action);
+    let spawn = move |parent: Option<&winit::window::Window>| {
+        let mut dialog = options.rfd_dialog();
+        if let Some(parent) = parent {
+            dialog = dialog.set_parent(parent);
+        }
+        std::thread::spawn(move || send(show(dialog)));
+    };
+    if options.modal {
+        add_update_message(UpdateMessage::FileDialog(Box::new(spawn)));
+    } else {
+        spawn(None);
+    }
+}
+
+/// Show a dialog to pick a file, or a directory with
+/// [`select_directories`](FileDialogOptions::select_directories).
+///
+/// The dialog doesn't block the event loop. `file_info_action` is called with the picked
+/// file once it's closed, or `None` if it was cancelled.
+pub fn open_file(
+    options: FileDialogOptions,
+    file_info_action: impl Fn(Option<FileInfo>) + 'static,
+) {
+    let select_directories = options.select_directories;
+    let allowed_types = options.allowed_types.clone();
+    spawn_file_dialog(
+        options,
+        move |dialog| {
+            if select_directories {
+                dialog.pick_folder()
+            } else {
+                dialog.pick_file()
+            }
+        },
+        move |path| file_info_action(path.map(|path| FileInfo::new(path, &allowed_types))),
+    );
+}
+
+/// Show a dialog to pick one or more files, or directories with
+/// [`select_directories`](FileDialogOptions::select_directories).
+///
+/// The dialog doesn't block the event loop. `file_info_action` is called with the picked
+/// files once it's closed, or `None` if it was cancelled.
+pub fn open_files(
+    options: FileDialogOptions,
+    file_info_action: impl Fn(Option<Vec<FileInfo>>) + 'static,
+) {
+    let select_directories = options.select_directories;
+    let allowed_types = options.allowed_types.clone();
+    spawn_file_dialog(
+        options,
+        move |dialog| {
+            if select_directories {
+                dialog.pick_folders()
+            } else {
+                dialog.pick_files()
+            }
+        },
+        move |paths| {
+            file_info_action(paths.map(|paths| {
+                paths
+                    .into_iter()
+                    .map(|path| FileInfo::new(path, &allowed_types))
+                    .collect()
+            }))
+        },
+    );
+}
+
+/// Show a dialog to pick where to save a file.
+///
+/// The dialog doesn't block the event loop. `file_info_action` is called with the picked
+/// path once it's closed, or `None` if it was cancelled.
 pub fn save_as(options: FileDialogOptions, file_info_action: impl Fn(Option<FileInfo>) + 'static) {
-    let send = create_ext_action(Scope::new(), move |path: Option<PathBuf>| {
-        file_info_action(path.map(|path| FileInfo { path, format: None }))
-    });
-    std::thread::spawn(move || {
-        let mut dialog = rfd::FileDialog::new();
-        if let Some(path) = options.starting_directory.as_ref() {
-            dialog = dialog.set_directory(path);
+    let allowed_types = options.allowed_types.clone();
+    spawn_file_dialog(
+        options,
+        |dialog| dialog.save_file(),
+        move |path| file_info_action(path.map(|path| FileInfo::new(path, &allowed_types))),
+    );
+}
+
+/// The buttons of a [`message_box`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageButtons {
+    Ok,
+    OkCancel,
+    YesNo,
+    YesNoCancel,
+}
+
+impl MessageButtons {
+    /// The results of the buttons in order, with the default button first.
+    fn results(self) -> &'static [MessageResult] {
+        match self {
+            MessageButtons::Ok => &[MessageResult::Ok],
+            MessageButtons::OkCancel => &[MessageResult::Ok, MessageResult::Cancel],
+            MessageButtons::YesNo => &[MessageResult::Yes, MessageResult::No],
+            MessageButtons::YesNoCancel => {
+                &[MessageResult::Yes, MessageResult::No, MessageResult::Cancel]
+            }
         }
-        let path = dialog.save_file();
-        send(path);
-    });
+    }
+
+    /// The result of dismissing the message box without picking a button, like by pressing
+    /// Escape.
+    fn dismissed(self) -> MessageResult {
+        match self {
+            MessageButtons::Ok => MessageResult::Ok,
+            MessageButtons::YesNo => MessageResult::No,
+            MessageButtons::OkCancel | MessageButtons::YesNoCancel => MessageResult::Cancel,
+        }
+    }
+}
+
+/// The button picked in a [`message_box`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageResult {
+    Ok,
+    Cancel,
+    Yes,
+    No,
 }
 
+impl MessageResult {
+    fn label(self) -> &'static str {
+        match self {
+            MessageResult::Ok => "OK",
+            MessageResult::Cancel => "Cancel",
+            MessageResult::Yes => "Yes",
+            MessageResult::No => "No",
+        }
+    }
+}
+
+/// Show a message box with `title`, `body` and `buttons`, and call `action` with the picked
+/// button once it's closed.
+///
+/// A native dialog is used when the platform supports the buttons, otherwise the message box
+/// is shown as a modal dialog in the current window, see [`add_modal`]. It doesn't block the
+/// event loop either way. Enter picks the first button and Escape dismisses the message box,
+/// which picks Cancel, or No if there's no Cancel button.
+///
+/// ```rust
+/// # use floem::action::{message_box, MessageButtons, MessageResult};
+/// message_box(
+///     "Unsaved changes",
+///     "Save the changes before closing?",
+///     MessageButtons::YesNoCancel,
+///     |result| if result == MessageResult::Yes { /* save */ },
+/// );
+/// ```
+pub fn message_box(
+    title: impl Into<String>,
+    body: impl Into<String>,
+    buttons: MessageButtons,
+    action: impl FnOnce(MessageResult) + 'static,
+) {
+    let title = title.into();
+    let body = body.into();
+    let native = match buttons {
+        MessageButtons::Ok => Some(rfd::MessageButtons::Ok),
+        MessageButtons::OkCancel => Some(rfd::MessageButtons::OkCancel),
+        MessageButtons::YesNo => Some(rfd::MessageButtons::YesNo),
+        MessageButtons::YesNoCancel => None,
+    };
+
+    if let Some(native) = native {
+        let send = create_ext_action(Scope::new(), move |confirmed: bool| {
+            action(if confirmed {
+                buttons.results()[0]
+            } else {
+                buttons.dismissed()
+            })
+        });
+        std::thread::spawn(move || {
+            let confirmed = rfd::MessageDialog::new()
+                .set_title(&title)
+                .set_description(&body)
+                .set_buttons(native)
+                .show();
+            send(confirmed);
+        });
+        return;
+    }
+
+    // The action is taken by the first button picked
+    let action: Rc<Cell<Option<Box<dyn FnOnce(MessageResult)>>>> =
+        Rc::new(Cell::new(Some(Box::new(action))));
+    let dismissed = buttons.dismissed();
+    let id = Rc::new(Cell::new(None));
+    let on_dismiss = {
+        let action = action.clone();
+        let id = id.clone();
+        move || {
+            if let Some(action) = action.take() {
+                action(dismissed);
+            }
+            if let Some(id) = id.get() {
+                remove_overlay(id);
+            }
+        }
+    };
+    id.set(Some(add_modal(on_dismiss, move |id| {
+        let buttons = buttons
+            .results()
+            .iter()
+            .copied()
+            .enumerate()
+            .map(move |(i, result)| {
+                let action = action.clone();
+                let button = button(move || result.label()).on_click_stop(move |_| {
+                    if let Some(action) = action.take() {
+                        action(result);
+                    }
+                    remove_overlay(id);
+                });
+                // The first button is focused so Enter picks it
+                let button_id = button.id();
+                button.on_mounted(move || {
+                    if i == 0 {
+                        button_id.request_focus();
+                    }
+                })
+            });
+        v_stack((
+            label(move || title.clone()).style(|s| s.font_bold().font_size(16.0)),
+            label(move || body.clone()),
+            h_stack_from_iter(buttons).style(|s| s.justify_end().gap(8.0, 0.0)),
+        ))
+        .style(|s| {
+            s.padding(20.0)
+                .gap(0.0, 12.0)
+                .min_width(280.0)
+                .max_width(480.0)
+                .border_radius(8.0)
+                .background(Color::WHITE)
+        })
+    })));
+}
+
+/// Show a context menu in the current window at `pos`, or at the cursor if `pos` is `None`.
+///
+/// Selecting an item runs its action. The menu is dismissed when clicking outside of it.
+/// Use [`Decorators::context_menu`](crate::views::Decorators::context_menu) to open a menu
+/// on secondary click.
 pub fn show_context_menu(menu: Menu, pos: Option<Point>) {
     add_update_message(UpdateMessage::ShowContextMenu { menu, pos });
 }
@@ -156,6 +607,45 @@ pub fn focus_window() {
     add_update_message(UpdateMessage::FocusWindow);
 }
 
+/// Show or hide the inspector overlay of the current window. While it's shown, the view under the
+/// cursor is outlined with its id, layout rectangle and computed style next to it.
+///
+/// It can be toggled with a key, like Shift+F12 here:
+///
+/// ```rust
+/// # use floem::{action::{register_shortcut, toggle_inspector_overlay}, keyboard::{Key, KeyCombination, ModifiersState, NamedKey}};
+/// # fn setup() {
+/// register_shortcut(
+///     KeyCombination::new(Key::Named(NamedKey::F12), ModifiersState::SHIFT),
+///     toggle_inspector_overlay,
+/// );
+/// # }
+/// ```
+pub fn toggle_inspector_overlay() {
+    add_update_message(UpdateMessage::ToggleInspectorOverlay);
+}
+
+/// Register a keyboard shortcut for the current window, which runs `action` when the key
+/// combination is pressed.
+///
+/// Shortcuts using Control, Alt or Super run before the focused view sees the key. Other shortcuts
+/// only run when no view has keyboard focus, so typing in a text input isn't interrupted.
+/// If several shortcuts match, the most recently registered one is used.
+pub fn register_shortcut(combination: KeyCombination, action: impl Fn() + 'static) -> ShortcutId {
+    let id = ShortcutId::next();
+    add_update_message(UpdateMessage::RegisterShortcut {
+        id,
+        combination,
+        action: Box::new(action),
+    });
+    id
+}
+
+/// Remove a shortcut registered with [`register_shortcut`].
+pub fn unregister_shortcut(id: ShortcutId) {
+    add_update_message(UpdateMessage::UnregisterShortcut(id));
+}
+
 pub fn set_ime_allowed(allowed: bool) {
     add_update_message(UpdateMessage::SetImeAllowed { allowed });
 }
@@ -164,6 +654,30 @@ pub fn set_ime_cursor_area(position: Point, size: Size) {
     add_update_message(UpdateMessage::SetImeCursorArea { position, size });
 }
 
+/// Shows or hides the cursor while it's over the current window.
+pub fn set_cursor_visible(visible: bool) {
+    add_update_message(UpdateMessage::SetCursorVisible(visible));
+}
+
+/// Confines the cursor to the current window or locks it in place. Relative motion can be
+/// read with [`on_pointer_motion`](crate::views::Decorators::on_pointer_motion) while it's locked.
+///
+/// Platforms support either confining or locking, so the other mode is used when the requested
+/// one isn't supported. Use [`CursorGrabMode::None`] to release the cursor again.
+pub fn set_cursor_grab(mode: CursorGrabMode) {
+    add_update_message(UpdateMessage::SetCursorGrab(mode));
+}
+
+/// Moves the focus to the next view in the tab order, like pressing Tab.
+pub fn focus_next() {
+    add_update_message(UpdateMessage::TabNavigation { backwards: false });
+}
+
+/// Moves the focus to the previous view in the tab order, like pressing Shift+Tab.
+pub fn focus_prev() {
+    add_update_message(UpdateMessage::TabNavigation { backwards: true });
+}
+
 /// Creates a new overlay on the current window.
 pub fn add_overlay<V: View + 'static>(position: Point, view: impl FnOnce(Id) -> V + 'static) -> Id {
     let id = Id::next();
@@ -171,6 +685,29 @@ pub fn add_overlay<V: View + 'static>(position: Point, view: impl FnOnce(Id) ->
         id,
         position,
         view: Box::new(move || Box::new(view(id))),
+        on_dismiss: None,
+    });
+    id
+}
+
+/// Creates a modal dialog on the current window, centered on top of all other content.
+///
+/// The dialog is shown over a scrim with the [`ModalScrimClass`] which covers the window,
+/// keeping pointer and key events from reaching the views below it. Tab only moves the
+/// focus between the views of the dialog, and Escape calls `on_dismiss`.
+///
+/// The dialog stays open until it's removed with [`remove_overlay`], which restores the
+/// focus to where it was before.
+pub fn add_modal<V: View + 'static>(
+    on_dismiss: impl Fn() + 'static,
+    view: impl FnOnce(Id) -> V + 'static,
+) -> Id {
+    let id = Id::next();
+    add_update_message(UpdateMessage::AddOverlay {
+        id,
+        position: Point::ZERO,
+        view: Box::new(move || Box::new(view(id))),
+        on_dismiss: Some(Box::new(on_dismiss)),
     });
     id
 }
@@ -179,3 +716,96 @@ pub fn add_overlay<V: View + 'static>(position: Point, view: impl FnOnce(Id) ->
 pub fn remove_overlay(id: Id) {
     add_update_message(UpdateMessage::RemoveOverlay { id });
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use floem_reactive::{create_effect, create_rw_signal};
+    use kurbo::Size;
+
+    use super::{exec_after, exec_interval, Timer, TimerToken};
+    use crate::{
+        app_handle::is_timer_cancelled,
+        views::{empty, scoped, show},
+        window::HeadlessWindow,
+    };
+
+    fn timer(deadline: Instant) -> Timer {
+        Timer {
+            token: TimerToken::INVALID,
+            action: Box::new(|_| {}),
+            deadline,
+            interval: None,
+        }
+    }
+
+    #[test]
+    fn next_deadline_skips_missed_ticks() {
+        let start = Instant::now();
+        let interval = Duration::from_millis(10);
+        let timer = timer(start);
+        assert_eq!(timer.next_deadline(interval, start), start + interval);
+        assert_eq!(
+            timer.next_deadline(interval, start + Duration::from_millis(35)),
+            start + Duration::from_millis(40)
+        );
+    }
+
+    #[test]
+    fn next_deadline_does_not_overflow() {
+        let start = Instant::now();
+        let timer = timer(start);
+        // More missed ticks than fit in a `u32`
+        let now = start + Duration::from_secs(60);
+        let next = timer.next_deadline(Duration::from_nanos(1), now);
+        assert!(next > now);
+        // An interval which can't be added to the deadline
+        assert!(timer.next_deadline(Duration::MAX, now) > now);
+    }
+
+    #[test]
+    fn removing_a_scoped_view_stops_its_effects_and_timers() {
+        let shown = create_rw_signal(true);
+        let count = create_rw_signal(0);
+        let runs = create_rw_signal(0);
+        let timers = create_rw_signal(Vec::new());
+        let effect_timers = create_rw_signal(Vec::new());
+        let hour = Duration::from_secs(60 * 60);
+        let view = move || {
+            scoped(move || {
+                create_effect(move |_| {
+                    count.track();
+                    runs.update(|runs| *runs += 1);
+                    let token = exec_after(hour, |_| {});
+                    effect_timers.update(|timers| timers.push(token));
+                });
+                timers.set(vec![exec_after(hour, |_| {}), exec_interval(hour, |_| {})]);
+                empty()
+            })
+        };
+        let mut window = HeadlessWindow::new(
+            move |_| show(move || shown.get(), view, empty),
+            Size::new(100.0, 100.0),
+            1.0,
+        );
+
+        // The effect running again doesn't cancel the timer it started before
+        count.set(1);
+        assert_eq!(runs.get_untracked(), 2);
+        let pending = || {
+            timers
+                .get_untracked()
+                .into_iter()
+                .chain(effect_timers.get_untracked())
+        };
+        assert!(!pending().any(is_timer_cancelled));
+
+        shown.set(false);
+        window.render();
+        count.set(2);
+        assert_eq!(runs.get_untracked(), 2);
+        assert_eq!(pending().count(), 4);
+        assert!(pending().all(is_timer_cancelled));
+    }
+}
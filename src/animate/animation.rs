@@ -1,15 +1,16 @@
-use crate::style::{Background, BorderColor, BorderRadius, TextColor};
+use crate::style::{Background, BorderColor, BorderRadius, StyleProp, TextColor};
 
 use super::{
     anim_val::AnimValue, AnimId, AnimPropKind, AnimState, AnimStateKind, AnimatedProp, Easing,
     EasingFn, EasingMode,
 };
-use std::{borrow::BorrowMut, collections::HashMap, time::Duration, time::Instant};
+use std::{borrow::BorrowMut, collections::HashMap, rc::Rc, time::Duration, time::Instant};
 
 use floem_reactive::create_effect;
 use peniko::Color;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, educe::Educe)]
+#[educe(Debug)]
 pub struct Animation {
     pub(crate) id: AnimId,
     pub(crate) state: AnimState,
@@ -20,6 +21,8 @@ pub struct Animation {
     pub(crate) repeat_mode: RepeatMode,
     pub(crate) repeat_count: usize,
     pub(crate) animated_props: HashMap<AnimPropKind, AnimatedProp>,
+    #[educe(Debug(ignore))]
+    pub(crate) on_complete: Option<Rc<dyn Fn()>>,
 }
 
 pub(crate) fn assert_valid_time(time: f64) {
@@ -51,6 +54,7 @@ pub fn animation() -> Animation {
         repeat_mode: RepeatMode::Times(1),
         repeat_count: 0,
         animated_props: HashMap::new(),
+        on_complete: None,
     }
 }
 
@@ -168,6 +172,23 @@ impl Animation {
         self
     }
 
+    /// Animate any style property to the value returned by `val_fn`.
+    pub fn style_prop<P: StyleProp>(self, prop: P, val_fn: impl Fn() -> P::Type + 'static) -> Self {
+        create_effect(move |_| {
+            let val = val_fn();
+
+            self.id.update_style_prop(prop, val);
+        });
+
+        self
+    }
+
+    /// Call `on_complete` when the animation completes, after the last repetition.
+    pub fn on_complete(mut self, on_complete: impl Fn() + 'static) -> Self {
+        self.on_complete = Some(Rc::new(on_complete));
+        self
+    }
+
     pub fn auto_reverse(mut self, auto_rev: bool) -> Self {
         self.auto_reverse = auto_rev;
         self
@@ -288,6 +309,9 @@ impl Animation {
                     if self.repeat_count >= times {
                         self.state = AnimState::Completed {
                             elapsed: Some(*elapsed),
+                        };
+                        if let Some(on_complete) = &self.on_complete {
+                            on_complete();
                         }
                     } else {
                         self.state = AnimState::PassInProgress {
@@ -41,6 +41,10 @@ pub enum EasingFn {
     Quintic,
     /// Creates an animation that accelerates and/or decelerates using a sine formula.
     Sine,
+    /// Creates an animation that follows a cubic Bézier curve from `(0, 0)` to `(1, 1)`
+    /// with the control points `(x1, y1)` and `(x2, y2)`, like the CSS `cubic-bezier()` function.
+    /// `x1` and `x2` must be in the range `[0, 1]`.
+    CubicBezier { x1: f64, y1: f64, x2: f64, y2: f64 },
 }
 
 // See https://easings.net/ and
@@ -62,6 +66,32 @@ fn elastic_easing(time: f64) -> f64 {
     }
 }
 
+/// Evaluates the cubic Bézier curve with the control points `p1` and `p2` at `t`.
+fn bezier(t: f64, p1: f64, p2: f64) -> f64 {
+    let u = 1.0 - t;
+    3.0 * u * u * t * p1 + 3.0 * u * t * t * p2 + t * t * t
+}
+
+fn cubic_bezier_easing(time: f64, x1: f64, y1: f64, x2: f64, y2: f64) -> f64 {
+    // Find the curve parameter for `time` on the x axis by bisection. `x` is monotonic
+    // as the control points are in `[0, 1]`.
+    let (mut low, mut high) = (0.0, 1.0);
+    let mut t = time;
+    for _ in 0..32 {
+        let x = bezier(t, x1, x2);
+        if (x - time).abs() < 1e-6 {
+            break;
+        }
+        if x < time {
+            low = t;
+        } else {
+            high = t;
+        }
+        t = (low + high) / 2.0;
+    }
+    bezier(t, y1, y2)
+}
+
 impl Easing {
     pub(crate) fn apply_easing_fn(&self, time: f64) -> f64 {
         assert_valid_time(time);
@@ -82,6 +112,7 @@ impl Easing {
             EasingFn::Quartic => time.powf(4.0),
             EasingFn::Quintic => time.powf(5.0),
             EasingFn::Sine => 1.0 - ((time * PI) / 2.0).cos(),
+            EasingFn::CubicBezier { x1, y1, x2, y2 } => cubic_bezier_easing(time, x1, y1, x2, y2),
             EasingFn::Back => todo!(),
             EasingFn::Bounce => todo!(),
         }
@@ -1,17 +1,23 @@
 use std::{cell::RefCell, rc::Rc, sync::Arc};
 
 use floem_reactive::WriteSignal;
+use kurbo::Size;
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use winit::{
     event_loop::{ControlFlow, EventLoop, EventLoopBuilder, EventLoopProxy},
     monitor::MonitorHandle,
-    window::WindowId,
+    window::{Fullscreen, Theme, WindowId, WindowLevel},
 };
 
 use crate::{
-    action::Timer, app_handle::ApplicationHandle, clipboard::Clipboard, inspector::Capture,
-    profiler::Profile, view::View, window::WindowConfig,
+    action::{Timer, TimerToken},
+    app_handle::ApplicationHandle,
+    clipboard::Clipboard,
+    inspector::Capture,
+    profiler::Profile,
+    view::View,
+    window::{CloseResponse, FrameTiming, WindowConfig, WindowGeometry},
 };
 
 use raw_window_handle::HasRawDisplayHandle;
@@ -38,6 +44,13 @@ pub(crate) enum UserEvent {
     AppUpdate,
     Idle,
     QuitApp,
+    #[cfg(feature = "accesskit")]
+    Accessibility {
+        window_id: WindowId,
+        request: accesskit::ActionRequest,
+    },
+    #[cfg(feature = "tray")]
+    Tray(crate::tray::TrayEvent),
 }
 
 pub(crate) enum AppUpdateEvent {
@@ -48,6 +61,53 @@ pub(crate) enum AppUpdateEvent {
     CloseWindow {
         window_id: WindowId,
     },
+    FocusWindow {
+        window_id: WindowId,
+    },
+    MinimizeWindow {
+        window_id: WindowId,
+    },
+    MaximizeWindow {
+        window_id: WindowId,
+    },
+    RestoreWindow {
+        window_id: WindowId,
+    },
+    SetFullscreen {
+        window_id: WindowId,
+        fullscreen: Option<Fullscreen>,
+    },
+    ToggleFullscreen {
+        window_id: WindowId,
+    },
+    SetWindowLevel {
+        window_id: WindowId,
+        window_level: WindowLevel,
+    },
+    SetCloseRequestedHandler {
+        window_id: WindowId,
+        handler: Box<dyn Fn() -> CloseResponse>,
+    },
+    SetFrameTimingHandler {
+        window_id: WindowId,
+        handler: Box<dyn Fn(&FrameTiming)>,
+    },
+    GetWindowGeometry {
+        window_id: WindowId,
+        callback: Box<dyn FnOnce(Option<WindowGeometry>)>,
+    },
+    SetWindowResizable {
+        window_id: WindowId,
+        resizable: bool,
+    },
+    SetWindowMinSize {
+        window_id: WindowId,
+        size: Option<Size>,
+    },
+    SetWindowMaxSize {
+        window_id: WindowId,
+        size: Option<Size>,
+    },
     CaptureWindow {
         window_id: WindowId,
         capture: WriteSignal<Option<Rc<Capture>>>,
@@ -59,6 +119,9 @@ pub(crate) enum AppUpdateEvent {
     RequestTimer {
         timer: Timer,
     },
+    CancelTimer {
+        token: TimerToken,
+    },
     #[cfg(target_os = "linux")]
     MenuAction {
         window_id: WindowId,
@@ -112,6 +175,20 @@ impl Application {
         self
     }
 
+    /// Set a handler which is called when the system theme changes, such as when the user
+    /// switches to dark mode.
+    pub fn on_system_theme_changed(mut self, action: impl Fn(Theme) + 'static) -> Self {
+        self.handle.as_mut().unwrap().system_theme_listener = Some(Box::new(action));
+        self
+    }
+
+    /// Show `tray` in the system tray while the application runs. See [`Tray`](crate::tray::Tray).
+    #[cfg(feature = "tray")]
+    pub fn tray(mut self, tray: crate::tray::Tray) -> Self {
+        self.handle.as_mut().unwrap().tray = Some(tray);
+        self
+    }
+
     /// create a new window for the application, if you want multiple windows,
     /// just chain more window method to the builder
     pub fn window<V: View + 'static>(
@@ -135,11 +212,18 @@ impl Application {
             handle.handle_timer(event_loop);
 
             match event {
+                #[cfg(feature = "tray")]
+                winit::event::Event::NewEvents(winit::event::StartCause::Init) => {
+                    // The icon can only be created once the event loop is running on macOS
+                    handle.show_tray();
+                }
                 winit::event::Event::NewEvents(_) => {}
                 winit::event::Event::WindowEvent { window_id, event } => {
                     handle.handle_window_event(window_id, event, event_loop);
                 }
-                winit::event::Event::DeviceEvent { .. } => {}
+                winit::event::Event::DeviceEvent { event, .. } => {
+                    handle.handle_device_event(event);
+                }
                 winit::event::Event::UserEvent(event) => {
                     handle.handle_user_event(event_loop, event);
                 }
@@ -1,11 +1,11 @@
-use std::{collections::HashMap, rc::Rc, time::Instant};
+use std::{cell::RefCell, collections::HashMap, rc::Rc, time::Instant};
 
-use kurbo::{Point, Size};
+use kurbo::{Point, Rect, Size, Vec2};
 use winit::{
-    dpi::{LogicalPosition, LogicalSize},
-    event::WindowEvent,
+    dpi::{LogicalPosition, LogicalSize, PhysicalPosition},
+    event::{DeviceEvent, WindowEvent},
     event_loop::{ControlFlow, EventLoopWindowTarget},
-    window::WindowId,
+    window::{Theme, WindowButtons, WindowId, WindowLevel},
 };
 
 use crate::{
@@ -15,13 +15,26 @@ use crate::{
     inspector::Capture,
     profiler::{Profile, ProfileEvent},
     view::View,
-    window::WindowConfig,
+    window::{CloseResponse, WindowConfig},
     window_handle::WindowHandle,
 };
 
+thread_local! {
+    /// The ids of the open windows, in the order they were opened
+    pub(crate) static WINDOW_IDS: RefCell<Vec<WindowId>> = Default::default();
+}
+
 pub(crate) struct ApplicationHandle {
     window_handles: HashMap<winit::window::WindowId, WindowHandle>,
     timers: HashMap<TimerToken, Timer>,
+    /// The last system theme reported by any window
+    system_theme: Option<Theme>,
+    pub(crate) system_theme_listener: Option<Box<dyn Fn(Theme)>>,
+    /// The tray icon to show once the event loop is running
+    #[cfg(feature = "tray")]
+    pub(crate) tray: Option<crate::tray::Tray>,
+    #[cfg(feature = "tray")]
+    active_tray: Option<crate::tray::ActiveTray>,
 }
 
 impl ApplicationHandle {
@@ -29,6 +42,12 @@ impl ApplicationHandle {
         Self {
             window_handles: HashMap::new(),
             timers: HashMap::new(),
+            system_theme: None,
+            system_theme_listener: None,
+            #[cfg(feature = "tray")]
+            tray: None,
+            #[cfg(feature = "tray")]
+            active_tray: None,
         }
     }
 
@@ -47,6 +66,25 @@ impl ApplicationHandle {
             UserEvent::QuitApp => {
                 event_loop.exit();
             }
+            #[cfg(feature = "accesskit")]
+            UserEvent::Accessibility { window_id, request } => {
+                if let Some(window_handle) = self.window_handles.get_mut(&window_id) {
+                    window_handle.accessibility_action(request);
+                }
+            }
+            #[cfg(feature = "tray")]
+            UserEvent::Tray(event) => {
+                if let Some(tray) = self.active_tray.as_ref() {
+                    tray.handle_event(event);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "tray")]
+    pub(crate) fn show_tray(&mut self) {
+        if let Some(tray) = self.tray.take() {
+            self.active_tray = tray.show();
         }
     }
 
@@ -63,9 +101,91 @@ impl ApplicationHandle {
                 AppUpdateEvent::CloseWindow { window_id } => {
                     self.close_window(window_id, event_loop);
                 }
+                AppUpdateEvent::FocusWindow { window_id } => {
+                    if let Some(handle) = self.window_handles.get(&window_id) {
+                        handle.bring_to_front();
+                    }
+                }
+                AppUpdateEvent::MinimizeWindow { window_id } => {
+                    if let Some(handle) = self.window_handles.get(&window_id) {
+                        handle.minimize();
+                    }
+                }
+                AppUpdateEvent::MaximizeWindow { window_id } => {
+                    if let Some(handle) = self.window_handles.get(&window_id) {
+                        handle.maximize();
+                    }
+                }
+                AppUpdateEvent::RestoreWindow { window_id } => {
+                    if let Some(handle) = self.window_handles.get(&window_id) {
+                        handle.restore();
+                    }
+                }
+                AppUpdateEvent::SetFullscreen {
+                    window_id,
+                    fullscreen,
+                } => {
+                    if let Some(handle) = self.window_handles.get(&window_id) {
+                        handle.set_fullscreen(fullscreen);
+                    }
+                }
+                AppUpdateEvent::ToggleFullscreen { window_id } => {
+                    if let Some(handle) = self.window_handles.get(&window_id) {
+                        handle.toggle_fullscreen();
+                    }
+                }
+                AppUpdateEvent::SetWindowLevel {
+                    window_id,
+                    window_level,
+                } => {
+                    if let Some(handle) = self.window_handles.get_mut(&window_id) {
+                        handle.set_window_level(window_level);
+                    }
+                }
+                AppUpdateEvent::SetCloseRequestedHandler { window_id, handler } => {
+                    if let Some(handle) = self.window_handles.get_mut(&window_id) {
+                        handle.close_requested_handler = Some(handler);
+                    }
+                }
+                AppUpdateEvent::SetFrameTimingHandler { window_id, handler } => {
+                    if let Some(handle) = self.window_handles.get_mut(&window_id) {
+                        handle.frame_timing_handler = Some(handler);
+                    }
+                }
+                AppUpdateEvent::GetWindowGeometry {
+                    window_id,
+                    callback,
+                } => {
+                    callback(
+                        self.window_handles
+                            .get(&window_id)
+                            .and_then(|handle| handle.geometry()),
+                    );
+                }
+                AppUpdateEvent::SetWindowResizable {
+                    window_id,
+                    resizable,
+                } => {
+                    if let Some(handle) = self.window_handles.get(&window_id) {
+                        handle.set_resizable(resizable);
+                    }
+                }
+                AppUpdateEvent::SetWindowMinSize { window_id, size } => {
+                    if let Some(handle) = self.window_handles.get_mut(&window_id) {
+                        handle.set_min_size(size);
+                    }
+                }
+                AppUpdateEvent::SetWindowMaxSize { window_id, size } => {
+                    if let Some(handle) = self.window_handles.get_mut(&window_id) {
+                        handle.set_max_size(size);
+                    }
+                }
                 AppUpdateEvent::RequestTimer { timer } => {
                     self.request_timer(timer, event_loop);
                 }
+                AppUpdateEvent::CancelTimer { token } => {
+                    self.remove_timer(&token, event_loop);
+                }
                 AppUpdateEvent::CaptureWindow { window_id, capture } => {
                     capture.set(self.capture_window(window_id).map(Rc::new));
                 }
@@ -100,6 +220,17 @@ impl ApplicationHandle {
         }
     }
 
+    /// Device events aren't tied to a window, so they go to the focused window.
+    pub(crate) fn handle_device_event(&mut self, event: DeviceEvent) {
+        if let DeviceEvent::MouseMotion { delta: (x, y) } = event {
+            for window_handle in self.window_handles.values_mut() {
+                if window_handle.has_focus() {
+                    window_handle.pointer_motion(Vec2::new(x, y));
+                }
+            }
+        }
+    }
+
     pub(crate) fn handle_window_event(
         &mut self,
         window_id: winit::window::WindowId,
@@ -149,73 +280,29 @@ impl ApplicationHandle {
             )
         });
 
+        #[cfg(feature = "accesskit")]
+        window_handle.accessibility_event(&event);
+
         match event {
-            WindowEvent::ActivationTokenDone { .. } => {}
-            WindowEvent::Resized(size) => {
-                let size: LogicalSize<f64> = size.to_logical(window_handle.scale);
-                let size = Size::new(size.width, size.height);
-                window_handle.size(size);
-            }
-            WindowEvent::Moved(position) => {
-                let position: LogicalPosition<f64> = position.to_logical(window_handle.scale);
-                let point = Point::new(position.x, position.y);
-                window_handle.position(point);
-            }
             WindowEvent::CloseRequested => {
-                self.close_window(window_id, event_loop);
+                if window_handle.close_requested() == CloseResponse::Close {
+                    self.close_window(window_id, event_loop);
+                }
             }
             WindowEvent::Destroyed => {
                 self.close_window(window_id, event_loop);
             }
-            WindowEvent::DroppedFile(_) => {}
-            WindowEvent::HoveredFile(_) => {}
-            WindowEvent::HoveredFileCancelled => {}
-            WindowEvent::Focused(focused) => {
-                window_handle.focused(focused);
-            }
-            WindowEvent::KeyboardInput { event, .. } => {
-                window_handle.key_event(event);
-            }
-            WindowEvent::ModifiersChanged(modifiers) => {
-                window_handle.modifiers = modifiers.state();
-            }
-            WindowEvent::Ime(ime) => {
-                window_handle.ime(ime);
-            }
-            WindowEvent::CursorMoved { position, .. } => {
-                let position: LogicalPosition<f64> = position.to_logical(window_handle.scale);
-                let point = Point::new(position.x, position.y);
-                window_handle.pointer_move(point);
-            }
-            WindowEvent::CursorEntered { .. } => {}
-            WindowEvent::CursorLeft { .. } => {
-                window_handle.pointer_leave();
-            }
-            WindowEvent::MouseWheel { delta, .. } => {
-                window_handle.mouse_wheel(delta);
-            }
-            WindowEvent::MouseInput { state, button, .. } => {
-                window_handle.mouse_input(button, state);
-            }
-            WindowEvent::TouchpadMagnify { .. } => {}
-            WindowEvent::SmartMagnify { .. } => {}
-            WindowEvent::TouchpadRotate { .. } => {}
-            WindowEvent::TouchpadPressure { .. } => {}
-            WindowEvent::AxisMotion { .. } => {}
-            WindowEvent::Touch(_) => {}
-            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
-                window_handle.scale(scale_factor);
-            }
             WindowEvent::ThemeChanged(theme) => {
-                window_handle.os_theme_changed(theme);
-            }
-            WindowEvent::Occluded(_) => {}
-            WindowEvent::MenuAction(id) => {
-                window_handle.menu_action(id);
-            }
-            WindowEvent::RedrawRequested => {
-                window_handle.render_frame();
+                window_handle.window_event(event);
+                // Every window reports the change, but the listener should only run once
+                if self.system_theme != Some(theme) {
+                    self.system_theme = Some(theme);
+                    if let Some(listener) = self.system_theme_listener.as_ref() {
+                        listener(theme);
+                    }
+                }
             }
+            event => window_handle.window_event(event),
         }
 
         if let Some((name, start, new_frame)) = start {
@@ -244,6 +331,30 @@ impl ApplicationHandle {
     ) {
         let mut window_builder = winit::window::WindowBuilder::new();
         let transparent = config.as_ref().and_then(|c| c.transparent).unwrap_or(false);
+        let window_level = config
+            .as_ref()
+            .and_then(|c| match c.always_on_top {
+                Some(true) => Some(WindowLevel::AlwaysOnTop),
+                _ => c.window_level,
+            })
+            .unwrap_or(WindowLevel::Normal);
+        let fallback_theme = config
+            .as_ref()
+            .and_then(|c| c.fallback_theme)
+            .unwrap_or(Theme::Light);
+        let scroll_line_height = config.as_ref().and_then(|c| c.scroll_line_height);
+        let multi_click_interval = config.as_ref().and_then(|c| c.multi_click_interval);
+        let show_frame_timing = config.as_ref().map_or(false, |c| c.show_frame_timing);
+        let centered = config
+            .as_ref()
+            .map(|c| c.centered && c.position.is_none())
+            .unwrap_or(false);
+        // Keep the window hidden until it has been moved to its final position, and until the
+        // accessibility adapter is created, which has to happen before the window is shown
+        let hidden = centered || cfg!(feature = "accesskit");
+        if hidden {
+            window_builder = window_builder.with_visible(false);
+        }
         let themed = if let Some(config) = config {
             if let Some(size) = config.size {
                 let size = if size.width == 0.0 || size.height == 0.0 {
@@ -254,8 +365,23 @@ impl ApplicationHandle {
                 window_builder =
                     window_builder.with_inner_size(LogicalSize::new(size.width, size.height));
             }
+            if let Some(size) = config.min_size {
+                window_builder =
+                    window_builder.with_min_inner_size(LogicalSize::new(size.width, size.height));
+            }
+            if let Some(size) = config.max_size {
+                window_builder =
+                    window_builder.with_max_inner_size(LogicalSize::new(size.width, size.height));
+            }
             if let Some(pos) = config.position {
-                window_builder = window_builder.with_position(LogicalPosition::new(pos.x, pos.y));
+                let size = config.size.unwrap_or(Size::new(800.0, 600.0));
+                if !config.validate_position || is_on_monitor(event_loop, pos, size) {
+                    window_builder =
+                        window_builder.with_position(LogicalPosition::new(pos.x, pos.y));
+                }
+            }
+            if let Some(maximized) = config.maximized {
+                window_builder = window_builder.with_maximized(maximized);
             }
             if let Some(show_titlebar) = config.show_titlebar {
                 #[cfg(target_os = "macos")]
@@ -276,12 +402,32 @@ impl ApplicationHandle {
             if let Some(transparent) = config.transparent {
                 window_builder = window_builder.with_transparent(transparent);
             }
+            if let Some(blur) = config.blur {
+                window_builder = window_builder.with_blur(blur);
+            }
             if let Some(fullscreen) = config.fullscreen {
                 window_builder = window_builder.with_fullscreen(Some(fullscreen));
             }
-            if let Some(window_level) = config.window_level {
-                window_builder = window_builder.with_window_level(window_level);
+            window_builder = window_builder.with_window_level(window_level);
+            if let Some(window_icon) = config.window_icon {
+                #[cfg(target_os = "macos")]
+                {
+                    let _ = window_icon;
+                    eprintln!("window icons are not supported on this platform");
+                }
+                #[cfg(not(target_os = "macos"))]
+                {
+                    window_builder = window_builder.with_window_icon(Some(window_icon));
+                }
             }
+            let resizable = config.resizable.unwrap_or(true);
+            let mut enabled_buttons = config.enabled_buttons.unwrap_or(WindowButtons::all());
+            if !resizable {
+                enabled_buttons.remove(WindowButtons::MAXIMIZE);
+            }
+            window_builder = window_builder
+                .with_resizable(resizable)
+                .with_enabled_buttons(enabled_buttons);
             if let Some(title) = config.title {
                 window_builder = window_builder.with_title(title);
             }
@@ -294,9 +440,59 @@ impl ApplicationHandle {
             Ok(window) => window,
             Err(_) => return,
         };
+        if centered {
+            self.center_window(&window, event_loop);
+        }
         let window_id = window.id();
-        let window_handle = WindowHandle::new(window, view_fn, transparent, themed);
+        let mut window_handle = WindowHandle::new(
+            window,
+            view_fn,
+            transparent,
+            themed,
+            window_level,
+            fallback_theme,
+        );
+        if let Some(scroll_line_height) = scroll_line_height {
+            window_handle.scroll_line_height = scroll_line_height;
+        }
+        if let Some(multi_click_interval) = multi_click_interval {
+            window_handle.multi_click_interval = multi_click_interval;
+        }
+        window_handle.show_frame_timing = show_frame_timing;
+        if hidden {
+            if let Some(window) = window_handle.window.as_ref() {
+                window.set_visible(true);
+            }
+        }
         self.window_handles.insert(window_id, window_handle);
+        WINDOW_IDS.with(|ids| ids.borrow_mut().push(window_id));
+    }
+
+    /// Center the window on the monitor of the focused window, or the primary monitor if
+    /// no window is focused.
+    fn center_window(
+        &self,
+        window: &winit::window::Window,
+        event_loop: &EventLoopWindowTarget<UserEvent>,
+    ) {
+        let monitor = self
+            .window_handles
+            .values()
+            .filter_map(|handle| handle.window.as_ref())
+            .find(|window| window.has_focus())
+            .and_then(|window| window.current_monitor())
+            .or_else(|| event_loop.primary_monitor())
+            .or_else(|| event_loop.available_monitors().next());
+        let Some(monitor) = monitor else {
+            return;
+        };
+        // Use the actual size of the window, which includes any size constraints
+        let window_size = window.outer_size();
+        let monitor_position = monitor.position();
+        let monitor_size = monitor.size();
+        let x = monitor_position.x + (monitor_size.width as i32 - window_size.width as i32) / 2;
+        let y = monitor_position.y + (monitor_size.height as i32 - window_size.height as i32) / 2;
+        window.set_outer_position(PhysicalPosition::new(x, y.max(monitor_position.y)));
     }
 
     fn close_window(
@@ -310,6 +506,7 @@ impl ApplicationHandle {
             handle.destroy();
         }
         self.window_handles.remove(&window_id);
+        WINDOW_IDS.with(|ids| ids.borrow_mut().retain(|id| *id != window_id));
         #[cfg(not(target_os = "macos"))]
         if self.window_handles.is_empty() {
             event_loop.exit();
@@ -336,6 +533,11 @@ impl ApplicationHandle {
         self.fire_timer(event_loop);
     }
 
+    fn remove_timer(&mut self, token: &TimerToken, event_loop: &EventLoopWindowTarget<UserEvent>) {
+        self.timers.remove(token);
+        self.fire_timer(event_loop);
+    }
+
     fn fire_timer(&mut self, event_loop: &EventLoopWindowTarget<UserEvent>) {
         if self.timers.is_empty() {
             return;
@@ -362,8 +564,17 @@ impl ApplicationHandle {
             .collect();
         if !tokens.is_empty() {
             for token in tokens {
-                if let Some(timer) = self.timers.remove(&token) {
+                // A cancellation may still be queued, in which case the timer must not fire.
+                if is_timer_cancelled(token) {
+                    self.timers.remove(&token);
+                    continue;
+                }
+                if let Some(mut timer) = self.timers.remove(&token) {
                     (timer.action)(token);
+                    if let Some(interval) = timer.interval {
+                        timer.deadline = timer.next_deadline(interval, Instant::now());
+                        self.timers.insert(token, timer);
+                    }
                 }
             }
             for (_, handle) in self.window_handles.iter_mut() {
@@ -373,3 +584,59 @@ impl ApplicationHandle {
         self.fire_timer(event_loop);
     }
 }
+
+pub(crate) fn is_timer_cancelled(token: TimerToken) -> bool {
+    APP_UPDATE_EVENTS.with(|events| {
+        events.borrow().iter().any(|event| {
+            matches!(event, AppUpdateEvent::CancelTimer { token: cancelled } if *cancelled == token)
+        })
+    })
+}
+
+/// Runs the timers requested so far which are due at `now` and weren't cancelled, as the event
+/// loop would. The other timers stay queued, and interval timers only fire once.
+#[cfg(test)]
+pub(crate) fn fire_due_timers(now: Instant) {
+    let events = APP_UPDATE_EVENTS.with(|events| std::mem::take(&mut *events.borrow_mut()));
+    let cancelled: Vec<TimerToken> = events
+        .iter()
+        .filter_map(|event| match event {
+            AppUpdateEvent::CancelTimer { token } => Some(*token),
+            _ => None,
+        })
+        .collect();
+    let mut due = Vec::new();
+    let pending = events
+        .into_iter()
+        .filter_map(|event| match event {
+            AppUpdateEvent::RequestTimer { timer } if timer.deadline <= now => {
+                if !cancelled.contains(&timer.token) {
+                    due.push(timer);
+                }
+                None
+            }
+            event => Some(event),
+        })
+        .collect();
+    APP_UPDATE_EVENTS.with(|events| *events.borrow_mut() = pending);
+    for mut timer in due {
+        (timer.action)(timer.token);
+    }
+}
+
+/// Check if the title bar area of a window at `pos` with `size` would be visible on any monitor.
+fn is_on_monitor(event_loop: &EventLoopWindowTarget<UserEvent>, pos: Point, size: Size) -> bool {
+    // Require a strip at the top of the window to be visible so it can still be moved
+    let title_bar = Rect::from_origin_size(pos, Size::new(size.width, 30.0));
+    event_loop.available_monitors().any(|monitor| {
+        let scale = monitor.scale_factor();
+        let origin: LogicalPosition<f64> = monitor.position().to_logical(scale);
+        let monitor_size: LogicalSize<f64> = monitor.size().to_logical(scale);
+        let monitor_rect = Rect::from_origin_size(
+            Point::new(origin.x, origin.y),
+            Size::new(monitor_size.width, monitor_size.height),
+        );
+        let visible = monitor_rect.intersect(title_bar);
+        visible.width() >= 50.0_f64.min(size.width) && visible.height() > 0.0
+    })
+}
@@ -14,17 +14,61 @@ static CLIPBOARD: Lazy<Mutex<Option<Clipboard>>> = Lazy::new(|| Mutex::new(None)
 
 pub struct Clipboard {
     clipboard: Box<dyn ClipboardProvider>,
-    #[allow(dead_code)]
     selection: Option<Box<dyn ClipboardProvider>>,
 }
 
 #[derive(Clone, Debug)]
 pub enum ClipboardError {
+    /// The clipboard could not be initialized, for example because there is no display server,
+    /// or the platform has no primary selection.
     NotAvailable,
     ProviderError(String),
 }
 
+impl std::fmt::Display for ClipboardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClipboardError::NotAvailable => f.write_str("clipboard is not available"),
+            ClipboardError::ProviderError(e) => write!(f, "clipboard error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ClipboardError {}
+
 impl Clipboard {
+    /// Get the text content of the clipboard.
+    pub fn get_text() -> Result<String, ClipboardError> {
+        Self::get_contents()
+    }
+
+    /// Replace the content of the clipboard with `text`.
+    pub fn set_text(text: &str) -> Result<(), ClipboardError> {
+        Self::set_contents(text.to_string())
+    }
+
+    /// Get the text of the primary selection. This is only available on X11 and Wayland.
+    pub fn get_selection_text() -> Result<String, ClipboardError> {
+        CLIPBOARD
+            .lock()
+            .as_mut()
+            .and_then(|clipboard| clipboard.selection.as_mut())
+            .ok_or(ClipboardError::NotAvailable)?
+            .get_contents()
+            .map_err(|e| ClipboardError::ProviderError(e.to_string()))
+    }
+
+    /// Replace the primary selection with `text`. This is only available on X11 and Wayland.
+    pub fn set_selection_text(text: &str) -> Result<(), ClipboardError> {
+        CLIPBOARD
+            .lock()
+            .as_mut()
+            .and_then(|clipboard| clipboard.selection.as_mut())
+            .ok_or(ClipboardError::NotAvailable)?
+            .set_contents(text.to_string())
+            .map_err(|e| ClipboardError::ProviderError(e.to_string()))
+    }
+
     pub fn get_contents() -> Result<String, ClipboardError> {
         CLIPBOARD
             .lock()
@@ -46,36 +90,43 @@ impl Clipboard {
     }
 
     pub(crate) unsafe fn init(display: RawDisplayHandle) {
-        *CLIPBOARD.lock() = Some(Self::new(display));
+        match Self::new(display) {
+            Ok(clipboard) => *CLIPBOARD.lock() = Some(clipboard),
+            Err(e) => eprintln!("failed to initialize the clipboard: {e}"),
+        }
     }
 
     /// # Safety
     /// The `display` must be valid as long as the returned Clipboard exists.
     unsafe fn new(
         #[allow(unused_variables)] /* on some platforms */ display: RawDisplayHandle,
-    ) -> Self {
+    ) -> Result<Self, ClipboardError> {
         #[cfg(not(any(target_os = "macos", windows)))]
         if let RawDisplayHandle::Wayland(display) = display {
             let (selection, clipboard) =
                 wayland_clipboard::create_clipboards_from_external(display.display);
-            return Self {
+            return Ok(Self {
                 clipboard: Box::new(clipboard),
                 selection: Some(Box::new(selection)),
-            };
+            });
         }
 
+        let clipboard =
+            ClipboardContext::new().map_err(|e| ClipboardError::ProviderError(e.to_string()))?;
+
         #[cfg(not(any(target_os = "macos", windows)))]
-        return Self {
-            clipboard: Box::new(ClipboardContext::new().unwrap()),
-            selection: Some(Box::new(
-                X11ClipboardContext::<X11SelectionClipboard>::new().unwrap(),
-            )),
-        };
+        return Ok(Self {
+            clipboard: Box::new(clipboard),
+            // The primary selection is optional, so don't fail if it is missing
+            selection: X11ClipboardContext::<X11SelectionClipboard>::new()
+                .ok()
+                .map(|selection| Box::new(selection) as Box<dyn ClipboardProvider>),
+        });
 
         #[cfg(any(target_os = "macos", windows))]
-        return Self {
-            clipboard: Box::new(ClipboardContext::new().unwrap()),
+        return Ok(Self {
+            clipboard: Box::new(clipboard),
             selection: None,
-        };
+        });
     }
 }
@@ -1,5 +1,7 @@
-use floem_renderer::Renderer as FloemRenderer;
-use kurbo::{Affine, Insets, Point, Rect, RoundedRect, Shape, Size, Vec2};
+use floem_renderer::{cosmic_text::TextLayout, Img, Renderer as FloemRenderer, Svg};
+use indexmap::IndexMap;
+use kurbo::{Affine, Point, Rect, RoundedRect, RoundedRectRadii, Shape, Size, Vec2};
+use peniko::BrushRef;
 use std::{
     any::Any,
     collections::{HashMap, HashSet},
@@ -9,24 +11,26 @@ use std::{
 };
 use taffy::{
     prelude::{Layout, Node},
-    style::{AvailableSpace, Display},
+    style::{
+        AlignItems, AlignSelf, AvailableSpace, Dimension, Display, LengthPercentage, Position,
+    },
+    tree::LayoutTree,
 };
 use winit::window::CursorIcon;
 
 use crate::{
     action::{exec_after, show_context_menu},
     animate::AnimId,
-    event::{Event, EventListener},
+    event::{DragEvent, Event, EventListener},
     id::Id,
     inspector::CaptureState,
     menu::Menu,
     responsive::{GridBreakpoints, ScreenSizeBp},
     style::{
-        BuiltinStyle, CursorStyle, DisplayProp, Style, StyleClassRef, StyleProp, StyleSelector,
-        ZIndex,
+        BackdropBlur, BuiltinStyle, CursorStyle, DisplayProp, Opacity, Overflow, OverflowProp,
+        Style, StyleClassRef, StyleProp, StyleSelector, ZIndex,
     },
-    unit::PxPct,
-    view::{paint_bg, paint_border, paint_outline, View, ViewData},
+    view::{paint_bg, paint_border, paint_bounds, paint_outline, View, ViewData},
     view_data::ChangeFlags,
 };
 
@@ -72,6 +76,18 @@ pub struct DragState {
     pub(crate) id: Id,
     pub(crate) offset: Vec2,
     pub(crate) released_at: Option<std::time::Instant>,
+    /// The payload made by the dragged view when the drag started
+    pub(crate) payload: Option<Rc<dyn Any>>,
+    /// The drop targets accepting the payload which it's been dragged onto
+    pub(crate) entered: HashSet<Id>,
+    /// Whether the innermost drop target under the pointer accepts the payload
+    pub(crate) target_accepts: Option<bool>,
+}
+
+/// A view receiving the payloads of drags which it accepts.
+pub(crate) struct DropTarget {
+    pub(crate) accepts: Box<dyn Fn(&dyn Any) -> bool>,
+    pub(crate) on_event: Box<dyn Fn(DragEvent<dyn Any>)>,
 }
 
 pub(crate) enum FrameUpdate {
@@ -88,6 +104,9 @@ pub struct AppState {
     /// when a view is active, it gets mouse event even when the mouse is
     /// not on it
     pub(crate) active: Option<Id>,
+    /// when a view has captured the pointer, it gets all pointer events until
+    /// the pointer is released
+    pub(crate) pointer_capture: Option<Id>,
     pub(crate) root: Option<Node>,
     pub(crate) root_size: Size,
     pub(crate) scale: f64,
@@ -96,9 +115,29 @@ pub struct AppState {
     stale_view_state: ViewState,
     pub(crate) scheduled_updates: Vec<FrameUpdate>,
     pub(crate) request_compute_layout: bool,
+    /// The views which requested layout since the last layout
+    pub(crate) layout_requests: HashSet<Id>,
+    /// Whether the whole tree has to be laid out again, like when the window is resized
+    pub(crate) full_layout: bool,
     pub(crate) request_paint: bool,
+    /// The window area which has to be repainted on the next frame
+    pub(crate) damage: Option<Rect>,
+    /// Whether the whole window has to be repainted on the next frame
+    pub(crate) full_repaint: bool,
+    /// The window areas sampled by the views blurring their backdrop on the last frame
+    pub(crate) backdrops: Vec<Rect>,
     pub(crate) disabled: HashSet<Id>,
+    /// Disabled views in this set absorb pointer presses instead of letting them through
+    pub(crate) disabled_absorbing: HashSet<Id>,
     pub(crate) keyboard_navigable: HashSet<Id>,
+    /// Views which can't be focused, even if they're made keyboard navigable later
+    pub(crate) focus_disabled: HashSet<Id>,
+    pub(crate) tab_index: HashMap<Id, i32>,
+    /// The access keys of views in the order they were set
+    pub(crate) access_keys: IndexMap<Id, char>,
+    /// Whether Alt is held, which underlines the access keys
+    pub(crate) show_access_keys: bool,
+    pub(crate) focus_traps: HashSet<Id>,
     pub(crate) draggable: HashSet<Id>,
     pub(crate) dragging: Option<DragState>,
     pub(crate) drag_start: Option<(Id, Point)>,
@@ -114,6 +153,8 @@ pub struct AppState {
     pub(crate) keyboard_navigation: bool,
     pub(crate) window_menu: HashMap<usize, Box<dyn Fn()>>,
     pub(crate) context_menu: HashMap<usize, Box<dyn Fn()>>,
+    /// The mounted listeners of new views, which are called after they're laid out
+    pub(crate) mounted_listeners: Vec<(Id, Box<dyn FnOnce()>)>,
 
     /// This is set if we're currently capturing the window for the inspector.
     pub(crate) capture: Option<CaptureState>,
@@ -133,6 +174,7 @@ impl AppState {
             root: None,
             focus: None,
             active: None,
+            pointer_capture: None,
             scale: 1.0,
             root_size: Size::ZERO,
             screen_size_bp: ScreenSizeBp::Xs,
@@ -141,9 +183,20 @@ impl AppState {
             view_states: HashMap::new(),
             scheduled_updates: Vec::new(),
             request_paint: false,
+            damage: None,
+            full_repaint: false,
+            backdrops: Vec::new(),
             request_compute_layout: false,
+            layout_requests: HashSet::new(),
+            full_layout: true,
             disabled: HashSet::new(),
+            disabled_absorbing: HashSet::new(),
             keyboard_navigable: HashSet::new(),
+            focus_disabled: HashSet::new(),
+            tab_index: HashMap::new(),
+            access_keys: IndexMap::new(),
+            show_access_keys: false,
+            focus_traps: HashSet::new(),
             draggable: HashSet::new(),
             dragging: None,
             drag_start: None,
@@ -156,6 +209,7 @@ impl AppState {
             grid_bps: GridBreakpoints::default(),
             window_menu: HashMap::new(),
             context_menu: HashMap::new(),
+            mounted_listeners: Vec::new(),
             capture: None,
         }
     }
@@ -178,10 +232,22 @@ impl AppState {
             false
         });
         let id = view.id();
+        let captured = self.pointer_capture == Some(id);
+        if captured {
+            self.pointer_capture = None;
+        }
+        // The pointer leaves the view as it's removed from under it, even if the pointer is
+        // captured
+        if self.hovered.remove(&id) || captured {
+            if let Some(action) = self.get_event_listener(id, &EventListener::PointerLeave) {
+                (*action)(&Event::PointerLeave);
+            }
+        }
         let view_state = self.view_state(id);
-        if let Some(action) = view_state.cleanup_listener.as_ref() {
+        for action in &view_state.cleanup_listeners {
             action();
         }
+        let scopes = std::mem::take(&mut view_state.scopes);
         let node = view_state.node;
         if let Ok(children) = self.taffy.children(node) {
             for child in children {
@@ -192,18 +258,27 @@ impl AppState {
         id.remove_id_path();
         self.view_states.remove(&id);
         self.disabled.remove(&id);
+        self.disabled_absorbing.remove(&id);
         self.keyboard_navigable.remove(&id);
+        self.focus_disabled.remove(&id);
+        self.tab_index.remove(&id);
+        self.access_keys.shift_remove(&id);
+        self.focus_traps.remove(&id);
         self.draggable.remove(&id);
         self.dragging_over.remove(&id);
         self.clicking.remove(&id);
-        self.hovered.remove(&id);
-        self.clicking.remove(&id);
         if self.focus == Some(id) {
             self.focus = None;
         }
         if self.active == Some(id) {
             self.active = None;
         }
+        if !self.mounted_listeners.is_empty() {
+            self.mounted_listeners.retain(|(view, _)| *view != id);
+        }
+        for scope in scopes {
+            scope.dispose();
+        }
     }
 
     pub fn is_hidden(&self, id: Id) -> bool {
@@ -222,12 +297,49 @@ impl AppState {
             .any(|id| self.is_hidden(*id))
     }
 
+    /// Is this view, or any parent view, disabled
+    pub fn is_disabled_recursive(&self, id: Id) -> bool {
+        id.id_path()
+            .map(|id_path| id_path.dispatch().iter().any(|id| self.is_disabled(id)))
+            .unwrap_or(false)
+    }
+
     pub(crate) fn can_focus(&self, id: Id) -> bool {
         self.keyboard_navigable.contains(&id)
-            && !self.is_disabled(&id)
+            && !self.is_disabled_recursive(id)
             && !self.is_hidden_recursive(id)
     }
 
+    /// The access key to underline in a label with the id `id`, which is the access key of the
+    /// closest view containing it that has one, while Alt is held.
+    pub(crate) fn shown_access_key(&self, id: Id) -> Option<char> {
+        if !self.show_access_keys {
+            return None;
+        }
+        let id_path = id.id_path()?;
+        id_path
+            .dispatch()
+            .iter()
+            .rev()
+            .find_map(|id| self.access_keys.get(id).copied())
+    }
+
+    /// The position of the view in the tab order, see [`Decorators::tab_index`](crate::views::Decorators::tab_index).
+    pub(crate) fn tab_index(&self, id: Id) -> i32 {
+        self.tab_index.get(&id).copied().unwrap_or(0)
+    }
+
+    /// The innermost focus trap containing the focused view.
+    pub(crate) fn focus_trap(&self) -> Option<Id> {
+        let id_path = self.focus?.id_path()?;
+        id_path
+            .dispatch()
+            .iter()
+            .rev()
+            .find(|id| self.focus_traps.contains(id))
+            .copied()
+    }
+
     pub fn is_hovered(&self, id: &Id) -> bool {
         self.hovered.contains(id)
     }
@@ -255,8 +367,78 @@ impl AppState {
             .unwrap_or(false)
     }
 
+    /// Send the payload being dragged to the drop target `id` as it's moved over `pos`.
+    fn drag_over(&mut self, id: Id, pos: Point) {
+        let Some(dragging) = self.dragging.as_mut() else {
+            return;
+        };
+        let Some(payload) = dragging.payload.clone() else {
+            return;
+        };
+        let Some(target) = self
+            .view_states
+            .get(&id)
+            .and_then(|view_state| view_state.drop_target.as_ref())
+        else {
+            return;
+        };
+        let accepts = (target.accepts)(&*payload);
+        if accepts {
+            if dragging.entered.insert(id) {
+                (target.on_event)(DragEvent::Enter(&*payload));
+            }
+            (target.on_event)(DragEvent::Over(&*payload, pos));
+        }
+        // Targets are given the event before the targets they're inside of
+        dragging.target_accepts.get_or_insert(accepts);
+    }
+
+    /// Drop the payload being dragged on the drop target `id` at `pos`, returning whether it
+    /// was accepted.
+    fn drop_payload(&mut self, id: Id, pos: Point) -> bool {
+        let Some(dragging) = self.dragging.as_mut() else {
+            return false;
+        };
+        let Some(payload) = dragging.payload.clone() else {
+            return false;
+        };
+        let Some(target) = self
+            .view_states
+            .get(&id)
+            .and_then(|view_state| view_state.drop_target.as_ref())
+            .filter(|target| (target.accepts)(&*payload))
+        else {
+            return false;
+        };
+        dragging.entered.remove(&id);
+        (target.on_event)(DragEvent::Drop(&*payload, pos));
+        true
+    }
+
+    /// Send [`DragEvent::Leave`] to the drop targets the payload has been dragged onto, unless
+    /// it's still over them.
+    pub(crate) fn leave_drop_targets(&mut self, over: &HashSet<Id>) {
+        let Some(dragging) = self.dragging.as_mut() else {
+            return;
+        };
+        let view_states = &self.view_states;
+        dragging.entered.retain(|id| {
+            if over.contains(id) {
+                return true;
+            }
+            if let Some(target) = view_states
+                .get(id)
+                .and_then(|view_state| view_state.drop_target.as_ref())
+            {
+                (target.on_event)(DragEvent::Leave);
+            }
+            false
+        });
+    }
+
     pub fn set_root_size(&mut self, size: Size) {
         self.root_size = size;
+        self.full_layout = true;
         self.compute_layout();
     }
 
@@ -293,7 +475,19 @@ impl AppState {
         self.get_computed_style(id).builtin()
     }
 
+    /// Run taffy for the views which requested layout. Only the closest ancestors of them
+    /// which keep their layout regardless of what's inside them are laid out again, unless
+    /// the whole tree has to be.
     pub fn compute_layout(&mut self) {
+        let requests = std::mem::take(&mut self.layout_requests);
+        if !std::mem::take(&mut self.full_layout) && !requests.is_empty() {
+            if let Some(roots) = self.relayout_roots(&requests) {
+                for root in roots {
+                    self.compute_subtree_layout(root);
+                }
+                return;
+            }
+        }
         if let Some(root) = self.root {
             let _ = self.taffy.compute_layout(
                 root,
@@ -305,6 +499,96 @@ impl AppState {
         }
     }
 
+    /// The views to lay out again for the layout requests of `requests`, or `None` if the whole
+    /// tree has to be laid out again.
+    fn relayout_roots(&self, requests: &HashSet<Id>) -> Option<Vec<Id>> {
+        let mut roots: Vec<Id> = Vec::new();
+        for id in requests {
+            // Removed views are laid out with their parent
+            let Some(id_path) = id.id_path() else {
+                continue;
+            };
+            let path = id_path.dispatch();
+            // The size of the requesting view itself may have changed, so it's laid out by an
+            // ancestor. A change inside of a view propagates up until one keeps its layout.
+            let root = (1..path.len().saturating_sub(1))
+                .rev()
+                .find(|&i| self.has_fixed_layout(path[i], path[i - 1]))?;
+            let root = path[root];
+            if !roots.contains(&root) {
+                roots.push(root);
+            }
+        }
+        // Views inside of other roots are laid out with them
+        let within_other_root = |id: &Id| {
+            id.id_path().map_or(false, |id_path| {
+                let path = id_path.dispatch();
+                path[..path.len() - 1]
+                    .iter()
+                    .any(|ancestor| roots.contains(ancestor))
+            })
+        };
+        let independent = roots.iter().copied().filter(|id| !within_other_root(id));
+        Some(independent.collect())
+    }
+
+    /// Whether the layout of the view `id` with the parent `parent` can't change when what's
+    /// inside of it changes.
+    fn has_fixed_layout(&self, id: Id, parent: Id) -> bool {
+        let style = |id: Id| {
+            let node = self.view_states.get(&id)?.node;
+            self.taffy.style(node).ok()
+        };
+        let (Some(style), Some(parent)) = (style(id), style(parent)) else {
+            return false;
+        };
+        let points = |dimension: Dimension| matches!(dimension, Dimension::Points(_));
+        let points_or_auto =
+            |dimension: Dimension| matches!(dimension, Dimension::Points(_) | Dimension::Auto);
+        let edges_in_points = |edges: &taffy::geometry::Rect<LengthPercentage>| {
+            [edges.left, edges.right, edges.top, edges.bottom]
+                .iter()
+                .all(|edge| matches!(edge, LengthPercentage::Points(_)))
+        };
+        // The automatic minimum size of flex and grid items depends on their contents, unless
+        // the item can't shrink
+        let content_independent_min = style.position == Position::Absolute
+            || (points(style.min_size.width) && points(style.min_size.height))
+            || (parent.display == Display::Flex && style.flex_shrink == 0.0);
+        points(style.size.width)
+            && points(style.size.height)
+            && points_or_auto(style.max_size.width)
+            && points_or_auto(style.max_size.height)
+            && content_independent_min
+            // Percentages are relative to the parent, which isn't known when laying out the view
+            // on its own
+            && edges_in_points(&style.padding)
+            && edges_in_points(&style.border)
+            // Baselines depend on the contents
+            && style.align_self != Some(AlignSelf::Baseline)
+            && parent.align_items != Some(AlignItems::Baseline)
+    }
+
+    /// Run taffy for the subtree of the view `id`, which keeps its size and location.
+    fn compute_subtree_layout(&mut self, id: Id) {
+        let Some(node) = self.view_states.get(&id).map(|view_state| view_state.node) else {
+            return;
+        };
+        let Ok(layout) = self.taffy.layout(node).copied() else {
+            return;
+        };
+        let _ = self.taffy.compute_layout(
+            node,
+            taffy::prelude::Size {
+                width: AvailableSpace::Definite(layout.size.width),
+                height: AvailableSpace::Definite(layout.size.height),
+            },
+        );
+        // Laying out the view on its own puts it at the origin, so it's moved back to where its
+        // parent put it
+        self.taffy.layout_mut(node).location = layout.location;
+    }
+
     /// Requests style for a view and all direct and indirect children.
     pub fn request_style_recursive(&mut self, id: Id) {
         let view = self.view_state(id);
@@ -315,6 +599,7 @@ impl AppState {
     /// Request that this the `id` view be styled, laid out and painted again.
     /// This will recursively request this for all parents.
     pub fn request_all(&mut self, id: Id) {
+        self.layout_requests.insert(id);
         self.request_changes(id, ChangeFlags::all());
         self.request_paint(id);
     }
@@ -353,6 +638,7 @@ impl AppState {
     }
 
     pub fn request_layout(&mut self, id: Id) {
+        self.layout_requests.insert(id);
         self.request_changes(id, ChangeFlags::LAYOUT)
     }
 
@@ -361,9 +647,18 @@ impl AppState {
         self.request_compute_layout = true;
     }
 
-    // `Id` is unused currently, but could be used to calculate damage regions.
-    pub fn request_paint(&mut self, _id: Id) {
+    /// Requests that `id` is painted again. Only the area it and its children painted on the
+    /// last frame is repainted, unless it wasn't painted yet. If they paint elsewhere now, the
+    /// area they paint is included.
+    pub fn request_paint(&mut self, id: Id) {
         self.request_paint = true;
+        match self.view_state(id).painted_rect {
+            Some(rect) if rect.is_empty() => {}
+            Some(rect) => {
+                self.damage = Some(self.damage.map_or(rect, |damage| damage.union(rect)));
+            }
+            None => self.full_repaint = true,
+        }
     }
 
     /// `viewport` is relative to the `id` view.
@@ -388,17 +683,100 @@ impl AppState {
             .map(|layout| layout.size)
             .unwrap_or_default();
         let rect = Size::new(size.width as f64, size.height as f64).to_rect();
-        let view_state = self.view_state(id);
-        let props = &view_state.layout_props;
-        let pixels = |px_pct, abs| match px_pct {
-            PxPct::Px(v) => v,
-            PxPct::Pct(pct) => pct * abs,
-        };
-        rect.inset(-Insets {
-            x0: props.border_left().0 + pixels(props.padding_left(), rect.width()),
-            x1: props.border_right().0 + pixels(props.padding_right(), rect.width()),
-            y0: props.border_top().0 + pixels(props.padding_top(), rect.height()),
-            y1: props.border_bottom().0 + pixels(props.padding_bottom(), rect.height()),
+        rect - self.view_state(id).content_insets(rect.size())
+    }
+
+    /// The `z_index` of a view, which orders it among its siblings.
+    pub(crate) fn z_index(&self, id: Id) -> i32 {
+        self.view_states
+            .get(&id)
+            .and_then(|view_state| view_state.combined_style.get(ZIndex))
+            .unwrap_or(0)
+    }
+
+    /// The rounded border box of a view, relative to the view, if its `overflow` style hides
+    /// children outside of it. Pointer events in the rounded off corners miss the view.
+    pub(crate) fn overflow_border_box(&self, id: Id) -> Option<RoundedRect> {
+        let style = &self.view_states.get(&id)?.combined_style;
+        if style.get(OverflowProp) == Overflow::Visible {
+            return None;
+        }
+        let layout = self.get_layout(id)?;
+        let size = Size::new(layout.size.width as f64, layout.size.height as f64);
+        let radii = style.border_radii(size).unwrap_or_default();
+        Some(size.to_rect().to_rounded_rect(radii))
+    }
+
+    /// The shape the children of a view are clipped to, relative to the view,
+    /// if its `overflow` style hides children outside of it. This is the content box, inside
+    /// of the border and padding.
+    pub(crate) fn overflow_clip(&self, id: Id) -> Option<RoundedRect> {
+        let border_box = self.overflow_border_box(id)?;
+        let insets = self
+            .view_states
+            .get(&id)?
+            .content_insets(border_box.rect().size());
+        let rect = border_box.rect() - insets;
+        // The corners of the content box are rounded less
+        let radii = border_box.radii();
+        let inner = |radius: f64, a: f64, b: f64| (radius - a.max(b)).max(0.0);
+        let radii = RoundedRectRadii::new(
+            inner(radii.top_left, insets.x0, insets.y0),
+            inner(radii.top_right, insets.x1, insets.y0),
+            inner(radii.bottom_right, insets.x1, insets.y1),
+            inner(radii.bottom_left, insets.x0, insets.y1),
+        );
+        Some(rect.to_rounded_rect(radii))
+    }
+
+    /// Where `point`, which is relative to the parent of `id`, is in the view `id`, if the view
+    /// is hit by pointer events there. Transparent views and the clipped away corners of views
+    /// aren't hit.
+    pub(crate) fn hit_test(&self, id: Id, point: Point) -> Option<Point> {
+        if self.is_transparent(id) {
+            return None;
+        }
+        // Children aren't hit outside of the content box of a parent clipping them
+        if let Some(clip) = id.parent().and_then(|parent| {
+            let viewport = self.view_states.get(&parent)?.viewport;
+            let offset = viewport
+                .map(|rect| rect.origin().to_vec2())
+                .unwrap_or_default();
+            Some(self.overflow_clip(parent)? + offset)
+        }) {
+            if !clip.contains(point) {
+                return None;
+            }
+        }
+        let layout_rect = self.view_states.get(&id)?.layout_rect;
+        let layout = self.get_layout(id)?;
+        let origin = Point::new(layout.location.x as f64, layout.location.y as f64);
+        let mut point = point - origin.to_vec2();
+        if let Some(transform) = self.view_transform(id) {
+            point = transform.inverse() * point;
+        }
+        if !layout_rect.with_origin(Point::ZERO).contains(point) {
+            return None;
+        }
+        match self.overflow_border_box(id) {
+            Some(border_box) if !border_box.contains(point) => None,
+            _ => Some(point),
+        }
+    }
+
+    /// The transform of a view from its style, relative to its layout location.
+    pub(crate) fn view_transform(&self, id: Id) -> Option<Affine> {
+        let view_state = self.view_states.get(&id)?;
+        let layout = self.get_layout(id)?;
+        let size = Size::new(layout.size.width as f64, layout.size.height as f64);
+        view_state.combined_style.resolved_transform(size)
+    }
+
+    /// Whether a view has an opacity of 0, so it and its children aren't painted and
+    /// don't receive pointer events.
+    pub(crate) fn is_transparent(&self, id: Id) -> bool {
+        self.view_states.get(&id).map_or(false, |view_state| {
+            view_state.combined_style.get(Opacity) <= 0.0
         })
     }
 
@@ -505,6 +883,8 @@ impl AppState {
             .and_then(|s| s.event_listeners.get(listener))
     }
 
+    /// Restyle the views which gained and lost the focus. The window handle sends them the
+    /// focus events.
     pub(crate) fn focus_changed(&mut self, old: Option<Id>, new: Option<Id>) {
         if let Some(id) = new {
             // To apply the styles of the Focus selector
@@ -513,9 +893,6 @@ impl AppState {
             {
                 self.request_style_recursive(id);
             }
-            if let Some(action) = self.get_event_listener(id, &EventListener::FocusGained) {
-                (*action)(&Event::FocusGained);
-            }
         }
 
         if let Some(old_id) = old {
@@ -525,9 +902,6 @@ impl AppState {
             {
                 self.request_style_recursive(old_id);
             }
-            if let Some(action) = self.get_event_listener(old_id, &EventListener::FocusLost) {
-                (*action)(&Event::FocusLost);
-            }
         }
     }
 }
@@ -620,11 +994,6 @@ impl<'a> EventCx<'a> {
             // we don't process events for hidden view
             return EventPropagation::Continue;
         }
-        if self.app_state.is_disabled(&id) && !event.allow_disabled() {
-            // if the view is disabled and the event is not processed
-            // for disabled views
-            return EventPropagation::Continue;
-        }
 
         // offset the event positions if the event has positions
         // e.g. pointer events, so that the position is relative
@@ -632,6 +1001,25 @@ impl<'a> EventCx<'a> {
         // of the view and the viewport of the view if it's in a scroll.
         let event = self.offset_event(id, event);
 
+        if self.app_state.is_disabled(&id) && !event.allow_disabled() {
+            // if the view is disabled and the event is not processed
+            // for disabled views, it's passed on to the views below unless
+            // the view absorbs pointer presses on it
+            let absorbed = self.app_state.disabled_absorbing.contains(&id)
+                && matches!(event, Event::PointerDown(_) | Event::PointerUp(_))
+                && event.point().map_or(false, |pos| {
+                    self.get_size(id)
+                        .unwrap_or_default()
+                        .to_rect()
+                        .contains(pos)
+                });
+            return if absorbed {
+                EventPropagation::Stop
+            } else {
+                EventPropagation::Continue
+            };
+        }
+
         // if there's id_path, it's an event only for a view.
         if let Some(id_path) = id_path {
             if id_path.is_empty() {
@@ -732,6 +1120,7 @@ impl<'a> EventCx<'a> {
                 if rect.contains(pointer_event.pos) {
                     if self.app_state.is_dragging() {
                         self.app_state.dragging_over.insert(id);
+                        self.app_state.drag_over(id, pointer_event.pos);
                         if let Some(action) = self.get_event_listener(id, &EventListener::DragOver)
                         {
                             (*action)(&event);
@@ -768,10 +1157,19 @@ impl<'a> EventCx<'a> {
                             // start dragging when moved 1 px
                             self.app_state.active = None;
                             self.update_active(id);
+                            let payload = self
+                                .app_state
+                                .view_states
+                                .get(&id)
+                                .and_then(|view_state| view_state.drag_payload.as_ref())
+                                .map(|payload| payload());
                             self.app_state.dragging = Some(DragState {
                                 id,
                                 offset: vec2,
                                 released_at: None,
+                                payload,
+                                entered: HashSet::new(),
+                                target_accepts: None,
                             });
                             id.request_paint();
                             if let Some(action) =
@@ -795,22 +1193,23 @@ impl<'a> EventCx<'a> {
 
                     if id_path.is_none() {
                         if on_view {
-                            if let Some(dragging) = self.app_state.dragging.as_mut() {
+                            if let Some(dragging) = self.app_state.dragging.as_ref() {
                                 let dragging_id = dragging.id;
-                                if let Some(action) =
-                                    self.get_event_listener(id, &EventListener::Drop)
-                                {
-                                    if (*action)(&event).is_processed() {
-                                        // if the drop is processed, we set dragging to none so that the animation
-                                        // for the dragged view back to its original position isn't played.
-                                        self.app_state.dragging = None;
-                                        id.request_paint();
-                                        if let Some(action) = self.get_event_listener(
-                                            dragging_id,
-                                            &EventListener::DragEnd,
-                                        ) {
-                                            (*action)(&event);
-                                        }
+                                let dropped = self.app_state.drop_payload(id, pointer_event.pos)
+                                    || self
+                                        .get_event_listener(id, &EventListener::Drop)
+                                        .map(|action| (*action)(&event).is_processed())
+                                        .unwrap_or(false);
+                                if dropped {
+                                    self.app_state.leave_drop_targets(&HashSet::new());
+                                    // if the drop is processed, we set dragging to none so that the animation
+                                    // for the dragged view back to its original position isn't played.
+                                    self.app_state.dragging = None;
+                                    id.request_paint();
+                                    if let Some(action) = self
+                                        .get_event_listener(dragging_id, &EventListener::DragEnd)
+                                    {
+                                        (*action)(&event);
                                     }
                                 }
                             }
@@ -949,9 +1348,15 @@ impl<'a> EventCx<'a> {
             .and_then(|view| view.viewport);
 
         if let Some(layout) = self.get_layout(id) {
+            let event = event.offset((layout.location.x as f64, layout.location.y as f64));
+            // The transform is applied before the viewport when painting
+            let event = match self.app_state.view_transform(id) {
+                Some(transform) => event.transform(transform.inverse()),
+                None => event,
+            };
             event.offset((
-                layout.location.x as f64 - viewport.map(|rect| rect.x0).unwrap_or(0.0),
-                layout.location.y as f64 - viewport.map(|rect| rect.y0).unwrap_or(0.0),
+                -viewport.map(|rect| rect.x0).unwrap_or(0.0),
+                -viewport.map(|rect| rect.y0).unwrap_or(0.0),
             ))
         } else {
             event
@@ -966,22 +1371,9 @@ impl<'a> EventCx<'a> {
         {
             return false;
         }
-        if let Some(point) = event.point() {
-            let layout_rect = self.app_state.get_layout_rect(id);
-            if let Some(layout) = self.get_layout(id) {
-                if layout_rect
-                    .with_origin(Point::new(
-                        layout.location.x as f64,
-                        layout.location.y as f64,
-                    ))
-                    .contains(point)
-                {
-                    return true;
-                }
-            }
-            false
-        } else {
-            true
+        match event.point() {
+            Some(point) => self.app_state.hit_test(id, point).is_some(),
+            None => true,
         }
     }
 }
@@ -1297,10 +1689,11 @@ impl<'a> ComputeLayoutCx<'a> {
         let child_layout_rect = view.compute_layout(self);
 
         let layout_rect = size.to_rect().with_origin(self.window_origin);
-        let layout_rect = if let Some(child_layout_rect) = child_layout_rect {
-            layout_rect.union(child_layout_rect)
-        } else {
-            layout_rect
+        // Children clipped by `overflow` can't be hit outside of this view
+        let clips_children = self.app_state().overflow_border_box(id).is_some();
+        let layout_rect = match child_layout_rect {
+            Some(child_layout_rect) if !clips_children => layout_rect.union(child_layout_rect),
+            _ => layout_rect,
         };
         self.app_state_mut().view_state(id).layout_rect = layout_rect;
 
@@ -1387,6 +1780,15 @@ pub struct PaintCx<'a> {
     pub(crate) saved_transforms: Vec<Affine>,
     pub(crate) saved_clips: Vec<Option<RoundedRect>>,
     pub(crate) saved_z_indexes: Vec<Option<i32>>,
+    /// The window area which is repainted, or `None` if the whole frame is painted
+    pub(crate) damage: Option<Rect>,
+    /// The window area painted by the view being painted and its children painted so far
+    pub(crate) painted: Option<Rect>,
+    /// Whether a view painted outside of `damage`, where it didn't paint on the last frame
+    pub(crate) painted_outside_damage: bool,
+    /// Whether a view painted outside of the layer it's faded or transformed in, which only
+    /// covers where it painted on the last frame
+    pub(crate) painted_outside_layer: bool,
 }
 
 impl<'a> PaintCx<'a> {
@@ -1413,6 +1815,14 @@ impl<'a> PaintCx<'a> {
         }
     }
 
+    /// Paint in window coordinates without a clip, for things drawn over all views.
+    pub(crate) fn clear_transform(&mut self) {
+        self.transform = Affine::IDENTITY;
+        self.clip = None;
+        self.paint_state.renderer.transform(Affine::IDENTITY);
+        self.paint_state.renderer.clear_clip();
+    }
+
     /// The entry point for painting a view. You shouldn't need to implement this yourself. Instead, implement [`View::paint`].
     /// It handles the internal work before and after painting [`View::paint`] implementations.
     /// It is responsible for
@@ -1427,10 +1837,22 @@ impl<'a> PaintCx<'a> {
 
         self.save();
         let size = self.transform(id);
-        let is_empty = self
+        let transform = self.app_state.view_transform(id);
+        if let Some(transform) = transform {
+            self.apply_transform(transform);
+        }
+        // Nothing is painted outside of the clip
+        let clip = self
             .clip
-            .map(|rect| rect.rect().intersect(size.to_rect()).is_empty())
-            .unwrap_or(false);
+            .map(|clip| self.transform.transform_rect_bbox(clip.rect()));
+        let outer_painted = self.painted.take();
+        // Views scaled down to nothing or fully transparent aren't painted
+        let is_empty = transform.map_or(false, |transform| transform.determinant() == 0.0)
+            || self.app_state.is_transparent(id)
+            || self
+                .clip
+                .map(|rect| rect.rect().intersect(size.to_rect()).is_empty())
+                .unwrap_or(false);
         if !is_empty {
             let style = self.app_state.get_computed_style(id).clone();
             let view_style_props = self.app_state.view_state(id).view_style_props.clone();
@@ -1439,12 +1861,97 @@ impl<'a> PaintCx<'a> {
                 self.set_z_index(z_index);
             }
 
+            let bounds = paint_bounds(&style, &view_style_props, size);
+            self.painted = Some(self.transform.transform_rect_bbox(bounds));
+
+            // This comes before the layer of the view, which doesn't have what's behind it
+            let blur = style.get(BackdropBlur);
+            if blur > 0.0 {
+                let radii = view_style_props.border_radii(size).unwrap_or_default();
+                self.paint_state
+                    .renderer
+                    .blur_backdrop(&size.to_rect().to_rounded_rect(radii), blur);
+                let sampled = self.transform.transform_rect_bbox(size.to_rect());
+                self.app_state
+                    .backdrops
+                    .push(sampled.inflate(blur * 3.0, blur * 3.0));
+            }
+
+            // The view and its children are faded as a whole. They're also painted into a
+            // layer if they're rotated, scaled or skewed, as only layers are fully transformed
+            // by every renderer.
+            let opacity = style.get(Opacity).min(1.0);
+            let layered = opacity < 1.0
+                || transform.map_or(false, |transform| {
+                    let [a, b, c, d, _, _] = transform.as_coeffs();
+                    (a, b, c, d) != (1.0, 0.0, 0.0, 1.0)
+                });
+            // The layer holds where the view and its children painted on the last frame, or
+            // where they're laid out if they weren't painted yet
+            let layer_bounds = layered.then(|| {
+                let view_state = self.app_state.view_state(id);
+                let last_painted = match view_state.painted_rect {
+                    Some(rect) if !rect.is_empty() => rect,
+                    _ => view_state.layout_rect,
+                };
+                bounds.union(self.transform.inverse().transform_rect_bbox(last_painted))
+            });
+            if let Some(layer_bounds) = layer_bounds {
+                self.paint_state
+                    .renderer
+                    .push_layer(opacity as f32, layer_bounds);
+            }
+
             paint_bg(self, &style, &view_style_props, size);
 
-            view.paint(self);
+            if let Some(clip) = self.app_state.overflow_clip(id) {
+                self.save();
+                self.clip(&clip);
+                view.paint(self);
+                self.restore();
+            } else {
+                view.paint(self);
+            }
             paint_border(self, &view_style_props, size);
-            paint_outline(self, &view_style_props, size)
+            paint_outline(self, &view_style_props, size);
+
+            if let Some(layer_bounds) = layer_bounds {
+                self.paint_state.renderer.pop_layer();
+                // What's painted outside of the layer is cut off, so the frame is painted again
+                // with the layer covering it. Rounding errors aren't worth another paint.
+                let covered = self
+                    .transform
+                    .transform_rect_bbox(layer_bounds)
+                    .inflate(1.0, 1.0);
+                let painted = self
+                    .painted
+                    .map(|painted| clip.map_or(painted, |clip| painted.intersect(clip)));
+                if painted
+                    .is_some_and(|painted| !painted.is_empty() && covered.union(painted) != covered)
+                {
+                    self.painted_outside_layer = true;
+                }
+            }
+        }
+
+        // The children are included, as they may paint outside of the view
+        let painted = self
+            .painted
+            .take()
+            .map(|painted| clip.map_or(painted, |clip| painted.intersect(clip)))
+            .filter(|painted| !painted.is_empty())
+            .unwrap_or(Rect::ZERO);
+        let last_painted = self.app_state.view_state(id).painted_rect.replace(painted);
+        if let Some(damage) = self.damage {
+            if last_painted != Some(painted) && damage.union(painted) != damage {
+                self.painted_outside_damage = true;
+            }
         }
+        self.painted = match outer_painted {
+            Some(outer) if !painted.is_empty() => Some(outer.union(painted)),
+            Some(outer) => Some(outer),
+            None => (!painted.is_empty()).then_some(painted),
+        };
 
         let mut drag_set_to_none = false;
         if let Some(dragging) = self.app_state.dragging.as_ref() {
@@ -1561,6 +2068,61 @@ impl<'a> PaintCx<'a> {
         self.paint_state.renderer.clear_clip();
     }
 
+    /// Fill `shape`. Like the other drawing methods of `PaintCx`, this keeps track of where
+    /// the view paints, so that area is repainted when the view requests a paint.
+    pub fn fill<'b>(
+        &mut self,
+        shape: &impl Shape,
+        brush: impl Into<BrushRef<'b>>,
+        blur_radius: f64,
+    ) {
+        let extent = blur_radius.max(0.0);
+        self.add_painted(shape.bounding_box().inflate(extent, extent));
+        self.paint_state.renderer.fill(shape, brush, blur_radius);
+    }
+
+    /// Stroke `shape` with a line which is `width` wide.
+    pub fn stroke<'b>(&mut self, shape: &impl Shape, brush: impl Into<BrushRef<'b>>, width: f64) {
+        let extent = width.max(0.0) / 2.0;
+        self.add_painted(shape.bounding_box().inflate(extent, extent));
+        self.paint_state.renderer.stroke(shape, brush, width);
+    }
+
+    /// Draw `layout` with its upper-left corner at `pos`.
+    pub fn draw_text(&mut self, layout: &TextLayout, pos: impl Into<Point>) {
+        let pos = pos.into();
+        self.add_painted(layout.size().to_rect() + pos.to_vec2());
+        self.paint_state.renderer.draw_text(layout, pos);
+    }
+
+    pub fn draw_svg<'b>(
+        &mut self,
+        svg: Svg<'b>,
+        rect: Rect,
+        brush: Option<impl Into<BrushRef<'b>>>,
+    ) {
+        self.add_painted(rect);
+        self.paint_state.renderer.draw_svg(svg, rect, brush);
+    }
+
+    pub fn draw_img(&mut self, img: Img<'_>, rect: Rect) {
+        self.add_painted(rect);
+        self.paint_state.renderer.draw_img(img, rect);
+    }
+
+    /// Adds `rect`, in the current coordinates, to the window area painted by the view.
+    fn add_painted(&mut self, rect: Rect) {
+        let rect = match self.clip {
+            Some(clip) => rect.intersect(clip.rect()),
+            None => rect,
+        };
+        if rect.width() <= 0.0 || rect.height() <= 0.0 {
+            return;
+        }
+        let rect = self.transform.transform_rect_bbox(rect);
+        self.painted = Some(self.painted.map_or(rect, |painted| painted.union(rect)));
+    }
+
     pub fn offset(&mut self, offset: (f64, f64)) {
         let mut new = self.transform.as_coeffs();
         new[4] += offset.0;
@@ -1576,13 +2138,25 @@ impl<'a> PaintCx<'a> {
         }
     }
 
+    /// Apply `transform` to what's drawn after it, on top of the current transform.
+    pub fn apply_transform(&mut self, transform: Affine) {
+        self.transform = self.transform * transform;
+        self.paint_state.renderer.transform(self.transform);
+        if let Some(rect) = self.clip.as_mut() {
+            // The clip is only tracked as a rectangle, so this is its bounds for rotations
+            let radius = rect.radii();
+            *rect = transform
+                .inverse()
+                .transform_rect_bbox(rect.rect())
+                .to_rounded_rect(radius);
+        }
+    }
+
     pub fn transform(&mut self, id: Id) -> Size {
         if let Some(layout) = self.get_layout(id) {
             let offset = layout.location;
-            let mut new = self.transform.as_coeffs();
-            new[4] += offset.x as f64;
-            new[5] += offset.y as f64;
-            self.transform = Affine::new(new);
+            // The offset is in the coordinates of the parent, which may be transformed
+            self.transform = self.transform * Affine::translate((offset.x as f64, offset.y as f64));
             self.paint_state.renderer.transform(self.transform);
 
             if let Some(rect) = self.clip.as_mut() {
@@ -1615,12 +2189,18 @@ pub struct PaintState {
 }
 
 impl PaintState {
-    pub fn new<W>(window: &W, scale: f64, size: Size) -> Self
+    pub fn new<W>(window: &W, scale: f64, size: Size, transparent: bool) -> Self
     where
         W: raw_window_handle::HasRawDisplayHandle + raw_window_handle::HasRawWindowHandle,
     {
         Self {
-            renderer: crate::renderer::Renderer::new(window, scale, size),
+            renderer: crate::renderer::Renderer::new(window, scale, size, transparent),
+        }
+    }
+
+    pub(crate) fn new_headless(scale: f64, size: Size) -> Self {
+        Self {
+            renderer: crate::renderer::Renderer::new_headless(scale, size),
         }
     }
 
@@ -1692,3 +2272,97 @@ impl DerefMut for PaintCx<'_> {
         &mut self.paint_state.renderer
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::HashSet, rc::Rc};
+
+    use floem_reactive::{create_rw_signal, RwSignal};
+    use kurbo::Size;
+    use taffy::prelude::Layout;
+
+    use crate::{
+        id::Id,
+        view::View,
+        views::{container, empty, h_stack, Decorators},
+        window_handle::WindowHandle,
+    };
+
+    /// Builds a window with a leaf of the width `width` in a container, which has a fixed size
+    /// if `fixed`. The ids of the leaf, the container, the view after it and the root are
+    /// returned with the window.
+    fn window(width: RwSignal<f64>, fixed: bool) -> (WindowHandle, [Id; 4]) {
+        let ids = Rc::new(RefCell::new(None));
+        let view_ids = ids.clone();
+        let mut handle = WindowHandle::new_headless(
+            move |_| {
+                let leaf = empty().style(move |s| s.width(width.get()).height(10.0));
+                let leaf_id = leaf.id();
+                let sibling = empty().style(|s| s.size(20.0, 20.0));
+                let container = container(h_stack((leaf, sibling))).style(move |s| {
+                    if fixed {
+                        s.size(100.0, 100.0).flex_shrink(0.0)
+                    } else {
+                        s
+                    }
+                });
+                let container_id = container.id();
+                let after = empty().style(|s| s.size(30.0, 30.0));
+                let after_id = after.id();
+                let root = h_stack((container, after)).style(|s| s.padding(5.0));
+                *view_ids.borrow_mut() = Some([leaf_id, container_id, after_id, root.id()]);
+                Box::new(root)
+            },
+            Size::new(300.0, 300.0),
+            1.0,
+        );
+        handle.process_update_no_paint();
+        let ids = ids.take().expect("the view is built with the window");
+        (handle, ids)
+    }
+
+    fn layouts(handle: &WindowHandle, ids: &[Id]) -> Vec<Layout> {
+        let app_state = handle.app_state();
+        ids.iter()
+            .map(|id| {
+                *app_state
+                    .taffy
+                    .layout(app_state.view_states[id].node)
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    /// Changes the width of the leaf, and checks that the views are laid out like in a window
+    /// which had that width from the start.
+    fn check_relayout(fixed: bool) {
+        let width = create_rw_signal(10.0);
+        let (mut handle, ids) = window(width, fixed);
+        let [leaf, container, _, root] = ids;
+        let app_state = handle.app_state();
+        assert_eq!(app_state.has_fixed_layout(container, root), fixed);
+        let roots = app_state.relayout_roots(&HashSet::from([leaf]));
+        if fixed {
+            assert_eq!(roots, Some(vec![container]));
+        } else {
+            assert!(!roots.is_some_and(|roots| roots.contains(&container)));
+        }
+
+        width.set(50.0);
+        handle.process_update_no_paint();
+        let (mut expected, expected_ids) = window(create_rw_signal(50.0), fixed);
+        assert_eq!(layouts(&handle, &ids), layouts(&expected, &expected_ids));
+        handle.destroy();
+        expected.destroy();
+    }
+
+    #[test]
+    fn relayout_in_fixed_size_ancestor() {
+        check_relayout(true);
+    }
+
+    #[test]
+    fn relayout_in_auto_sized_ancestors() {
+        check_relayout(false);
+    }
+}
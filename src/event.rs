@@ -1,12 +1,15 @@
-use kurbo::{Point, Size};
+use std::{any::Any, path::PathBuf};
+
+use kurbo::{Affine, Point, Size, Vec2};
 use winit::{
-    keyboard::{KeyCode, PhysicalKey},
+    event::TouchPhase,
+    keyboard::{KeyCode, ModifiersState, PhysicalKey},
     window::Theme,
 };
 
 use crate::{
     keyboard::KeyEvent,
-    pointer::{PointerInputEvent, PointerMoveEvent, PointerWheelEvent},
+    pointer::{PointerInputEvent, PointerMoveEvent, PointerWheelEvent, TouchEvent},
 };
 
 #[derive(Debug, Hash, PartialEq, Eq)]
@@ -32,6 +35,10 @@ pub enum EventListener {
     ImePreedit,
     ImeCommit,
     PointerWheel,
+    PointerMotion,
+    Touch,
+    PinchGesture,
+    SmartMagnify,
     FocusGained,
     FocusLost,
     ThemeChanged,
@@ -41,6 +48,35 @@ pub enum EventListener {
     WindowGotFocus,
     WindowLostFocus,
     WindowMaximizeChanged,
+    WindowFullscreenChanged,
+    DroppedFile,
+    HoveredFile,
+}
+
+/// What happens to a payload dragged over a drop target, see
+/// [`Decorators::drop_target`](crate::views::Decorators::drop_target). Points are relative
+/// to the target.
+#[derive(Debug)]
+pub enum DragEvent<'a, T: ?Sized> {
+    /// The payload was dragged onto the target.
+    Enter(&'a T),
+    /// The payload was moved over the target. This follows `Enter`.
+    Over(&'a T, Point),
+    /// The payload was dragged off the target, or the drag ended somewhere else.
+    Leave,
+    /// The payload was dropped on the target, which ends the drag without a `Leave`.
+    Drop(&'a T, Point),
+}
+
+impl<'a> DragEvent<'a, dyn Any> {
+    pub(crate) fn downcast<T: 'static>(self) -> Option<DragEvent<'a, T>> {
+        Some(match self {
+            DragEvent::Enter(payload) => DragEvent::Enter(payload.downcast_ref()?),
+            DragEvent::Over(payload, pos) => DragEvent::Over(payload.downcast_ref()?, pos),
+            DragEvent::Leave => DragEvent::Leave,
+            DragEvent::Drop(payload, pos) => DragEvent::Drop(payload.downcast_ref()?, pos),
+        })
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -50,6 +86,19 @@ pub enum Event {
     PointerMove(PointerMoveEvent),
     PointerWheel(PointerWheelEvent),
     PointerLeave,
+    /// Relative motion of the mouse as reported by the device, which keeps coming when the
+    /// cursor is locked with [`set_cursor_grab`](crate::action::set_cursor_grab). The delta is
+    /// in device units, without the scaling and acceleration applied to the cursor.
+    PointerMotion(Vec2),
+    Touch(TouchEvent),
+    /// A touchpad pinch gesture. `delta` is the incremental change in magnification,
+    /// where positive values zoom in.
+    PinchGesture {
+        delta: f64,
+        phase: TouchPhase,
+    },
+    /// A discrete zoom request, such as a two finger double tap on a touchpad.
+    SmartMagnify,
     KeyDown(KeyEvent),
     KeyUp(KeyEvent),
     ImeEnabled,
@@ -59,12 +108,18 @@ pub enum Event {
         cursor: Option<(usize, usize)>,
     },
     ImeCommit(String),
+    /// A file was dropped on the window.
+    DroppedFile(PathBuf),
+    /// A file is being dragged over the window. `None` means the file left the window
+    /// or the drag was cancelled.
+    HoveredFile(Option<PathBuf>),
     WindowGotFocus,
     WindowLostFocus,
     WindowClosed,
     WindowResized(Size),
     WindowMoved(Point),
     WindowMaximizeChanged(bool),
+    WindowFullscreenChanged(bool),
     ThemeChanged(Theme),
     FocusGained,
     FocusLost,
@@ -84,11 +139,18 @@ impl Event {
             | Event::ImeDisabled
             | Event::ImePreedit { .. }
             | Event::ImeCommit(_)
+            | Event::DroppedFile(_)
+            | Event::PointerMotion(_)
+            | Event::Touch(_)
+            | Event::HoveredFile(_)
+            | Event::PinchGesture { .. }
+            | Event::SmartMagnify
             | Event::ThemeChanged(_)
             | Event::WindowClosed
             | Event::WindowResized(_)
             | Event::WindowMoved(_)
             | Event::WindowMaximizeChanged(_)
+            | Event::WindowFullscreenChanged(_)
             | Event::WindowGotFocus
             | Event::WindowLostFocus => false,
             Event::KeyDown(_) | Event::KeyUp(_) => true,
@@ -110,11 +172,18 @@ impl Event {
             | Event::ImeDisabled
             | Event::ImePreedit { .. }
             | Event::ImeCommit(_)
+            | Event::DroppedFile(_)
+            | Event::PointerMotion(_)
+            | Event::Touch(_)
+            | Event::HoveredFile(_)
+            | Event::PinchGesture { .. }
+            | Event::SmartMagnify
             | Event::ThemeChanged(_)
             | Event::WindowClosed
             | Event::WindowResized(_)
             | Event::WindowMoved(_)
             | Event::WindowMaximizeChanged(_)
+            | Event::WindowFullscreenChanged(_)
             | Event::WindowGotFocus
             | Event::WindowLostFocus => false,
         }
@@ -146,6 +215,12 @@ impl Event {
             | Event::ImeDisabled
             | Event::ImePreedit { .. }
             | Event::ImeCommit(_)
+            | Event::DroppedFile(_)
+            | Event::PointerMotion(_)
+            | Event::Touch(_)
+            | Event::HoveredFile(_)
+            | Event::PinchGesture { .. }
+            | Event::SmartMagnify
             | Event::KeyDown(_)
             | Event::KeyUp(_) => false,
             Event::PointerLeave
@@ -156,6 +231,7 @@ impl Event {
             | Event::WindowMoved(_)
             | Event::WindowGotFocus
             | Event::WindowMaximizeChanged(_)
+            | Event::WindowFullscreenChanged(_)
             | Event::WindowLostFocus => true,
         }
     }
@@ -167,7 +243,9 @@ impl Event {
             }
             Event::PointerMove(pointer_event) => Some(pointer_event.pos),
             Event::PointerWheel(pointer_event) => Some(pointer_event.pos),
+            Event::Touch(touch_event) => Some(touch_event.pos),
             Event::PointerLeave
+            | Event::PointerMotion(_)
             | Event::KeyDown(_)
             | Event::KeyUp(_)
             | Event::FocusGained
@@ -177,15 +255,34 @@ impl Event {
             | Event::ImePreedit { .. }
             | Event::ThemeChanged(_)
             | Event::ImeCommit(_)
+            | Event::DroppedFile(_)
+            | Event::HoveredFile(_)
+            | Event::PinchGesture { .. }
+            | Event::SmartMagnify
             | Event::WindowClosed
             | Event::WindowResized(_)
             | Event::WindowMoved(_)
             | Event::WindowMaximizeChanged(_)
+            | Event::WindowFullscreenChanged(_)
             | Event::WindowGotFocus
             | Event::WindowLostFocus => None,
         }
     }
 
+    /// The modifier keys held when the event occurred, for pointer, touch and key events.
+    pub fn modifiers(&self) -> Option<ModifiersState> {
+        match self {
+            Event::PointerDown(pointer_event) | Event::PointerUp(pointer_event) => {
+                Some(pointer_event.modifiers)
+            }
+            Event::PointerMove(pointer_event) => Some(pointer_event.modifiers),
+            Event::PointerWheel(pointer_event) => Some(pointer_event.modifiers),
+            Event::Touch(touch_event) => Some(touch_event.modifiers),
+            Event::KeyDown(key_event) | Event::KeyUp(key_event) => Some(key_event.modifiers),
+            _ => None,
+        }
+    }
+
     pub fn scale(mut self, scale: f64) -> Event {
         match &mut self {
             Event::PointerDown(pointer_event) | Event::PointerUp(pointer_event) => {
@@ -200,7 +297,12 @@ impl Event {
                 pointer_event.pos.x /= scale;
                 pointer_event.pos.y /= scale;
             }
+            Event::Touch(touch_event) => {
+                touch_event.pos.x /= scale;
+                touch_event.pos.y /= scale;
+            }
             Event::PointerLeave
+            | Event::PointerMotion(_)
             | Event::KeyDown(_)
             | Event::KeyUp(_)
             | Event::FocusGained
@@ -210,10 +312,15 @@ impl Event {
             | Event::ImePreedit { .. }
             | Event::ThemeChanged(_)
             | Event::ImeCommit(_)
+            | Event::DroppedFile(_)
+            | Event::HoveredFile(_)
+            | Event::PinchGesture { .. }
+            | Event::SmartMagnify
             | Event::WindowClosed
             | Event::WindowResized(_)
             | Event::WindowMoved(_)
             | Event::WindowMaximizeChanged(_)
+            | Event::WindowFullscreenChanged(_)
             | Event::WindowGotFocus
             | Event::WindowLostFocus => {}
         }
@@ -231,7 +338,52 @@ impl Event {
             Event::PointerWheel(pointer_event) => {
                 pointer_event.pos -= offset;
             }
+            Event::Touch(touch_event) => {
+                touch_event.pos -= offset;
+            }
+            Event::PointerLeave
+            | Event::PointerMotion(_)
+            | Event::KeyDown(_)
+            | Event::KeyUp(_)
+            | Event::FocusGained
+            | Event::FocusLost
+            | Event::ImeEnabled
+            | Event::ImeDisabled
+            | Event::ImePreedit { .. }
+            | Event::ThemeChanged(_)
+            | Event::ImeCommit(_)
+            | Event::DroppedFile(_)
+            | Event::HoveredFile(_)
+            | Event::PinchGesture { .. }
+            | Event::SmartMagnify
+            | Event::WindowClosed
+            | Event::WindowResized(_)
+            | Event::WindowMoved(_)
+            | Event::WindowMaximizeChanged(_)
+            | Event::WindowFullscreenChanged(_)
+            | Event::WindowGotFocus
+            | Event::WindowLostFocus => {}
+        }
+        self
+    }
+
+    /// Map the positions of the event with `transform`.
+    pub fn transform(mut self, transform: Affine) -> Event {
+        match &mut self {
+            Event::PointerDown(pointer_event) | Event::PointerUp(pointer_event) => {
+                pointer_event.pos = transform * pointer_event.pos;
+            }
+            Event::PointerMove(pointer_event) => {
+                pointer_event.pos = transform * pointer_event.pos;
+            }
+            Event::PointerWheel(pointer_event) => {
+                pointer_event.pos = transform * pointer_event.pos;
+            }
+            Event::Touch(touch_event) => {
+                touch_event.pos = transform * touch_event.pos;
+            }
             Event::PointerLeave
+            | Event::PointerMotion(_)
             | Event::KeyDown(_)
             | Event::KeyUp(_)
             | Event::FocusGained
@@ -241,10 +393,15 @@ impl Event {
             | Event::ImePreedit { .. }
             | Event::ThemeChanged(_)
             | Event::ImeCommit(_)
+            | Event::DroppedFile(_)
+            | Event::HoveredFile(_)
+            | Event::PinchGesture { .. }
+            | Event::SmartMagnify
             | Event::WindowClosed
             | Event::WindowResized(_)
             | Event::WindowMoved(_)
             | Event::WindowMaximizeChanged(_)
+            | Event::WindowFullscreenChanged(_)
             | Event::WindowGotFocus
             | Event::WindowLostFocus => {}
         }
@@ -258,16 +415,23 @@ impl Event {
             Event::PointerMove(_) => Some(EventListener::PointerMove),
             Event::PointerWheel(_) => Some(EventListener::PointerWheel),
             Event::PointerLeave => Some(EventListener::PointerLeave),
+            Event::PointerMotion(_) => Some(EventListener::PointerMotion),
+            Event::Touch(_) => Some(EventListener::Touch),
+            Event::PinchGesture { .. } => Some(EventListener::PinchGesture),
+            Event::SmartMagnify => Some(EventListener::SmartMagnify),
             Event::KeyDown(_) => Some(EventListener::KeyDown),
             Event::KeyUp(_) => Some(EventListener::KeyUp),
             Event::ImeEnabled => Some(EventListener::ImeEnabled),
             Event::ImeDisabled => Some(EventListener::ImeDisabled),
             Event::ImePreedit { .. } => Some(EventListener::ImePreedit),
             Event::ImeCommit(_) => Some(EventListener::ImeCommit),
+            Event::DroppedFile(_) => Some(EventListener::DroppedFile),
+            Event::HoveredFile(_) => Some(EventListener::HoveredFile),
             Event::WindowClosed => Some(EventListener::WindowClosed),
             Event::WindowResized(_) => Some(EventListener::WindowResized),
             Event::WindowMoved(_) => Some(EventListener::WindowMoved),
             Event::WindowMaximizeChanged(_) => Some(EventListener::WindowMaximizeChanged),
+            Event::WindowFullscreenChanged(_) => Some(EventListener::WindowFullscreenChanged),
             Event::WindowGotFocus => Some(EventListener::WindowGotFocus),
             Event::WindowLostFocus => Some(EventListener::WindowLostFocus),
             Event::FocusLost => Some(EventListener::FocusLost),
@@ -1,6 +1,16 @@
-use std::{cell::Cell, collections::VecDeque, sync::Arc};
+use std::{
+    cell::Cell,
+    collections::VecDeque,
+    future::Future,
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake},
+    thread::Thread,
+};
 
-use floem_reactive::{create_effect, untrack, with_scope, ReadSignal, Scope, Trigger, WriteSignal};
+use floem_reactive::{
+    create_effect, create_signal, untrack, with_scope, ReadSignal, Scope, Trigger, WriteSignal,
+};
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 
@@ -144,3 +154,52 @@ pub fn create_signal_from_channel<T: Send + 'static>(
 
     read
 }
+
+/// Run `future` to completion on a background thread, and call `on_complete` with its output
+/// on the UI thread.
+///
+/// `on_complete` runs during the event loop's idle processing, so it can safely update signals.
+/// It isn't called if `cx` is disposed before the future completes.
+pub fn spawn_future<T: Send + 'static>(
+    cx: Scope,
+    future: impl Future<Output = T> + Send + 'static,
+    on_complete: impl FnOnce(T) + 'static,
+) {
+    let send = create_ext_action(cx, on_complete);
+    std::thread::spawn(move || {
+        send(block_on(future));
+    });
+}
+
+/// Create a signal which is `None` until `future` completes on a background thread,
+/// and then holds its output.
+pub fn create_signal_from_future<T: Send + 'static>(
+    future: impl Future<Output = T> + Send + 'static,
+) -> ReadSignal<Option<T>> {
+    let (read, write) = create_signal(None);
+    spawn_future(Scope::current(), future, move |value| {
+        write.set(Some(value));
+    });
+    read
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// A minimal executor which parks the current thread until `future` makes progress.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Arc::new(ThreadWaker(std::thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
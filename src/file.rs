@@ -35,6 +35,19 @@ pub struct FileInfo {
 }
 
 impl FileInfo {
+    /// The info of `path`, with the first of `allowed_types` matching its extension.
+    pub(crate) fn new(path: PathBuf, allowed_types: &Option<Vec<FileSpec>>) -> FileInfo {
+        let extension = path.extension().and_then(|extension| extension.to_str());
+        let format = extension.and_then(|extension| {
+            allowed_types.iter().flatten().copied().find(|spec| {
+                spec.extensions
+                    .iter()
+                    .any(|allowed| allowed.eq_ignore_ascii_case(extension))
+            })
+        });
+        FileInfo { path, format }
+    }
+
     /// Returns the underlying path.
     pub fn path(&self) -> &Path {
         &self.path
@@ -54,6 +67,7 @@ pub struct FileDialogOptions {
     pub(crate) title: Option<String>,
     pub(crate) button_text: Option<String>,
     pub(crate) starting_directory: Option<PathBuf>,
+    pub(crate) modal: bool,
 }
 
 impl FileDialogOptions {
@@ -157,6 +171,13 @@ impl FileDialogOptions {
         self
     }
 
+    /// Make the dialog modal to the current window, so the window doesn't get input while
+    /// the dialog is shown.
+    pub fn modal(mut self) -> Self {
+        self.modal = true;
+        self
+    }
+
     /// Force the starting directory to the specified `path`.
     ///
     /// # User experience
@@ -167,4 +188,31 @@ impl FileDialogOptions {
         self.starting_directory = Some(path.into());
         self
     }
+
+    /// The `rfd` dialog for these options. `rfd` doesn't support showing hidden files,
+    /// packages as directories, the name label or the button text, so they're ignored.
+    pub(crate) fn rfd_dialog(&self) -> rfd::FileDialog {
+        let mut dialog = rfd::FileDialog::new();
+        if let Some(title) = &self.title {
+            dialog = dialog.set_title(title);
+        }
+        if let Some(path) = &self.starting_directory {
+            dialog = dialog.set_directory(path);
+        }
+        if let Some(name) = &self.default_name {
+            dialog = dialog.set_file_name(name);
+        }
+        if let Some(allowed_types) = &self.allowed_types {
+            // The first filter is selected in the dialog, so the default type goes first
+            let default_type = self.default_type.or_else(|| allowed_types.first().copied());
+            for spec in default_type.iter().chain(
+                allowed_types
+                    .iter()
+                    .filter(|spec| Some(**spec) != default_type),
+            ) {
+                dialog = dialog.add_filter(spec.name, spec.extensions);
+            }
+        }
+        dialog
+    }
 }
@@ -0,0 +1,143 @@
+//! Loading fonts which aren't installed on the system, like fonts embedded in the application.
+//!
+//! Once loaded, a font is used like an installed font, by naming its family with `font_family`
+//! in a style:
+//!
+//! ```rust,no_run
+//! use floem::views::{label, Decorators};
+//!
+//! floem::font::load_font_from_path("assets/Inter-Regular.ttf").unwrap();
+//! floem::font::load_font_from_path("assets/Inter-Bold.ttf").unwrap();
+//!
+//! label(|| "Hello").style(|s| s.font_family("Inter".to_string()));
+//! ```
+//!
+//! Any number of fonts can be loaded for a family, like one for each weight and style, and the
+//! closest match for the weight and style of the text is used. If several fonts have the same
+//! family, weight and style, including installed fonts, the one which was loaded first is used.
+//!
+//! Fonts should be loaded before creating the views using them, as text which has already been
+//! laid out isn't updated.
+//!
+//! # Fallbacks
+//!
+//! Characters which aren't in the font of the text, like emoji or CJK characters in a Latin
+//! font, are looked up in fallback fonts in this order:
+//!
+//! 1. The other families the `font_family` style lists, in order, like `"Inter, Noto Sans"`.
+//! 2. The fallbacks set for each of those families with [`set_font_fallbacks`].
+//! 3. The fallbacks set for all text with [`set_default_font_fallbacks`], which is empty by
+//!    default.
+//! 4. The platform's fallbacks for the script of the characters, which cover the emoji and CJK
+//!    fonts that come with the platform.
+
+use std::{collections::HashMap, path::Path};
+
+use floem_renderer::cosmic_text::{FamilyOwned, FONT_SYSTEM};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+
+#[derive(Default)]
+struct Fallbacks {
+    /// The fallbacks of font families by their lowercased name.
+    families: HashMap<String, Vec<FamilyOwned>>,
+    default: Vec<FamilyOwned>,
+}
+
+static FALLBACKS: Lazy<Mutex<Fallbacks>> = Lazy::new(Default::default);
+
+#[derive(Debug)]
+pub enum FontError {
+    /// The font file could not be read.
+    Io(std::io::Error),
+    /// The data didn't contain any font which could be loaded.
+    InvalidFont,
+}
+
+impl std::fmt::Display for FontError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FontError::Io(e) => write!(f, "failed to read font: {e}"),
+            FontError::InvalidFont => f.write_str("no font could be loaded"),
+        }
+    }
+}
+
+impl std::error::Error for FontError {}
+
+/// Load the fonts in `data`, which is the content of a TrueType or OpenType font file or
+/// collection. Fonts can be embedded in the application with `include_bytes!`.
+pub fn load_font_from_bytes(data: &[u8]) -> Result<(), FontError> {
+    let mut font_system = FONT_SYSTEM.lock();
+    let db = font_system.db_mut();
+    let faces = db.len();
+    db.load_font_data(data.to_vec());
+    if db.len() == faces {
+        return Err(FontError::InvalidFont);
+    }
+    Ok(())
+}
+
+/// Load the fonts in the TrueType or OpenType font file or collection at `path`.
+pub fn load_font_from_path(path: impl AsRef<Path>) -> Result<(), FontError> {
+    let data = std::fs::read(path).map_err(FontError::Io)?;
+    load_font_from_bytes(&data)
+}
+
+/// Set the fonts which are tried in order for characters missing from fonts of `family`,
+/// replacing the fallbacks set for it before. Each fallback can be a family name or a generic
+/// family like `monospace`.
+///
+/// ```rust
+/// floem::font::set_font_fallbacks("Inter", ["Noto Sans CJK SC", "Noto Color Emoji"]);
+/// ```
+pub fn set_font_fallbacks<S: AsRef<str>>(family: &str, fallbacks: impl IntoIterator<Item = S>) {
+    let fallbacks = parse_families(fallbacks);
+    FALLBACKS
+        .lock()
+        .families
+        .insert(family.to_lowercase(), fallbacks);
+}
+
+/// Set the fonts which are tried in order for characters missing from the fonts of any text,
+/// after the fallbacks of its families.
+pub fn set_default_font_fallbacks<S: AsRef<str>>(fallbacks: impl IntoIterator<Item = S>) {
+    FALLBACKS.lock().default = parse_families(fallbacks);
+}
+
+fn parse_families<S: AsRef<str>>(families: impl IntoIterator<Item = S>) -> Vec<FamilyOwned> {
+    families
+        .into_iter()
+        .flat_map(|family| FamilyOwned::parse_list(family.as_ref()).collect::<Vec<_>>())
+        .collect()
+}
+
+/// The font families to lay out text with the `font_family` style `family` with, which are its
+/// families followed by their fallbacks and the default fallbacks. `None` means the default
+/// font is used without any fallbacks besides the platform's.
+pub(crate) fn font_families(family: Option<&str>) -> Option<Vec<FamilyOwned>> {
+    let fallbacks = FALLBACKS.lock();
+    if family.is_none() && fallbacks.default.is_empty() {
+        return None;
+    }
+    let primary: Vec<FamilyOwned> = match family {
+        Some(family) => FamilyOwned::parse_list(family).collect(),
+        None => vec![FamilyOwned::SansSerif],
+    };
+    let mut families = Vec::new();
+    let mut push = |family: &FamilyOwned| {
+        if !families.contains(family) {
+            families.push(family.clone());
+        }
+    };
+    primary.iter().for_each(&mut push);
+    for family in &primary {
+        if let FamilyOwned::Name(name) = family {
+            if let Some(family_fallbacks) = fallbacks.families.get(&name.to_lowercase()) {
+                family_fallbacks.iter().for_each(&mut push);
+            }
+        }
+    }
+    fallbacks.default.iter().for_each(&mut push);
+    Some(families)
+}
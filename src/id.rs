@@ -8,13 +8,15 @@
 //! These ids are assigned via the [ViewContext](crate::ViewContext) and are unique across the entire application.
 //!
 
-use std::{any::Any, cell::RefCell, collections::HashMap, sync::atomic::AtomicU64};
+use std::{any::Any, cell::RefCell, collections::HashMap, rc::Rc, sync::atomic::AtomicU64};
 
+use floem_reactive::{with_scope, Scope};
 use kurbo::{Point, Rect};
 
 use crate::{
+    accessibility::AccessUpdate,
     animate::Animation,
-    context::{EventCallback, MenuCallback, ResizeCallback},
+    context::{DropTarget, EventCallback, MenuCallback, ResizeCallback},
     event::EventListener,
     style::{Style, StyleClassRef, StyleSelector},
     update::{UpdateMessage, CENTRAL_DEFERRED_UPDATE_MESSAGES, CENTRAL_UPDATE_MESSAGES},
@@ -50,6 +52,11 @@ impl Id {
         self.0
     }
 
+    #[cfg(feature = "accesskit")]
+    pub(crate) fn from_raw(id: u64) -> Id {
+        Id(id)
+    }
+
     pub fn new(&self) -> Id {
         let mut id_path =
             ID_PATHS.with(|id_paths| id_paths.borrow().get(self).cloned().unwrap_or_default());
@@ -119,6 +126,19 @@ impl Id {
         self.add_update_message(UpdateMessage::Active(*self));
     }
 
+    /// Send all pointer events to this view regardless of the pointer position, until
+    /// the pointer button is released or [`Id::release_pointer`] is called.
+    ///
+    /// Calling this while handling the pointer up event keeps the pointer captured.
+    pub fn capture_pointer(&self) {
+        self.add_update_message(UpdateMessage::CapturePointer(*self));
+    }
+
+    /// Release the pointer if it's captured by this view.
+    pub fn release_pointer(&self) {
+        self.add_update_message(UpdateMessage::ReleasePointer(*self));
+    }
+
     pub fn update_disabled(&self, is_disabled: bool) {
         self.add_update_message(UpdateMessage::Disabled {
             id: *self,
@@ -127,7 +147,7 @@ impl Id {
     }
 
     pub fn request_paint(&self) {
-        self.add_update_message(UpdateMessage::RequestPaint);
+        self.add_update_message(UpdateMessage::RequestPaint(*self));
     }
 
     pub fn request_layout(&self) {
@@ -174,28 +194,93 @@ impl Id {
         self.add_update_message(UpdateMessage::KeyboardNavigable { id: *self });
     }
 
+    /// Stop the view from being focused, even if it's `keyboard_navigatable`.
+    pub fn disable_focus(&self) {
+        self.add_update_message(UpdateMessage::DisableFocus { id: *self });
+    }
+
+    pub(crate) fn update_accessibility(&self, update: AccessUpdate) {
+        self.add_update_message(UpdateMessage::Accessibility { id: *self, update });
+    }
+
+    pub fn access_key(&self, key: char) {
+        self.add_update_message(UpdateMessage::AccessKey { id: *self, key });
+    }
+
+    pub fn tab_index(&self, index: i32) {
+        self.add_update_message(UpdateMessage::TabIndex { id: *self, index });
+    }
+
+    pub fn focus_trap(&self) {
+        self.add_update_message(UpdateMessage::FocusTrap { id: *self });
+    }
+
+    /// Absorb pointer presses on the view while it's disabled, instead of letting them through
+    /// to the views below it.
+    pub fn disabled_absorbs_pointer(&self) {
+        self.add_update_message(UpdateMessage::DisabledAbsorbsPointer { id: *self });
+    }
+
     pub fn draggable(&self) {
         self.add_update_message(UpdateMessage::Draggable { id: *self });
     }
 
+    /// Make the view draggable, with `payload` making the payload of each drag.
+    pub(crate) fn update_drag_payload(&self, payload: Box<dyn Fn() -> Rc<dyn Any>>) {
+        self.add_update_message(UpdateMessage::DragPayload { id: *self, payload });
+    }
+
+    pub(crate) fn update_drop_target(&self, target: DropTarget) {
+        self.add_update_message(UpdateMessage::DropTarget { id: *self, target });
+    }
+
+    /// Handle `listener` events of this view with `action`. Like the other listeners, `action`
+    /// runs in the scope it's registered in, so it sees the context values provided there.
     pub fn update_event_listener(&self, listener: EventListener, action: Box<EventCallback>) {
+        let scope = Scope::current();
         self.add_update_message(UpdateMessage::EventListener {
             id: *self,
             listener,
-            action,
+            action: Box::new(move |event| with_scope(scope, || action(event))),
         });
     }
 
     pub fn update_resize_listener(&self, action: Box<ResizeCallback>) {
-        self.add_update_message(UpdateMessage::ResizeListener { id: *self, action });
+        let scope = Scope::current();
+        self.add_update_message(UpdateMessage::ResizeListener {
+            id: *self,
+            action: Box::new(move |rect| with_scope(scope, || action(rect))),
+        });
     }
 
     pub fn update_move_listener(&self, action: Box<dyn Fn(Point)>) {
-        self.add_update_message(UpdateMessage::MoveListener { id: *self, action });
+        let scope = Scope::current();
+        self.add_update_message(UpdateMessage::MoveListener {
+            id: *self,
+            action: Box::new(move |point| with_scope(scope, || action(point))),
+        });
     }
 
     pub fn update_cleanup_listener(&self, action: Box<dyn Fn()>) {
-        self.add_update_message(UpdateMessage::CleanupListener { id: *self, action });
+        let scope = Scope::current();
+        self.add_update_message(UpdateMessage::CleanupListener {
+            id: *self,
+            action: Box::new(move || with_scope(scope, &action)),
+        });
+    }
+
+    pub fn update_mounted_listener(&self, action: Box<dyn FnOnce()>) {
+        let scope = Scope::current();
+        self.add_update_message(UpdateMessage::MountedListener {
+            id: *self,
+            action: Box::new(move || with_scope(scope, action)),
+        });
+    }
+
+    /// Dispose `scope` when this view is removed. Timers started within it are cancelled then.
+    pub fn own_scope(&self, scope: Scope) {
+        crate::action::cancel_timers_with(scope);
+        self.add_update_message(UpdateMessage::OwnScope { id: *self, scope });
     }
 
     pub fn update_animation(&self, animation: Animation) {
@@ -1,5 +1,5 @@
 use crate::app::{add_app_update_event, AppUpdateEvent};
-use crate::context::{AppState, StyleCx};
+use crate::context::{AppState, PaintCx, StyleCx};
 use crate::event::{Event, EventListener};
 use crate::id::Id;
 use crate::profiler::profiler;
@@ -14,8 +14,10 @@ use crate::widgets::button;
 use crate::window::WindowConfig;
 use crate::{new_window, style, EventPropagation};
 use floem_reactive::{create_effect, create_rw_signal, create_signal, RwSignal, Scope};
+use floem_renderer::cosmic_text::{Attrs, AttrsList, TextLayout};
+use floem_renderer::Renderer;
 use image::DynamicImage;
-use kurbo::{Point, Rect, Size};
+use kurbo::{Point, Rect, Size, Vec2};
 use peniko::Color;
 use std::cell::Cell;
 use std::collections::{HashMap, HashSet};
@@ -128,6 +130,92 @@ impl CaptureState {
     }
 }
 
+/// Finds the innermost view at `pos`, relative to the parent of `view`, which pointer events
+/// at `pos` would reach.
+fn view_at<'a>(view: &'a dyn View, app_state: &AppState, pos: Point) -> Option<&'a dyn View> {
+    let id = view.id();
+    if app_state.is_hidden(id) {
+        return None;
+    }
+    let mut pos = app_state.hit_test(id, pos)?;
+    // Children are offset by scrolling
+    if let Some(viewport) = app_state
+        .view_states
+        .get(&id)
+        .and_then(|view| view.viewport)
+    {
+        pos += viewport.origin().to_vec2();
+    }
+    // Children painted on top are hit first
+    let mut children = view_children(view);
+    children.sort_by_key(|child| app_state.z_index(child.id()));
+    children
+        .into_iter()
+        .rev()
+        .find_map(|child| view_at(child, app_state, pos))
+        .or(Some(view))
+}
+
+/// Paints the overlay toggled with [`toggle_inspector_overlay`](crate::action::toggle_inspector_overlay),
+/// which outlines the view at `pos` and shows its id, layout and computed style next to it.
+pub(crate) fn paint_overlay(cx: &mut PaintCx, root: &dyn View, pos: Point, window_size: Size) {
+    let Some(view) = view_at(root, cx.app_state, pos) else {
+        return;
+    };
+    let id = view.id();
+    let rect = cx.app_state.get_layout_rect(id);
+
+    let mut lines = vec![
+        format!("{} #{}", view.debug_name(), id.to_raw()),
+        format!(
+            "x {} y {}, {} × {}",
+            rect.x0,
+            rect.y0,
+            rect.width(),
+            rect.height()
+        ),
+    ];
+    let mut style: Vec<_> = cx
+        .app_state
+        .get_computed_style(id)
+        .map
+        .iter()
+        .map(|(prop, value)| {
+            let name = format!("{prop:?}");
+            let name = name.strip_prefix("floem::style::").unwrap_or(&name);
+            let value = match value {
+                StyleMapValue::Val(value) => (prop.info.debug_any)(&**value),
+                StyleMapValue::Unset => "Unset".to_owned(),
+            };
+            format!("{name}: {value}")
+        })
+        .collect();
+    style.sort_unstable();
+    lines.extend(style);
+
+    let mut text_layout = TextLayout::new();
+    text_layout.set_text(
+        &lines.join("\n"),
+        AttrsList::new(Attrs::new().color(Color::WHITE).font_size(12.0)),
+    );
+    let size = text_layout.size();
+    let panel = Size::new(size.width + 10.0, size.height + 10.0);
+    // Show the panel below the view, or above it if it doesn't fit
+    let y = if rect.y1 + panel.height <= window_size.height || rect.y0 < panel.height {
+        rect.y1
+    } else {
+        rect.y0 - panel.height
+    };
+    let x = rect.x0.min(window_size.width - panel.width).max(0.0);
+    let panel = panel.to_rect().with_origin(Point::new(x, y));
+
+    cx.clear_transform();
+    cx.fill(&rect, Color::rgba8(66, 135, 245, 60), 0.0);
+    cx.stroke(&rect, Color::rgb8(66, 135, 245), 1.0);
+    cx.fill(&panel, Color::BLACK.with_alpha_factor(0.8), 0.0);
+    cx.draw_text(&text_layout, panel.origin() + Vec2::new(5.0, 5.0));
+}
+
 fn captured_view_name(view: &CapturedView) -> impl View {
     let name = static_label(view.name.clone());
     let id = text(view.id.to_raw()).style(|s| {
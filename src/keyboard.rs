@@ -1,7 +1,204 @@
-pub use winit::keyboard::{Key, KeyCode, ModifiersState, NamedKey, NativeKey, PhysicalKey};
+use std::sync::atomic::AtomicU64;
+
+pub use winit::event::ElementState;
+pub use winit::keyboard::{
+    Key, KeyCode, KeyLocation, ModifiersState, NamedKey, NativeKey, NativeKeyCode, PhysicalKey,
+    SmolStr,
+};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct KeyEvent {
-    pub key: winit::event::KeyEvent,
+    pub key: KeyInput,
+    /// The modifier keys held when the event occurred.
+    pub modifiers: ModifiersState,
+}
+
+/// A key being pressed or released, with the fields of winit's `KeyEvent`. Unlike winit's
+/// event, it can be created by floem, so key presses can be simulated.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct KeyInput {
+    pub physical_key: PhysicalKey,
+    pub logical_key: Key,
+    pub text: Option<SmolStr>,
+    pub location: KeyLocation,
+    pub state: ElementState,
+    pub repeat: bool,
+    key_without_modifiers: Key,
+}
+
+impl KeyInput {
+    /// A press or release of `key` on the main part of the keyboard, with the text the key
+    /// types. Enter and Space have their physical keys, so they activate views like real ones.
+    pub fn new(key: Key, state: ElementState) -> Self {
+        let physical_key = match key {
+            Key::Named(NamedKey::Enter) => PhysicalKey::Code(KeyCode::Enter),
+            Key::Named(NamedKey::Space) => PhysicalKey::Code(KeyCode::Space),
+            _ => PhysicalKey::Unidentified(NativeKeyCode::Unidentified),
+        };
+        Self {
+            physical_key,
+            text: key.to_text().map(SmolStr::new),
+            logical_key: key.clone(),
+            location: KeyLocation::Standard,
+            state,
+            repeat: false,
+            key_without_modifiers: key,
+        }
+    }
+
+    /// The key ignoring the held modifiers, so `Alt+F` gives `f` even where Option composes
+    /// another character on macOS. It's the logical key on platforms which don't report it.
+    pub fn key_without_modifiers(&self) -> &Key {
+        &self.key_without_modifiers
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_key_without_modifiers(mut self, key: Key) -> Self {
+        self.key_without_modifiers = key;
+        self
+    }
+}
+
+impl From<winit::event::KeyEvent> for KeyInput {
+    fn from(event: winit::event::KeyEvent) -> Self {
+        #[cfg(any(
+            target_os = "windows",
+            target_os = "macos",
+            target_os = "linux",
+            target_os = "freebsd"
+        ))]
+        let key_without_modifiers = {
+            use winit::platform::modifier_supplement::KeyEventExtModifierSupplement;
+            event.key_without_modifiers()
+        };
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "macos",
+            target_os = "linux",
+            target_os = "freebsd"
+        )))]
+        let key_without_modifiers = event.logical_key.clone();
+        Self {
+            physical_key: event.physical_key,
+            logical_key: event.logical_key,
+            text: event.text,
+            location: event.location,
+            state: event.state,
+            repeat: event.repeat,
+            key_without_modifiers,
+        }
+    }
+}
+
+/// A key together with the modifiers which must be held, used to register shortcuts.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyCombination {
+    pub key: Key,
     pub modifiers: ModifiersState,
 }
+
+impl KeyCombination {
+    pub fn new(key: Key, modifiers: ModifiersState) -> Self {
+        Self { key, modifiers }
+    }
+
+    /// Check if the key event is this combination. Character keys are compared ignoring case,
+    /// so Shift is only matched through `modifiers`.
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        if event.modifiers != self.modifiers {
+            return false;
+        }
+        match (&self.key, &event.key.logical_key) {
+            (Key::Character(a), Key::Character(b)) => a.to_lowercase() == b.to_lowercase(),
+            (a, b) => a == b,
+        }
+    }
+
+    /// The text shown for the combination in menus, like `⌘S` on macOS and `Ctrl+S` on other
+    /// platforms.
+    pub fn label(&self) -> String {
+        let key = match &self.key {
+            Key::Character(c) => c.to_uppercase(),
+            Key::Named(named) => named_key_label(*named),
+            key => format!("{key:?}"),
+        };
+        let modifiers = [
+            (ModifiersState::CONTROL, "⌃", "Ctrl"),
+            (ModifiersState::ALT, "⌥", "Alt"),
+            (ModifiersState::SHIFT, "⇧", "Shift"),
+            (ModifiersState::SUPER, "⌘", "Super"),
+        ];
+        let held = modifiers
+            .into_iter()
+            .filter(|(modifier, _, _)| self.modifiers.contains(*modifier));
+        if cfg!(target_os = "macos") {
+            held.map(|(_, symbol, _)| symbol).collect::<String>() + &key
+        } else {
+            held.map(|(_, _, name)| format!("{name}+"))
+                .collect::<String>()
+                + &key
+        }
+    }
+
+    /// Shortcuts with Control, Alt or Super take precedence over the focused view.
+    pub(crate) fn is_command(&self) -> bool {
+        self.modifiers
+            .intersects(ModifiersState::CONTROL | ModifiersState::ALT | ModifiersState::SUPER)
+    }
+}
+
+/// The text shown for `key` in menus
+fn named_key_label(key: NamedKey) -> String {
+    let mac = cfg!(target_os = "macos");
+    let label = match key {
+        NamedKey::Enter if mac => "↩",
+        NamedKey::Tab if mac => "⇥",
+        NamedKey::Backspace if mac => "⌫",
+        NamedKey::Delete if mac => "⌦",
+        NamedKey::Escape if mac => "⎋",
+        NamedKey::Escape => "Esc",
+        NamedKey::Delete => "Del",
+        NamedKey::ArrowUp => "↑",
+        NamedKey::ArrowDown => "↓",
+        NamedKey::ArrowLeft => "←",
+        NamedKey::ArrowRight => "→",
+        NamedKey::PageUp => "PgUp",
+        NamedKey::PageDown => "PgDn",
+        key => return format!("{key:?}"),
+    };
+    label.to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShortcutId(u64);
+
+impl ShortcutId {
+    pub(crate) fn next() -> ShortcutId {
+        static SHORTCUT_COUNTER: AtomicU64 = AtomicU64::new(0);
+        ShortcutId(SHORTCUT_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Key, KeyCombination, ModifiersState, NamedKey};
+
+    #[test]
+    fn label_lists_modifiers_before_the_key() {
+        let save = KeyCombination::new(
+            Key::Character("s".into()),
+            ModifiersState::SHIFT | ModifiersState::CONTROL,
+        );
+        let escape = KeyCombination::new(Key::Named(NamedKey::Escape), ModifiersState::empty());
+        let up = KeyCombination::new(Key::Named(NamedKey::ArrowUp), ModifiersState::ALT);
+        if cfg!(target_os = "macos") {
+            assert_eq!(save.label(), "⌃⇧S");
+            assert_eq!(escape.label(), "⎋");
+            assert_eq!(up.label(), "⌥↑");
+        } else {
+            assert_eq!(save.label(), "Ctrl+Shift+S");
+            assert_eq!(escape.label(), "Esc");
+            assert_eq!(up.label(), "Alt+↑");
+        }
+    }
+}
@@ -41,7 +41,33 @@
 //!
 //! ### Global state
 //!
-//! Global state can be implemented using [provide_context](floem_reactive::provide_context) and [use_context](floem_reactive::use_context).
+//! App-global state, like a settings service shared by every window, can be provided with
+//! [provide_global_context](floem_reactive::provide_global_context) and read anywhere with
+//! [use_context](floem_reactive::use_context).
+//!
+//! Values provided with [provide_context](floem_reactive::provide_context) are scoped instead.
+//! A value provided while building a view is found by the views built after it in the same
+//! scope, and a nested scope can provide another value of the same type for the views in it.
+//! To pass a value to a subtree only, build it with [scoped](views::scoped):
+//! ```rust
+//! # use floem::reactive::{provide_context, use_context};
+//! # use floem::views::{label, scoped};
+//! #[derive(Clone)]
+//! struct Theme {
+//!     accent: &'static str,
+//! }
+//!
+//! let view = scoped(|| {
+//!     provide_context(Theme { accent: "blue" });
+//!     label(|| use_context::<Theme>().unwrap().accent)
+//! });
+//! ```
+//!
+//! Event handlers, timers and effects run in the scope they were created in, so they see the
+//! same values as the view they belong to. Each window builds its view in a scope of its own,
+//! so a value provided with `provide_context` in the view function of a window is only found in
+//! that window. Values provided globally, or before the application is run outside of all
+//! scopes, are found in every window.
 //!
 //! ## Styling
 //! You can style your views by applying [Styles](style::Style) through the
@@ -89,6 +115,7 @@
 //! See the [Renderer module](renderer) for more info.
 //!
 //!
+pub mod accessibility;
 pub mod action;
 pub mod animate;
 mod app;
@@ -98,16 +125,21 @@ pub mod context;
 pub mod event;
 pub mod ext_event;
 pub mod file;
+pub mod font;
 pub mod id;
 mod inspector;
 pub mod keyboard;
 pub mod menu;
 mod nav;
+#[cfg(feature = "notifications")]
+pub mod notification;
 pub mod pointer;
 mod profiler;
 pub mod renderer;
 pub mod responsive;
 pub mod style;
+#[cfg(feature = "tray")]
+pub mod tray;
 pub mod unit;
 mod update;
 pub mod view;
@@ -1,5 +1,9 @@
 use std::sync::atomic::AtomicU64;
 
+use floem_reactive::RwSignal;
+
+use crate::keyboard::KeyCombination;
+
 /// An entry in a menu.
 ///
 /// An entry is either a [`MenuItem`], a submenu (i.e. [`Menu`]).
@@ -30,6 +34,14 @@ impl Menu {
         }
     }
 
+    /// Set the access key of the menu, like `f` for a File menu, which opens it when pressed
+    /// with Alt in a window menu, or pressed while its parent menu is open. See
+    /// [`MenuItem::access_key`].
+    pub fn access_key(mut self, key: char) -> Self {
+        self.item.access_key = Some(key);
+        self
+    }
+
     pub(crate) fn popup(mut self) -> Self {
         self.popup = true;
         self
@@ -60,15 +72,14 @@ impl Menu {
                 MenuEntry::Item(item) => {
                     menu.add_item(
                         item.id as u32,
-                        &item.title,
-                        // item.key.as_ref(),
+                        &item.platform_title(),
                         item.selected,
                         item.enabled,
                     );
                 }
                 MenuEntry::SubMenu(m) => {
                     let enabled = m.item.enabled;
-                    let title = m.item.title.clone();
+                    let title = m.item.platform_title();
                     menu.add_dropdown(m.platform_menu(), &title, enabled);
                 }
             }
@@ -80,9 +91,10 @@ impl Menu {
 pub struct MenuItem {
     pub(crate) id: u64,
     pub(crate) title: String,
-    // key: Option<HotKey>,
-    selected: Option<bool>,
+    pub(crate) shortcut: Option<KeyCombination>,
+    pub(crate) selected: Option<bool>,
     pub(crate) enabled: bool,
+    pub(crate) access_key: Option<char>,
     pub(crate) action: Option<Box<dyn Fn()>>,
 }
 
@@ -99,13 +111,47 @@ impl MenuItem {
         Self {
             id,
             title: title.into(),
-            // key: None,
+            shortcut: None,
             selected: None,
             enabled: true,
+            access_key: None,
             action: None,
         }
     }
 
+    /// Create a checkbox item which shows a checkmark while `state` is `true`,
+    /// and flips `state` when selected.
+    ///
+    /// `state` is read when the menu is built, so menus built in a reactive closure
+    /// (like [`Decorators::window_menu`](crate::views::Decorators::window_menu)) are
+    /// rebuilt when it changes.
+    pub fn toggle(title: impl Into<String>, state: RwSignal<bool>) -> Self {
+        Self::new(title)
+            .checked(state.get())
+            .action(move || state.update(|checked| *checked = !*checked))
+    }
+
+    /// Create a radio item which shows a checkmark while `state` equals `value`,
+    /// and sets `state` to `value` when selected.
+    ///
+    /// Items sharing the same `state` form a group where only one of them is checked.
+    pub fn radio<T: PartialEq + Clone + 'static>(
+        title: impl Into<String>,
+        state: RwSignal<T>,
+        value: T,
+    ) -> Self {
+        let checked = state.with(|state| *state == value);
+        Self::new(title)
+            .checked(checked)
+            .action(move || state.set(value.clone()))
+    }
+
+    /// Show a checkmark next to the item if `checked` is `true`.
+    pub fn checked(mut self, checked: bool) -> Self {
+        self.selected = Some(checked);
+        self
+    }
+
     pub fn action(mut self, action: impl Fn() + 'static) -> Self {
         self.action = Some(Box::new(action));
         self
@@ -115,4 +161,59 @@ impl MenuItem {
         self.enabled = enabled;
         self
     }
+
+    /// Set the access key of the item, which selects it when pressed while the menu is open.
+    /// The first occurrence of the key in the title is underlined. This is only supported by
+    /// the menus on Windows.
+    pub fn access_key(mut self, key: char) -> Self {
+        self.access_key = Some(key);
+        self
+    }
+
+    /// Set the keyboard shortcut of the item, which is shown next to its title.
+    ///
+    /// The shortcuts of the items in the window menu, set with
+    /// [`Decorators::window_menu`](crate::views::Decorators::window_menu), are registered for the
+    /// window like with [`register_shortcut`](crate::action::register_shortcut) and run the action
+    /// of the item. Shortcuts registered with `register_shortcut` take precedence.
+    ///
+    /// Context menus only exist while they're open, so the shortcuts of their items are only
+    /// shown if they're registered for the window. Selecting an item without an action then
+    /// runs the action of the shortcut. Shortcuts aren't shown in the menus on macOS.
+    pub fn shortcut(mut self, combination: KeyCombination) -> Self {
+        self.shortcut = Some(combination);
+        self
+    }
+
+    /// The title as the platform menus expect it, with `&` marking the access key and the
+    /// shortcut after a tab on Windows.
+    pub(crate) fn platform_title(&self) -> String {
+        let mut title = self.access_key_title();
+        if cfg!(target_os = "windows") {
+            if let Some(shortcut) = &self.shortcut {
+                title.push('\t');
+                title.push_str(&shortcut.label());
+            }
+        }
+        title
+    }
+
+    fn access_key_title(&self) -> String {
+        let Some(key) = self.access_key.filter(|_| cfg!(target_os = "windows")) else {
+            return self.title.clone();
+        };
+        let mut title = String::with_capacity(self.title.len() + 1);
+        let mut marked = false;
+        for c in self.title.chars() {
+            if c == '&' {
+                // A single `&` would mark the next character
+                title.push('&');
+            } else if !marked && c.to_lowercase().eq(key.to_lowercase()) {
+                title.push('&');
+                marked = true;
+            }
+            title.push(c);
+        }
+        title
+    }
 }
@@ -15,6 +15,7 @@ pub(crate) fn view_arrow_navigation(key: NamedKey, app_state: &mut AppState, vie
                 view,
                 app_state,
                 matches!(key, NamedKey::ArrowUp | NamedKey::ArrowLeft),
+                None,
             );
             return;
         }
@@ -0,0 +1,156 @@
+//! Desktop notifications, shown with [`Notification::show`] or [`notify`]. This needs the
+//! `notifications` feature.
+//!
+//! Notifications are supported on Windows, macOS, Linux and FreeBSD, and showing them does
+//! nothing on other platforms. Clicks and action buttons are only reported on Linux and FreeBSD.
+
+use floem_reactive::Scope;
+
+use crate::ext_event::create_ext_action;
+
+/// How the user responded to a notification
+#[derive(Debug)]
+#[cfg_attr(not(any(target_os = "linux", target_os = "freebsd")), allow(dead_code))]
+enum Response {
+    Click,
+    Action(String),
+}
+
+/// A desktop notification.
+///
+/// ```rust,no_run
+/// # use floem::notification::Notification;
+/// Notification::new("Download finished", "report.pdf was saved")
+///     .action("open", "Open")
+///     .on_action(|action| println!("{action} was picked"))
+///     .show();
+/// ```
+pub struct Notification {
+    title: String,
+    body: String,
+    icon: Option<String>,
+    actions: Vec<(String, String)>,
+    on_click: Option<Box<dyn FnOnce()>>,
+    on_action: Option<Box<dyn FnOnce(String)>>,
+}
+
+impl Notification {
+    pub fn new(title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            body: body.into(),
+            icon: None,
+            actions: Vec::new(),
+            on_click: None,
+            on_action: None,
+        }
+    }
+
+    /// Set the icon, which is the name of an icon in the icon theme or a path to an image.
+    pub fn icon(mut self, icon: impl Into<String>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Add a button with `label`, which passes `id` to the [`on_action`](Self::on_action)
+    /// handler when it's clicked.
+    pub fn action(mut self, id: impl Into<String>, label: impl Into<String>) -> Self {
+        self.actions.push((id.into(), label.into()));
+        self
+    }
+
+    /// Set a handler which is called when the notification is clicked, like to focus the
+    /// window it's about with [`focus_window`](crate::window::focus_window).
+    pub fn on_click(mut self, action: impl FnOnce() + 'static) -> Self {
+        self.on_click = Some(Box::new(action));
+        self
+    }
+
+    /// Set a handler which is called with the id of the button which was clicked.
+    pub fn on_action(mut self, action: impl FnOnce(String) + 'static) -> Self {
+        self.on_action = Some(Box::new(action));
+        self
+    }
+
+    /// Show the notification. This doesn't block, and it doesn't need a window.
+    pub fn show(self) {
+        let Notification {
+            title,
+            body,
+            icon,
+            actions,
+            on_click,
+            on_action,
+        } = self;
+        #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+        let clickable = on_click.is_some();
+        let respond = create_ext_action(Scope::new(), move |response| match response {
+            Response::Click => {
+                if let Some(on_click) = on_click {
+                    on_click();
+                }
+            }
+            Response::Action(action) => {
+                if let Some(on_action) = on_action {
+                    on_action(action);
+                }
+            }
+        });
+
+        #[cfg(any(
+            target_os = "windows",
+            target_os = "macos",
+            target_os = "linux",
+            target_os = "freebsd"
+        ))]
+        std::thread::spawn(move || {
+            let mut notification = notify_rust::Notification::new();
+            notification.summary(&title).body(&body);
+            if let Some(icon) = &icon {
+                notification.icon(icon);
+            }
+            for (id, label) in &actions {
+                notification.action(id, label);
+            }
+            // Notification servers only report clicks on the body with the default action
+            #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+            if clickable {
+                notification.action("default", "");
+            }
+
+            #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+            if let Ok(handle) = notification.show() {
+                handle.wait_for_action(|action| match action {
+                    // The body of the notification was clicked
+                    "default" => respond(Response::Click),
+                    "__closed" => {}
+                    action => respond(Response::Action(action.to_string())),
+                });
+            }
+
+            #[cfg(not(any(target_os = "linux", target_os = "freebsd")))]
+            {
+                let _ = notification.show();
+                drop(respond);
+            }
+        });
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "macos",
+            target_os = "linux",
+            target_os = "freebsd"
+        )))]
+        drop((title, body, icon, actions, respond));
+    }
+}
+
+/// Show a notification with `title`, `body` and optionally an `icon`. See [`Notification`]
+/// to get notified when it's clicked.
+pub fn notify(title: impl Into<String>, body: impl Into<String>, icon: Option<&str>) {
+    let mut notification = Notification::new(title, body);
+    if let Some(icon) = icon {
+        notification = notification.icon(icon);
+    }
+    notification.show();
+}
@@ -1,10 +1,14 @@
 use kurbo::{Point, Vec2};
-use winit::{event::MouseButton, keyboard::ModifiersState};
+use winit::{
+    event::{MouseButton, TouchPhase},
+    keyboard::ModifiersState,
+};
 
 #[derive(Debug, Clone)]
 pub struct PointerWheelEvent {
     pub pos: Point,
     pub delta: Vec2,
+    /// The modifier keys held when the event occurred.
     pub modifiers: ModifiersState,
 }
 
@@ -57,12 +61,27 @@ impl PointerButton {
 pub struct PointerInputEvent {
     pub pos: Point,
     pub button: PointerButton,
+    /// The modifier keys held when the event occurred.
     pub modifiers: ModifiersState,
+    /// The number of consecutive clicks of the primary button, so 2 for a double click and 3
+    /// for a triple click. A click only counts if it's close to the previous one in both time
+    /// and position. This is 0 for other buttons.
     pub count: u8,
 }
 
 #[derive(Debug, Clone)]
 pub struct PointerMoveEvent {
     pub pos: Point,
+    /// The modifier keys held when the event occurred.
+    pub modifiers: ModifiersState,
+}
+
+#[derive(Debug, Clone)]
+pub struct TouchEvent {
+    /// A unique identifier for the finger, stable for the duration of the touch.
+    pub id: u64,
+    pub phase: TouchPhase,
+    pub pos: Point,
+    /// The modifier keys held when the event occurred.
     pub modifiers: ModifiersState,
 }
@@ -48,6 +48,8 @@
 //! - Only one view can be focused at a time.
 //!
 use crate::cosmic_text::TextLayout;
+use crate::view::View;
+use crate::window::HeadlessWindow;
 use floem_renderer::Img;
 use floem_tiny_skia::TinySkiaRenderer;
 use floem_vger::VgerRenderer;
@@ -55,6 +57,20 @@ use image::DynamicImage;
 use kurbo::{Affine, Rect, Shape, Size};
 use peniko::BrushRef;
 
+/// Renders `view` in a window of `size` logical pixels with the scale factor `scale`, without
+/// opening a window. This is meant for comparing views to reference images in tests.
+///
+/// The view is styled, laid out and painted once. The pixels are returned as unpremultiplied
+/// RGBA8 values, row by row, for an image which is `size` times `scale` physical pixels large,
+/// rounded down.
+///
+/// Text is rendered with the fonts installed on the system, so images with text may differ
+/// slightly between machines. Comparisons should allow for some tolerance in the pixel values.
+/// To send events to the view before rendering it, use [`HeadlessWindow`].
+pub fn render_to_buffer(view: impl View + 'static, size: Size, scale: f64) -> Vec<u8> {
+    HeadlessWindow::new(move |_| view, size, scale).render()
+}
+
 #[allow(clippy::large_enum_variant)]
 pub enum Renderer {
     Vger(VgerRenderer),
@@ -62,7 +78,7 @@ pub enum Renderer {
 }
 
 impl Renderer {
-    pub fn new<W>(window: &W, scale: f64, size: Size) -> Self
+    pub fn new<W>(window: &W, scale: f64, size: Size, transparent: bool) -> Self
     where
         W: raw_window_handle::HasRawDisplayHandle + raw_window_handle::HasRawWindowHandle,
     {
@@ -78,7 +94,13 @@ impl Renderer {
         };
 
         let vger_err = if !force_tiny_skia {
-            match VgerRenderer::new(window, size.width as u32, size.height as u32, scale) {
+            match VgerRenderer::new(
+                window,
+                size.width as u32,
+                size.height as u32,
+                scale,
+                transparent,
+            ) {
                 Ok(vger) => return Self::Vger(vger),
                 Err(err) => Some(err),
             }
@@ -86,11 +108,16 @@ impl Renderer {
             None
         };
 
-        let tiny_skia_err =
-            match TinySkiaRenderer::new(window, size.width as u32, size.height as u32, scale) {
-                Ok(tiny_skia) => return Self::TinySkia(tiny_skia),
-                Err(err) => err,
-            };
+        let tiny_skia_err = match TinySkiaRenderer::new(
+            window,
+            size.width as u32,
+            size.height as u32,
+            scale,
+            transparent,
+        ) {
+            Ok(tiny_skia) => return Self::TinySkia(tiny_skia),
+            Err(err) => err,
+        };
 
         if !force_tiny_skia {
             panic!("Failed to create VgerRenderer: {}\nFailed to create TinySkiaRenderer: {tiny_skia_err}", vger_err.unwrap());
@@ -99,6 +126,16 @@ impl Renderer {
         }
     }
 
+    /// Creates a renderer without a window, which returns each frame from `finish`. This always
+    /// uses tiny-skia, which renders the same pixels on every machine given the same fonts.
+    pub fn new_headless(scale: f64, size: Size) -> Self {
+        let size = Size::new(size.width.max(1.0), size.height.max(1.0));
+        match TinySkiaRenderer::new_headless(size.width as u32, size.height as u32, scale, false) {
+            Ok(tiny_skia) => Self::TinySkia(tiny_skia),
+            Err(err) => panic!("Failed to create TinySkiaRenderer: {err}"),
+        }
+    }
+
     pub fn resize(&mut self, scale: f64, size: Size) {
         let size = Size::new(size.width.max(1.0), size.height.max(1.0));
         match self {
@@ -127,6 +164,13 @@ impl floem_renderer::Renderer for Renderer {
         }
     }
 
+    fn begin_damaged(&mut self, damage: Rect) -> bool {
+        match self {
+            Renderer::Vger(r) => r.begin_damaged(damage),
+            Renderer::TinySkia(r) => r.begin_damaged(damage),
+        }
+    }
+
     fn clip(&mut self, shape: &impl Shape) {
         match self {
             Renderer::Vger(v) => {
@@ -149,6 +193,39 @@ impl floem_renderer::Renderer for Renderer {
         }
     }
 
+    fn push_layer(&mut self, alpha: f32, bounds: Rect) {
+        match self {
+            Renderer::Vger(v) => {
+                v.push_layer(alpha, bounds);
+            }
+            Renderer::TinySkia(v) => {
+                v.push_layer(alpha, bounds);
+            }
+        }
+    }
+
+    fn pop_layer(&mut self) {
+        match self {
+            Renderer::Vger(v) => {
+                v.pop_layer();
+            }
+            Renderer::TinySkia(v) => {
+                v.pop_layer();
+            }
+        }
+    }
+
+    fn blur_backdrop(&mut self, shape: &impl Shape, radius: f64) {
+        match self {
+            Renderer::Vger(v) => {
+                v.blur_backdrop(shape, radius);
+            }
+            Renderer::TinySkia(v) => {
+                v.blur_backdrop(shape, radius);
+            }
+        }
+    }
+
     fn stroke<'b>(&mut self, shape: &impl Shape, brush: impl Into<BrushRef<'b>>, width: f64) {
         match self {
             Renderer::Vger(v) => {
@@ -243,3 +320,25 @@ impl floem_renderer::Renderer for Renderer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use kurbo::Size;
+    use peniko::Color;
+
+    use super::render_to_buffer;
+    use crate::views::{empty, Decorators};
+
+    #[test]
+    fn render_to_buffer_paints_the_view() {
+        let view = empty().style(|s| s.size(50.0, 50.0).background(Color::RED));
+        let pixels = render_to_buffer(view, Size::new(100.0, 50.0), 2.0);
+        assert_eq!(pixels.len(), 200 * 100 * 4);
+        let pixel = |x: usize, y: usize| &pixels[(y * 200 + x) * 4..][..4];
+        assert_eq!(pixel(10, 10), [255, 0, 0, 255]);
+        assert_eq!(pixel(99, 99), [255, 0, 0, 255]);
+        // The window is white outside of the view
+        assert_eq!(pixel(100, 0), [255, 255, 255, 255]);
+        assert_eq!(pixel(199, 99), [255, 255, 255, 255]);
+    }
+}
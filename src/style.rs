@@ -27,6 +27,7 @@
 
 use floem_renderer::cosmic_text;
 use floem_renderer::cosmic_text::{LineHeightValue, Weight};
+use kurbo::Affine;
 use peniko::Color;
 use std::any::{type_name, Any};
 use std::collections::hash_map::Entry;
@@ -44,11 +45,13 @@ use taffy::{
     geometry::Size,
     prelude::{GridPlacement, Line, Rect},
     style::{LengthPercentage, Style as TaffyStyle, TrackSizingFunction},
+    style_helpers::line,
 };
 
+use crate::animate::{Easing, EasingFn, EasingMode};
 use crate::context::InteractionState;
 use crate::responsive::{ScreenSize, ScreenSizeBp};
-use crate::unit::{Px, PxPct, PxPctAuto, UnitExt};
+use crate::unit::{GridTrack, Px, PxPct, PxPctAuto, UnitExt};
 use crate::view::View;
 use crate::views::{empty, stack, text, Decorators};
 
@@ -63,6 +66,7 @@ pub trait StylePropValue: Clone + PartialEq + Debug {
 }
 
 impl StylePropValue for i32 {}
+impl StylePropValue for usize {}
 impl StylePropValue for bool {}
 impl StylePropValue for f32 {}
 impl StylePropValue for f64 {
@@ -80,13 +84,59 @@ impl StylePropValue for TrackSizingFunction {}
 impl<T: StylePropValue> StylePropValue for Line<T> {}
 impl StylePropValue for GridPlacement {}
 impl StylePropValue for CursorStyle {}
+impl StylePropValue for Overflow {}
 impl StylePropValue for BoxShadow {}
+impl StylePropValue for Brush {
+    fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
+        match (self, other) {
+            (Brush::Solid(this), Brush::Solid(other)) => {
+                this.interpolate(other, value).map(Brush::Solid)
+            }
+            _ => None,
+        }
+    }
+}
 impl StylePropValue for String {}
 impl StylePropValue for Weight {}
 impl StylePropValue for cosmic_text::Style {}
 impl StylePropValue for TextOverflow {}
+impl StylePropValue for TextAlign {}
+impl StylePropValue for Direction {}
 impl StylePropValue for LineHeightValue {}
 impl StylePropValue for Size<LengthPercentage> {}
+impl StylePropValue for Affine {
+    /// The translation, rotation, skew and scale are interpolated separately, as interpolating
+    /// the coefficients would shrink a rotation halfway through it.
+    fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
+        let (mut this, other) = (decompose_affine(*self), decompose_affine(*other));
+        // Rotate the shorter way around
+        let turn = 2.0 * std::f64::consts::PI;
+        this[2] += ((other[2] - this[2]) / turn).round() * turn;
+        let [tx, ty, angle, sx, skew, sy] =
+            std::array::from_fn(|i| this[i].interpolate(&other[i], value).unwrap());
+        Some(
+            Affine::translate((tx, ty))
+                * Affine::rotate(angle)
+                * Affine::new([sx, 0.0, skew, sy, 0.0, 0.0]),
+        )
+    }
+}
+
+/// Split `affine` into its translation, rotation in radians, x scale, skew and y scale, which
+/// make it up when applied in reverse order.
+fn decompose_affine(affine: Affine) -> [f64; 6] {
+    let [a, b, c, d, tx, ty] = affine.as_coeffs();
+    let angle = b.atan2(a);
+    let (sin, cos) = angle.sin_cos();
+    [
+        tx,
+        ty,
+        angle,
+        a.hypot(b),
+        c * cos + d * sin,
+        d * cos - c * sin,
+    ]
+}
 
 impl<T: StylePropValue> StylePropValue for Option<T> {
     fn debug_view(&self) -> Option<Box<dyn View>> {
@@ -556,9 +606,8 @@ impl<T: StylePropValue> TransitionState<T> {
             if let Some(transition) = &self.transition {
                 let time = now.saturating_duration_since(active.start).as_secs_f64();
                 if time < transition.duration {
-                    if let Some(i) =
-                        T::interpolate(&active.before, &active.after, time / transition.duration)
-                    {
+                    let progress = transition.easing.ease(time / transition.duration);
+                    if let Some(i) = T::interpolate(&active.before, &active.after, progress) {
                         active.current = i;
                         *request_transition = true;
                         return true;
@@ -595,11 +644,44 @@ impl<T: StylePropValue> Default for TransitionState<T> {
 #[derive(Clone, Debug)]
 pub struct Transition {
     duration: f64,
+    easing: Easing,
 }
 
 impl Transition {
+    /// A transition of `duration` seconds which progresses at a constant rate.
     pub fn linear(duration: f64) -> Self {
-        Self { duration }
+        Self {
+            duration,
+            easing: Easing::default(),
+        }
+    }
+
+    /// A transition of `duration` seconds which speeds up quickly and then slows down,
+    /// like the CSS `ease` timing function.
+    pub fn ease(duration: f64) -> Self {
+        Self::linear(duration).easing_fn(EasingFn::CubicBezier {
+            x1: 0.25,
+            y1: 0.1,
+            x2: 0.25,
+            y2: 1.0,
+        })
+    }
+
+    /// A transition of `duration` seconds which starts and ends slowly.
+    pub fn ease_in_out(duration: f64) -> Self {
+        Self::linear(duration)
+            .easing_fn(EasingFn::Cubic)
+            .ease_mode(EasingMode::InOut)
+    }
+
+    pub fn easing_fn(mut self, easing_fn: EasingFn) -> Self {
+        self.easing.func = easing_fn;
+        self
+    }
+
+    pub fn ease_mode(mut self, mode: EasingMode) -> Self {
+        self.easing.mode = mode;
+        self
     }
 }
 
@@ -785,6 +867,36 @@ impl Style {
         }
     }
 
+    /// The radius of each corner of a view of `size`, or `None` if none of the corners
+    /// are rounded.
+    pub(crate) fn border_radii(&self, size: kurbo::Size) -> Option<kurbo::RoundedRectRadii> {
+        resolve_border_radii(
+            self.get(BorderRadius),
+            [
+                self.get(BorderTopLeftRadius),
+                self.get(BorderTopRightRadius),
+                self.get(BorderBottomRightRadius),
+                self.get(BorderBottomLeftRadius),
+            ],
+            size,
+        )
+    }
+
+    /// The transform of a view of `size`, around its transform origin, or `None` if it
+    /// isn't transformed.
+    pub(crate) fn resolved_transform(&self, size: kurbo::Size) -> Option<Affine> {
+        let transform = self.get(Transform)?;
+        let resolve = |value: PxPct, length: f64| match value {
+            PxPct::Px(px) => px,
+            PxPct::Pct(pct) => length * (pct / 100.),
+        };
+        let origin = kurbo::Vec2::new(
+            resolve(self.get(TransformOriginX), size.width),
+            resolve(self.get(TransformOriginY), size.height),
+        );
+        Some(Affine::translate(origin) * transform * Affine::translate(-origin))
+    }
+
     pub(crate) fn builtin(&self) -> BuiltinStyle<'_> {
         BuiltinStyle { style: self }
     }
@@ -895,6 +1007,40 @@ pub enum TextOverflow {
     Ellipsis,
 }
 
+/// How the lines of text are aligned horizontally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+    /// Spread out the words of lines to fill the width, except for the last line of a
+    /// paragraph which is aligned to the left.
+    Justify,
+}
+
+/// The direction of text and of rows, see [`Style::direction`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Left to right, for scripts like Latin.
+    Ltr,
+    /// Right to left, for scripts like Arabic and Hebrew.
+    Rtl,
+}
+
+/// How a view treats children that overflow its bounds.
+///
+/// There's no `scroll` value like in CSS, as a style can't add the scroll bars and scrolling of
+/// a view. Wrap the content in a [`scroll`](crate::views::scroll()) view instead, which clips
+/// it like [`Overflow::Hidden`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Children can draw outside of the view.
+    Visible,
+    /// Children are clipped to the content box of the view, inside of its border and padding,
+    /// both when painting and when hit testing pointer events.
+    Hidden,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum CursorStyle {
     Default,
@@ -912,8 +1058,88 @@ pub enum CursorStyle {
     SeResize,
     NeswResize,
     NwseResize,
+    /// Shows that something can't be done, like dropping a payload on a drop target which
+    /// rejects it.
+    NotAllowed,
+}
+
+/// A paint for filling shapes, either a solid color or a gradient.
+///
+/// Gradients are sized to the shape they fill, like CSS gradients.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Brush {
+    Solid(Color),
+    /// A gradient along a line at `angle` degrees, like CSS `linear-gradient`.
+    /// An angle of 0 goes from the bottom to the top, and 90 from left to right.
+    LinearGradient {
+        angle: f64,
+        stops: Vec<(f32, Color)>,
+    },
+    /// A gradient from the center to the farthest corner, like CSS `radial-gradient`.
+    RadialGradient {
+        stops: Vec<(f32, Color)>,
+    },
 }
 
+impl Brush {
+    /// Create a linear gradient at `angle` degrees. `stops` are pairs of an offset
+    /// in the range `[0, 1]` and a color.
+    pub fn linear_gradient(angle: f64, stops: impl IntoIterator<Item = (f32, Color)>) -> Self {
+        Brush::LinearGradient {
+            angle,
+            stops: stops.into_iter().collect(),
+        }
+    }
+
+    /// Create a radial gradient. `stops` are pairs of an offset in the range `[0, 1]`
+    /// and a color.
+    pub fn radial_gradient(stops: impl IntoIterator<Item = (f32, Color)>) -> Self {
+        Brush::RadialGradient {
+            stops: stops.into_iter().collect(),
+        }
+    }
+
+    /// Resolve the brush to a renderer brush for filling `rect`.
+    pub(crate) fn to_peniko(&self, rect: kurbo::Rect) -> peniko::Brush {
+        fn color_stops(stops: &[(f32, Color)]) -> Vec<peniko::ColorStop> {
+            stops
+                .iter()
+                .map(|&(offset, color)| peniko::ColorStop { offset, color })
+                .collect()
+        }
+
+        let center = rect.center();
+        match self {
+            Brush::Solid(color) => peniko::Brush::Solid(*color),
+            Brush::LinearGradient { angle, stops } => {
+                let (sin, cos) = angle.to_radians().sin_cos();
+                let direction = kurbo::Vec2::new(sin, -cos);
+                // The gradient line is long enough for the corners to get the first and last colors
+                let half_length = (rect.width() * sin.abs() + rect.height() * cos.abs()) / 2.0;
+                peniko::Gradient::new_linear(
+                    center - direction * half_length,
+                    center + direction * half_length,
+                )
+                .with_stops(color_stops(stops).as_slice())
+                .into()
+            }
+            Brush::RadialGradient { stops } => {
+                let radius = (rect.width() / 2.0).hypot(rect.height() / 2.0);
+                peniko::Gradient::new_radial(center, radius as f32)
+                    .with_stops(color_stops(stops).as_slice())
+                    .into()
+            }
+        }
+    }
+}
+
+impl From<Color> for Brush {
+    fn from(color: Color) -> Self {
+        Brush::Solid(color)
+    }
+}
+
+/// A shadow painted behind a view, like CSS `box-shadow`.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct BoxShadow {
     pub blur_radius: PxPct,
@@ -935,6 +1161,24 @@ impl Default for BoxShadow {
     }
 }
 
+impl BoxShadow {
+    pub fn new(
+        h_offset: impl Into<PxPct>,
+        v_offset: impl Into<PxPct>,
+        blur_radius: impl Into<PxPct>,
+        spread: impl Into<PxPct>,
+        color: Color,
+    ) -> Self {
+        Self {
+            blur_radius: blur_radius.into(),
+            color,
+            spread: spread.into(),
+            h_offset: h_offset.into(),
+            v_offset: v_offset.into(),
+        }
+    }
+}
+
 /// The value for a [`Style`] property
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StyleValue<T> {
@@ -993,6 +1237,30 @@ impl<T> From<T> for StyleValue<T> {
     }
 }
 
+/// Resolve the radius of each corner, in the order top left, top right, bottom right
+/// and bottom left. Corners without their own radius use `border_radius`.
+pub(crate) fn resolve_border_radii(
+    border_radius: PxPct,
+    corners: [Option<PxPct>; 4],
+    size: kurbo::Size,
+) -> Option<kurbo::RoundedRectRadii> {
+    let resolve = |radius: Option<PxPct>| match radius.unwrap_or(border_radius) {
+        PxPct::Px(px) => px,
+        PxPct::Pct(pct) => size.min_side() * (pct / 100.),
+    };
+    let [top_left, top_right, bottom_right, bottom_left] = corners.map(resolve);
+    if top_left > 0.0 || top_right > 0.0 || bottom_right > 0.0 || bottom_left > 0.0 {
+        Some(kurbo::RoundedRectRadii::new(
+            top_left.max(0.0),
+            top_right.max(0.0),
+            bottom_right.max(0.0),
+            bottom_left.max(0.0),
+        ))
+    } else {
+        None
+    }
+}
+
 macro_rules! define_builtin_props {
     (
         $($type_name:ident $name:ident $($opt:ident)?:
@@ -1056,6 +1324,10 @@ define_builtin_props!(
     BorderRight border_right: Px {} = Px(0.0),
     BorderBottom border_bottom: Px {} = Px(0.0),
     BorderRadius border_radius: PxPct {} = PxPct::Px(0.0),
+    BorderTopLeftRadius border_top_left_radius nocb: Option<PxPct> {} = None,
+    BorderTopRightRadius border_top_right_radius nocb: Option<PxPct> {} = None,
+    BorderBottomRightRadius border_bottom_right_radius nocb: Option<PxPct> {} = None,
+    BorderBottomLeftRadius border_bottom_left_radius nocb: Option<PxPct> {} = None,
     OutlineColor outline_color: Color {} = Color::TRANSPARENT,
     Outline outline: Px {} = Px(0.0),
     BorderColor border_color: Color {} = Color::BLACK,
@@ -1072,19 +1344,29 @@ define_builtin_props!(
     InsetRight inset_right: PxPctAuto {} = PxPctAuto::Auto,
     InsetBottom inset_bottom: PxPctAuto {} = PxPctAuto::Auto,
     ZIndex z_index nocb: Option<i32> {} = None,
+    Transform transform nocb: Option<Affine> {} = None,
+    TransformOriginX transform_origin_x: PxPct {} = PxPct::Pct(50.0),
+    TransformOriginY transform_origin_y: PxPct {} = PxPct::Pct(50.0),
+    Opacity opacity: f64 {} = 1.0,
+    BackdropBlur backdrop_blur: f64 {} = 0.0,
     Cursor cursor nocb: Option<CursorStyle> {} = None,
     TextColor color nocb: Option<Color> { inherited } = None,
     Background background nocb: Option<Color> {} = None,
+    BackgroundBrush background_brush nocb: Option<Brush> {} = None,
     Foreground foreground nocb: Option<Color> {} = None,
-    BoxShadowProp box_shadow nocb: Option<BoxShadow> {} = None,
+    BoxShadowProp box_shadow nocb: Vec<BoxShadow> {} = Vec::new(),
     FontSize font_size nocb: Option<f32> { inherited } = None,
     FontFamily font_family nocb: Option<String> { inherited } = None,
     FontWeight font_weight nocb: Option<Weight> { inherited } = None,
     FontStyle font_style nocb: Option<cosmic_text::Style> { inherited } = None,
     CursorColor cursor_color nocb: Option<Color> {} = None,
     TextOverflowProp text_overflow: TextOverflow {} = TextOverflow::Wrap,
+    TextAlignProp text_align nocb: Option<TextAlign> { inherited } = None,
+    DirectionProp direction nocb: Direction { inherited } = Direction::Ltr,
+    MaxLines max_lines nocb: Option<usize> {} = None,
+    OverflowProp overflow: Overflow {} = Overflow::Visible,
     LineHeight line_height nocb: Option<LineHeightValue> { inherited } = None,
-    AspectRatio aspect_ratio: Option<f32> {} = None,
+    AspectRatio aspect_ratio nocb: Option<f32> {} = None,
     Gap gap nocb: Size<LengthPercentage> {} = Size::zero(),
 );
 
@@ -1107,6 +1389,7 @@ prop_extracter! {
         pub padding_top: PaddingTop,
         pub padding_right: PaddingRight,
         pub padding_bottom: PaddingBottom,
+        pub direction: DirectionProp,
     }
 }
 
@@ -1148,10 +1431,17 @@ impl Style {
     }
 
     /// The visual style to apply when the mouse hovers over the element
+    ///
+    /// When several of the interaction states apply, their styles are applied in this order
+    /// with later ones winning: `hover`, `focus`, `selected`, `disabled`, `focus_visible` and
+    /// `active`. So pressing a hovered view shows its `active` style over its `hover` style.
+    /// Disabled views are never hovered or pressed.
     pub fn hover(self, style: impl FnOnce(Style) -> Style) -> Self {
         self.selector(StyleSelector::Hover, style)
     }
 
+    /// The visual style to apply when the element has the keyboard focus. See
+    /// [`Style::hover`] for how it's combined with the other states.
     pub fn focus(self, style: impl FnOnce(Style) -> Style) -> Self {
         self.selector(StyleSelector::Focus, style)
     }
@@ -1169,6 +1459,9 @@ impl Style {
         self.selector(StyleSelector::Disabled, style)
     }
 
+    /// The visual style to apply while the element is pressed, with the pointer over it or
+    /// with the keyboard when it's focused. It wins over the other states, see
+    /// [`Style::hover`].
     pub fn active(self, style: impl FnOnce(Style) -> Style) -> Self {
         self.selector(StyleSelector::Active, style)
     }
@@ -1215,6 +1508,17 @@ impl Style {
         )
     }
 
+    /// Keep the view at a ratio of `width / height`, like `16.0 / 9.0`. If only one of the
+    /// width and height is set, or stretched to fill the parent, the other is derived from it.
+    /// If neither is, the view is sized to fit its content and then the ratio is applied, and
+    /// if both are, the ratio is ignored. Ratios which aren't positive are also ignored.
+    pub fn aspect_ratio(self, ratio: impl Into<Option<f32>>) -> Self {
+        let ratio = ratio
+            .into()
+            .filter(|ratio| ratio.is_finite() && *ratio > 0.0);
+        self.set(AspectRatio, ratio)
+    }
+
     pub fn size(self, width: impl Into<PxPctAuto>, height: impl Into<PxPctAuto>) -> Self {
         self.width(width).height(height)
     }
@@ -1452,6 +1756,9 @@ impl Style {
             .inset_bottom(inset)
     }
 
+    /// Sets the cursor shown while the pointer is over the view. The topmost view with a
+    /// cursor under the pointer decides it, unless a view captured the pointer, in which case
+    /// its cursor is used wherever the pointer is.
     pub fn cursor(self, cursor: impl Into<StyleValue<CursorStyle>>) -> Self {
         self.set_style_value(Cursor, cursor.into().map(Some))
     }
@@ -1464,34 +1771,67 @@ impl Style {
         self.set_style_value(Background, color.into().map(Some))
     }
 
+    /// Fill the background with a [`Brush`], such as a gradient.
+    /// This takes precedence over [`Style::background`].
+    pub fn background_brush(self, brush: impl Into<Brush>) -> Self {
+        self.set(BackgroundBrush, Some(brush.into()))
+    }
+
+    /// Sets the radius of the top left corner, overriding `border_radius`.
+    pub fn border_top_left_radius(self, radius: impl Into<PxPct>) -> Self {
+        self.set(BorderTopLeftRadius, Some(radius.into()))
+    }
+
+    /// Sets the radius of the top right corner, overriding `border_radius`.
+    pub fn border_top_right_radius(self, radius: impl Into<PxPct>) -> Self {
+        self.set(BorderTopRightRadius, Some(radius.into()))
+    }
+
+    /// Sets the radius of the bottom right corner, overriding `border_radius`.
+    pub fn border_bottom_right_radius(self, radius: impl Into<PxPct>) -> Self {
+        self.set(BorderBottomRightRadius, Some(radius.into()))
+    }
+
+    /// Sets the radius of the bottom left corner, overriding `border_radius`.
+    pub fn border_bottom_left_radius(self, radius: impl Into<PxPct>) -> Self {
+        self.set(BorderBottomLeftRadius, Some(radius.into()))
+    }
+
+    /// Add a shadow to the view. Shadows stack, with the first one painted on top.
+    pub fn box_shadow(self, shadow: BoxShadow) -> Self {
+        let mut shadows = self.get(BoxShadowProp);
+        shadows.push(shadow);
+        self.set(BoxShadowProp, shadows)
+    }
+
+    /// Update the last shadow added with [`Style::box_shadow`], adding one if there are none.
+    fn update_box_shadow(self, f: impl FnOnce(&mut BoxShadow)) -> Self {
+        let mut shadows = self.get(BoxShadowProp);
+        if shadows.is_empty() {
+            shadows.push(BoxShadow::default());
+        }
+        f(shadows.last_mut().unwrap());
+        self.set(BoxShadowProp, shadows)
+    }
+
     pub fn box_shadow_blur(self, blur_radius: impl Into<PxPct>) -> Self {
-        let mut value = self.get(BoxShadowProp).unwrap_or_default();
-        value.blur_radius = blur_radius.into();
-        self.set(BoxShadowProp, Some(value))
+        self.update_box_shadow(|shadow| shadow.blur_radius = blur_radius.into())
     }
 
     pub fn box_shadow_color(self, color: Color) -> Self {
-        let mut value = self.get(BoxShadowProp).unwrap_or_default();
-        value.color = color;
-        self.set(BoxShadowProp, Some(value))
+        self.update_box_shadow(|shadow| shadow.color = color)
     }
 
     pub fn box_shadow_spread(self, spread: impl Into<PxPct>) -> Self {
-        let mut value = self.get(BoxShadowProp).unwrap_or_default();
-        value.spread = spread.into();
-        self.set(BoxShadowProp, Some(value))
+        self.update_box_shadow(|shadow| shadow.spread = spread.into())
     }
 
     pub fn box_shadow_h_offset(self, h_offset: impl Into<PxPct>) -> Self {
-        let mut value = self.get(BoxShadowProp).unwrap_or_default();
-        value.h_offset = h_offset.into();
-        self.set(BoxShadowProp, Some(value))
+        self.update_box_shadow(|shadow| shadow.h_offset = h_offset.into())
     }
 
     pub fn box_shadow_v_offset(self, v_offset: impl Into<PxPct>) -> Self {
-        let mut value = self.get(BoxShadowProp).unwrap_or_default();
-        value.v_offset = v_offset.into();
-        self.set(BoxShadowProp, Some(value))
+        self.update_box_shadow(|shadow| shadow.v_offset = v_offset.into())
     }
 
     pub fn font_size(self, size: impl Into<StyleValue<f32>>) -> Self {
@@ -1518,14 +1858,48 @@ impl Style {
         self.set_style_value(CursorColor, color.into().map(Some))
     }
 
+    /// Set the line height as a multiple of the font size.
     pub fn line_height(self, normal: f32) -> Self {
         self.set(LineHeight, Some(LineHeightValue::Normal(normal)))
     }
 
+    /// Set the line height in pixels.
+    pub fn line_height_px(self, px: f32) -> Self {
+        self.set(LineHeight, Some(LineHeightValue::Px(px)))
+    }
+
+    /// Set how lines of text are aligned. Text is aligned within the width of the view when it
+    /// wraps, which is the default [`TextOverflow`].
+    pub fn text_align(self, align: impl Into<StyleValue<TextAlign>>) -> Self {
+        self.set_style_value(TextAlignProp, align.into().map(Some))
+    }
+
     pub fn text_ellipsis(self) -> Self {
         self.text_overflow(TextOverflow::Ellipsis)
     }
 
+    /// Wrap text over at most `max_lines` lines, and truncate it with an ellipsis if it
+    /// doesn't fit in those.
+    pub fn max_lines(self, max_lines: usize) -> Self {
+        self.text_overflow(TextOverflow::Ellipsis)
+            .set(MaxLines, Some(max_lines.max(1)))
+    }
+
+    /// Set the direction of the view and its children. Right to left mirrors the layout: rows
+    /// are laid out from the right, the left and right padding, margins and insets are
+    /// swapped, and labels are aligned to the right unless they have a `text_align`.
+    ///
+    /// Text is reordered for its script in either direction, so right to left text in a left to
+    /// right view, or the other way around, is shown correctly.
+    pub fn direction(self, direction: Direction) -> Self {
+        self.set(DirectionProp, direction)
+    }
+
+    /// Lay out the view and its children from right to left. See [`Style::direction`].
+    pub fn rtl(self) -> Self {
+        self.direction(Direction::Rtl)
+    }
+
     pub fn text_clip(self) -> Self {
         self.text_overflow(TextOverflow::Clip)
     }
@@ -1580,10 +1954,89 @@ impl Style {
         self.flex_direction(taffy::style::FlexDirection::Column)
     }
 
+    /// Lay out the children in a grid, with the tracks set by [`Style::grid_columns`] and
+    /// [`Style::grid_rows`]. The space between rows and columns is set with [`Style::gap`].
+    pub fn grid(self) -> Self {
+        self.display(taffy::style::Display::Grid)
+    }
+
+    /// Set the columns of a grid. Children are put in the cells in order, row by row, unless
+    /// they're placed with [`Style::grid_column_span`] or [`Style::grid_row_span`], and rows
+    /// are added as needed.
+    ///
+    /// ```rust
+    /// # use floem::{style::Style, unit::GridTrack};
+    /// // A fixed width column for labels, with the values taking the rest of the width
+    /// Style::new()
+    ///     .grid()
+    ///     .grid_columns([GridTrack::Px(120.0), GridTrack::Fr(1.0)])
+    ///     .gap(10.0, 5.0);
+    /// ```
+    pub fn grid_columns(self, columns: impl IntoIterator<Item = GridTrack>) -> Self {
+        self.grid_template_columns(columns.into_iter().map(Into::into).collect())
+    }
+
+    /// Set the rows of a grid. Rows added for children which don't fit in them are sized to
+    /// fit their content.
+    pub fn grid_rows(self, rows: impl IntoIterator<Item = GridTrack>) -> Self {
+        self.grid_template_rows(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Place the view in a grid starting at `column`, counting from 1, and spanning `span`
+    /// columns. Negative columns count from the end.
+    pub fn grid_column_span(self, column: i16, span: u16) -> Self {
+        self.grid_column(Line {
+            start: line(column),
+            end: GridPlacement::Span(span),
+        })
+    }
+
+    /// Place the view in a grid starting at `row`, counting from 1, and spanning `span` rows.
+    /// Negative rows count from the end.
+    pub fn grid_row_span(self, row: i16, span: u16) -> Self {
+        self.grid_row(Line {
+            start: line(row),
+            end: GridPlacement::Span(span),
+        })
+    }
+
+    /// Set the stacking order of the view among its siblings. Siblings with a higher
+    /// z-index are painted on top and receive pointer events first, and siblings with
+    /// the same z-index are ordered by declaration.
     pub fn z_index(self, z_index: i32) -> Self {
         self.set(ZIndex, Some(z_index))
     }
 
+    /// Transform how the view is painted and where it receives pointer events, around its
+    /// [transform origin](Self::transform_origin). The layout of the view isn't affected.
+    pub fn transform(self, transform: Affine) -> Self {
+        self.set(Transform, Some(transform))
+    }
+
+    /// Rotate the view by `radians` clockwise, after its current transform.
+    pub fn rotate(self, radians: f64) -> Self {
+        let transform = self.get(Transform).unwrap_or(Affine::IDENTITY);
+        self.transform(Affine::rotate(radians) * transform)
+    }
+
+    /// Scale the view by `factor`, after its current transform.
+    pub fn scale(self, factor: f64) -> Self {
+        let transform = self.get(Transform).unwrap_or(Affine::IDENTITY);
+        self.transform(Affine::scale(factor) * transform)
+    }
+
+    /// Move the view by `x` and `y`, after its current transform.
+    pub fn translate(self, x: f64, y: f64) -> Self {
+        let transform = self.get(Transform).unwrap_or(Affine::IDENTITY);
+        self.transform(Affine::translate((x, y)) * transform)
+    }
+
+    /// Set the point the transform of the view is applied around, relative to the view.
+    /// This is the center of the view by default.
+    pub fn transform_origin(self, x: impl Into<PxPct>, y: impl Into<PxPct>) -> Self {
+        self.transform_origin_x(x).transform_origin_y(y)
+    }
+
     /// Allow the application of a function if the option exists.  
     /// This is useful for chaining together a bunch of optional style changes.  
     /// ```rust,ignore
@@ -1620,6 +2073,23 @@ impl Style {
 impl Style {
     pub fn to_taffy_style(&self) -> TaffyStyle {
         let style = self.builtin();
+        let rtl = style.direction() == Direction::Rtl;
+        let flex_direction = match style.flex_direction() {
+            FlexDirection::Row if rtl => FlexDirection::RowReverse,
+            FlexDirection::RowReverse if rtl => FlexDirection::Row,
+            flex_direction => flex_direction,
+        };
+        // Left and right are the start and end, which are swapped from right to left
+        fn mirror<T>(rtl: bool, left: T, right: T) -> (T, T) {
+            if rtl {
+                (right, left)
+            } else {
+                (left, right)
+            }
+        }
+        let padding = mirror(rtl, style.padding_left(), style.padding_right());
+        let margin = mirror(rtl, style.margin_left(), style.margin_right());
+        let inset = mirror(rtl, style.inset_left(), style.inset_right());
         TaffyStyle {
             display: style.display(),
             position: style.position(),
@@ -1635,7 +2105,7 @@ impl Style {
                 width: style.max_width().into(),
                 height: style.max_height().into(),
             },
-            flex_direction: style.flex_direction(),
+            flex_direction,
             flex_grow: style.flex_grow(),
             flex_shrink: style.flex_shrink(),
             flex_basis: style.flex_basis().into(),
@@ -1653,21 +2123,21 @@ impl Style {
                 bottom: LengthPercentage::Points(style.border_bottom().0 as f32),
             },
             padding: Rect {
-                left: style.padding_left().into(),
+                left: padding.0.into(),
                 top: style.padding_top().into(),
-                right: style.padding_right().into(),
+                right: padding.1.into(),
                 bottom: style.padding_bottom().into(),
             },
             margin: Rect {
-                left: style.margin_left().into(),
+                left: margin.0.into(),
                 top: style.margin_top().into(),
-                right: style.margin_right().into(),
+                right: margin.1.into(),
                 bottom: style.margin_bottom().into(),
             },
             inset: Rect {
-                left: style.inset_left().into(),
+                left: inset.0.into(),
                 top: style.inset_top().into(),
-                right: style.inset_right().into(),
+                right: inset.1.into(),
                 bottom: style.inset_bottom().into(),
             },
             gap: style.gap(),
@@ -1682,11 +2152,15 @@ impl Style {
 
 #[cfg(test)]
 mod tests {
-    use super::{Style, StyleValue};
+    use super::{Style, StylePropValue, StyleValue};
     use crate::{
+        context::InteractionState,
+        responsive::ScreenSizeBp,
         style::{PaddingBottom, PaddingLeft},
         unit::PxPct,
     };
+    use kurbo::Affine;
+    use taffy::{geometry::Size, Taffy};
 
     #[test]
     fn style_override() {
@@ -1761,4 +2235,142 @@ mod tests {
             StyleValue::Val(PxPct::Px(100.0))
         );
     }
+
+    #[test]
+    fn max_width_limits_flex_grow() {
+        let mut taffy = Taffy::new();
+        let capped = taffy
+            .new_leaf(
+                Style::new()
+                    .flex_grow(1.0)
+                    .max_width(200.0)
+                    .to_taffy_style(),
+            )
+            .unwrap();
+        let growing = taffy
+            .new_leaf(Style::new().flex_grow(1.0).to_taffy_style())
+            .unwrap();
+        let half = taffy
+            .new_leaf(
+                Style::new()
+                    .width_pct(50.0)
+                    .max_width(300.0)
+                    .to_taffy_style(),
+            )
+            .unwrap();
+        let root = taffy
+            .new_with_children(
+                Style::new().width(1000.0).to_taffy_style(),
+                &[capped, growing],
+            )
+            .unwrap();
+        let column = taffy
+            .new_with_children(Style::new().width(1000.0).to_taffy_style(), &[half])
+            .unwrap();
+        taffy.compute_layout(root, Size::MAX_CONTENT).unwrap();
+        taffy.compute_layout(column, Size::MAX_CONTENT).unwrap();
+
+        // The capped child stops growing and the space it would have taken goes to its sibling
+        assert_eq!(taffy.layout(capped).unwrap().size.width, 200.0);
+        assert_eq!(taffy.layout(growing).unwrap().size.width, 800.0);
+        assert_eq!(taffy.layout(half).unwrap().size.width, 300.0);
+    }
+
+    #[test]
+    fn interaction_state_order() {
+        let style = Style::new()
+            .active(|s| s.padding_left(3.0))
+            .focus(|s| s.padding_left(2.0))
+            .hover(|s| s.padding_left(1.0));
+        let padding = |state: InteractionState| {
+            let mut style = style.clone();
+            style.apply_interact_state(&state, ScreenSizeBp::Xs);
+            style.get(PaddingLeft)
+        };
+
+        assert_eq!(padding(InteractionState::default()), PxPct::Px(0.0));
+        let hovered = || InteractionState {
+            is_hovered: true,
+            ..Default::default()
+        };
+        assert_eq!(padding(hovered()), PxPct::Px(1.0));
+        // The later states win regardless of the order they were set in
+        assert_eq!(
+            padding(InteractionState {
+                is_focused: true,
+                ..hovered()
+            }),
+            PxPct::Px(2.0)
+        );
+        assert_eq!(
+            padding(InteractionState {
+                is_focused: true,
+                is_clicking: true,
+                ..hovered()
+            }),
+            PxPct::Px(3.0)
+        );
+        // Disabled views aren't hovered
+        assert_eq!(
+            padding(InteractionState {
+                is_disabled: true,
+                ..hovered()
+            }),
+            PxPct::Px(0.0)
+        );
+    }
+
+    #[test]
+    fn transform_around_origin() {
+        let size = kurbo::Size::new(100.0, 50.0);
+        let rotated = Style::new()
+            .rotate(std::f64::consts::FRAC_PI_2)
+            .resolved_transform(size)
+            .unwrap();
+        // The center stays in place, and the top left corner rotates around it
+        let center = rotated * kurbo::Point::new(50.0, 25.0);
+        assert!((center - kurbo::Point::new(50.0, 25.0)).hypot() < 1e-9);
+        let corner = rotated * kurbo::Point::ZERO;
+        assert!((corner - kurbo::Point::new(75.0, -25.0)).hypot() < 1e-9);
+
+        let scaled = Style::new()
+            .scale(2.0)
+            .transform_origin(0.0, 0.0)
+            .resolved_transform(size)
+            .unwrap();
+        assert_eq!(
+            scaled * kurbo::Point::new(10.0, 5.0),
+            kurbo::Point::new(20.0, 10.0)
+        );
+
+        assert_eq!(Style::new().resolved_transform(size), None);
+    }
+
+    #[test]
+    fn transform_interpolates_rotation() {
+        use std::f64::consts::PI;
+
+        let close = |a: Affine, b: Affine| {
+            let (a, b) = (a.as_coeffs(), b.as_coeffs());
+            a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-9)
+        };
+        let halfway = |from: Affine, to: Affine| from.interpolate(&to, 0.5).unwrap();
+
+        // The rotation keeps its scale halfway through
+        let rotated = halfway(Affine::IDENTITY, Affine::rotate(PI / 2.0));
+        assert!(close(rotated, Affine::rotate(PI / 4.0)));
+
+        // It goes the shorter way around
+        let rotated = halfway(Affine::rotate(-3.0), Affine::rotate(3.0));
+        assert!(close(rotated, Affine::rotate(PI)));
+
+        let moved = halfway(
+            Affine::translate((10.0, 0.0)) * Affine::scale(2.0),
+            Affine::translate((20.0, 10.0)) * Affine::rotate(PI / 2.0) * Affine::scale(4.0),
+        );
+        assert!(close(
+            moved,
+            Affine::translate((15.0, 5.0)) * Affine::rotate(PI / 4.0) * Affine::scale(3.0)
+        ));
+    }
 }
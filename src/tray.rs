@@ -0,0 +1,182 @@
+//! An icon in the system tray, or the status bar on macOS, added with
+//! [`Application::tray`](crate::Application::tray). This needs the `tray` feature.
+//!
+//! Tray icons are supported on Windows and macOS. On other platforms no icon is shown.
+#![cfg_attr(not(any(target_os = "windows", target_os = "macos")), allow(dead_code))]
+
+use crate::menu::Menu;
+
+/// An icon in the system tray with a tooltip and a menu.
+///
+/// ```rust,no_run
+/// # use floem::{menu::{Menu, MenuItem}, tray::Tray, Application};
+/// # let icon = vec![0; 16 * 16 * 4];
+/// Application::new()
+///     .tray(
+///         Tray::new()
+///             .icon(icon, 16, 16)
+///             .tooltip("Syncing")
+///             .menu(Menu::new("").entry(MenuItem::new("Quit").action(floem::quit_app))),
+///     )
+///     .run();
+/// ```
+#[derive(Default)]
+pub struct Tray {
+    pub(crate) icon: Option<(Vec<u8>, u32, u32)>,
+    pub(crate) tooltip: Option<String>,
+    pub(crate) menu: Option<Menu>,
+    pub(crate) on_click: Option<Box<dyn Fn()>>,
+}
+
+impl Tray {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the icon to an image of `width` by `height` pixels, in RGBA order.
+    pub fn icon(mut self, rgba: Vec<u8>, width: u32, height: u32) -> Self {
+        self.icon = Some((rgba, width, height));
+        self
+    }
+
+    /// Set the text shown while the pointer is over the icon.
+    pub fn tooltip(mut self, tooltip: impl Into<String>) -> Self {
+        self.tooltip = Some(tooltip.into());
+        self
+    }
+
+    /// Set the menu which is opened by clicking the icon. Only the entries of `menu` are
+    /// shown, not its title. Selecting an item runs its action, like in a window menu.
+    pub fn menu(mut self, menu: Menu) -> Self {
+        self.menu = Some(menu);
+        self
+    }
+
+    /// Set a handler which is called when the icon is clicked with the primary button. This
+    /// can show the main window with [`focus_window`](crate::window::focus_window), or hide it
+    /// with [`minimize_window`](crate::window::minimize_window).
+    pub fn on_click(mut self, action: impl Fn() + 'static) -> Self {
+        self.on_click = Some(Box::new(action));
+        self
+    }
+}
+
+/// An event of the tray icon, which is sent to the event loop
+#[derive(Debug)]
+pub(crate) enum TrayEvent {
+    Click,
+    Menu(String),
+}
+
+/// A tray icon which is shown, with the actions of its menu
+pub(crate) struct ActiveTray {
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    _icon: tray_icon::TrayIcon,
+    actions: std::collections::HashMap<String, Box<dyn Fn()>>,
+    on_click: Option<Box<dyn Fn()>>,
+}
+
+impl ActiveTray {
+    pub(crate) fn handle_event(&self, event: TrayEvent) {
+        let action = match &event {
+            TrayEvent::Click => self.on_click.as_ref(),
+            TrayEvent::Menu(id) => self.actions.get(id),
+        };
+        if let Some(action) = action {
+            action();
+        }
+    }
+}
+
+impl Tray {
+    /// Show the icon. This must be called once the event loop is running, and it returns
+    /// `None` if the platform doesn't support tray icons.
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    pub(crate) fn show(self) -> Option<ActiveTray> {
+        use crate::app::{Application, UserEvent};
+        use tray_icon::{menu::MenuEvent, ClickType, TrayIconBuilder, TrayIconEvent};
+
+        fn send(event: TrayEvent) {
+            Application::with_event_loop_proxy(|proxy| {
+                let _ = proxy.send_event(UserEvent::Tray(event));
+            });
+        }
+
+        let mut actions = std::collections::HashMap::new();
+        let mut builder = TrayIconBuilder::new();
+        if let Some(tooltip) = self.tooltip {
+            builder = builder.with_tooltip(tooltip);
+        }
+        if let Some((rgba, width, height)) = self.icon {
+            if let Ok(icon) = tray_icon::Icon::from_rgba(rgba, width, height) {
+                builder = builder.with_icon(icon);
+            }
+        }
+        if let Some(menu) = self.menu {
+            let tray_menu = tray_icon::menu::Menu::new();
+            for entry in menu.children {
+                let _ = tray_menu.append(&*tray_menu_entry(entry, &mut actions));
+            }
+            builder = builder.with_menu(Box::new(tray_menu));
+        }
+        let icon = builder.build().ok()?;
+
+        TrayIconEvent::set_event_handler(Some(|event: TrayIconEvent| {
+            if event.click_type == ClickType::Left {
+                send(TrayEvent::Click);
+            }
+        }));
+        MenuEvent::set_event_handler(Some(|event: MenuEvent| {
+            send(TrayEvent::Menu(event.id.0));
+        }));
+
+        Some(ActiveTray {
+            _icon: icon,
+            actions,
+            on_click: self.on_click,
+        })
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    pub(crate) fn show(self) -> Option<ActiveTray> {
+        None
+    }
+}
+
+/// The tray menu entry for `entry`, adding the actions of its items to `actions`.
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+fn tray_menu_entry(
+    entry: crate::menu::MenuEntry,
+    actions: &mut std::collections::HashMap<String, Box<dyn Fn()>>,
+) -> Box<dyn tray_icon::menu::IsMenuItem> {
+    use crate::menu::MenuEntry;
+    use tray_icon::menu::{CheckMenuItem, MenuItem, PredefinedMenuItem, Submenu};
+
+    match entry {
+        MenuEntry::Separator => Box::new(PredefinedMenuItem::separator()),
+        MenuEntry::Item(item) => {
+            let title = item.platform_title();
+            let (id, tray_item): (_, Box<dyn tray_icon::menu::IsMenuItem>) = match item.selected {
+                Some(checked) => {
+                    let tray_item = CheckMenuItem::new(title, item.enabled, checked, None);
+                    (tray_item.id().clone(), Box::new(tray_item))
+                }
+                None => {
+                    let tray_item = MenuItem::new(title, item.enabled, None);
+                    (tray_item.id().clone(), Box::new(tray_item))
+                }
+            };
+            if let Some(action) = item.action {
+                actions.insert(id.0, action);
+            }
+            tray_item
+        }
+        MenuEntry::SubMenu(menu) => {
+            let submenu = Submenu::new(menu.item.platform_title(), menu.item.enabled);
+            for entry in menu.children {
+                let _ = submenu.append(&*tray_menu_entry(entry, actions));
+            }
+            Box::new(submenu)
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use taffy::style::{Dimension, LengthPercentage, LengthPercentageAuto};
+use taffy::style::{
+    Dimension, LengthPercentage, LengthPercentageAuto, MaxTrackSizingFunction,
+    MinTrackSizingFunction, NonRepeatedTrackSizingFunction, TrackSizingFunction,
+};
 
 /// A pixel value
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -79,6 +82,32 @@ where
     }
 }
 
+/// The size of a row or column of a grid. See [`Style::grid_columns`](crate::style::Style::grid_columns).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GridTrack {
+    Px(f64),
+    Pct(f64),
+    /// A share of the space left over by the other tracks, like `fr` in CSS. The space is split
+    /// between the `Fr` tracks in proportion to their values.
+    Fr(f32),
+    /// Sized to fit the content of the track.
+    Auto,
+    MinContent,
+    MaxContent,
+}
+
+impl<T> From<T> for GridTrack
+where
+    T: Into<PxPct>,
+{
+    fn from(value: T) -> Self {
+        match value.into() {
+            PxPct::Px(v) => GridTrack::Px(v),
+            PxPct::Pct(v) => GridTrack::Pct(v),
+        }
+    }
+}
+
 pub trait UnitExt {
     fn pct(self) -> Pct;
     fn px(self) -> Px;
@@ -123,6 +152,41 @@ impl From<PxPct> for LengthPercentage {
     }
 }
 
+impl From<GridTrack> for TrackSizingFunction {
+    fn from(value: GridTrack) -> Self {
+        let (min, max) = match value {
+            GridTrack::Px(v) => {
+                let v = LengthPercentage::Points(v as f32);
+                (
+                    MinTrackSizingFunction::Fixed(v),
+                    MaxTrackSizingFunction::Fixed(v),
+                )
+            }
+            GridTrack::Pct(v) => {
+                let v = LengthPercentage::Percent(v as f32 / 100.0);
+                (
+                    MinTrackSizingFunction::Fixed(v),
+                    MaxTrackSizingFunction::Fixed(v),
+                )
+            }
+            GridTrack::Fr(v) => (
+                MinTrackSizingFunction::Auto,
+                MaxTrackSizingFunction::Flex(v),
+            ),
+            GridTrack::Auto => (MinTrackSizingFunction::Auto, MaxTrackSizingFunction::Auto),
+            GridTrack::MinContent => (
+                MinTrackSizingFunction::MinContent,
+                MaxTrackSizingFunction::MinContent,
+            ),
+            GridTrack::MaxContent => (
+                MinTrackSizingFunction::MaxContent,
+                MaxTrackSizingFunction::MaxContent,
+            ),
+        };
+        TrackSizingFunction::Single(NonRepeatedTrackSizingFunction { min, max })
+    }
+}
+
 impl From<PxPctAuto> for LengthPercentageAuto {
     fn from(value: PxPctAuto) -> Self {
         match value {
@@ -1,13 +1,16 @@
-use std::{any::Any, cell::RefCell, collections::HashMap};
+use std::{any::Any, cell::RefCell, collections::HashMap, rc::Rc};
 
+use floem_reactive::Scope;
 use kurbo::{Point, Rect, Size, Vec2};
-use winit::window::ResizeDirection;
+use winit::window::{CursorGrabMode, ResizeDirection};
 
 use crate::{
+    accessibility::AccessUpdate,
     animate::{AnimUpdateMsg, Animation},
-    context::{EventCallback, ResizeCallback},
+    context::{DropTarget, EventCallback, ResizeCallback},
     event::EventListener,
     id::Id,
+    keyboard::{KeyCombination, ShortcutId},
     menu::Menu,
     style::{Style, StyleClassRef, StyleSelector},
     view::View,
@@ -34,6 +37,14 @@ pub(crate) enum UpdateMessage {
     Focus(Id),
     ClearFocus(Id),
     Active(Id),
+    RegisterShortcut {
+        id: ShortcutId,
+        combination: KeyCombination,
+        action: Box<dyn Fn()>,
+    },
+    UnregisterShortcut(ShortcutId),
+    CapturePointer(Id),
+    ReleasePointer(Id),
     WindowScale(f64),
     Disabled {
         id: Id,
@@ -43,7 +54,7 @@ pub(crate) enum UpdateMessage {
         id: Id,
         flags: ChangeFlags,
     },
-    RequestPaint,
+    RequestPaint(Id),
     State {
         id: Id,
         state: Box<dyn Any>,
@@ -65,9 +76,37 @@ pub(crate) enum UpdateMessage {
     KeyboardNavigable {
         id: Id,
     },
+    DisableFocus {
+        id: Id,
+    },
+    AccessKey {
+        id: Id,
+        key: char,
+    },
+    TabIndex {
+        id: Id,
+        index: i32,
+    },
+    FocusTrap {
+        id: Id,
+    },
+    TabNavigation {
+        backwards: bool,
+    },
+    DisabledAbsorbsPointer {
+        id: Id,
+    },
     Draggable {
         id: Id,
     },
+    DragPayload {
+        id: Id,
+        payload: Box<dyn Fn() -> Rc<dyn Any>>,
+    },
+    DropTarget {
+        id: Id,
+        target: DropTarget,
+    },
     EventListener {
         id: Id,
         listener: EventListener,
@@ -85,6 +124,14 @@ pub(crate) enum UpdateMessage {
         id: Id,
         action: Box<dyn Fn()>,
     },
+    MountedListener {
+        id: Id,
+        action: Box<dyn FnOnce()>,
+    },
+    OwnScope {
+        id: Id,
+        scope: Scope,
+    },
     ToggleWindowMaximized,
     SetWindowMaximized(bool),
     MinimizeWindow,
@@ -117,11 +164,20 @@ pub(crate) enum UpdateMessage {
         id: Id,
         position: Point,
         view: Box<dyn FnOnce() -> Box<dyn View>>,
+        /// Makes the overlay a modal, which is called when it's dismissed with Escape.
+        on_dismiss: Option<Box<dyn Fn()>>,
     },
     RemoveOverlay {
         id: Id,
     },
     Inspect,
+    ToggleInspectorOverlay,
+    /// Show a file dialog, parented to the window if it has one
+    FileDialog(Box<dyn FnOnce(Option<&winit::window::Window>)>),
+    Accessibility {
+        id: Id,
+        update: AccessUpdate,
+    },
     ScrollTo {
         id: Id,
         rect: Option<Rect>,
@@ -130,6 +186,8 @@ pub(crate) enum UpdateMessage {
     SetImeAllowed {
         allowed: bool,
     },
+    SetCursorVisible(bool),
+    SetCursorGrab(CursorGrabMode),
     SetImeCursorArea {
         position: Point,
         size: Size,
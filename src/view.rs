@@ -84,7 +84,7 @@
 //!
 
 use floem_renderer::Renderer;
-use kurbo::{Circle, Insets, Line, Point, Rect, RoundedRect, Size};
+use kurbo::{Circle, Line, Point, Rect, RoundedRect, RoundedRectRadii, Size, Vec2};
 use std::any::Any;
 use taffy::prelude::Node;
 
@@ -161,6 +161,12 @@ pub trait View {
         core::any::type_name::<Self>().into()
     }
 
+    /// The text the view shows, for assistive technology like screen readers. This is the name
+    /// of views like labels, and the value of views like text inputs.
+    fn accessible_text(&self) -> Option<String> {
+        None
+    }
+
     /// Use this method to react to changes in view-related state.
     /// You will usually send state to this hook manually using the `View`'s `Id` handle
     ///
@@ -227,10 +233,9 @@ pub trait View {
     /// `View`-specific implementation. Will be called in the [`View::paint_main`] entry point method.
     /// Usually you'll call the child `View::paint_main` method. But you might also draw text, adjust the offset, clip or draw text.
     fn paint(&mut self, cx: &mut PaintCx) {
-        self.for_each_child_mut(&mut |child| {
+        for child in children_in_paint_order(self, cx.app_state) {
             cx.paint_view(child);
-            false
-        });
+        }
     }
 
     /// Scrolls the view and all direct and indirect children to bring the `target` view to be
@@ -274,16 +279,31 @@ pub fn default_event<V: View + ?Sized>(
     id_path: Option<&[Id]>,
     event: Event,
 ) -> EventPropagation {
-    let mut handled = false;
-    view.for_each_child_rev_mut(&mut |child| {
-        handled |= cx.view_event(child, id_path, event.clone()).is_processed();
-        handled
-    });
-    if handled {
-        EventPropagation::Stop
-    } else {
-        EventPropagation::Continue
+    // Children painted on top get the event first
+    for child in children_in_paint_order(view, cx.app_state)
+        .into_iter()
+        .rev()
+    {
+        if cx.view_event(child, id_path, event.clone()).is_processed() {
+            return EventPropagation::Stop;
+        }
     }
+    EventPropagation::Continue
+}
+
+/// The children of `view` in the order they're painted. This is declaration order,
+/// stably sorted by `z_index` so children with the same z-index keep their order.
+pub(crate) fn children_in_paint_order<'a, V: View + ?Sized>(
+    view: &'a mut V,
+    app_state: &AppState,
+) -> Vec<&'a mut dyn View> {
+    let mut children = Vec::new();
+    view.for_each_child_mut(&mut |child| {
+        children.push(child);
+        false
+    });
+    children.sort_by_key(|child| app_state.z_index(child.id()));
+    children
 }
 
 pub(crate) fn paint_bg(
@@ -292,69 +312,76 @@ pub(crate) fn paint_bg(
     style: &ViewStyleProps,
     size: Size,
 ) {
-    let radius = match style.border_radius() {
-        crate::unit::PxPct::Px(px) => px,
-        crate::unit::PxPct::Pct(pct) => size.min_side() * (pct / 100.),
-    };
-    if radius > 0.0 {
-        let rect = size.to_rect();
+    let rect = size.to_rect();
+    if let Some(radii) = style.border_radii(size) {
         let width = rect.width();
         let height = rect.height();
-        if width > 0.0 && height > 0.0 && radius > width.max(height) / 2.0 {
+        let circle_radius = radii
+            .as_single_radius()
+            .filter(|radius| width > 0.0 && height > 0.0 && *radius > width.max(height) / 2.0);
+        if let Some(radius) = circle_radius {
             let radius = width.max(height) / 2.0;
+            paint_box_shadow(cx, computed_style, rect, Some(radius.into()));
             let circle = Circle::new(rect.center(), radius);
-            let bg = match style.background() {
-                Some(color) => color,
+            let bg = match background_brush(style, rect) {
+                Some(bg) => bg,
                 None => return,
             };
-            cx.fill(&circle, bg, 0.0);
+            cx.fill(&circle, &bg, 0.0);
         } else {
-            paint_box_shadow(cx, computed_style, rect, Some(radius));
-            let bg = match style.background() {
-                Some(color) => color,
+            paint_box_shadow(cx, computed_style, rect, Some(radii));
+            let bg = match background_brush(style, rect) {
+                Some(bg) => bg,
                 None => return,
             };
-            let rounded_rect = rect.to_rounded_rect(radius);
-            cx.fill(&rounded_rect, bg, 0.0);
+            let rounded_rect = rect.to_rounded_rect(radii);
+            cx.fill(&rounded_rect, &bg, 0.0);
         }
     } else {
-        paint_box_shadow(cx, computed_style, size.to_rect(), None);
-        let bg = match style.background() {
-            Some(color) => color,
+        paint_box_shadow(cx, computed_style, rect, None);
+        let bg = match background_brush(style, rect) {
+            Some(bg) => bg,
             None => return,
         };
-        cx.fill(&size.to_rect(), bg, 0.0);
+        cx.fill(&rect, &bg, 0.0);
     }
 }
 
-fn paint_box_shadow(cx: &mut PaintCx, style: &Style, rect: Rect, rect_radius: Option<f64>) {
-    if let Some(shadow) = style.get(BoxShadowProp).as_ref() {
-        let min = rect.size().min_side();
-        let h_offset = match shadow.h_offset {
-            crate::unit::PxPct::Px(px) => px,
-            crate::unit::PxPct::Pct(pct) => min * (pct / 100.),
-        };
-        let v_offset = match shadow.v_offset {
-            crate::unit::PxPct::Px(px) => px,
-            crate::unit::PxPct::Pct(pct) => min * (pct / 100.),
-        };
-        let spread = match shadow.spread {
-            crate::unit::PxPct::Px(px) => px,
-            crate::unit::PxPct::Pct(pct) => min * (pct / 100.),
-        };
-        let blur_radius = match shadow.blur_radius {
-            crate::unit::PxPct::Px(px) => px,
-            crate::unit::PxPct::Pct(pct) => min * (pct / 100.),
-        };
-        let inset = Insets::new(
-            -h_offset / 2.0,
-            -v_offset / 2.0,
-            h_offset / 2.0,
-            v_offset / 2.0,
-        );
-        let rect = rect.inflate(spread, spread).inset(inset);
-        if let Some(radii) = rect_radius {
-            let rounded_rect = RoundedRect::from_rect(rect, radii + spread);
+/// Grow each corner radius by `amount`, for shapes drawn around the border.
+fn inflate_radii(radii: RoundedRectRadii, amount: f64) -> RoundedRectRadii {
+    RoundedRectRadii::new(
+        (radii.top_left + amount).max(0.0),
+        (radii.top_right + amount).max(0.0),
+        (radii.bottom_right + amount).max(0.0),
+        (radii.bottom_left + amount).max(0.0),
+    )
+}
+
+/// The brush for the background of a view filling `rect`, if it has one.
+fn background_brush(style: &ViewStyleProps, rect: Rect) -> Option<peniko::Brush> {
+    match style.background_brush() {
+        Some(brush) => Some(brush.to_peniko(rect)),
+        None => style.background().map(peniko::Brush::Solid),
+    }
+}
+
+fn paint_box_shadow(
+    cx: &mut PaintCx,
+    style: &Style,
+    rect: Rect,
+    rect_radii: Option<RoundedRectRadii>,
+) {
+    let min = rect.size().min_side();
+    let resolve = |value| resolve_shadow_length(value, min);
+    // The first shadow is on top, so paint them in reverse.
+    for shadow in style.get(BoxShadowProp).iter().rev() {
+        let h_offset = resolve(shadow.h_offset);
+        let v_offset = resolve(shadow.v_offset);
+        let spread = resolve(shadow.spread);
+        let blur_radius = resolve(shadow.blur_radius);
+        let rect = rect.inflate(spread, spread) + Vec2::new(h_offset, v_offset);
+        if let Some(radii) = rect_radii {
+            let rounded_rect = RoundedRect::from_rect(rect, inflate_radii(radii, spread));
             cx.fill(&rounded_rect, shadow.color, blur_radius);
         } else {
             cx.fill(&rect, shadow.color, blur_radius);
@@ -362,6 +389,32 @@ fn paint_box_shadow(cx: &mut PaintCx, style: &Style, rect: Rect, rect_radius: Op
     }
 }
 
+/// Resolves a length of a box shadow, where percentages are relative to the shorter side.
+fn resolve_shadow_length(value: crate::unit::PxPct, min: f64) -> f64 {
+    match value {
+        crate::unit::PxPct::Px(px) => px,
+        crate::unit::PxPct::Pct(pct) => min * (pct / 100.),
+    }
+}
+
+/// The area covered by the background, box shadows, border and outline of a view of `size`.
+pub(crate) fn paint_bounds(style: &Style, view_style: &ViewStyleProps, size: Size) -> Rect {
+    let rect = size.to_rect();
+    let min = rect.size().min_side();
+    let outline = view_style.outline().0.max(0.0);
+    let mut bounds = rect.inflate(outline, outline);
+    for shadow in style.get(BoxShadowProp).iter() {
+        let extent = resolve_shadow_length(shadow.spread, min)
+            + resolve_shadow_length(shadow.blur_radius, min).max(0.0);
+        let offset = Vec2::new(
+            resolve_shadow_length(shadow.h_offset, min),
+            resolve_shadow_length(shadow.v_offset, min),
+        );
+        bounds = bounds.union(rect.inflate(extent, extent) + offset);
+    }
+    bounds
+}
+
 pub(crate) fn paint_outline(cx: &mut PaintCx, style: &ViewStyleProps, size: Size) {
     let outline = style.outline().0;
     if outline == 0. {
@@ -370,12 +423,9 @@ pub(crate) fn paint_outline(cx: &mut PaintCx, style: &ViewStyleProps, size: Size
     }
     let half = outline / 2.0;
     let rect = size.to_rect().inflate(half, half);
-    let border_radius = match style.border_radius() {
-        crate::unit::PxPct::Px(px) => px,
-        crate::unit::PxPct::Pct(pct) => size.min_side() * (pct / 100.),
-    };
+    let radii = style.border_radii(size).unwrap_or_default();
     cx.stroke(
-        &rect.to_rounded_rect(border_radius + half),
+        &rect.to_rounded_rect(inflate_radii(radii, half)),
         style.outline_color(),
         outline,
     );
@@ -391,12 +441,8 @@ pub(crate) fn paint_border(cx: &mut PaintCx, style: &ViewStyleProps, size: Size)
     if left == top && top == right && right == bottom && bottom == left && left > 0.0 {
         let half = left / 2.0;
         let rect = size.to_rect().inflate(-half, -half);
-        let radius = match style.border_radius() {
-            crate::unit::PxPct::Px(px) => px,
-            crate::unit::PxPct::Pct(pct) => size.min_side() * (pct / 100.),
-        };
-        if radius > 0.0 {
-            cx.stroke(&rect.to_rounded_rect(radius), border_color, left);
+        if let Some(radii) = style.border_radii(size) {
+            cx.stroke(&rect.to_rounded_rect(radii), border_color, left);
         } else {
             cx.stroke(&rect, border_color, left);
         }
@@ -451,131 +497,66 @@ pub(crate) fn view_children(view: &dyn View) -> Vec<&dyn View> {
     result
 }
 
-/// Tab navigation finds the next or previous view with the `keyboard_navigatable` status in the tree.
-#[allow(dead_code)]
-pub(crate) fn view_tab_navigation(root_view: &dyn View, app_state: &mut AppState, backwards: bool) {
-    let start = app_state
-        .focus
-        .filter(|id| id.id_path().is_some())
-        .unwrap_or(root_view.id());
-
-    assert!(
-        view_filtered_children(root_view, start.id_path().unwrap().dispatch()).is_some(),
-        "The focused view is missing from the tree"
-    );
+/// Tab navigation moves the focus to the next or previous view with the `keyboard_navigatable`
+/// status in the tab order, wrapping around at the ends. With `within` set, only its descendants
+/// are considered, which traps the focus inside of it.
+pub(crate) fn view_tab_navigation(
+    root_view: &dyn View,
+    app_state: &mut AppState,
+    backwards: bool,
+    within: Option<Id>,
+) {
+    let root = within
+        .and_then(|within| view_find(root_view, within))
+        .unwrap_or(root_view);
+    let order = view_tab_order(root, app_state);
+    if order.is_empty() {
+        return;
+    }
 
-    let tree_iter = |id: Id| {
-        if backwards {
-            view_tree_previous(root_view, &id)
-                .unwrap_or_else(|| view_nested_last_child(root_view).id())
-        } else {
-            view_tree_next(root_view, &id).unwrap_or(root_view.id())
-        }
+    let current = app_state
+        .focus
+        .and_then(|focus| order.iter().position(|id| *id == focus));
+    let next = match current {
+        Some(i) if backwards => (i + order.len() - 1) % order.len(),
+        Some(i) => (i + 1) % order.len(),
+        None if backwards => order.len() - 1,
+        None => 0,
     };
 
-    let mut new_focus = tree_iter(start);
-    while new_focus != start && !app_state.can_focus(new_focus) {
-        new_focus = tree_iter(new_focus);
-    }
-
     app_state.clear_focus();
-    app_state.update_focus(new_focus, true);
+    app_state.update_focus(order[next], true);
 }
 
-fn view_filtered_children<'a>(view: &'a dyn View, id_path: &[Id]) -> Option<Vec<&'a dyn View>> {
-    let id = id_path[0];
-    let id_path = &id_path[1..];
-
-    if id == view.id() {
-        if id_path.is_empty() {
-            Some(view_children(view))
-        } else if let Some(child) = view.child(id_path[0]) {
-            view_filtered_children(child, id_path)
-        } else {
-            None
-        }
-    } else {
-        None
-    }
-}
-
-/// Get the next item in the tree, either the first child or the next sibling of this view or of the first parent view
-fn view_tree_next(root_view: &dyn View, id: &Id) -> Option<Id> {
-    let id_path = id.id_path().unwrap();
-
-    if let Some(child) = view_filtered_children(root_view, id_path.dispatch())
-        .unwrap()
-        .into_iter()
-        .next()
-    {
-        return Some(child.id());
-    }
-
-    let mut ancestor = *id;
-    loop {
-        let id_path = ancestor.id_path().unwrap();
-        if id_path.dispatch().is_empty() {
-            return None;
-        }
-        if let Some(next_sibling) = view_next_sibling(root_view, id_path.dispatch()) {
-            return Some(next_sibling.id());
+/// The views which can be focused with Tab below `root`. Views with a positive `tab_index`
+/// come first in increasing order, followed by the rest in declaration order. Views with
+/// a negative `tab_index` are left out.
+pub(crate) fn view_tab_order(root: &dyn View, app_state: &AppState) -> Vec<Id> {
+    let mut order = Vec::new();
+    let mut views = vec![root];
+    while let Some(view) = views.pop() {
+        let id = view.id();
+        if app_state.can_focus(id) {
+            order.push(id);
         }
-        ancestor = ancestor.parent()?;
+        views.extend(view_children(view).into_iter().rev());
     }
+    order.retain(|id| app_state.tab_index(*id) >= 0);
+    // Sort the default index of 0 after positive ones
+    order.sort_by_key(|id| match app_state.tab_index(*id) {
+        0 => i32::MAX,
+        index => index,
+    });
+    order
 }
 
-/// Get the id of the view after this one (but with the same parent and level of nesting)
-fn view_next_sibling<'a>(root_view: &'a dyn View, id_path: &[Id]) -> Option<&'a dyn View> {
-    let id = *id_path.last().unwrap();
-    let parent = &id_path[0..(id_path.len() - 1)];
-
-    if parent.is_empty() {
-        // We're the root, which has no sibling
-        return None;
-    }
-
-    let children = view_filtered_children(root_view, parent).unwrap();
-    let pos = children.iter().position(|v| v.id() == id).unwrap();
-
-    if pos + 1 < children.len() {
-        Some(children[pos + 1])
-    } else {
-        None
-    }
-}
-
-/// Get the next item in the tree, the deepest last child of the previous sibling of this view or the parent
-fn view_tree_previous(root_view: &dyn View, id: &Id) -> Option<Id> {
-    let id_path = id.id_path().unwrap();
-
-    view_previous_sibling(root_view, id_path.dispatch())
-        .map(|view| view_nested_last_child(view).id())
-        .or_else(|| {
-            (root_view.id() != *id).then_some(
-                id.parent()
-                    .unwrap_or_else(|| view_nested_last_child(root_view).id()),
-            )
-        })
-}
-
-/// Get the id of the view before this one (but with the same parent and level of nesting)
-fn view_previous_sibling<'a>(root_view: &'a dyn View, id_path: &[Id]) -> Option<&'a dyn View> {
-    let id = *id_path.last().unwrap();
-    let parent = &id_path[0..(id_path.len() - 1)];
-
-    if parent.is_empty() {
-        // We're the root, which has no sibling
+fn view_find<'a>(view: &'a dyn View, id: Id) -> Option<&'a dyn View> {
+    let id_path = id.id_path()?;
+    let (first, rest) = id_path.dispatch().split_first()?;
+    if *first != view.id() {
         return None;
     }
-
-    let children = view_filtered_children(root_view, parent).unwrap();
-    let pos = children.iter().position(|v| v.id() == id).unwrap();
-
-    if pos > 0 {
-        Some(children[pos - 1])
-    } else {
-        None
-    }
+    rest.iter().try_fold(view, |view, id| view.child(*id))
 }
 
 pub(crate) fn view_children_set_parent_id(view: &dyn View) {
@@ -587,14 +568,6 @@ pub(crate) fn view_children_set_parent_id(view: &dyn View) {
     });
 }
 
-fn view_nested_last_child(view: &dyn View) -> &dyn View {
-    let mut last_child = view;
-    while let Some(new_last_child) = view_children(last_child).pop() {
-        last_child = new_last_child;
-    }
-    last_child
-}
-
 /// Produces an ascii art debug display of all of the views.
 #[allow(dead_code)]
 pub(crate) fn view_debug_tree(root_view: &dyn View) {
@@ -671,6 +644,10 @@ impl View for Box<dyn View> {
         (**self).debug_name()
     }
 
+    fn accessible_text(&self) -> Option<String> {
+        (**self).accessible_text()
+    }
+
     fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn Any>) {
         (**self).update(cx, state)
     }
@@ -704,3 +681,46 @@ impl View for Box<dyn View> {
         (**self).scroll_to(cx, target, rect)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use floem_reactive::create_rw_signal;
+    use kurbo::Size;
+
+    use crate::{
+        event::EventListener,
+        keyboard::{Key, NamedKey},
+        views::{empty, h_stack, Decorators},
+        window::HeadlessWindow,
+    };
+
+    #[test]
+    fn tab_visits_each_view_once_in_tab_order() {
+        let focused = create_rw_signal(Vec::new());
+        let input = move |name: &'static str| {
+            empty()
+                .keyboard_navigatable()
+                .on_event_cont(EventListener::FocusGained, move |_| {
+                    focused.update(|focused| focused.push(name))
+                })
+        };
+        let mut window = HeadlessWindow::new(
+            move |_| {
+                h_stack((
+                    input("a"),
+                    input("b").tab_index(1),
+                    input("c"),
+                    input("skipped").tab_index(-1),
+                    input("d"),
+                ))
+            },
+            Size::new(100.0, 100.0),
+            1.0,
+        );
+        for _ in 0..5 {
+            window.simulate_key(Key::Named(NamedKey::Tab));
+        }
+        // The view with a positive tab index comes first, and the focus wraps around
+        assert_eq!(focused.get_untracked(), ["b", "a", "c", "d", "b"]);
+    }
+}
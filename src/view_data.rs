@@ -1,21 +1,28 @@
 use crate::{
+    accessibility::AccessInfo,
     animate::{AnimPropKind, Animation},
-    context::{EventCallback, InteractionState, MenuCallback, MoveListener, ResizeListener},
+    context::{
+        DropTarget, EventCallback, InteractionState, MenuCallback, MoveListener, ResizeListener,
+    },
     event::EventListener,
     id::{Id, ID_PATHS},
     pointer::PointerInputEvent,
     prop_extracter,
     responsive::ScreenSizeBp,
     style::{
-        Background, BorderBottom, BorderColor, BorderLeft, BorderRadius, BorderRight, BorderTop,
-        LayoutProps, Outline, OutlineColor, Style, StyleClassRef, StyleSelectors,
+        resolve_border_radii, Background, BackgroundBrush, BorderBottom, BorderBottomLeftRadius,
+        BorderBottomRightRadius, BorderColor, BorderLeft, BorderRadius, BorderRight, BorderTop,
+        BorderTopLeftRadius, BorderTopRightRadius, Direction, LayoutProps, Outline, OutlineColor,
+        Style, StyleClassRef, StyleSelectors,
     },
+    unit::PxPct,
     view::View,
 };
 use bitflags::bitflags;
-use kurbo::Rect;
+use floem_reactive::Scope;
+use kurbo::{Insets, Rect, RoundedRectRadii, Size};
 use smallvec::SmallVec;
-use std::{collections::HashMap, marker::PhantomData, time::Duration};
+use std::{any::Any, collections::HashMap, marker::PhantomData, rc::Rc, time::Duration};
 use taffy::node::Node;
 
 /// A stack of view attributes. Each entry is associated with a view decorator call.
@@ -105,11 +112,33 @@ prop_extracter! {
         pub border_right: BorderRight,
         pub border_bottom: BorderBottom,
         pub border_radius: BorderRadius,
+        pub border_top_left_radius: BorderTopLeftRadius,
+        pub border_top_right_radius: BorderTopRightRadius,
+        pub border_bottom_right_radius: BorderBottomRightRadius,
+        pub border_bottom_left_radius: BorderBottomLeftRadius,
 
         pub outline: Outline,
         pub outline_color: OutlineColor,
         pub border_color: BorderColor,
         pub background: Background,
+        pub background_brush: BackgroundBrush,
+    }
+}
+
+impl ViewStyleProps {
+    /// The radius of each corner of a view of `size`, or `None` if none of the corners
+    /// are rounded.
+    pub(crate) fn border_radii(&self, size: Size) -> Option<RoundedRectRadii> {
+        resolve_border_radii(
+            self.border_radius(),
+            [
+                self.border_top_left_radius(),
+                self.border_top_right_radius(),
+                self.border_bottom_right_radius(),
+                self.border_bottom_left_radius(),
+            ],
+            size,
+        )
     }
 }
 
@@ -131,11 +160,16 @@ pub struct ViewState {
     pub(crate) has_style_selectors: StyleSelectors,
     pub(crate) viewport: Option<Rect>,
     pub(crate) layout_rect: Rect,
+    /// The window area the view and its children painted on the last frame, which is repainted
+    /// when it requests a paint.
+    pub(crate) painted_rect: Option<Rect>,
     pub(crate) layout_props: LayoutProps,
     pub(crate) view_style_props: ViewStyleProps,
     pub(crate) animation: Option<Animation>,
     pub(crate) class: Option<StyleClassRef>,
     pub(crate) dragging_style: Option<Style>,
+    pub(crate) drag_payload: Option<Box<dyn Fn() -> Rc<dyn Any>>>,
+    pub(crate) drop_target: Option<DropTarget>,
     pub(crate) combined_style: Style,
     pub(crate) taffy_style: taffy::style::Style,
     pub(crate) event_listeners: HashMap<EventListener, Box<EventCallback>>,
@@ -143,8 +177,11 @@ pub struct ViewState {
     pub(crate) popout_menu: Option<Box<MenuCallback>>,
     pub(crate) resize_listener: Option<ResizeListener>,
     pub(crate) move_listener: Option<MoveListener>,
-    pub(crate) cleanup_listener: Option<Box<dyn Fn()>>,
+    pub(crate) cleanup_listeners: Vec<Box<dyn Fn()>>,
+    /// Reactive scopes owned by the view, which are disposed when it's removed.
+    pub(crate) scopes: Vec<Scope>,
     pub(crate) last_pointer_down: Option<PointerInputEvent>,
+    pub(crate) accessibility: AccessInfo,
 }
 
 impl ViewState {
@@ -153,6 +190,7 @@ impl ViewState {
             node: taffy.new_leaf(taffy::style::Style::DEFAULT).unwrap(),
             viewport: None,
             layout_rect: Rect::ZERO,
+            painted_rect: None,
             layout_props: Default::default(),
             view_style_props: Default::default(),
             requested_changes: ChangeFlags::all(),
@@ -163,13 +201,17 @@ impl ViewState {
             combined_style: Style::new(),
             taffy_style: taffy::style::Style::DEFAULT,
             dragging_style: None,
+            drag_payload: None,
+            drop_target: None,
             event_listeners: HashMap::new(),
             context_menu: None,
             popout_menu: None,
             resize_listener: None,
             move_listener: None,
-            cleanup_listener: None,
+            cleanup_listeners: Vec::new(),
+            scopes: Vec::new(),
             last_pointer_down: None,
+            accessibility: AccessInfo::default(),
         }
     }
 
@@ -239,4 +281,26 @@ impl ViewState {
 
         new_frame
     }
+
+    /// The border and padding around the content box of the view, which has the border box
+    /// `size`.
+    pub(crate) fn content_insets(&self, size: Size) -> Insets {
+        let props = &self.layout_props;
+        let pixels = |px_pct, abs| match px_pct {
+            PxPct::Px(v) => v,
+            PxPct::Pct(pct) => pct * abs,
+        };
+        // The left and right padding are swapped from right to left
+        let (padding_left, padding_right) = if props.direction() == Direction::Rtl {
+            (props.padding_right(), props.padding_left())
+        } else {
+            (props.padding_left(), props.padding_right())
+        };
+        Insets {
+            x0: props.border_left().0 + pixels(padding_left, size.width),
+            x1: props.border_right().0 + pixels(padding_right, size.width),
+            y0: props.border_top().0 + pixels(props.padding_top(), size.height),
+            y1: props.border_bottom().0 + pixels(props.padding_bottom(), size.height),
+        }
+    }
 }
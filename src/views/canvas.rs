@@ -0,0 +1,81 @@
+use floem_reactive::create_effect;
+use kurbo::Size;
+
+use crate::{
+    context::PaintCx,
+    id::Id,
+    view::{View, ViewData},
+};
+
+/// A view which draws with a closure. See [`canvas`]
+pub struct Canvas {
+    data: ViewData,
+    paint_fn: Box<dyn Fn(&mut PaintCx, Size)>,
+}
+
+/// A view which is drawn by `paint_fn`, for graphics which don't fit views and styles, like
+/// charts.
+///
+/// `paint_fn` is called with the size of the view whenever it's painted. It draws with the
+/// [`Renderer`](crate::Renderer) methods of the [`PaintCx`], in the coordinates of the view,
+/// and can use [`PaintCx::clip`], [`PaintCx::offset`] and [`PaintCx::apply_transform`]. The
+/// drawing is clipped to the view, and the clip and transforms are restored afterwards.
+///
+/// The canvas is a single view for events, so handlers get pointer positions relative to it.
+/// Use [`Canvas::track`] to repaint it when signals change.
+///
+/// ```rust
+/// # use floem::{kurbo::Circle, peniko::Color, reactive::create_rw_signal, views::canvas, Renderer};
+/// let radius = create_rw_signal(20.0);
+/// canvas(move |cx, size| {
+///     let center = size.to_rect().center();
+///     cx.fill(&Circle::new(center, radius.get_untracked()), Color::RED, 0.0);
+/// })
+/// .track(move || radius.track());
+/// ```
+pub fn canvas(paint_fn: impl Fn(&mut PaintCx, Size) + 'static) -> Canvas {
+    Canvas {
+        data: ViewData::new(Id::next()),
+        paint_fn: Box::new(paint_fn),
+    }
+}
+
+impl Canvas {
+    /// Repaint the canvas when the signals read by `track` change.
+    pub fn track(self, track: impl Fn() + 'static) -> Self {
+        let id = self.id();
+        create_effect(move |prev: Option<()>| {
+            track();
+            if prev.is_some() {
+                id.request_paint();
+            }
+        });
+        self
+    }
+}
+
+impl View for Canvas {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Canvas".into()
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        let Some(layout) = cx.get_layout(self.id()) else {
+            return;
+        };
+        let size = Size::new(layout.size.width as f64, layout.size.height as f64);
+        cx.save();
+        // Drawing outside of the view wouldn't be repainted when it changes
+        cx.clip(&size.to_rect());
+        (self.paint_fn)(cx, size);
+        cx.restore();
+    }
+}
@@ -1,8 +1,10 @@
-use kurbo::Size;
+use kurbo::{Shape, Size};
 
 use crate::{
+    context::EventCx,
+    event::{Event, EventPropagation},
     id::Id,
-    view::{View, ViewData},
+    view::{default_event, View, ViewData},
 };
 
 pub struct Clip {
@@ -45,21 +47,36 @@ impl View for Clip {
         "Clip".into()
     }
 
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        // Pointer events in the clipped away corners don't reach the child.
+        if let Some(point) = event.point() {
+            let size = cx.get_size(self.id()).unwrap_or_default();
+            let radii = cx
+                .get_computed_style(self.id())
+                .and_then(|style| style.border_radii(size));
+            if let Some(radii) = radii {
+                if !size.to_rect().to_rounded_rect(radii).contains(point) {
+                    return EventPropagation::Continue;
+                }
+            }
+        }
+        default_event(self, cx, id_path, event)
+    }
+
     fn paint(&mut self, cx: &mut crate::context::PaintCx) {
         cx.save();
-        let style = cx.get_builtin_style(self.id());
-        let border_radius = style.border_radius();
         let size = cx
             .get_layout(self.id())
             .map(|layout| Size::new(layout.size.width as f64, layout.size.height as f64))
             .unwrap_or_default();
 
-        let radius = match border_radius {
-            crate::unit::PxPct::Px(px) => px,
-            crate::unit::PxPct::Pct(pct) => size.min_side() * (pct / 100.),
-        };
-        if radius > 0.0 {
-            let rect = size.to_rect().to_rounded_rect(radius);
+        if let Some(radii) = cx.get_computed_style(self.id()).border_radii(size) {
+            let rect = size.to_rect().to_rounded_rect(radii);
             cx.clip(&rect);
         } else {
             cx.clip(&size.to_rect());
@@ -1,13 +1,19 @@
+use std::{any::Any, path::Path, rc::Rc};
+
 use floem_reactive::{create_effect, create_updater};
-use kurbo::{Point, Rect};
+use kurbo::{Point, Rect, Vec2};
+use winit::event::TouchPhase;
 
 use crate::{
+    accessibility::{AccessUpdate, Role},
     action::{set_window_menu, set_window_title, update_window_scale},
     animate::Animation,
-    event::{Event, EventListener},
+    context::DropTarget,
+    event::{DragEvent, Event, EventListener},
     menu::Menu,
     style::{Style, StyleClass, StyleSelector},
     view::View,
+    views::{tooltip, Tooltip},
     EventPropagation,
 };
 
@@ -63,12 +69,101 @@ pub trait Decorators: View + Sized {
         self
     }
 
+    /// Stops the element from being focused, by clicking on it or with the keyboard.
+    fn disable_focus(self) -> Self {
+        let id = self.id();
+        id.disable_focus();
+        self
+    }
+
+    /// Sets the position of the element in the order Tab moves the focus in, like tabindex
+    /// in html. Elements with a positive index come first in increasing order, followed by
+    /// the ones with the default of 0 in declaration order. A negative index leaves the
+    /// element out, though it can still be focused by clicking on it.
+    fn tab_index(self, index: i32) -> Self {
+        let id = self.id();
+        id.tab_index(index);
+        self
+    }
+
+    /// Sets the access key of the element, also called a mnemonic, which clicks it when pressed
+    /// with Alt, like Alt+S for a Save button. The key is underlined in the labels inside the
+    /// element while Alt is held, if they contain it.
+    ///
+    /// If several visible elements have the same access key, pressing it moves the focus
+    /// between them in the order their access keys were set instead of clicking one. Shortcuts
+    /// registered with [`register_shortcut`](crate::action::register_shortcut) take precedence
+    /// over access keys.
+    fn access_key(self, key: char) -> Self {
+        let id = self.id();
+        id.access_key(key.to_lowercase().next().unwrap_or(key));
+        self
+    }
+
+    /// Keeps Tab from moving the focus outside of the element once it's inside of it.
+    fn focus_trap(self) -> Self {
+        let id = self.id();
+        id.focus_trap();
+        self
+    }
+
     fn draggable(self) -> Self {
         let id = self.id();
         id.draggable();
         self
     }
 
+    /// Make the view draggable, carrying a payload made by `payload` when a drag starts to
+    /// the drop targets it's dragged over. See [`Decorators::drop_target`].
+    fn drag_payload<T: 'static>(self, payload: impl Fn() -> T + 'static) -> Self {
+        let id = self.id();
+        id.update_drag_payload(Box::new(move || Rc::new(payload()) as Rc<dyn Any>));
+        self
+    }
+
+    /// Make the view a drop target for payloads of the type `T` that `accepts` returns `true`
+    /// for. `on_drag` is called as the accepted payloads are dragged over the view and dropped
+    /// on it. Other payloads can't be dropped on the view, which the cursor shows.
+    ///
+    /// ```rust
+    /// use floem::event::DragEvent;
+    /// use floem::reactive::create_rw_signal;
+    /// use floem::views::{label, v_stack, Decorators};
+    ///
+    /// let done = create_rw_signal(Vec::<String>::new());
+    /// v_stack((
+    ///     label(|| "Write the docs").drag_payload(|| "Write the docs".to_string()),
+    ///     label(|| "Done").drop_target(
+    ///         |_task: &String| true,
+    ///         move |event| {
+    ///             if let DragEvent::Drop(task, _) = event {
+    ///                 done.update(|done| done.push(task.clone()));
+    ///             }
+    ///         },
+    ///     ),
+    /// ));
+    /// ```
+    fn drop_target<T: 'static>(
+        self,
+        accepts: impl Fn(&T) -> bool + 'static,
+        on_drag: impl Fn(DragEvent<T>) + 'static,
+    ) -> Self {
+        let id = self.id();
+        id.update_drop_target(DropTarget {
+            accepts: Box::new(move |payload| payload.downcast_ref().map(&accepts).unwrap_or(false)),
+            on_event: Box::new(move |event| {
+                if let Some(event) = event.downcast() {
+                    on_drag(event);
+                }
+            }),
+        });
+        self
+    }
+
+    /// Disable the view and its children while `disabled_fn` returns `true`. Disabled views
+    /// aren't hovered, pressed or focused, skip the keyboard events and are left out of the
+    /// tab order. They're styled with [`Style::disabled`]. Pointer presses on them go through
+    /// to the views below, unless [`Decorators::disabled_absorbs_pointer`] is used.
     fn disabled(self, disabled_fn: impl Fn() -> bool + 'static) -> Self {
         let id = self.id();
 
@@ -80,6 +175,42 @@ pub trait Decorators: View + Sized {
         self
     }
 
+    /// Absorb pointer presses on the view while it's disabled, so the views below it don't
+    /// get them.
+    fn disabled_absorbs_pointer(self) -> Self {
+        let id = self.id();
+        id.disabled_absorbs_pointer();
+        self
+    }
+
+    /// Set what kind of control the view is for assistive technology like screen readers. See
+    /// the [`accessibility`](crate::accessibility) module.
+    fn role(self, role: Role) -> Self {
+        self.id().update_accessibility(AccessUpdate::Role(role));
+        self
+    }
+
+    /// Set the name which assistive technology uses for the view. Views showing text, like
+    /// labels, are named by their text without this.
+    fn accessible_name(self, name: impl Fn() -> String + 'static) -> Self {
+        let id = self.id();
+        create_effect(move |_| {
+            let name = name();
+            id.update_accessibility(AccessUpdate::Name(name));
+        });
+        self
+    }
+
+    /// Set whether the view is checked for assistive technology, for views like checkboxes.
+    fn accessible_checked(self, checked: impl Fn() -> bool + 'static) -> Self {
+        let id = self.id();
+        create_effect(move |_| {
+            let checked = checked();
+            id.update_accessibility(AccessUpdate::Checked(checked));
+        });
+        self
+    }
+
     /// Add an event hanlder for the given EventListener
     fn on_event(
         self,
@@ -192,6 +323,67 @@ pub trait Decorators: View + Sized {
         })
     }
 
+    /// Add a handler for files dropped on this view.
+    ///
+    /// The handler is only called on the deepest view under the cursor that has one,
+    /// so nested drop targets take priority over their parents.
+    fn on_file_drop(self, action: impl Fn(&Path) + 'static) -> Self {
+        self.on_event_stop(EventListener::DroppedFile, move |e| {
+            if let Event::DroppedFile(path) = e {
+                action(path);
+            }
+        })
+    }
+
+    /// Add a handler for files being dragged over this view.
+    ///
+    /// The handler receives `None` when the drag leaves the window or is cancelled.
+    fn on_file_hover(self, action: impl Fn(Option<&Path>) + 'static) -> Self {
+        self.on_event_stop(EventListener::HoveredFile, move |e| {
+            if let Event::HoveredFile(path) = e {
+                action(path.as_deref());
+            }
+        })
+    }
+
+    /// Add a handler for touchpad pinch gestures over this view.
+    ///
+    /// The handler receives the incremental magnification delta and the phase of the gesture.
+    fn on_magnify(self, action: impl Fn(f64, TouchPhase) + 'static) -> Self {
+        self.on_event_stop(EventListener::PinchGesture, move |e| {
+            if let Event::PinchGesture { delta, phase } = e {
+                action(*delta, *phase);
+            }
+        })
+    }
+
+    /// Add a handler for discrete zoom requests over this view, such as a two finger double tap.
+    fn on_smart_magnify(self, action: impl Fn() + 'static) -> Self {
+        self.on_event_stop(EventListener::SmartMagnify, move |_| action())
+    }
+
+    /// Add a handler for relative mouse motion while the view has captured the pointer or is
+    /// under the cursor. This keeps coming when the cursor is locked, see
+    /// [`Event::PointerMotion`].
+    fn on_pointer_motion(self, action: impl Fn(Vec2) + 'static) -> Self {
+        self.on_event_cont(EventListener::PointerMotion, move |e| {
+            if let Event::PointerMotion(delta) = e {
+                action(*delta);
+            }
+        })
+    }
+
+    /// Add a handler which is called when the pointer moves onto the view.
+    fn on_pointer_enter(self, action: impl Fn() + 'static) -> Self {
+        self.on_event_cont(EventListener::PointerEnter, move |_| action())
+    }
+
+    /// Add a handler which is called when the pointer moves off the view, leaves the window
+    /// or the view is removed while it's under the pointer.
+    fn on_pointer_leave(self, action: impl Fn() + 'static) -> Self {
+        self.on_event_cont(EventListener::PointerLeave, move |_| action())
+    }
+
     fn on_resize(self, action: impl Fn(Rect) + 'static) -> Self {
         let id = self.id();
         id.update_resize_listener(Box::new(action));
@@ -204,6 +396,20 @@ pub trait Decorators: View + Sized {
         self
     }
 
+    /// Run `action` once the view is added to a window and laid out, so its layout can be
+    /// measured and it can be focused. It's called during the update which first laid out
+    /// the view, or not at all if the view is removed before that update finishes.
+    fn on_mounted(self, action: impl FnOnce() + 'static) -> Self {
+        let id = self.id();
+        id.update_mounted_listener(Box::new(action));
+        self
+    }
+
+    /// Run `action` when the view is removed, like when its window is closed. The cleanup
+    /// actions of children run before those of their parents.
+    ///
+    /// To register cleanup while building a view, use
+    /// [`on_cleanup`](floem_reactive::on_cleanup) in a [`scoped`](crate::views::scoped) view.
     fn on_cleanup(self, action: impl Fn() + 'static) -> Self {
         let id = self.id();
         id.update_cleanup_listener(Box::new(action));
@@ -243,6 +449,10 @@ pub trait Decorators: View + Sized {
         self
     }
 
+    /// Set the menu of the window, which is rebuilt when the signals read by `menu_fn` change.
+    ///
+    /// The menu isn't shown by the platform yet, but the shortcuts of its items run their
+    /// actions.
     fn window_menu(self, menu_fn: impl Fn() -> Menu + 'static) -> Self {
         create_effect(move |_| {
             let menu = menu_fn();
@@ -264,6 +474,15 @@ pub trait Decorators: View + Sized {
         id.update_popout_menu(Box::new(menu));
         self
     }
+
+    /// Shows `tip` in a tooltip when the pointer rests over the view.
+    /// See [`tooltip`](crate::views::tooltip()) for when it shows and hides.
+    fn tooltip<T: View + 'static>(self, tip: impl Fn() -> T + 'static) -> Tooltip
+    where
+        Self: 'static,
+    {
+        tooltip(self, tip)
+    }
 }
 
 impl<V: View> Decorators for V {}
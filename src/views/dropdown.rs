@@ -0,0 +1,370 @@
+use std::rc::Rc;
+
+use floem_reactive::{create_effect, create_rw_signal, RwSignal};
+use kurbo::{Point, Rect, Size};
+
+use super::list::Item;
+use super::{container, dyn_container, scroll, v_stack_from_iter, Decorators, Stack};
+use crate::{
+    action::{add_overlay, remove_overlay},
+    context::{ComputeLayoutCx, EventCx, UpdateCx},
+    event::{Event, EventListener},
+    id::Id,
+    keyboard::{Key, NamedKey},
+    style::Style,
+    style_class,
+    view::{default_compute_layout, default_event, View, ViewData},
+    EventPropagation,
+};
+
+style_class!(pub DropdownClass);
+style_class!(pub DropdownPopupClass);
+style_class!(pub DropdownItemClass);
+
+enum DropdownUpdate {
+    Select(usize),
+    Close,
+}
+
+/// The list of options of an open dropdown.
+struct Popup<T> {
+    overlay: Id,
+    items: Rc<Vec<T>>,
+}
+
+/// A button displaying the selected item, which opens a list of options to pick from.
+pub struct Dropdown<T: 'static> {
+    data: ViewData,
+    child: Box<dyn View>,
+    selected: RwSignal<T>,
+    options: Box<dyn Fn() -> Vec<T>>,
+    view_fn: Rc<dyn Fn(&T) -> Box<dyn View>>,
+    highlighted: RwSignal<Option<usize>>,
+    popup: Option<Popup<T>>,
+    window_origin: Point,
+}
+
+/// A button displaying the `selected` item, which opens a list of `options` below it
+/// when clicked. `view_fn` creates the view for an item, both for the button and
+/// for the list.
+///
+/// While the list is open, the arrow keys move the highlighted option, Enter selects it
+/// and Escape or a click outside of the list closes it. The list opens above the button
+/// instead when there isn't room for it below.
+pub fn dropdown<T, I, V>(
+    selected: RwSignal<T>,
+    options: impl Fn() -> I + 'static,
+    view_fn: impl Fn(&T) -> V + 'static,
+) -> Dropdown<T>
+where
+    T: Clone + PartialEq + 'static,
+    I: IntoIterator<Item = T>,
+    V: View + 'static,
+{
+    let view_fn: Rc<dyn Fn(&T) -> Box<dyn View>> = Rc::new(move |item| Box::new(view_fn(item)));
+    let child = {
+        let view_fn = view_fn.clone();
+        dyn_container(move || selected.get(), move |item| view_fn(&item))
+    };
+    Dropdown {
+        data: ViewData::new(Id::next()),
+        child: Box::new(child),
+        selected,
+        options: Box::new(move || options().into_iter().collect()),
+        view_fn,
+        highlighted: create_rw_signal(None),
+        popup: None,
+        window_origin: Point::ZERO,
+    }
+    .keyboard_navigatable()
+    .class(DropdownClass)
+}
+
+impl<T: Clone + PartialEq + 'static> Dropdown<T> {
+    fn open(&mut self, cx: &mut EventCx) {
+        let items = Rc::new((self.options)());
+        let selected = self.selected.get_untracked();
+        self.highlighted
+            .set(items.iter().position(|item| *item == selected));
+
+        let size = cx.get_size(self.id()).unwrap_or_default();
+        let trigger = Rect::from_origin_size(self.window_origin, size);
+        let window = cx.app_state.root_size / cx.app_state.scale;
+
+        let id = self.id();
+        let view_fn = self.view_fn.clone();
+        let highlighted = self.highlighted;
+        let popup_items = items.clone();
+        let overlay = add_overlay(Point::ZERO, move |_| {
+            popup(id, &popup_items, view_fn, highlighted, trigger, window)
+        });
+        self.popup = Some(Popup { overlay, items });
+        id.request_focus();
+    }
+
+    fn close(&mut self) {
+        if let Some(popup) = self.popup.take() {
+            remove_overlay(popup.overlay);
+        }
+    }
+
+    fn select(&mut self, index: usize) {
+        if let Some(item) = self.popup.as_ref().and_then(|popup| popup.items.get(index)) {
+            if *item != self.selected.get_untracked() {
+                self.selected.set(item.clone());
+            }
+        }
+        self.close();
+        // Clicking an option takes the focus from the dropdown
+        self.id().request_focus();
+    }
+
+    fn handle_key(&mut self, cx: &mut EventCx, key: &Key) -> bool {
+        let Some(popup) = &self.popup else {
+            return match key {
+                Key::Named(NamedKey::Enter | NamedKey::Space)
+                | Key::Named(NamedKey::ArrowDown | NamedKey::ArrowUp) => {
+                    self.open(cx);
+                    true
+                }
+                _ => false,
+            };
+        };
+        let length = popup.items.len();
+        let current = self.highlighted.get_untracked();
+        let next = match key {
+            Key::Named(NamedKey::ArrowDown) => {
+                current.map_or(0, |i| (i + 1).min(length.saturating_sub(1)))
+            }
+            Key::Named(NamedKey::ArrowUp) => {
+                current.map_or(length.saturating_sub(1), |i| i.saturating_sub(1))
+            }
+            Key::Named(NamedKey::Home) => 0,
+            Key::Named(NamedKey::End) => length.saturating_sub(1),
+            Key::Named(NamedKey::Enter) => {
+                match current {
+                    Some(index) => self.select(index),
+                    None => self.close(),
+                }
+                return true;
+            }
+            Key::Named(NamedKey::Escape) => {
+                self.close();
+                return true;
+            }
+            _ => return false,
+        };
+        if length > 0 && current != Some(next) {
+            self.highlighted.set(Some(next));
+        }
+        true
+    }
+}
+
+/// The full window backdrop holding the list of options, which closes the dropdown
+/// when clicked.
+fn popup<T: 'static>(
+    dropdown: Id,
+    items: &[T],
+    view_fn: Rc<dyn Fn(&T) -> Box<dyn View>>,
+    highlighted: RwSignal<Option<usize>>,
+    trigger: Rect,
+    window: Size,
+) -> impl View {
+    let below = window.height - trigger.y1;
+    let above = trigger.y0;
+    let top = create_rw_signal(trigger.y1);
+
+    let list = DropdownList::new(
+        items.iter().enumerate().map(|(index, item)| {
+            Item {
+                data: ViewData::new(Id::next()),
+                index,
+                selection: highlighted,
+                child: Box::new(container(view_fn(item)).class(DropdownItemClass)),
+            }
+            .on_event_cont(EventListener::PointerMove, move |_| {
+                if highlighted.get_untracked() != Some(index) {
+                    highlighted.set(Some(index));
+                }
+            })
+            .on_click_stop(move |_| {
+                dropdown.update_state(DropdownUpdate::Select(index), false);
+            })
+        }),
+        highlighted,
+    );
+
+    container(
+        scroll(list)
+            .class(DropdownPopupClass)
+            // Clicks in the list don't reach the backdrop
+            .on_event_stop(EventListener::PointerDown, |_| {})
+            .on_resize(move |rect| {
+                // Flip above the button if the list doesn't fit below it
+                let height = rect.height();
+                let new_top = if height > below && above > below {
+                    trigger.y0 - height
+                } else {
+                    trigger.y1
+                };
+                if new_top != top.get_untracked() {
+                    top.set(new_top);
+                }
+            })
+            .style(move |s| {
+                s.absolute()
+                    .inset_left(trigger.x0)
+                    .inset_top(top.get())
+                    .min_width(trigger.width())
+                    .max_height(below.max(above))
+            }),
+    )
+    .on_event_stop(EventListener::PointerDown, move |_| {
+        dropdown.update_state(DropdownUpdate::Close, false);
+    })
+    .style(move |s| s.width(window.width).height(window.height))
+}
+
+impl<T: Clone + PartialEq + 'static> View for Dropdown<T> {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Dropdown".into()
+    }
+
+    fn update(&mut self, _cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
+        if let Ok(update) = state.downcast::<DropdownUpdate>() {
+            match *update {
+                DropdownUpdate::Select(index) => self.select(index),
+                DropdownUpdate::Close => self.close(),
+            }
+        }
+    }
+
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        match &event {
+            Event::PointerDown(e) if e.button.is_primary() => {
+                if self.popup.is_some() {
+                    self.close();
+                } else {
+                    self.open(cx);
+                }
+                return EventPropagation::Stop;
+            }
+            Event::KeyDown(e) => {
+                if self.handle_key(cx, &e.key.logical_key) {
+                    return EventPropagation::Stop;
+                }
+            }
+            _ => {}
+        }
+
+        default_event(self, cx, id_path, event)
+    }
+
+    fn compute_layout(&mut self, cx: &mut ComputeLayoutCx) -> Option<Rect> {
+        self.window_origin = cx.window_origin;
+        default_compute_layout(self, cx)
+    }
+}
+
+impl<T: 'static> Drop for Dropdown<T> {
+    fn drop(&mut self) {
+        if let Some(popup) = self.popup.take() {
+            remove_overlay(popup.overlay);
+        }
+    }
+}
+
+/// The options in the popup, restyled and scrolled to when the highlighted option changes.
+struct DropdownList {
+    data: ViewData,
+    highlighted: RwSignal<Option<usize>>,
+    child: Stack,
+}
+
+impl DropdownList {
+    fn new(items: impl Iterator<Item = Item>, highlighted: RwSignal<Option<usize>>) -> Self {
+        let id = Id::next();
+        create_effect(move |_| {
+            highlighted.track();
+            id.update_state((), false);
+        });
+        Self {
+            data: ViewData::new(id),
+            highlighted,
+            child: v_stack_from_iter(items).style(|s| s.width_full()),
+        }
+    }
+}
+
+impl View for DropdownList {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn view_style(&self) -> Option<Style> {
+        Some(Style::new().width_full())
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Dropdown List".into()
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
+        if state.downcast::<()>().is_ok() {
+            cx.app_state_mut().request_style_recursive(self.id());
+            if let Some(index) = self.highlighted.get_untracked() {
+                if let Some(item) = self.child.children.get(index) {
+                    item.id().scroll_to(None);
+                }
+            }
+        }
+    }
+}
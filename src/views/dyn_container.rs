@@ -1,4 +1,4 @@
-use floem_reactive::{as_child_of_current_scope, create_updater, Scope};
+use floem_reactive::{as_child_of_current_scope, create_memo, create_updater, Scope};
 
 use crate::{
     id::Id,
@@ -79,6 +79,8 @@ pub fn dyn_container<CF: Fn(T) -> Box<dyn View> + 'static, T: 'static>(
 
     let child_fn = Box::new(as_child_of_current_scope(child_fn));
     let (child, child_scope) = child_fn(initial);
+    // The scope is also disposed if the container is removed along with its child
+    child.id().own_scope(child_scope);
     DynamicContainer {
         data: ViewData::new(id),
         child,
@@ -123,7 +125,83 @@ impl<T: 'static> View for DynamicContainer<T> {
             old_child_scope.dispose();
             self.child.id().set_parent(self.id());
             view_children_set_parent_id(&*self.child);
+            self.child.id().own_scope(self.child_scope);
             cx.request_all(self.id());
         }
     }
 }
+
+/// A view showing `then_view` while `cond` is true, and `else_view` otherwise.
+///
+/// The shown view is rebuilt when `cond` changes, and the scope of the view it replaces is
+/// disposed, so its effects stop and its cleanups run.
+///
+/// ```rust
+/// # use floem::reactive::create_rw_signal;
+/// # use floem::views::{label, show};
+/// let logged_in = create_rw_signal(false);
+/// show(
+///     move || logged_in.get(),
+///     || label(|| "Welcome back"),
+///     || label(|| "Please log in"),
+/// );
+/// ```
+pub fn show<V1: View + 'static, V2: View + 'static>(
+    cond: impl Fn() -> bool + 'static,
+    then_view: impl Fn() -> V1 + 'static,
+    else_view: impl Fn() -> V2 + 'static,
+) -> DynamicContainer<bool> {
+    // The memo only notifies the container when the condition actually changes
+    let cond = create_memo(move |_| cond());
+    dyn_container(
+        move || cond.get(),
+        move |cond| -> Box<dyn View> {
+            if cond {
+                Box::new(then_view())
+            } else {
+                Box::new(else_view())
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use floem_reactive::{create_effect, create_rw_signal, on_cleanup};
+    use kurbo::Size;
+
+    use super::show;
+    use crate::{views::empty, window::HeadlessWindow};
+
+    #[test]
+    fn swapped_out_views_stop_their_effects() {
+        let shown = create_rw_signal(true);
+        let tracked = create_rw_signal(0);
+        let runs = create_rw_signal(0);
+        let cleanups = create_rw_signal(0);
+        let child = move || {
+            create_effect(move |_| {
+                tracked.track();
+                runs.update(|runs| *runs += 1);
+            });
+            on_cleanup(move || cleanups.update(|cleanups| *cleanups += 1));
+            empty()
+        };
+        let mut window = HeadlessWindow::new(
+            move |_| show(move || shown.get(), child, child),
+            Size::new(100.0, 100.0),
+            1.0,
+        );
+        for _ in 0..3 {
+            shown.update(|shown| *shown = !*shown);
+            window.render();
+        }
+        // Each of the four views ran its effect once, and the three replaced ones were disposed
+        assert_eq!(runs.get_untracked(), 4);
+        assert_eq!(cleanups.get_untracked(), 3);
+
+        // Only the effect of the shown view is left
+        tracked.set(1);
+        assert_eq!(runs.get_untracked(), 5);
+    }
+}
@@ -30,6 +30,11 @@ where
     phantom: PhantomData<T>,
 }
 
+/// A stack whose children are built from the items returned by `each_fn`.
+///
+/// Children are identified by the key returned by `key_fn`. When the items change, only the
+/// views for new keys are built and the views for removed keys are dropped. Views for keys
+/// which are still present are moved to their new position, so their state is kept.
 pub fn dyn_stack<IF, I, T, KF, K, VF, V>(each_fn: IF, key_fn: KF, view_fn: VF) -> DynStack<V, T>
 where
     IF: Fn() -> I + 'static,
@@ -332,3 +337,43 @@ pub(super) fn apply_diff<T, V, VF>(
     // items
     children.retain(|c| c.is_some());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{diff, FxIndexSet};
+
+    #[test]
+    fn diff_reorder_moves_items() {
+        let from: FxIndexSet<_> = [1, 2, 3, 4].into_iter().collect();
+        let to: FxIndexSet<_> = [4, 2, 1, 3].into_iter().collect();
+        let diff = diff::<_, ()>(&from, &to);
+        assert!(!diff.clear);
+        assert!(diff.removed.is_empty());
+        assert!(diff.added.is_empty());
+        let moves: Vec<_> = diff.moved.iter().map(|op| (op.from, op.to)).collect();
+        assert_eq!(moves, [(3, 0), (0, 2), (2, 3)]);
+
+        // Applying the moves like `apply_diff` gives the new order
+        let mut items: Vec<_> = from.iter().copied().map(Some).collect();
+        let taken: Vec<_> = moves
+            .iter()
+            .map(|&(from, to)| (to, items[from].take()))
+            .collect();
+        for (to, item) in taken {
+            items[to] = item;
+        }
+        let items: Vec<_> = items.into_iter().flatten().collect();
+        assert_eq!(items, to.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn diff_keeps_items_next_to_insertions() {
+        let from: FxIndexSet<_> = [1, 2, 3].into_iter().collect();
+        let to: FxIndexSet<_> = [1, 5, 2, 3].into_iter().collect();
+        let diff = diff::<_, ()>(&from, &to);
+        assert!(!diff.clear);
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].at, 1);
+    }
+}
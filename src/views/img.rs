@@ -1,15 +1,23 @@
-use std::rc::Rc;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
-use floem_reactive::create_effect;
+use floem_reactive::{create_effect, Scope};
 use floem_renderer::Renderer;
 use image::{DynamicImage, GenericImageView};
+use kurbo::{Rect, Size};
 use sha2::{Digest, Sha256};
 
 use crate::{
+    accessibility::{AccessUpdate, Role},
+    ext_event::create_ext_action,
     id::Id,
     style::Style,
     unit::UnitExt,
-    view::{View, ViewData},
+    view::{view_children_set_parent_id, View, ViewData},
 };
 
 use taffy::prelude::Node;
@@ -21,6 +29,7 @@ pub struct ImageStyle {
 
 /// How the content of a replaced element, such as an img or video, should be resized to fit its container.
 /// See  https://developer.mozilla.org/en-US/docs/Web/CSS/object-fit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ObjectFit {
     /// The replaced content is sized to fill the element's content box.
     /// The entire object will completely fill the box.
@@ -85,6 +94,65 @@ impl ImageStyle {
     }
 }
 
+/// Where [`img_from`] loads an image from.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ImageSource {
+    Path(PathBuf),
+    /// A `file://` URL, or with the `image-url` feature also a `http://` or `https://` URL.
+    Url(String),
+}
+
+impl From<PathBuf> for ImageSource {
+    fn from(path: PathBuf) -> Self {
+        ImageSource::Path(path)
+    }
+}
+
+impl From<&Path> for ImageSource {
+    fn from(path: &Path) -> Self {
+        ImageSource::Path(path.to_path_buf())
+    }
+}
+
+impl From<&str> for ImageSource {
+    /// Strings containing `://` are URLs, and other strings are paths.
+    fn from(source: &str) -> Self {
+        if source.contains("://") {
+            ImageSource::Url(source.to_string())
+        } else {
+            ImageSource::Path(source.into())
+        }
+    }
+}
+
+impl From<String> for ImageSource {
+    fn from(source: String) -> Self {
+        source.as_str().into()
+    }
+}
+
+#[derive(Clone)]
+struct CachedImage {
+    img: Rc<DynamicImage>,
+    hash: Vec<u8>,
+}
+
+thread_local! {
+    /// Decoded images by where they were loaded from.
+    static IMAGE_CACHE: RefCell<HashMap<ImageSource, CachedImage>> = RefCell::new(HashMap::new());
+}
+
+/// Drop the decoded images [`img_from`] keeps around to show them again without decoding them.
+/// Views currently showing an image keep it.
+pub fn clear_img_cache() {
+    IMAGE_CACHE.with(|cache| cache.borrow_mut().clear());
+}
+
+enum ImageLoad {
+    Started(ImageSource),
+    Done(ImageSource, Result<CachedImage, String>),
+}
+
 pub struct Img {
     data: ViewData,
     //FIXME: store the pixel format(once its added to vger), for now we only store RGBA(RGB is converted to RGBA)
@@ -92,6 +160,12 @@ pub struct Img {
     img_hash: Option<Vec<u8>>,
     img_dimensions: Option<(u32, u32)>,
     content_node: Option<Node>,
+    /// The source of the image which is being loaded or has been loaded.
+    source: Option<ImageSource>,
+    fit: ObjectFit,
+    pixel_ratio: f64,
+    placeholder: Option<Box<dyn View>>,
+    on_error: Option<Box<dyn Fn(&str)>>,
 }
 
 pub fn img(image: impl Fn() -> Vec<u8> + 'static) -> Img {
@@ -103,12 +177,177 @@ pub(crate) fn img_dynamic(image: impl Fn() -> Option<Rc<DynamicImage>> + 'static
     create_effect(move |_| {
         id.update_state(image(), false);
     });
-    Img {
-        data: ViewData::new(id),
-        img: None,
-        img_hash: None,
-        img_dimensions: None,
-        content_node: None,
+    Img::new(id)
+}
+
+/// An image loaded from `source`, which can be a path or a URL. The image is read and decoded
+/// on another thread, and shown once it's done. Decoded images are cached by their source, so
+/// showing the same image again doesn't decode it again.
+///
+/// Use [`Img::placeholder`] to show something else while the image is loading or if it failed
+/// to load, and [`Img::on_error`] to find out why it did.
+///
+/// ```rust
+/// use floem::views::{img_from, label, Decorators, ObjectFit};
+///
+/// img_from(|| "assets/photo.png")
+///     .fit(ObjectFit::Cover)
+///     .placeholder(label(|| "Loading..."))
+///     .on_error(|err| eprintln!("{err}"))
+///     .style(|s| s.size(200.0, 150.0));
+/// ```
+pub fn img_from<S: Into<ImageSource>>(source: impl Fn() -> S + 'static) -> Img {
+    let id = Id::next();
+    create_effect(move |_| {
+        let source: ImageSource = source().into();
+        id.update_state(ImageLoad::Started(source.clone()), false);
+
+        if let Some(image) = IMAGE_CACHE.with(|cache| cache.borrow().get(&source).cloned()) {
+            id.update_state(ImageLoad::Done(source, Ok(image)), false);
+            return;
+        }
+
+        // The scope of the effect is disposed when the source changes or the view is removed,
+        // so the action of a load which is no longer needed is dropped with it
+        let send = {
+            let source = source.clone();
+            create_ext_action(
+                Scope::current(),
+                move |result: Result<(DynamicImage, Vec<u8>), String>| {
+                    let result = result.map(|(img, hash)| {
+                        let image = CachedImage {
+                            img: Rc::new(img),
+                            hash,
+                        };
+                        IMAGE_CACHE
+                            .with(|cache| cache.borrow_mut().insert(source.clone(), image.clone()));
+                        image
+                    });
+                    id.update_state(ImageLoad::Done(source, result), false);
+                },
+            )
+        };
+        std::thread::spawn(move || send(load_image(&source)));
+    });
+    Img::new(id)
+}
+
+/// Read and decode the image at `source`, and hash its pixels.
+fn load_image(source: &ImageSource) -> Result<(DynamicImage, Vec<u8>), String> {
+    let bytes = match source {
+        ImageSource::Path(path) => read_file(path)?,
+        ImageSource::Url(url) => match url.strip_prefix("file://") {
+            Some(path) => read_file(Path::new(path))?,
+            None => fetch(url)?,
+        },
+    };
+    let img = image::load_from_memory(&bytes)
+        .map_err(|err| format!("Failed to decode the image {source:?}: {err}"))?;
+    let mut hasher = Sha256::new();
+    hasher.update(img.as_bytes());
+    Ok((img, hasher.finalize().to_vec()))
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|err| format!("Failed to read {}: {err}", path.display()))
+}
+
+#[cfg(feature = "image-url")]
+fn fetch(url: &str) -> Result<Vec<u8>, String> {
+    use std::io::Read;
+
+    let response = ureq::get(url)
+        .call()
+        .map_err(|err| format!("Failed to fetch {url}: {err}"))?;
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .read_to_end(&mut bytes)
+        .map_err(|err| format!("Failed to fetch {url}: {err}"))?;
+    Ok(bytes)
+}
+
+#[cfg(not(feature = "image-url"))]
+fn fetch(url: &str) -> Result<Vec<u8>, String> {
+    Err(format!(
+        "Failed to fetch {url}: loading images from URLs requires the `image-url` feature"
+    ))
+}
+
+/// Where an image of `size` is drawn in `rect` with `fit`. It's centered when it doesn't cover
+/// `rect` exactly.
+fn fit_rect(fit: ObjectFit, rect: Rect, size: Size) -> Rect {
+    if size.is_empty() {
+        return rect;
+    }
+    let scale_x = rect.width() / size.width;
+    let scale_y = rect.height() / size.height;
+    let size = match fit {
+        ObjectFit::Fill => return rect,
+        ObjectFit::Contain => size * scale_x.min(scale_y),
+        ObjectFit::Cover => size * scale_x.max(scale_y),
+        ObjectFit::ScaleDown => size * scale_x.min(scale_y).min(1.0),
+        ObjectFit::None => size,
+    };
+    Rect::from_center_size(rect.center(), size)
+}
+
+impl Img {
+    fn new(id: Id) -> Self {
+        id.update_accessibility(AccessUpdate::Role(Role::Image));
+        Img {
+            data: ViewData::new(id),
+            img: None,
+            img_hash: None,
+            img_dimensions: None,
+            content_node: None,
+            source: None,
+            fit: ObjectFit::Fill,
+            pixel_ratio: 1.0,
+            placeholder: None,
+            on_error: None,
+        }
+    }
+
+    /// Set how the image is resized to fit the view. The default is [`ObjectFit::Fill`].
+    pub fn fit(mut self, fit: ObjectFit) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    /// Set how many pixels of the image there are per logical pixel, like 2 for images made
+    /// for high DPI displays. The natural size of the image is its size in pixels divided by
+    /// this, and defaults to its size in pixels.
+    pub fn pixel_ratio(mut self, pixel_ratio: f64) -> Self {
+        if pixel_ratio > 0.0 {
+            self.pixel_ratio = pixel_ratio;
+        }
+        self
+    }
+
+    /// Show `placeholder` instead of the image while there's none, which is while it's loading
+    /// or when it failed to load.
+    pub fn placeholder(mut self, placeholder: impl View + 'static) -> Self {
+        placeholder.id().set_parent(self.id());
+        view_children_set_parent_id(&placeholder);
+        self.placeholder = Some(Box::new(placeholder));
+        self
+    }
+
+    /// Add a handler which is called with the error when the image fails to load.
+    pub fn on_error(mut self, on_error: impl Fn(&str) + 'static) -> Self {
+        self.on_error = Some(Box::new(on_error));
+        self
+    }
+
+    fn set_image(&mut self, img: Option<Rc<DynamicImage>>, hash: Option<Vec<u8>>) {
+        self.img_dimensions = img.as_ref().map(|img| img.dimensions());
+        self.img = img;
+        self.img_hash = hash;
+    }
+
+    fn showing_placeholder(&self) -> bool {
+        self.img.is_none() && self.placeholder.is_some()
     }
 }
 
@@ -121,20 +360,67 @@ impl View for Img {
         &mut self.data
     }
 
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        if self.showing_placeholder() {
+            for_each(self.placeholder.as_deref().unwrap());
+        }
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        if self.showing_placeholder() {
+            for_each(self.placeholder.as_deref_mut().unwrap());
+        }
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        self.for_each_child_mut(for_each);
+    }
+
     fn debug_name(&self) -> std::borrow::Cow<'static, str> {
         "Img".into()
     }
 
     fn update(&mut self, cx: &mut crate::context::UpdateCx, state: Box<dyn std::any::Any>) {
-        if let Ok(img) = state.downcast::<Option<Rc<DynamicImage>>>() {
-            self.img_hash = (*img).as_ref().map(|img| {
-                let mut hasher = Sha256::new();
-                hasher.update(img.as_bytes());
-                hasher.finalize().to_vec()
-            });
-            self.img = *img;
-            self.img_dimensions = self.img.as_ref().map(|img| img.dimensions());
-            cx.request_layout(self.id());
+        let state = match state.downcast::<Option<Rc<DynamicImage>>>() {
+            Ok(img) => {
+                let hash = (*img).as_ref().map(|img| {
+                    let mut hasher = Sha256::new();
+                    hasher.update(img.as_bytes());
+                    hasher.finalize().to_vec()
+                });
+                self.set_image(*img, hash);
+                cx.request_layout(self.id());
+                return;
+            }
+            Err(state) => state,
+        };
+        if let Ok(load) = state.downcast::<ImageLoad>() {
+            match *load {
+                ImageLoad::Started(source) => {
+                    if self.source.as_ref() == Some(&source) {
+                        return;
+                    }
+                    self.source = Some(source);
+                    self.set_image(None, None);
+                }
+                ImageLoad::Done(source, result) => {
+                    // Ignore images which finished loading after the source changed
+                    if self.source.as_ref() != Some(&source) {
+                        return;
+                    }
+                    match result {
+                        Ok(image) => self.set_image(Some(image.img), Some(image.hash)),
+                        Err(err) => match &self.on_error {
+                            Some(on_error) => on_error(&err),
+                            None => eprintln!("{err}"),
+                        },
+                    }
+                }
+            }
+            cx.request_all(self.id());
         }
     }
 
@@ -148,13 +434,16 @@ impl View for Img {
                         .unwrap(),
                 );
             }
+            if self.showing_placeholder() {
+                return vec![cx.layout_view(self.placeholder.as_deref_mut().unwrap())];
+            }
             let content_node = self.content_node.unwrap();
 
             let (width, height) = self.img_dimensions.unwrap_or((0, 0));
 
             let style = Style::new()
-                .width((width as f64).px())
-                .height((height as f64).px())
+                .width((width as f64 / self.pixel_ratio).px())
+                .height((height as f64 / self.pixel_ratio).px())
                 .to_taffy_style();
             let _ = cx.app_state_mut().taffy.set_style(content_node, style);
 
@@ -164,7 +453,15 @@ impl View for Img {
 
     fn paint(&mut self, cx: &mut crate::context::PaintCx) {
         if let Some(img) = self.img.as_ref() {
-            let rect = cx.get_content_rect(self.id());
+            let content_rect = cx.get_content_rect(self.id());
+            let (width, height) = self.img_dimensions.unwrap_or((0, 0));
+            let size = Size::new(width as f64, height as f64) / self.pixel_ratio;
+            let rect = fit_rect(self.fit, content_rect, size);
+            let clip = matches!(self.fit, ObjectFit::Cover | ObjectFit::None);
+            if clip {
+                cx.save();
+                cx.clip(&content_rect);
+            }
             cx.draw_img(
                 floem_renderer::Img {
                     img,
@@ -173,6 +470,11 @@ impl View for Img {
                 },
                 rect,
             );
+            if clip {
+                cx.restore();
+            }
+        } else if let Some(placeholder) = self.placeholder.as_deref_mut() {
+            cx.paint_view(placeholder);
         }
     }
 }
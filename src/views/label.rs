@@ -1,14 +1,21 @@
-use std::{any::Any, fmt::Display};
+use std::{any::Any, fmt::Display, ops::Range};
 
 use crate::{
-    context::UpdateCx,
-    cosmic_text::{Attrs, AttrsList, FamilyOwned, TextLayout},
+    context::{EventCx, UpdateCx},
+    cosmic_text::{Align, Attrs, AttrsList, TextLayout},
+    event::Event,
+    font::font_families,
     id::Id,
+    keyboard::{Key, ModifiersState},
     prop_extracter,
     style::Style,
-    style::{FontProps, LineHeight, TextColor, TextOverflow, TextOverflowProp},
+    style::{
+        CursorColor, Direction, DirectionProp, FontProps, LineHeight, MaxLines, TextAlign,
+        TextAlignProp, TextColor, TextOverflow, TextOverflowProp,
+    },
     unit::PxPct,
     view::{View, ViewData},
+    Clipboard, EventPropagation,
 };
 use floem_reactive::create_updater;
 use floem_renderer::Renderer;
@@ -16,11 +23,35 @@ use kurbo::{Point, Rect};
 use peniko::Color;
 use taffy::prelude::Node;
 
+use super::{text_input::get_dbl_click_selection, Decorators};
+
 prop_extracter! {
     Extracter {
         color: TextColor,
         text_overflow: TextOverflowProp,
         line_height: LineHeight,
+        selection_color: CursorColor,
+        text_align: TextAlignProp,
+        direction: DirectionProp,
+        max_lines: MaxLines,
+    }
+}
+
+/// What truncated text ends with.
+const ELLIPSIS: &str = "...";
+
+/// The selected text of a [`Label::selectable`] label, by byte offsets in the text.
+#[derive(Clone, Copy)]
+struct TextSelection {
+    /// Where the selection started.
+    anchor: usize,
+    /// Where the selection ends, which is moved when dragging.
+    active: usize,
+}
+
+impl TextSelection {
+    fn range(&self) -> Range<usize> {
+        self.anchor.min(self.active)..self.anchor.max(self.active)
     }
 }
 
@@ -40,6 +71,71 @@ pub struct Label {
     text_overflow_listener: Option<TextOverflowListener>,
     font: FontProps,
     style: Extracter,
+    selectable: bool,
+    selection: Option<TextSelection>,
+    selecting: bool,
+}
+
+/// The rectangles covering the text in `range` of `text_layout`, relative to the text.
+pub(crate) fn text_selection_rects(text_layout: &TextLayout, range: Range<usize>) -> Vec<Rect> {
+    if range.is_empty() {
+        return Vec::new();
+    }
+    let width = text_layout.size().width;
+    let start = text_layout.hit_position(range.start);
+    let end = text_layout.hit_position(range.end);
+    let start_top = start.point.y - start.glyph_ascent;
+    let start_bottom = start.point.y + start.glyph_descent;
+    let end_top = end.point.y - end.glyph_ascent;
+    let end_bottom = end.point.y + end.glyph_descent;
+    if start.line == end.line {
+        return vec![Rect::new(
+            start.point.x,
+            start_top,
+            end.point.x,
+            start_bottom,
+        )];
+    }
+    let mut rects = vec![Rect::new(start.point.x, start_top, width, start_bottom)];
+    if end_top > start_bottom {
+        rects.push(Rect::new(0.0, start_bottom, width, end_top));
+    }
+    rects.push(Rect::new(0.0, end_top, end.point.x, end_bottom));
+    rects
+}
+
+/// The byte offset in `label` for the byte offset `index` in the shown text, which is `label`
+/// truncated with an ellipsis if `shown` is set. The ellipsis stands for the truncated text,
+/// so offsets in and after it map to the end of `label`.
+fn label_index(label: &str, shown: Option<&str>, index: usize) -> usize {
+    let visible = match shown {
+        Some(shown) => shown.strip_suffix(ELLIPSIS).unwrap_or(shown).len(),
+        None => label.len(),
+    };
+    if index > visible {
+        return label.len();
+    }
+    let mut index = index.min(label.len());
+    while !label.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// The byte offset in the shown text for the byte offset `index` in `label`, the reverse of
+/// [`label_index`]. Offsets in the truncated text map to the end of the shown text.
+fn shown_index(label: &str, shown: Option<&str>, index: usize) -> usize {
+    match shown {
+        Some(shown) => {
+            let visible = shown.strip_suffix(ELLIPSIS).unwrap_or(shown).len();
+            if index > visible {
+                shown.len()
+            } else {
+                index
+            }
+        }
+        None => index.min(label.len()),
+    }
 }
 
 impl Label {
@@ -55,6 +151,9 @@ impl Label {
             text_overflow_listener: None,
             font: FontProps::default(),
             style: Default::default(),
+            selectable: false,
+            selection: None,
+            selecting: false,
         }
     }
 }
@@ -85,6 +184,73 @@ impl Label {
         self
     }
 
+    /// Let the text be selected by dragging over it, and copied with Ctrl+C, or Cmd+C on
+    /// macOS. Double clicking selects a word and triple clicking a line.
+    ///
+    /// This makes the label focusable, as it needs to be focused to copy from it.
+    pub fn selectable(mut self) -> Self {
+        self.selectable = true;
+        self.keyboard_navigatable()
+    }
+
+    /// The text layout which is shown.
+    fn visible_text_layout(&self) -> Option<&TextLayout> {
+        self.available_text_layout
+            .as_ref()
+            .or(self.text_layout.as_ref())
+    }
+
+    fn text_origin(&self, cx: &EventCx) -> Point {
+        self.text_node
+            .and_then(|node| cx.app_state.taffy.layout(node).ok())
+            .map(|layout| Point::new(layout.location.x as f64, layout.location.y as f64))
+            .unwrap_or_default()
+    }
+
+    /// The byte offset in the text closest to `pos`.
+    fn text_index_at(&self, cx: &EventCx, pos: Point) -> Option<usize> {
+        let text_layout = self.visible_text_layout()?;
+        let origin = self.text_origin(cx);
+        let index = text_layout.hit_point(pos - origin.to_vec2()).index;
+        Some(label_index(
+            &self.label,
+            self.available_text.as_deref(),
+            index,
+        ))
+    }
+
+    /// The line of the text around `index`.
+    fn line_range(&self, index: usize) -> Range<usize> {
+        let start = self.label[..index].rfind('\n').map_or(0, |i| i + 1);
+        let end = self.label[index..]
+            .find('\n')
+            .map_or(self.label.len(), |i| index + i);
+        start..end
+    }
+
+    fn selected_text(&self) -> Option<&str> {
+        let range = self.selection?.range();
+        self.label.get(range).filter(|text| !text.is_empty())
+    }
+
+    /// The line under the first `key` in the shown text, relative to the text.
+    fn access_key_underline(&self, key: char) -> Option<Rect> {
+        let text_layout = self.visible_text_layout()?;
+        let text = self.available_text.as_deref().unwrap_or(&self.label);
+        let (index, c) = text
+            .char_indices()
+            .find(|(_, c)| c.to_lowercase().eq(key.to_lowercase()))?;
+        let start = text_layout.hit_position(index);
+        let end = text_layout.hit_position(index + c.len_utf8());
+        let y = start.point.y + (start.glyph_descent / 2.0).max(1.0);
+        let x1 = if end.line == start.line {
+            end.point.x
+        } else {
+            text_layout.size().width
+        };
+        Some(Rect::new(start.point.x, y, x1, y + 1.0))
+    }
+
     fn get_attrs_list(&self) -> AttrsList {
         let mut attrs = Attrs::new().color(self.style.color().unwrap_or(Color::BLACK));
         if let Some(font_size) = self.font.size() {
@@ -93,10 +259,7 @@ impl Label {
         if let Some(font_style) = self.font.style() {
             attrs = attrs.style(font_style);
         }
-        let font_family = self.font.family().as_ref().map(|font_family| {
-            let family: Vec<FamilyOwned> = FamilyOwned::parse_list(font_family).collect();
-            family
-        });
+        let font_family = font_families(self.font.family().as_deref());
         if let Some(font_family) = font_family.as_ref() {
             attrs = attrs.family(font_family);
         }
@@ -113,14 +276,77 @@ impl Label {
         let mut text_layout = TextLayout::new();
         let attrs_list = self.get_attrs_list();
         text_layout.set_text(self.label.as_str(), attrs_list.clone());
+        self.align(&mut text_layout);
         self.text_layout = Some(text_layout);
 
         if let Some(new_text) = self.available_text.as_ref() {
             let mut text_layout = TextLayout::new();
             text_layout.set_text(new_text, attrs_list);
+            self.align(&mut text_layout);
             self.available_text_layout = Some(text_layout);
         }
     }
+
+    /// Wrap the text at `width`, and truncate it with an ellipsis if it's more than `max_lines`
+    /// lines.
+    fn clamp_lines(&mut self, width: f32, max_lines: usize) {
+        let mut text_layout = self.text_layout.clone().unwrap();
+        text_layout.set_size(width, f32::MAX);
+        let Some(last_line) = text_layout.layout_runs().nth(max_lines - 1) else {
+            self.available_text = None;
+            self.available_text_layout = Some(text_layout);
+            return;
+        };
+        if text_layout.layout_runs().count() == max_lines {
+            self.available_text = None;
+            self.available_text_layout = Some(text_layout);
+            return;
+        }
+
+        let mut ellipsis = TextLayout::new();
+        ellipsis.set_text(ELLIPSIS, self.get_attrs_list());
+        let ellipsis_width = ellipsis.size().width;
+        let line_y = last_line.line_y as f64;
+        let mut index = text_layout
+            .hit_point(Point::new(width as f64 - ellipsis_width, line_y))
+            .index;
+        // The ellipsis can make the last line wrap, so remove characters until it doesn't
+        loop {
+            let text = format!("{}{ELLIPSIS}", self.label[..index].trim_end());
+            let mut clamped = TextLayout::new();
+            clamped.set_text(&text, self.get_attrs_list());
+            self.align(&mut clamped);
+            clamped.set_size(width, f32::MAX);
+            if index == 0 || clamped.layout_runs().count() <= max_lines {
+                self.available_text = Some(text);
+                self.available_text_layout = Some(clamped);
+                return;
+            }
+            index = self.label[..index]
+                .char_indices()
+                .next_back()
+                .map_or(0, |(i, _)| i);
+        }
+    }
+
+    /// The alignment of the text, which defaults to the right from right to left.
+    fn text_align(&self) -> Option<TextAlign> {
+        self.style
+            .text_align()
+            .or_else(|| (self.style.direction() == Direction::Rtl).then_some(TextAlign::Right))
+    }
+
+    fn align(&self, text_layout: &mut TextLayout) {
+        let align = self.text_align().map(|align| match align {
+            TextAlign::Left => Align::Left,
+            TextAlign::Center => Align::Center,
+            TextAlign::Right => Align::Right,
+            TextAlign::Justify => Align::Justified,
+        });
+        for line in text_layout.lines.iter_mut() {
+            line.set_align(align);
+        }
+    }
 }
 
 impl View for Label {
@@ -136,9 +362,15 @@ impl View for Label {
         format!("Label: {:?}", self.label).into()
     }
 
+    fn accessible_text(&self) -> Option<String> {
+        Some(self.label.clone())
+    }
+
     fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn Any>) {
         if let Ok(state) = state.downcast() {
             self.label = *state;
+            self.selection = None;
+            self.selecting = false;
             self.text_layout = None;
             self.available_text = None;
             self.available_width = None;
@@ -147,6 +379,74 @@ impl View for Label {
         }
     }
 
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        _id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        if !self.selectable {
+            return EventPropagation::Continue;
+        }
+        match &event {
+            Event::PointerDown(e) if e.button.is_primary() => {
+                let Some(index) = self.text_index_at(cx, e.pos) else {
+                    return EventPropagation::Continue;
+                };
+                let range = match e.count {
+                    2 => get_dbl_click_selection(index, &self.label),
+                    3 => self.line_range(index),
+                    _ => index..index,
+                };
+                self.selection = Some(TextSelection {
+                    anchor: range.start,
+                    active: range.end,
+                });
+                self.selecting = e.count < 2;
+                self.id().request_focus();
+                // Keep getting pointer events when dragging outside of the label
+                self.id().capture_pointer();
+                cx.app_state_mut().request_paint(self.id());
+                return EventPropagation::Stop;
+            }
+            Event::PointerMove(e) if self.selecting => {
+                if let Some(index) = self.text_index_at(cx, e.pos) {
+                    if let Some(selection) = self.selection.as_mut() {
+                        selection.active = index;
+                    }
+                    cx.app_state_mut().request_paint(self.id());
+                }
+                return EventPropagation::Stop;
+            }
+            Event::PointerUp(_) if self.selecting => {
+                self.selecting = false;
+                return EventPropagation::Stop;
+            }
+            Event::FocusLost => {
+                self.selecting = false;
+                if self.selection.take().is_some() {
+                    cx.app_state_mut().request_paint(self.id());
+                }
+            }
+            Event::KeyDown(e) => {
+                #[cfg(target_os = "macos")]
+                let command = e.modifiers == ModifiersState::SUPER;
+                #[cfg(not(target_os = "macos"))]
+                let command = e.modifiers == ModifiersState::CONTROL;
+                let copy =
+                    matches!(&e.key.logical_key, Key::Character(c) if c.eq_ignore_ascii_case("c"));
+                if command && copy {
+                    if let Some(text) = self.selected_text() {
+                        let _ = Clipboard::set_text(text);
+                    }
+                    return EventPropagation::Stop;
+                }
+            }
+            _ => {}
+        }
+        EventPropagation::Continue
+    }
+
     fn style(&mut self, cx: &mut crate::context::StyleCx<'_>) {
         if self.font.read(cx) | self.style.read(cx) {
             self.text_layout = None;
@@ -170,7 +470,9 @@ impl View for Label {
                 let width = size.width.ceil() as f32;
                 let mut height = size.height as f32;
 
-                if self.style.text_overflow() == TextOverflow::Wrap {
+                let clamped = self.style.text_overflow() == TextOverflow::Ellipsis
+                    && self.style.max_lines().unwrap_or(1) > 1;
+                if self.style.text_overflow() == TextOverflow::Wrap || clamped {
                     if let Some(t) = self.available_text_layout.as_ref() {
                         height = height.max(t.size().height as f32);
                     }
@@ -217,11 +519,18 @@ impl View for Label {
         let text_layout = self.text_layout.as_ref().unwrap();
         let width = text_layout.size().width as f32;
         let available_width = layout.size.width - padding;
-        if text_overflow == TextOverflow::Ellipsis {
+        let max_lines = self.style.max_lines().unwrap_or(1);
+        if text_overflow == TextOverflow::Ellipsis && max_lines > 1 {
+            if self.available_width != Some(available_width) {
+                self.clamp_lines(available_width, max_lines);
+                self.available_width = Some(available_width);
+                cx.app_state_mut().request_layout(self.id());
+            }
+        } else if text_overflow == TextOverflow::Ellipsis {
             if width > available_width {
                 if self.available_width != Some(available_width) {
                     let mut dots_text = TextLayout::new();
-                    dots_text.set_text("...", self.get_attrs_list());
+                    dots_text.set_text(ELLIPSIS, self.get_attrs_list());
 
                     let dots_width = dots_text.size().width as f32;
                     let width_left = available_width - dots_width;
@@ -229,7 +538,7 @@ impl View for Label {
                     let index = hit_point.index;
 
                     let new_text = if index > 0 {
-                        format!("{}...", &self.label[..index])
+                        format!("{}{ELLIPSIS}", &self.label[..index])
                     } else {
                         "".to_string()
                     };
@@ -243,7 +552,9 @@ impl View for Label {
                 self.available_text_layout = None;
             }
         } else if text_overflow == TextOverflow::Wrap {
-            if width > available_width {
+            // Aligned text is laid out in the available width even when it fits, so it's
+            // aligned within it
+            if width > available_width || self.text_align().is_some() {
                 if self.available_width != Some(available_width) {
                     let mut text_layout = text_layout.clone();
                     text_layout.set_size(available_width, f32::MAX);
@@ -281,10 +592,110 @@ impl View for Label {
         let text_node = self.text_node.unwrap();
         let location = cx.app_state.taffy.layout(text_node).unwrap().location;
         let point = Point::new(location.x as f64, location.y as f64);
+        let selection_color = self
+            .style
+            .selection_color()
+            .unwrap_or(Color::rgba8(0, 0, 0, 60));
+        let rects = match (self.selection, self.visible_text_layout()) {
+            (Some(selection), Some(text_layout)) => {
+                // The selection is in the text, which can be truncated in the shown layout
+                let shown = self.available_text.as_deref();
+                let range = selection.range();
+                let start = shown_index(&self.label, shown, range.start);
+                let end = shown_index(&self.label, shown, range.end);
+                text_selection_rects(text_layout, start..end)
+            }
+            _ => Vec::new(),
+        };
+        for rect in rects {
+            cx.fill(&(rect + point.to_vec2()), selection_color, 0.0);
+        }
         if let Some(text_layout) = self.available_text_layout.as_ref() {
             cx.draw_text(text_layout, point);
         } else {
             cx.draw_text(self.text_layout.as_ref().unwrap(), point);
         }
+        if let Some(key) = cx.app_state.shown_access_key(self.id()) {
+            if let Some(underline) = self.access_key_underline(key) {
+                let color = self.style.color().unwrap_or(Color::BLACK);
+                cx.fill(&(underline + point.to_vec2()), color, 0.0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{label_index, shown_index, text, ELLIPSIS};
+    use crate::cosmic_text::TextLayout;
+
+    #[test]
+    fn ellipsized_indices_map_to_the_label() {
+        let label = "héllo wörld";
+        let shown = Some("héllo w...");
+        let visible = "héllo w".len();
+
+        assert_eq!(label_index(label, shown, 0), 0);
+        assert_eq!(label_index(label, shown, visible), visible);
+        // Offsets inside a character go to its start
+        assert_eq!(label_index(label, shown, 2), 1);
+        // The ellipsis stands for the rest of the label
+        assert_eq!(label_index(label, shown, visible + 1), label.len());
+        assert_eq!(
+            label_index(label, shown, visible + "...".len()),
+            label.len()
+        );
+
+        assert_eq!(shown_index(label, shown, 1), 1);
+        assert_eq!(
+            shown_index(label, shown, "héllo wö".len()),
+            "héllo w...".len()
+        );
+        assert_eq!(shown_index(label, shown, label.len()), "héllo w...".len());
+    }
+
+    #[test]
+    fn indices_without_ellipsis_stay_in_the_label() {
+        let label = "日本語";
+        assert_eq!(label_index(label, None, 4), 3);
+        assert_eq!(label_index(label, None, 100), label.len());
+        assert_eq!(shown_index(label, None, 100), label.len());
+        // A label truncated to nothing
+        assert_eq!(label_index(label, Some(""), 0), 0);
+        assert_eq!(label_index(label, Some(""), 1), label.len());
+    }
+
+    #[test]
+    fn labels_within_the_lines_are_not_clamped() {
+        let mut label = text("one two");
+        label.set_text_layout();
+        let width = label.text_layout.as_ref().unwrap().size().width as f32;
+        label.clamp_lines(width + 1.0, 2);
+        assert_eq!(label.available_text, None);
+        // Wrapped into exactly two lines
+        label.clamp_lines(width * 0.75, 2);
+        assert_eq!(label.available_text, None);
+    }
+
+    #[test]
+    fn clamped_labels_end_with_an_ellipsis_within_the_lines() {
+        let content = "one two three four five six seven eight nine ten";
+        let mut label = text(content);
+        label.set_text_layout();
+        let mut word = TextLayout::new();
+        word.set_text("three", label.get_attrs_list());
+        let word_width = word.size().width as f32;
+
+        // At some of the widths adding the ellipsis wraps the last line, so the text is
+        // shortened further
+        for step in 0..10 {
+            let width = word_width * (1.0 + step as f32 * 0.25);
+            label.clamp_lines(width, 2);
+            let shown = label.available_text.clone().unwrap();
+            let visible = shown.strip_suffix(ELLIPSIS).unwrap();
+            assert!(content.starts_with(visible), "{shown:?}");
+            let lines = label.available_text_layout.as_ref().unwrap().layout_runs();
+            assert!(lines.count() <= 2, "{shown:?} at {width}");
+        }
     }
 }
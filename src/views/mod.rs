@@ -48,12 +48,24 @@ pub use tab::*;
 mod tooltip;
 pub use tooltip::*;
 
+mod dropdown;
+pub use dropdown::*;
+
+mod tab_view;
+pub use tab_view::*;
+
+mod split;
+pub use split::*;
+
 mod stack;
 pub use stack::*;
 
 mod text_input;
 pub use text_input::*;
 
+mod text_area;
+pub use text_area::*;
+
 mod empty;
 pub use empty::*;
 
@@ -65,3 +77,18 @@ pub use drag_resize_window_area::*;
 
 mod img;
 pub use img::*;
+
+mod spinner;
+pub use spinner::*;
+
+mod scoped;
+pub use scoped::*;
+
+mod portal;
+pub use portal::*;
+
+mod toast;
+pub use toast::*;
+
+mod canvas;
+pub use canvas::*;
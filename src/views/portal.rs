@@ -0,0 +1,211 @@
+use std::{cell::Cell, rc::Rc};
+
+use floem_reactive::{create_rw_signal, with_scope, RwSignal, Scope};
+use kurbo::{Point, Rect};
+
+use crate::{
+    action::{add_overlay, remove_overlay},
+    context::ComputeLayoutCx,
+    id::Id,
+    view::{default_compute_layout, View, ViewData},
+    views::{container, Decorators},
+};
+
+/// Where the content of a [`portal`] is placed in the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortalTarget {
+    /// At the origin of the portal, following it when it moves.
+    Portal,
+    /// At the origin of the window, so the content is positioned like the other overlays.
+    Window,
+}
+
+/// A view which shows its content in an overlay of the window. See [`portal`]
+pub struct Portal {
+    data: ViewData,
+    target: PortalTarget,
+    view_fn: Option<Box<dyn FnOnce() -> Box<dyn View>>>,
+    scope: Scope,
+    overlay: Rc<Cell<Option<Id>>>,
+    /// The window origin of the portal, which the content is placed at
+    origin: RwSignal<Point>,
+}
+
+/// A view which shows the view built by `view_fn` in an overlay at the root of the window,
+/// instead of as its child.
+///
+/// The content is placed at `target`, which is either the origin of the portal, following it
+/// when it moves, or the origin of the window. Either way it's painted above all the other
+/// views of the window and isn't clipped by the ancestors of the portal. Pointer events reach
+/// the content before the views below it. Its layout is independent of the portal, so
+/// percentages are relative to the window.
+///
+/// The content is built once the portal has been laid out, in a child scope of the scope the
+/// portal was created in, and it's removed along with the portal.
+///
+/// ```rust
+/// # use floem::views::{label, portal, Decorators, PortalTarget};
+/// portal(PortalTarget::Portal, || {
+///     label(|| "Shown above everything").style(|s| s.padding(8.0))
+/// });
+/// ```
+pub fn portal<V: View + 'static>(
+    target: PortalTarget,
+    view_fn: impl FnOnce() -> V + 'static,
+) -> Portal {
+    let id = Id::next();
+    let scope = Scope::current().create_child();
+    let overlay: Rc<Cell<Option<Id>>> = Rc::new(Cell::new(None));
+    id.own_scope(scope);
+    id.update_cleanup_listener(Box::new({
+        let overlay = overlay.clone();
+        move || {
+            if let Some(overlay) = overlay.take() {
+                remove_overlay(overlay);
+            }
+        }
+    }));
+    Portal {
+        data: ViewData::new(id),
+        target,
+        view_fn: Some(Box::new(move || Box::new(view_fn()))),
+        scope,
+        overlay,
+        origin: with_scope(scope, || create_rw_signal(Point::ZERO)),
+    }
+}
+
+impl View for Portal {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Portal".into()
+    }
+
+    fn compute_layout(&mut self, cx: &mut ComputeLayoutCx) -> Option<Rect> {
+        if self.target == PortalTarget::Portal && self.origin.get_untracked() != cx.window_origin {
+            self.origin.set(cx.window_origin);
+        }
+        if let Some(view_fn) = self.view_fn.take() {
+            let scope = self.scope;
+            let origin = self.origin;
+            self.overlay.set(Some(add_overlay(Point::ZERO, move |_| {
+                with_scope(scope, move || {
+                    container(view_fn()).style(move |s| {
+                        let origin = origin.get();
+                        s.margin_left(origin.x).margin_top(origin.y)
+                    })
+                })
+            })));
+        }
+        default_compute_layout(self, cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use floem_reactive::{create_effect, create_rw_signal};
+    use kurbo::Size;
+    use peniko::Color;
+
+    use super::{portal, PortalTarget};
+    use crate::{
+        style::Overflow,
+        view::View,
+        views::{container, empty, show, Decorators},
+        window::HeadlessWindow,
+    };
+
+    fn pixel(frame: &[u8], x: usize, y: usize) -> Vec<u8> {
+        frame[(y * 100 + x) * 4..][..4].to_vec()
+    }
+
+    fn red_square() -> impl View {
+        empty().style(|s| s.size(10.0, 10.0).background(Color::RED))
+    }
+
+    #[test]
+    fn content_follows_the_portal() {
+        let left = create_rw_signal(20.0);
+        let mut window = HeadlessWindow::new(
+            move |_| {
+                // The portal is in a view which clips everything to nothing
+                let clipped = container(portal(PortalTarget::Portal, red_square))
+                    .style(|s| s.size(0.0, 0.0).overflow(Overflow::Hidden));
+                container(clipped).style(move |s| s.margin_left(left.get()).margin_top(30.0))
+            },
+            Size::new(100.0, 100.0),
+            1.0,
+        );
+        let frame = window.render();
+        assert_eq!(pixel(&frame, 25, 35), [255, 0, 0, 255]);
+        assert_ne!(pixel(&frame, 5, 5), [255, 0, 0, 255]);
+
+        left.set(60.0);
+        let frame = window.render();
+        assert_eq!(pixel(&frame, 65, 35), [255, 0, 0, 255]);
+        assert_ne!(pixel(&frame, 25, 35), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn content_is_placed_at_the_window_target() {
+        let mut window = HeadlessWindow::new(
+            |_| {
+                container(portal(PortalTarget::Window, red_square))
+                    .style(|s| s.margin_left(50.0).margin_top(50.0))
+            },
+            Size::new(100.0, 100.0),
+            1.0,
+        );
+        let frame = window.render();
+        assert_eq!(pixel(&frame, 5, 5), [255, 0, 0, 255]);
+        assert_ne!(pixel(&frame, 55, 55), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn content_is_removed_and_disposed_with_the_portal() {
+        let shown = create_rw_signal(true);
+        let tracked = create_rw_signal(0);
+        let runs = Rc::new(Cell::new(0));
+        let mut window = HeadlessWindow::new(
+            {
+                let runs = runs.clone();
+                move |_| {
+                    show(
+                        move || shown.get(),
+                        move || {
+                            let runs = runs.clone();
+                            portal(PortalTarget::Portal, move || {
+                                create_effect(move |_| {
+                                    tracked.track();
+                                    runs.set(runs.get() + 1);
+                                });
+                                red_square()
+                            })
+                        },
+                        empty,
+                    )
+                }
+            },
+            Size::new(100.0, 100.0),
+            1.0,
+        );
+        assert_eq!(pixel(&window.render(), 5, 5), [255, 0, 0, 255]);
+        tracked.set(1);
+        assert_eq!(runs.get(), 2);
+
+        // The declaring view is unmounted
+        shown.set(false);
+        assert_ne!(pixel(&window.render(), 5, 5), [255, 0, 0, 255]);
+        tracked.set(2);
+        assert_eq!(runs.get(), 2);
+    }
+}
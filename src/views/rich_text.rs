@@ -1,24 +1,99 @@
-use std::any::Any;
+use std::{any::Any, ops::Range, rc::Rc};
 
 use floem_reactive::create_effect;
-use floem_renderer::{cosmic_text::TextLayout, Renderer};
+use floem_renderer::{
+    cosmic_text::{Attrs, AttrsList, TextLayout, Weight},
+    Renderer,
+};
 use kurbo::{Point, Rect};
+use peniko::Color;
 use taffy::prelude::Node;
 
 use crate::{
-    context::UpdateCx,
+    context::{EventCx, UpdateCx},
+    event::Event,
+    font::font_families,
     id::Id,
-    style::{Style, TextOverflow},
+    prop_extracter,
+    style::{FontProps, LineHeight, Style, TextColor, TextOverflow},
     unit::PxPct,
     view::{View, ViewData},
+    EventPropagation,
 };
 
+prop_extracter! {
+    Extracter {
+        color: TextColor,
+        line_height: LineHeight,
+    }
+}
+
 pub struct RichText {
     data: ViewData,
     text_layout: TextLayout,
     text_node: Option<Node>,
     text_overflow: TextOverflow,
     available_width: f32,
+    spans: Option<Spans>,
+}
+
+/// A run of text in [`rich_text_spans`] with its own style. See [`span`].
+pub struct TextSpan {
+    text: String,
+    color: Option<Box<dyn Fn() -> Color>>,
+    weight: Option<Weight>,
+    font_size: Option<f32>,
+    on_click: Option<Box<dyn Fn()>>,
+}
+
+/// A span of `text` which is styled like the rest of the [`rich_text_spans`] view it's in,
+/// unless it overrides that.
+pub fn span(text: impl Into<String>) -> TextSpan {
+    TextSpan {
+        text: text.into(),
+        color: None,
+        weight: None,
+        font_size: None,
+        on_click: None,
+    }
+}
+
+impl TextSpan {
+    /// Set the color of the span. This is reactive, so the span is recolored when signals
+    /// read by `color` change.
+    pub fn color(mut self, color: impl Fn() -> Color + 'static) -> Self {
+        self.color = Some(Box::new(color));
+        self
+    }
+
+    pub fn weight(mut self, weight: Weight) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    pub fn font_size(mut self, font_size: f32) -> Self {
+        self.font_size = Some(font_size);
+        self
+    }
+
+    /// Add a handler which is called when the span is clicked, which makes it work like a link.
+    pub fn on_click(mut self, on_click: impl Fn() + 'static) -> Self {
+        self.on_click = Some(Box::new(on_click));
+        self
+    }
+}
+
+/// The spans of a [`rich_text_spans`] view and the state needed to lay them out.
+struct Spans {
+    spans: Rc<[TextSpan]>,
+    /// The byte ranges of the spans in the text.
+    ranges: Vec<Range<usize>>,
+    /// The current colors of the spans with a color.
+    colors: Vec<Option<Color>>,
+    font: FontProps,
+    style: Extracter,
+    /// The span the pointer was pressed on, if it's clickable.
+    pressed: Option<usize>,
 }
 
 pub fn rich_text(text_layout: impl Fn() -> TextLayout + 'static) -> RichText {
@@ -34,6 +109,145 @@ pub fn rich_text(text_layout: impl Fn() -> TextLayout + 'static) -> RichText {
         text_node: None,
         text_overflow: TextOverflow::Wrap,
         available_width: 0.0,
+        spans: None,
+    }
+}
+
+/// Text made of `spans` which each can have their own color, weight and size, with the
+/// font and text color of the view as the default. Lines are broken across spans, and spans
+/// with a click handler can be used as inline links.
+///
+/// ```rust
+/// use floem::cosmic_text::Weight;
+/// use floem::peniko::Color;
+/// use floem::reactive::create_rw_signal;
+/// use floem::views::{rich_text_spans, span};
+///
+/// let visited = create_rw_signal(false);
+/// rich_text_spans([
+///     span("Floem").weight(Weight::BOLD),
+///     span(" is a UI library, see its "),
+///     span("homepage")
+///         .color(move || if visited.get() { Color::PURPLE } else { Color::BLUE })
+///         .on_click(move || visited.set(true)),
+///     span("."),
+/// ]);
+/// ```
+pub fn rich_text_spans(spans: impl IntoIterator<Item = TextSpan>) -> RichText {
+    let id = Id::next();
+    let spans: Rc<[TextSpan]> = spans.into_iter().collect();
+    let mut ranges = Vec::with_capacity(spans.len());
+    let mut start = 0;
+    for span in spans.iter() {
+        ranges.push(start..start + span.text.len());
+        start += span.text.len();
+    }
+
+    {
+        let spans = spans.clone();
+        create_effect(move |_| {
+            let colors: Vec<Option<Color>> = spans
+                .iter()
+                .map(|span| span.color.as_ref().map(|color| color()))
+                .collect();
+            id.update_state(colors, false);
+        });
+    }
+
+    RichText {
+        data: ViewData::new(id),
+        text_layout: TextLayout::new(),
+        text_node: None,
+        text_overflow: TextOverflow::Wrap,
+        available_width: 0.0,
+        spans: Some(Spans {
+            colors: vec![None; spans.len()],
+            spans,
+            ranges,
+            font: FontProps::default(),
+            style: Default::default(),
+            pressed: None,
+        }),
+    }
+}
+
+impl Spans {
+    fn text_layout(&self) -> TextLayout {
+        let font_family = font_families(self.font.family().as_deref());
+        let mut attrs = Attrs::new().color(self.style.color().unwrap_or(Color::BLACK));
+        if let Some(font_size) = self.font.size() {
+            attrs = attrs.font_size(font_size);
+        }
+        if let Some(font_style) = self.font.style() {
+            attrs = attrs.style(font_style);
+        }
+        if let Some(font_family) = font_family.as_ref() {
+            attrs = attrs.family(font_family);
+        }
+        if let Some(font_weight) = self.font.weight() {
+            attrs = attrs.weight(font_weight);
+        }
+        if let Some(line_height) = self.style.line_height() {
+            attrs = attrs.line_height(line_height);
+        }
+
+        let mut attrs_list = AttrsList::new(attrs);
+        for ((span, range), color) in self.spans.iter().zip(&self.ranges).zip(&self.colors) {
+            let mut span_attrs = attrs;
+            if let Some(color) = color {
+                span_attrs = span_attrs.color(*color);
+            }
+            if let Some(weight) = span.weight {
+                span_attrs = span_attrs.weight(weight);
+            }
+            if let Some(font_size) = span.font_size {
+                span_attrs = span_attrs.font_size(font_size);
+            }
+            attrs_list.add_span(range.clone(), span_attrs);
+        }
+
+        let text: String = self.spans.iter().map(|span| span.text.as_str()).collect();
+        let mut text_layout = TextLayout::new();
+        text_layout.set_text(&text, attrs_list);
+        text_layout
+    }
+
+    /// The index of the span containing the byte at `index` in the text.
+    fn span_at(&self, index: usize) -> Option<usize> {
+        self.ranges.iter().position(|range| range.contains(&index))
+    }
+}
+
+impl RichText {
+    fn relayout_spans(&mut self) {
+        if let Some(spans) = self.spans.as_ref() {
+            self.text_layout = spans.text_layout();
+            if self.text_overflow == TextOverflow::Wrap && self.available_width > 0.0 {
+                self.text_layout.set_size(self.available_width, f32::MAX);
+            }
+        }
+    }
+
+    /// The index of the span under `pos` and the byte offset of the character under it in
+    /// the text.
+    fn hit_span(&self, cx: &EventCx, pos: Point) -> Option<(usize, usize)> {
+        let spans = self.spans.as_ref()?;
+        let location = cx.app_state.taffy.layout(self.text_node?).ok()?.location;
+        let hit = self.text_layout.hit_point(Point::new(
+            pos.x - location.x as f64,
+            pos.y - location.y as f64,
+        ));
+        if !hit.is_inside {
+            return None;
+        }
+        Some((spans.span_at(hit.index)?, hit.index))
+    }
+
+    /// The index of the span under `pos` if it has a click handler.
+    fn clickable_span(&self, cx: &EventCx, pos: Point) -> Option<usize> {
+        let (span, _) = self.hit_span(cx, pos)?;
+        let spans = self.spans.as_ref()?;
+        spans.spans[span].on_click.is_some().then_some(span)
     }
 }
 
@@ -59,6 +273,17 @@ impl View for RichText {
     }
 
     fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn Any>) {
+        let state = match state.downcast::<Vec<Option<Color>>>() {
+            Ok(colors) => {
+                if let Some(spans) = self.spans.as_mut() {
+                    spans.colors = *colors;
+                    self.relayout_spans();
+                    cx.request_layout(self.id());
+                }
+                return;
+            }
+            Err(state) => state,
+        };
         if let Ok(state) = state.downcast() {
             let mut text_layout: TextLayout = *state;
             if self.text_overflow == TextOverflow::Wrap && self.available_width > 0.0 {
@@ -70,6 +295,46 @@ impl View for RichText {
         }
     }
 
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        _id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        match &event {
+            Event::PointerDown(e) if e.button.is_primary() => {
+                let span = self.clickable_span(cx, e.pos);
+                if let Some(spans) = self.spans.as_mut() {
+                    spans.pressed = span;
+                    if span.is_some() {
+                        return EventPropagation::Stop;
+                    }
+                }
+            }
+            Event::PointerUp(e) if e.button.is_primary() => {
+                let span = self.clickable_span(cx, e.pos);
+                if let Some(spans) = self.spans.as_mut() {
+                    let pressed = spans.pressed.take();
+                    if let Some(span) = span.filter(|span| pressed == Some(*span)) {
+                        (spans.spans[span].on_click.as_ref().unwrap())();
+                        return EventPropagation::Stop;
+                    }
+                }
+            }
+            _ => {}
+        }
+        EventPropagation::Continue
+    }
+
+    fn style(&mut self, cx: &mut crate::context::StyleCx<'_>) {
+        if let Some(spans) = self.spans.as_mut() {
+            if spans.font.read(cx) | spans.style.read(cx) {
+                self.relayout_spans();
+                cx.app_state_mut().request_layout(self.id());
+            }
+        }
+    }
+
     fn layout(&mut self, cx: &mut crate::context::LayoutCx) -> taffy::prelude::Node {
         cx.layout_node(self.id(), true, |cx| {
             let size = self.text_layout.size();
@@ -0,0 +1,28 @@
+use floem_reactive::{with_scope, Scope};
+
+use crate::view::View;
+
+/// Builds the view returned by `view_fn` in a new child scope of the current scope, which is
+/// disposed when the view is removed.
+///
+/// Effects and signals created while building the view then stop with it, instead of living
+/// as long as the scope the view was created in. Timers started with
+/// [`exec_after`](crate::action::exec_after) in the scope are cancelled, and other resources
+/// can be released with [`on_cleanup`](floem_reactive::on_cleanup).
+///
+/// ```rust
+/// # use floem::reactive::{create_effect, create_rw_signal, on_cleanup};
+/// # use floem::views::{label, scoped};
+/// let count = create_rw_signal(0);
+/// let view = scoped(move || {
+///     create_effect(move |_| println!("count is {}", count.get()));
+///     on_cleanup(|| println!("the view was removed"));
+///     label(move || count.get().to_string())
+/// });
+/// ```
+pub fn scoped<V: View + 'static>(view_fn: impl FnOnce() -> V) -> V {
+    let scope = Scope::current().create_child();
+    let view = with_scope(scope, view_fn);
+    view.id().own_scope(scope);
+    view
+}
@@ -1,9 +1,13 @@
+use std::time::{Duration, Instant};
+
 use floem_reactive::create_effect;
 use floem_renderer::Renderer;
 use kurbo::{Point, Rect, Size, Vec2};
 use peniko::Color;
 
 use crate::{
+    action::exec_after,
+    animate::{Easing, EasingFn, EasingMode},
     context::{AppState, ComputeLayoutCx, PaintCx},
     event::Event,
     id::Id,
@@ -24,6 +28,18 @@ enum ScrollState {
     HiddenBar(bool),
     PropagatePointerWheel(bool),
     VerticalScrollAsHorizontal(bool),
+    AnimationFrame,
+}
+
+/// The interval between frames of a smooth scroll.
+const SMOOTH_SCROLL_FRAME: Duration = Duration::from_millis(16);
+
+/// An in progress smooth scroll of the child viewport.
+struct ScrollAnimation {
+    from: Point,
+    to: Point,
+    start: Instant,
+    duration: Duration,
 }
 
 /// Minimum length for any scrollbar to be when measured on that
@@ -94,6 +110,9 @@ pub struct Scroll {
     track_style: ScrollStyle,
     track_hover_style: ScrollStyle,
     hide: bool,
+    /// The duration of programmatic scrolls. They are instant if this is `None`.
+    smooth_scroll: Option<Duration>,
+    animation: Option<ScrollAnimation>,
 }
 
 pub fn scroll<V: View + 'static>(child: V) -> Scroll {
@@ -118,6 +137,8 @@ pub fn scroll<V: View + 'static>(child: V) -> Scroll {
         handle_hover_style: Default::default(),
         track_style: Default::default(),
         track_hover_style: Default::default(),
+        smooth_scroll: None,
+        animation: None,
     }
 }
 
@@ -127,6 +148,16 @@ impl Scroll {
         self
     }
 
+    /// Animate scrolls requested by [`Scroll::on_scroll_to`], [`Scroll::scroll_to_view`],
+    /// [`Id::scroll_to`] and similar over `duration`, instead of jumping to the target.
+    ///
+    /// Scrolling with the mouse wheel or the scroll bars is not animated and
+    /// interrupts an animated scroll.
+    pub fn smooth_scroll(mut self, duration: Duration) -> Self {
+        self.smooth_scroll = Some(duration);
+        self
+    }
+
     pub fn on_ensure_visible(self, to: impl Fn() -> Rect + 'static) -> Self {
         let id = self.id();
         create_effect(move |_| {
@@ -168,6 +199,7 @@ impl Scroll {
         self
     }
 
+    /// Scroll the smallest distance which makes the view returned by `view` visible.
     pub fn scroll_to_view(self, view: impl Fn() -> Option<Id> + 'static) -> Self {
         let id = self.id();
         create_effect(move |_| {
@@ -204,12 +236,67 @@ impl Scroll {
     }
 
     fn scroll_delta(&mut self, app_state: &mut AppState, delta: Vec2) {
+        self.animation = None;
         let new_origin = self.child_viewport.origin() + delta;
         self.clamp_child_viewport(app_state, self.child_viewport.with_origin(new_origin));
     }
 
     fn scroll_to(&mut self, app_state: &mut AppState, origin: Point) {
+        let Some(duration) = self.smooth_scroll else {
+            self.clamp_child_viewport(app_state, self.child_viewport.with_origin(origin));
+            return;
+        };
+
+        let to = self.clamp_origin(origin);
+        let from = self.child_viewport.origin();
+        if from == to {
+            self.animation = None;
+            return;
+        }
+
+        // Starting from the current position retargets an animation which is in progress
+        let in_progress = self.animation.is_some();
+        self.animation = Some(ScrollAnimation {
+            from,
+            to,
+            start: Instant::now(),
+            duration,
+        });
+        if !in_progress {
+            self.request_animation_frame();
+        }
+    }
+
+    fn request_animation_frame(&self) {
+        let id = self.id();
+        exec_after(SMOOTH_SCROLL_FRAME, move |_| {
+            id.update_state(ScrollState::AnimationFrame, false);
+        });
+    }
+
+    fn animation_frame(&mut self, app_state: &mut AppState) {
+        let Some(animation) = &self.animation else {
+            return;
+        };
+
+        let elapsed = animation.start.elapsed();
+        let (origin, done) = if elapsed >= animation.duration {
+            (animation.to, true)
+        } else {
+            let easing = Easing {
+                mode: EasingMode::Out,
+                func: EasingFn::Cubic,
+            };
+            let t = easing.ease(elapsed.as_secs_f64() / animation.duration.as_secs_f64());
+            (animation.from.lerp(animation.to, t), false)
+        };
+
         self.clamp_child_viewport(app_state, self.child_viewport.with_origin(origin));
+        if done {
+            self.animation = None;
+        } else {
+            self.request_animation_frame();
+        }
     }
 
     /// Pan the smallest distance that makes the target [`Rect`] visible.
@@ -266,7 +353,7 @@ impl Scroll {
         let delta_x = if x0.abs() > x1.abs() { x0 } else { x1 };
         let delta_y = if y0.abs() > y1.abs() { y0 } else { y1 };
         let new_origin = self.child_viewport.origin() + Vec2::new(delta_x, delta_y);
-        self.clamp_child_viewport(app_state, self.child_viewport.with_origin(new_origin));
+        self.scroll_to(app_state, new_origin);
     }
 
     fn update_size(&mut self, app_state: &mut AppState) {
@@ -274,6 +361,13 @@ impl Scroll {
         self.actual_rect = app_state.get_content_rect(self.id());
     }
 
+    /// Clamp an origin of the child viewport to the scrollable area.
+    fn clamp_origin(&self, origin: Point) -> Point {
+        let max_x = (self.child_size.width - self.actual_rect.width()).max(0.0);
+        let max_y = (self.child_size.height - self.actual_rect.height()).max(0.0);
+        Point::new(origin.x.clamp(0.0, max_x), origin.y.clamp(0.0, max_y))
+    }
+
     fn clamp_child_viewport(
         &mut self,
         app_state: &mut AppState,
@@ -678,6 +772,9 @@ impl View for Scroll {
                 ScrollState::VerticalScrollAsHorizontal(value) => {
                     self.vertical_scroll_as_horizontal = value;
                 }
+                ScrollState::AnimationFrame => {
+                    self.animation_frame(cx.app_state);
+                }
             }
             cx.request_layout(self.id());
         }
@@ -735,6 +832,7 @@ impl View for Scroll {
             Event::PointerDown(event) => {
                 if !self.hide && event.button.is_primary() {
                     self.held = BarHeldState::None;
+                    self.animation = None;
 
                     let pos = event.pos + scroll_offset;
 
@@ -873,12 +971,8 @@ impl View for Scroll {
     fn paint(&mut self, cx: &mut crate::context::PaintCx) {
         cx.save();
         let style = cx.get_computed_style(self.id());
-        let radius = match style.get(BorderRadius) {
-            crate::unit::PxPct::Px(px) => px,
-            crate::unit::PxPct::Pct(pct) => self.actual_rect.size().min_side() * (pct / 100.),
-        };
-        if radius > 0.0 {
-            let rect = self.actual_rect.to_rounded_rect(radius);
+        if let Some(radii) = style.border_radii(self.actual_rect.size()) {
+            let rect = self.actual_rect.to_rounded_rect(radii);
             cx.clip(&rect);
         } else {
             cx.clip(&self.actual_rect);
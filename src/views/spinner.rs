@@ -0,0 +1,137 @@
+use std::{
+    f64::consts::TAU,
+    time::{Duration, Instant},
+};
+
+use floem_reactive::create_effect;
+use floem_renderer::Renderer;
+use kurbo::{Arc, Point, Vec2};
+use peniko::Color;
+
+use crate::{
+    action::exec_after,
+    context::{PaintCx, StyleCx, UpdateCx},
+    id::Id,
+    style::{Style, TextColor},
+    view::{View, ViewData},
+    window::{use_window_focus, use_window_occluded},
+};
+
+/// The time between the frames of the animation
+const FRAME: Duration = Duration::from_millis(16);
+/// How long a full turn of the spinner takes
+const TURN: Duration = Duration::from_secs(1);
+
+enum SpinnerState {
+    /// Whether the window is focused and visible
+    Running(bool),
+    Frame,
+}
+
+pub struct Spinner {
+    data: ViewData,
+    color: Option<Color>,
+    running: bool,
+    frame_requested: bool,
+    /// How far the spinner has turned, in turns
+    turn: f64,
+    /// When the last frame was painted, unless the spinner is paused
+    last_frame: Option<Instant>,
+}
+
+/// A turning arc showing that something is in progress, like a future started with
+/// [`spawn_future`](crate::ext_event::spawn_future).
+///
+/// The spinner turns while it's shown and stops when it's removed. It's paused while the
+/// window doesn't have focus or is hidden, so it doesn't use frames when no one is looking.
+/// The arc has the text color of the view, and it's 24 pixels large unless styled otherwise.
+pub fn spinner() -> Spinner {
+    let id = Id::next();
+    let focused = use_window_focus();
+    let occluded = use_window_occluded();
+    create_effect(move |_| {
+        let running = focused.get() && !occluded.get();
+        id.update_state(SpinnerState::Running(running), false);
+    });
+    Spinner {
+        data: ViewData::new(id),
+        color: None,
+        running: focused.get_untracked() && !occluded.get_untracked(),
+        frame_requested: false,
+        turn: 0.0,
+        last_frame: None,
+    }
+}
+
+impl View for Spinner {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Spinner".into()
+    }
+
+    fn view_style(&self) -> Option<Style> {
+        Some(Style::new().size(24.0, 24.0))
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
+        if let Ok(state) = state.downcast::<SpinnerState>() {
+            match *state {
+                SpinnerState::Running(running) => {
+                    self.running = running;
+                    if !running {
+                        self.last_frame = None;
+                    }
+                }
+                SpinnerState::Frame => self.frame_requested = false,
+            }
+            cx.app_state_mut().request_paint(self.id());
+        }
+    }
+
+    fn style(&mut self, cx: &mut StyleCx<'_>) {
+        let color = cx.style().get(TextColor);
+        if color != self.color {
+            self.color = color;
+            cx.app_state_mut().request_paint(self.id());
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        let now = Instant::now();
+        if let Some(last_frame) = self.last_frame {
+            self.turn = (self.turn + (now - last_frame).as_secs_f64() / TURN.as_secs_f64()) % 1.0;
+        }
+        // The next frame is only requested when this one is painted, so the spinner stops
+        // when it's no longer painted
+        if self.running {
+            self.last_frame = Some(now);
+            if !self.frame_requested {
+                self.frame_requested = true;
+                let id = self.id();
+                exec_after(FRAME, move |_| {
+                    id.update_state(SpinnerState::Frame, false);
+                });
+            }
+        }
+
+        let size = cx.get_layout(self.id()).unwrap().size;
+        let diameter = size.width.min(size.height) as f64;
+        let width = diameter / 8.0;
+        let radius = (diameter - width) / 2.0;
+        let arc = Arc {
+            center: Point::new(size.width as f64 / 2.0, size.height as f64 / 2.0),
+            radii: Vec2::new(radius, radius),
+            start_angle: self.turn * TAU,
+            sweep_angle: TAU * 0.75,
+            x_rotation: 0.0,
+        };
+        cx.stroke(&arc, self.color.unwrap_or(Color::BLACK), width);
+    }
+}
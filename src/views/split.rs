@@ -0,0 +1,250 @@
+use floem_reactive::{create_rw_signal, RwSignal};
+use kurbo::{Point, Rect};
+
+use super::{container, empty, Container, Decorators, Empty};
+use crate::{
+    context::EventCx,
+    event::Event,
+    id::Id,
+    style::{CursorStyle, Style},
+    style_class,
+    view::{default_event, View, ViewData},
+    EventPropagation,
+};
+
+style_class!(pub SplitDividerClass);
+
+/// Two panes with a divider between them which can be dragged to resize them.
+/// See [`h_split`] and [`v_split`].
+pub struct Split {
+    data: ViewData,
+    first: Container,
+    divider: Empty,
+    second: Container,
+    ratio: RwSignal<f64>,
+    default_ratio: f64,
+    min_sizes: RwSignal<(f64, f64)>,
+    vertical: bool,
+    /// Where the divider was grabbed along the axis while it's dragged.
+    grab_offset: Option<f64>,
+}
+
+/// Two panes side by side with a divider between them. `ratio` is the fraction of the
+/// width, not counting the divider, which `first` takes up.
+///
+/// Dragging the divider updates `ratio`, and double clicking it resets it to the
+/// ratio the split was created with, or the one set with [`Split::default_ratio`].
+/// Splits can be nested to create more panes.
+///
+/// ```rust
+/// use floem::reactive::create_rw_signal;
+/// use floem::views::{h_split, label, v_split};
+///
+/// let sidebar = create_rw_signal(0.2);
+/// let editor = create_rw_signal(0.7);
+/// h_split(
+///     sidebar,
+///     label(|| "Files"),
+///     v_split(editor, label(|| "Editor"), label(|| "Terminal")),
+/// )
+/// .min_sizes(100.0, 200.0);
+/// ```
+pub fn h_split(
+    ratio: RwSignal<f64>,
+    first: impl View + 'static,
+    second: impl View + 'static,
+) -> Split {
+    split(ratio, first, second, false)
+}
+
+/// Two panes above each other with a divider between them. `ratio` is the fraction of
+/// the height, not counting the divider, which `first` takes up.
+///
+/// See [`h_split`] for more.
+pub fn v_split(
+    ratio: RwSignal<f64>,
+    first: impl View + 'static,
+    second: impl View + 'static,
+) -> Split {
+    split(ratio, first, second, true)
+}
+
+fn split(
+    ratio: RwSignal<f64>,
+    first: impl View + 'static,
+    second: impl View + 'static,
+    vertical: bool,
+) -> Split {
+    let min_sizes = create_rw_signal((0.0, 0.0));
+    let pane_style = move |s: Style, ratio: f64, min_size: f64| {
+        let s = s.flex_basis(0.0).flex_grow(ratio.clamp(0.0, 1.0) as f32);
+        if vertical {
+            s.min_height(min_size)
+        } else {
+            s.min_width(min_size)
+        }
+    };
+    let first = container(first).style(move |s| pane_style(s, ratio.get(), min_sizes.get().0));
+    let second =
+        container(second).style(move |s| pane_style(s, 1.0 - ratio.get(), min_sizes.get().1));
+    let cursor = if vertical {
+        CursorStyle::RowResize
+    } else {
+        CursorStyle::ColResize
+    };
+    let divider = empty()
+        .class(SplitDividerClass)
+        .style(move |s| s.flex_shrink(0.0).cursor(cursor));
+
+    Split {
+        data: ViewData::new(Id::next()),
+        first,
+        divider,
+        second,
+        default_ratio: ratio.get_untracked(),
+        ratio,
+        min_sizes,
+        vertical,
+        grab_offset: None,
+    }
+}
+
+impl Split {
+    /// Set the ratio which double clicking the divider resets the split to.
+    pub fn default_ratio(mut self, ratio: f64) -> Self {
+        self.default_ratio = ratio;
+        self
+    }
+
+    /// Set the smallest size along the axis of the split that dragging the divider can
+    /// make the first and the second pane.
+    pub fn min_sizes(self, first: f64, second: f64) -> Self {
+        self.min_sizes.set((first, second));
+        self
+    }
+
+    fn along(&self, point: Point) -> f64 {
+        if self.vertical {
+            point.y
+        } else {
+            point.x
+        }
+    }
+
+    fn divider_rect(&self, cx: &EventCx) -> Rect {
+        cx.get_layout(self.divider.id())
+            .map(|layout| {
+                Rect::from_origin_size(
+                    (layout.location.x as f64, layout.location.y as f64),
+                    (layout.size.width as f64, layout.size.height as f64),
+                )
+            })
+            .unwrap_or_default()
+    }
+
+    /// Move the divider so it starts at `offset` along the axis of the split.
+    fn drag_to(&mut self, cx: &EventCx, offset: f64) {
+        let size = cx.get_size(self.id()).unwrap_or_default();
+        let divider = self.divider_rect(cx).size();
+        let available = if self.vertical {
+            size.height - divider.height
+        } else {
+            size.width - divider.width
+        };
+        if available <= 0.0 {
+            return;
+        }
+        let (min_first, min_second) = self.min_sizes.get_untracked();
+        let max = (available - min_second) / available;
+        let min = (min_first / available).min(max);
+        let ratio = (offset / available).clamp(min.max(0.0), max.clamp(0.0, 1.0));
+        if self.ratio.get_untracked() != ratio {
+            self.ratio.set(ratio);
+        }
+    }
+}
+
+impl View for Split {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn view_style(&self) -> Option<Style> {
+        let style = Style::new().size_full();
+        Some(if self.vertical {
+            style.flex_col()
+        } else {
+            style.flex_row()
+        })
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        if !for_each(&self.first) && !for_each(&self.divider) {
+            for_each(&self.second);
+        }
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        if !for_each(&mut self.first) && !for_each(&mut self.divider) {
+            for_each(&mut self.second);
+        }
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        if !for_each(&mut self.second) && !for_each(&mut self.divider) {
+            for_each(&mut self.first);
+        }
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Split".into()
+    }
+
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        match &event {
+            Event::PointerDown(e) if e.button.is_primary() => {
+                let divider = self.divider_rect(cx);
+                if divider.contains(e.pos) {
+                    if e.count == 2 {
+                        let ratio = self.default_ratio.clamp(0.0, 1.0);
+                        if self.ratio.get_untracked() != ratio {
+                            self.ratio.set(ratio);
+                        }
+                    } else {
+                        self.grab_offset = Some(self.along(e.pos) - self.along(divider.origin()));
+                        cx.update_active(self.divider.id());
+                        // Keep getting pointer events when dragging outside of the split
+                        self.id().capture_pointer();
+                    }
+                    return EventPropagation::Stop;
+                }
+            }
+            Event::PointerMove(e) => {
+                if let Some(grab_offset) = self.grab_offset {
+                    self.drag_to(cx, self.along(e.pos) - grab_offset);
+                    return EventPropagation::Stop;
+                }
+            }
+            Event::PointerUp(_) => {
+                if self.grab_offset.take().is_some() {
+                    return EventPropagation::Stop;
+                }
+            }
+            _ => {}
+        }
+
+        default_event(self, cx, id_path, event)
+    }
+}
@@ -12,6 +12,7 @@ pub struct Stack {
     data: ViewData,
     pub(crate) children: Vec<Box<dyn View>>,
     direction: Option<FlexDirection>,
+    grid: bool,
 }
 
 pub fn stack<VT: ViewTuple + 'static>(children: VT) -> Stack {
@@ -19,6 +20,7 @@ pub fn stack<VT: ViewTuple + 'static>(children: VT) -> Stack {
         data: ViewData::new(Id::next()),
         children: children.into_views(),
         direction: None,
+        grid: false,
     }
 }
 
@@ -28,6 +30,7 @@ pub fn h_stack<VT: ViewTuple + 'static>(children: VT) -> Stack {
         data: ViewData::new(Id::next()),
         children: children.into_views(),
         direction: Some(FlexDirection::Row),
+        grid: false,
     }
 }
 
@@ -37,6 +40,7 @@ pub fn v_stack<VT: ViewTuple + 'static>(children: VT) -> Stack {
         data: ViewData::new(Id::next()),
         children: children.into_views(),
         direction: Some(FlexDirection::Column),
+        grid: false,
     }
 }
 
@@ -51,6 +55,7 @@ where
             .map(|v| -> Box<dyn View> { Box::new(v) })
             .collect(),
         direction,
+        grid: false,
     }
 }
 
@@ -78,6 +83,39 @@ where
     from_iter(iterator, Some(FlexDirection::Column))
 }
 
+/// A stack which lays out its children in a grid. The tracks are set with
+/// [`Style::grid_columns`] and [`Style::grid_rows`].
+///
+/// ```rust
+/// # use floem::{unit::GridTrack, views::{grid, label, Decorators}};
+/// grid((
+///     label(|| "Name"),
+///     label(|| "Floem"),
+///     label(|| "Description"),
+///     label(|| "A native Rust UI library"),
+/// ))
+/// .style(|s| s.grid_columns([GridTrack::Auto, GridTrack::Fr(1.0)]).gap(10.0, 5.0));
+/// ```
+pub fn grid<VT: ViewTuple + 'static>(children: VT) -> Stack {
+    Stack {
+        data: ViewData::new(Id::next()),
+        children: children.into_views(),
+        direction: None,
+        grid: true,
+    }
+}
+
+/// Creates a grid from an iterator of views. See [`grid`].
+pub fn grid_from_iter<V>(iterator: impl IntoIterator<Item = V>) -> Stack
+where
+    V: View + 'static,
+{
+    Stack {
+        grid: true,
+        ..from_iter(iterator, None)
+    }
+}
+
 impl View for Stack {
     fn view_data(&self) -> &ViewData {
         &self.data
@@ -88,6 +126,9 @@ impl View for Stack {
     }
 
     fn view_style(&self) -> Option<crate::style::Style> {
+        if self.grid {
+            return Some(Style::new().grid());
+        }
         self.direction
             .map(|direction| Style::new().flex_direction(direction))
     }
@@ -120,6 +161,9 @@ impl View for Stack {
     }
 
     fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        if self.grid {
+            return "Grid".into();
+        }
         match self.direction {
             Some(FlexDirection::Column) => "Vertical Stack".into(),
             Some(FlexDirection::Row) => "Horizontal Stack".into(),
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use floem_reactive::create_effect;
 use floem_renderer::{
     usvg::Tree,
@@ -5,19 +7,31 @@ use floem_renderer::{
     Renderer,
 };
 use kurbo::Size;
+use peniko::Color;
 use sha2::{Digest, Sha256};
 
 use crate::{
+    context::StyleCx,
     id::Id,
+    style::TextColor,
     view::{View, ViewData},
 };
 
 pub struct Svg {
     data: ViewData,
+    svg_str: Option<String>,
+    /// The text color of the view, which tints the svg and is what `currentColor` in it
+    /// resolves to.
+    color: Option<Color>,
     svg_tree: Option<Tree>,
     svg_hash: Option<Vec<u8>>,
 }
 
+/// A view rendering the svg source returned by `svg_str`. It's rasterized at the scale of the
+/// window, and again when that changes.
+///
+/// The svg is tinted with the text color of the view, set with
+/// [`Style::color`](crate::style::Style::color), and `currentColor` in it resolves to that color.
 pub fn svg(svg_str: impl Fn() -> String + 'static) -> Svg {
     let id = Id::next();
     create_effect(move |_| {
@@ -26,11 +40,71 @@ pub fn svg(svg_str: impl Fn() -> String + 'static) -> Svg {
     });
     Svg {
         data: ViewData::new(id),
+        svg_str: None,
+        color: None,
         svg_tree: None,
         svg_hash: None,
     }
 }
 
+/// A view rendering the svg file at the path returned by `path`. Nothing is shown if the file
+/// can't be read. See [`svg`] for more.
+pub fn svg_file(path: impl Fn() -> PathBuf + 'static) -> Svg {
+    svg(move || {
+        let path = path();
+        std::fs::read_to_string(&path).unwrap_or_else(|err| {
+            eprintln!("Failed to read the svg {}: {err}", path.display());
+            String::new()
+        })
+    })
+}
+
+impl Svg {
+    fn parse(&mut self) {
+        let Some(svg_str) = self.svg_str.as_deref() else {
+            return;
+        };
+        let text = match self.color {
+            Some(color) => with_current_color(svg_str, color),
+            None => svg_str.to_string(),
+        };
+        self.svg_tree = Tree::from_str(&text, &usvg::Options::default()).ok();
+
+        let mut hasher = Sha256::new();
+        hasher.update(&text);
+        let hash = hasher.finalize().to_vec();
+        self.svg_hash = Some(hash);
+    }
+}
+
+/// Sets the `color` attribute of the root element of the svg, which is what `currentColor`
+/// resolves to, unless it's already set.
+fn with_current_color(svg_str: &str, color: Color) -> String {
+    if !svg_str.contains("currentColor") {
+        return svg_str.to_string();
+    }
+    let Some(start) = svg_str.find("<svg") else {
+        return svg_str.to_string();
+    };
+    let tag_end = svg_str[start..]
+        .find('>')
+        .map_or(svg_str.len(), |end| start + end);
+    let root_tag = &svg_str[start..tag_end];
+    if root_tag.contains(" color=") {
+        return svg_str.to_string();
+    }
+    let name_end = start + "<svg".len();
+    format!(
+        r#"{} color="rgba({},{},{},{})"{}"#,
+        &svg_str[..name_end],
+        color.r,
+        color.g,
+        color.b,
+        color.a as f64 / 255.0,
+        &svg_str[name_end..]
+    )
+}
+
 impl View for Svg {
     fn view_data(&self) -> &ViewData {
         &self.data
@@ -46,25 +120,27 @@ impl View for Svg {
 
     fn update(&mut self, cx: &mut crate::context::UpdateCx, state: Box<dyn std::any::Any>) {
         if let Ok(state) = state.downcast::<String>() {
-            let text = &*state;
-            self.svg_tree = Tree::from_str(text, &usvg::Options::default()).ok();
-
-            let mut hasher = Sha256::new();
-            hasher.update(text);
-            let hash = hasher.finalize().to_vec();
-            self.svg_hash = Some(hash);
-
+            self.svg_str = Some(*state);
+            self.parse();
             cx.request_layout(self.id());
         }
     }
 
+    fn style(&mut self, cx: &mut StyleCx<'_>) {
+        let color = cx.style().get(TextColor);
+        if color != self.color {
+            self.color = color;
+            self.parse();
+            cx.app_state_mut().request_paint(self.id());
+        }
+    }
+
     fn paint(&mut self, cx: &mut crate::context::PaintCx) {
         if let Some(tree) = self.svg_tree.as_ref() {
             let hash = self.svg_hash.as_ref().unwrap();
             let layout = cx.get_layout(self.id()).unwrap();
             let rect = Size::new(layout.size.width as f64, layout.size.height as f64).to_rect();
-            let color = cx.app_state.get_builtin_style(self.id()).color();
-            cx.draw_svg(floem_renderer::Svg { tree, hash }, rect, color);
+            cx.draw_svg(floem_renderer::Svg { tree, hash }, rect, self.color);
         }
     }
 }
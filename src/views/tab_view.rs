@@ -0,0 +1,390 @@
+use std::rc::Rc;
+
+use floem_reactive::{as_child_of_current_scope, create_effect, create_rw_signal, RwSignal, Scope};
+use taffy::style::Display;
+use winit::keyboard::ModifiersState;
+
+use super::list::Item;
+use super::{container, h_stack_from_iter, scroll, Decorators, Scroll};
+use crate::{
+    context::{StyleCx, UpdateCx},
+    event::{Event, EventListener},
+    id::Id,
+    keyboard::{Key, NamedKey},
+    style::{DisplayProp, Style},
+    style_class,
+    view::{view_children_set_parent_id, View, ViewData},
+    EventPropagation,
+};
+
+style_class!(pub TabViewClass);
+style_class!(pub TabHeaderStripClass);
+style_class!(pub TabHeaderClass);
+style_class!(pub TabContentClass);
+
+type ContentFn = dyn Fn(usize) -> (Box<dyn View>, Scope);
+
+/// A strip of tab headers above the content of the selected tab. See [`tab_view`].
+pub struct TabView {
+    data: ViewData,
+    headers: Scroll,
+    header_ids: Rc<[Id]>,
+    content: TabContent,
+}
+
+/// A strip of headers with the content of the selected tab below it. `tabs` is a list of
+/// headers and functions creating the content of their tab, and `selected` is the index
+/// of the selected tab.
+///
+/// Clicking a header selects its tab. When a header is focused, Left and Right move to
+/// the previous or next tab. Ctrl+Shift+Tab and Ctrl+Tab do the same while any view in the
+/// tab view is focused, unless the view handles them itself. The header strip scrolls when
+/// the headers don't fit.
+///
+/// The content of a tab is only created when it's selected and is dropped when another tab
+/// is selected, unless [`TabView::keep_alive`] is used.
+///
+/// Tabs with different kinds of content can box them:
+///
+/// ```rust
+/// use floem::reactive::create_rw_signal;
+/// use floem::view::View;
+/// use floem::views::{label, tab_view, text_input};
+///
+/// let name = create_rw_signal(String::new());
+/// let tabs: Vec<(_, Box<dyn Fn() -> Box<dyn View>>)> = vec![
+///     (label(|| "About"), Box::new(|| Box::new(label(|| "A tab view")))),
+///     (label(|| "Name"), Box::new(move || Box::new(text_input(name)))),
+/// ];
+/// tab_view(create_rw_signal(0), tabs);
+/// ```
+pub fn tab_view<H, F, V>(
+    selected: RwSignal<usize>,
+    tabs: impl IntoIterator<Item = (H, F)>,
+) -> TabView
+where
+    H: View + 'static,
+    F: Fn() -> V + 'static,
+    V: View + 'static,
+{
+    let (headers, content_fns): (Vec<H>, Vec<F>) = tabs.into_iter().unzip();
+    let count = headers.len();
+
+    let header_ids: Rc<[Id]> = (0..count).map(|_| Id::next()).collect();
+    let select = {
+        let header_ids = header_ids.clone();
+        move |index: usize| {
+            if selected.get_untracked() != index {
+                selected.set(index);
+            }
+            header_ids[index].request_focus();
+        }
+    };
+
+    let step = move |index: usize, backwards: bool| {
+        if backwards {
+            (index + count - 1) % count
+        } else {
+            (index + 1) % count
+        }
+    };
+
+    let selection = selection_signal(selected);
+    let headers = headers.into_iter().enumerate().map(|(index, header)| {
+        let select = select.clone();
+        let select_key = select.clone();
+        Item {
+            data: ViewData::new(header_ids[index]),
+            index,
+            selection,
+            child: Box::new(container(header).class(TabHeaderClass)),
+        }
+        .keyboard_navigatable()
+        .on_click_stop(move |_| select(index))
+        .on_event(EventListener::KeyDown, move |e| {
+            let Event::KeyDown(key_event) = e else {
+                return EventPropagation::Continue;
+            };
+            if key_event.modifiers.contains(ModifiersState::CONTROL) {
+                return EventPropagation::Continue;
+            }
+            let backwards = match key_event.key.logical_key {
+                Key::Named(NamedKey::ArrowLeft) => true,
+                Key::Named(NamedKey::ArrowRight) => false,
+                _ => return EventPropagation::Continue,
+            };
+            select_key(step(index, backwards));
+            EventPropagation::Stop
+        })
+    });
+
+    let content_fns: Vec<Box<dyn Fn() -> Box<dyn View>>> = content_fns
+        .into_iter()
+        .map(|content_fn| Box::new(move || Box::new(content_fn()) as Box<dyn View>) as Box<_>)
+        .collect();
+    let content_fn = Box::new(as_child_of_current_scope(move |index: usize| {
+        content_fns[index]()
+    }));
+
+    let id = Id::next();
+    create_effect(move |_| {
+        let index = selected.get();
+        id.update_state(index, false);
+    });
+
+    let active = selected.get_untracked();
+    TabView {
+        data: ViewData::new(id),
+        headers: scroll(h_stack_from_iter(headers)).class(TabHeaderStripClass),
+        header_ids,
+        content: TabContent::new(count, active, content_fn),
+    }
+    .class(TabViewClass)
+    .on_event(EventListener::KeyDown, move |e| {
+        let Event::KeyDown(key_event) = e else {
+            return EventPropagation::Continue;
+        };
+        let modifiers = key_event.modifiers;
+        if count == 0
+            || key_event.key.logical_key != Key::Named(NamedKey::Tab)
+            || !modifiers.contains(ModifiersState::CONTROL)
+        {
+            return EventPropagation::Continue;
+        }
+        let backwards = modifiers.contains(ModifiersState::SHIFT);
+        select(step(selected.get_untracked(), backwards));
+        EventPropagation::Stop
+    })
+}
+
+/// The selected index as an `Option`, which is what `Item` marks its child as selected by.
+fn selection_signal(selected: RwSignal<usize>) -> RwSignal<Option<usize>> {
+    let selection = create_rw_signal(Some(selected.get_untracked()));
+    create_effect(move |_| {
+        let index = selected.get();
+        if selection.get_untracked() != Some(index) {
+            selection.set(Some(index));
+        }
+    });
+    selection
+}
+
+impl TabView {
+    /// Keep the content of tabs alive after they have been shown, instead of dropping it
+    /// when another tab is selected. Inactive content is hidden and isn't laid out.
+    pub fn keep_alive(mut self) -> Self {
+        self.content.keep_alive = true;
+        self
+    }
+}
+
+impl View for TabView {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn view_style(&self) -> Option<Style> {
+        Some(Style::new().flex_col())
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        if !for_each(&self.headers) {
+            for_each(&self.content);
+        }
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        if !for_each(&mut self.headers) {
+            for_each(&mut self.content);
+        }
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        if !for_each(&mut self.content) {
+            for_each(&mut self.headers);
+        }
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Tab View".into()
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
+        if let Ok(index) = state.downcast::<usize>() {
+            cx.app_state_mut()
+                .request_style_recursive(self.headers.id());
+            if let Some(header) = self.header_ids.get(*index) {
+                header.scroll_to(None);
+            }
+            self.content.select(cx, *index);
+        }
+    }
+}
+
+/// The content of the tabs which have been created.
+struct TabContent {
+    data: ViewData,
+    active: usize,
+    keep_alive: bool,
+    children: Vec<Option<(Box<dyn View>, Scope)>>,
+    content_fn: Box<ContentFn>,
+}
+
+impl TabContent {
+    fn new(count: usize, active: usize, content_fn: Box<ContentFn>) -> Self {
+        let mut content = Self {
+            data: ViewData::new(Id::next()),
+            active,
+            keep_alive: false,
+            children: (0..count).map(|_| None).collect(),
+            content_fn,
+        };
+        content.create_active();
+        content.class(TabContentClass)
+    }
+
+    fn create_active(&mut self) -> bool {
+        match self.children.get_mut(self.active) {
+            Some(child @ None) => {
+                *child = Some((self.content_fn)(self.active));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn select(&mut self, cx: &mut UpdateCx, index: usize) {
+        if index == self.active {
+            return;
+        }
+        if !self.keep_alive {
+            if let Some((mut child, scope)) =
+                self.children.get_mut(self.active).and_then(Option::take)
+            {
+                cx.app_state_mut().remove_view(&mut child);
+                scope.dispose();
+            }
+        }
+        self.active = index;
+        if self.create_active() {
+            let child = &self.children[index].as_ref().unwrap().0;
+            child.id().set_parent(self.id());
+            view_children_set_parent_id(&**child);
+        }
+        cx.request_all(self.id());
+    }
+}
+
+impl View for TabContent {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn view_style(&self) -> Option<Style> {
+        Some(Style::new().flex_grow(1.0))
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for child in self.children.iter().flatten() {
+            if for_each(&child.0) {
+                break;
+            }
+        }
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for child in self.children.iter_mut().flatten() {
+            if for_each(&mut child.0) {
+                break;
+            }
+        }
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for child in self.children.iter_mut().rev().flatten() {
+            if for_each(&mut child.0) {
+                break;
+            }
+        }
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Tab Content".into()
+    }
+
+    fn style(&mut self, cx: &mut StyleCx<'_>) {
+        for (i, child) in self
+            .children
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, child)| child.as_mut().map(|child| (i, &mut child.0)))
+        {
+            cx.style_view(child);
+            let child_view = cx.app_state_mut().view_state(child.id());
+            child_view.combined_style = child_view.combined_style.clone().set(
+                DisplayProp,
+                if i != self.active {
+                    // set display to none for non active child
+                    Display::None
+                } else {
+                    Display::Flex
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use floem_reactive::create_rw_signal;
+    use kurbo::Size;
+
+    use super::tab_view;
+    use crate::{
+        keyboard::{Key, ModifiersState, NamedKey},
+        views::{empty, Decorators},
+        window::{HeadlessWindow, WindowEvent},
+    };
+
+    #[test]
+    fn control_tab_cycles_tabs_while_their_content_is_focused() {
+        let selected = create_rw_signal(0);
+        let tabs = (0..3).map(|_| {
+            (empty(), || {
+                let content = empty().keyboard_navigatable();
+                content.id().request_focus();
+                content
+            })
+        });
+        let mut window = HeadlessWindow::new(
+            move |_| tab_view(selected, tabs),
+            Size::new(200.0, 200.0),
+            1.0,
+        );
+        window.window_event(WindowEvent::ModifiersChanged(
+            ModifiersState::CONTROL.into(),
+        ));
+        window.simulate_key(Key::Named(NamedKey::Tab));
+        assert_eq!(selected.get_untracked(), 1);
+
+        let modifiers = ModifiersState::CONTROL | ModifiersState::SHIFT;
+        window.window_event(WindowEvent::ModifiersChanged(modifiers.into()));
+        window.simulate_key(Key::Named(NamedKey::Tab));
+        window.simulate_key(Key::Named(NamedKey::Tab));
+        assert_eq!(selected.get_untracked(), 2);
+    }
+}
@@ -0,0 +1,636 @@
+use std::{
+    any::Any,
+    ops::Range,
+    time::{Duration, Instant},
+};
+
+use floem_reactive::{create_effect, RwSignal};
+use floem_renderer::{
+    cosmic_text::{Attrs, AttrsList, TextLayout},
+    Renderer,
+};
+use kurbo::{Point, Rect, Vec2};
+use peniko::Color;
+use taffy::prelude::Node;
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{label::text_selection_rects, text_input::get_dbl_click_selection, Decorators};
+use crate::{
+    accessibility::Role,
+    action::{exec_after, set_ime_allowed, set_ime_cursor_area},
+    context::{EventCx, UpdateCx},
+    event::{Event, EventListener},
+    font::font_families,
+    id::Id,
+    keyboard::{Key, KeyEvent, ModifiersState, NamedKey},
+    prop_extracter,
+    style::{CursorColor, FontProps, LineHeight, Style, TextColor},
+    style_class,
+    unit::PxPct,
+    view::{View, ViewData},
+    Clipboard, EventPropagation,
+};
+
+style_class!(pub TextAreaClass);
+
+prop_extracter! {
+    Extracter {
+        color: TextColor,
+        cursor_color: CursorColor,
+        line_height: LineHeight,
+    }
+}
+
+const DEFAULT_FONT_SIZE: f32 = 14.0;
+const CURSOR_BLINK_INTERVAL_MS: u64 = 500;
+
+/// A multi-line text editor. See [`text_area`].
+pub struct TextArea {
+    data: ViewData,
+    buffer: RwSignal<String>,
+    /// The text being edited, which is kept in sync with `buffer`.
+    text: String,
+    /// The byte offset of the caret in `text`.
+    cursor: usize,
+    /// The other end of the selection, which goes from here to the caret.
+    anchor: Option<usize>,
+    /// The x position vertical caret movement tries to stay at.
+    preferred_x: Option<f64>,
+    text_layout: TextLayout,
+    text_node: Option<Node>,
+    available_width: f32,
+    font: FontProps,
+    style: Extracter,
+    selecting: bool,
+    /// The IME composition shown at the caret which isn't part of the text yet.
+    preedit: Option<Preedit>,
+    /// The caret rect in window coordinates which was last reported to the IME.
+    ime_cursor_area: Option<Rect>,
+    scroll_to_cursor: bool,
+    last_cursor_action_on: Instant,
+}
+
+struct Preedit {
+    text: String,
+    /// The byte range of the IME cursor in `text`. `None` means it's hidden.
+    cursor: Option<(usize, usize)>,
+}
+
+/// A multi-line text editor bound to `buffer`. Lines wrap at the width of the view.
+///
+/// The arrow keys, Home and End move the caret, and select text along with Shift.
+/// Backspace and Delete remove text, Enter inserts a new line, and text can be selected,
+/// cut, copied and pasted with the pointer and the usual shortcuts. IME composition is
+/// shown inline at the caret.
+///
+/// ```rust
+/// use floem::reactive::create_rw_signal;
+/// use floem::views::{text_area, Decorators};
+///
+/// let notes = create_rw_signal(String::new());
+/// text_area(notes).style(|s| s.width(300.0).height(200.0));
+/// ```
+pub fn text_area(buffer: RwSignal<String>) -> TextArea {
+    let id = Id::next();
+    create_effect(move |_| {
+        let text = buffer.get();
+        id.update_state(text, false);
+    });
+
+    TextArea {
+        data: ViewData::new(id),
+        buffer,
+        text: buffer.get_untracked(),
+        cursor: 0,
+        anchor: None,
+        preferred_x: None,
+        text_layout: TextLayout::new(),
+        text_node: None,
+        available_width: 0.0,
+        font: FontProps::default(),
+        style: Default::default(),
+        selecting: false,
+        preedit: None,
+        ime_cursor_area: None,
+        scroll_to_cursor: false,
+        last_cursor_action_on: Instant::now(),
+    }
+    .class(TextAreaClass)
+    .keyboard_navigatable()
+    .role(Role::MultilineTextInput)
+    .on_event_cont(EventListener::FocusGained, |_| set_ime_allowed(true))
+    .on_event_cont(EventListener::FocusLost, |_| set_ime_allowed(false))
+}
+
+impl TextArea {
+    fn attrs_list(&self) -> AttrsList {
+        let mut attrs = Attrs::new()
+            .color(self.style.color().unwrap_or(Color::BLACK))
+            .font_size(self.font.size().unwrap_or(DEFAULT_FONT_SIZE));
+        if let Some(font_style) = self.font.style() {
+            attrs = attrs.style(font_style);
+        }
+        let font_family = font_families(self.font.family().as_deref());
+        if let Some(font_family) = font_family.as_ref() {
+            attrs = attrs.family(font_family);
+        }
+        if let Some(font_weight) = self.font.weight() {
+            attrs = attrs.weight(font_weight);
+        }
+        if let Some(line_height) = self.style.line_height() {
+            attrs = attrs.line_height(line_height);
+        }
+        AttrsList::new(attrs)
+    }
+
+    /// The text which is shown, which is the text with the IME composition inserted at the
+    /// caret.
+    fn display_text(&self) -> String {
+        let mut text = self.text.clone();
+        if let Some(preedit) = &self.preedit {
+            text.insert_str(self.cursor, &preedit.text);
+        }
+        text
+    }
+
+    /// The position of the caret in [`Self::display_text`].
+    fn display_cursor(&self) -> usize {
+        match &self.preedit {
+            Some(preedit) => {
+                self.cursor
+                    + preedit
+                        .cursor
+                        .map_or(preedit.text.len(), |(start, _)| start)
+            }
+            None => self.cursor,
+        }
+    }
+
+    fn update_text_layout(&mut self) {
+        let mut text_layout = TextLayout::new();
+        // An empty layout has no lines, so there would be no caret position
+        let text = self.display_text();
+        text_layout.set_text(if text.is_empty() { " " } else { &text }, self.attrs_list());
+        if self.available_width > 0.0 {
+            text_layout.set_size(self.available_width, f32::MAX);
+        }
+        self.text_layout = text_layout;
+    }
+
+    fn text_origin(&self, cx: &EventCx) -> Point {
+        self.text_node
+            .and_then(|node| cx.app_state.taffy.layout(node).ok())
+            .map(|layout| Point::new(layout.location.x as f64, layout.location.y as f64))
+            .unwrap_or_default()
+    }
+
+    /// The offset in the text closest to `point`, relative to the text.
+    fn index_at(&self, point: Point) -> usize {
+        snap_to_char(&self.text, self.text_layout.hit_point(point).index)
+    }
+
+    /// The caret rect relative to the text.
+    fn cursor_rect(&self) -> Rect {
+        let position = self.text_layout.hit_position(self.display_cursor());
+        Rect::new(
+            position.point.x,
+            position.point.y - position.glyph_ascent,
+            position.point.x + 1.0,
+            position.point.y + position.glyph_descent,
+        )
+    }
+
+    fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.anchor?;
+        (anchor != self.cursor).then(|| anchor.min(self.cursor)..anchor.max(self.cursor))
+    }
+
+    fn selected_text(&self) -> Option<&str> {
+        self.text.get(self.selection()?)
+    }
+
+    /// Move the caret to `index`, extending the selection if `select` is set.
+    fn move_to(&mut self, index: usize, select: bool) {
+        if select {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = index;
+    }
+
+    /// Replace the selection with `text`, or insert it at the caret.
+    fn insert(&mut self, text: &str) {
+        let range = self.selection().unwrap_or(self.cursor..self.cursor);
+        self.text.replace_range(range.clone(), text);
+        self.cursor = range.start + text.len();
+        self.anchor = None;
+        self.buffer.set(self.text.clone());
+    }
+
+    fn delete(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        self.text.replace_range(range.clone(), "");
+        self.cursor = range.start;
+        self.anchor = None;
+        self.buffer.set(self.text.clone());
+    }
+
+    /// The offset at the start or end of the visual line with the caret.
+    fn line_edge(&self, end: bool) -> usize {
+        let position = self.text_layout.hit_position(self.display_cursor());
+        let x = if end {
+            self.text_layout.size().width + 1.0
+        } else {
+            0.0
+        };
+        self.index_at(Point::new(x, position.point.y))
+    }
+
+    /// The offset on the visual line above or below the caret, closest to where the caret was
+    /// horizontally.
+    fn vertical(&mut self, down: bool) -> usize {
+        let position = self.text_layout.hit_position(self.cursor);
+        let x = *self.preferred_x.get_or_insert(position.point.x);
+        let y = if down {
+            position.point.y + position.glyph_descent + 1.0
+        } else {
+            position.point.y - position.glyph_ascent - 1.0
+        };
+        if y < 0.0 {
+            return 0;
+        }
+        if y > self.text_layout.size().height {
+            return self.text.len();
+        }
+        self.index_at(Point::new(x, y))
+    }
+
+    fn handle_key_down(&mut self, event: &KeyEvent) -> bool {
+        let shift = event.modifiers.contains(ModifiersState::SHIFT);
+        #[cfg(target_os = "macos")]
+        let command = event.modifiers.contains(ModifiersState::SUPER);
+        #[cfg(not(target_os = "macos"))]
+        let command = event.modifiers.contains(ModifiersState::CONTROL);
+
+        let vertical = matches!(
+            event.key.logical_key,
+            Key::Named(NamedKey::ArrowUp | NamedKey::ArrowDown)
+        );
+        if !vertical {
+            self.preferred_x = None;
+        }
+
+        match &event.key.logical_key {
+            Key::Character(ch) if command => match ch.to_lowercase().as_str() {
+                "a" => {
+                    self.anchor = Some(0);
+                    self.cursor = self.text.len();
+                }
+                "c" => {
+                    if let Some(text) = self.selected_text() {
+                        let _ = Clipboard::set_text(text);
+                    }
+                }
+                "x" => {
+                    if let Some(selection) = self.selection() {
+                        let _ = Clipboard::set_text(&self.text[selection.clone()]);
+                        self.delete(selection);
+                    }
+                }
+                "v" => {
+                    if let Ok(text) = Clipboard::get_text() {
+                        self.insert(&text);
+                    }
+                }
+                _ => return false,
+            },
+            Key::Character(ch) => self.insert(ch),
+            Key::Named(NamedKey::Space) => self.insert(" "),
+            Key::Named(NamedKey::Enter) => self.insert("\n"),
+            Key::Named(NamedKey::Backspace) => {
+                let range = self
+                    .selection()
+                    .unwrap_or_else(|| prev_boundary(&self.text, self.cursor)..self.cursor);
+                self.delete(range);
+            }
+            Key::Named(NamedKey::Delete) => {
+                let range = self
+                    .selection()
+                    .unwrap_or_else(|| self.cursor..next_boundary(&self.text, self.cursor));
+                self.delete(range);
+            }
+            Key::Named(NamedKey::ArrowLeft) => {
+                let index = match self.selection() {
+                    Some(selection) if !shift => selection.start,
+                    _ => prev_boundary(&self.text, self.cursor),
+                };
+                self.move_to(index, shift);
+            }
+            Key::Named(NamedKey::ArrowRight) => {
+                let index = match self.selection() {
+                    Some(selection) if !shift => selection.end,
+                    _ => next_boundary(&self.text, self.cursor),
+                };
+                self.move_to(index, shift);
+            }
+            Key::Named(NamedKey::ArrowUp) => {
+                let index = self.vertical(false);
+                self.move_to(index, shift);
+            }
+            Key::Named(NamedKey::ArrowDown) => {
+                let index = self.vertical(true);
+                self.move_to(index, shift);
+            }
+            Key::Named(NamedKey::Home) => {
+                let index = if command { 0 } else { self.line_edge(false) };
+                self.move_to(index, shift);
+            }
+            Key::Named(NamedKey::End) => {
+                let index = if command {
+                    self.text.len()
+                } else {
+                    self.line_edge(true)
+                };
+                self.move_to(index, shift);
+            }
+            Key::Named(NamedKey::Escape) => {
+                // Through an update message, so the view gets `FocusLost`
+                self.id().clear_focus();
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// The start of the character at or before `index` in `text`.
+fn snap_to_char(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// The start of the grapheme before `index` in `text`.
+fn prev_boundary(text: &str, index: usize) -> usize {
+    text[..index]
+        .grapheme_indices(true)
+        .next_back()
+        .map_or(0, |(i, _)| i)
+}
+
+/// The end of the grapheme after `index` in `text`.
+fn next_boundary(text: &str, index: usize) -> usize {
+    text[index..]
+        .graphemes(true)
+        .next()
+        .map_or(index, |grapheme| index + grapheme.len())
+}
+
+/// The line of `text` around `index`.
+fn line_range(text: &str, index: usize) -> Range<usize> {
+    let start = text[..index].rfind('\n').map_or(0, |i| i + 1);
+    let end = text[index..].find('\n').map_or(text.len(), |i| index + i);
+    start..end
+}
+
+impl View for TextArea {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        format!("TextArea: {:?}", self.text).into()
+    }
+
+    fn accessible_text(&self) -> Option<String> {
+        Some(self.text.clone())
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn Any>) {
+        if let Ok(text) = state.downcast::<String>() {
+            if *text != self.text {
+                self.text = *text;
+                self.cursor = snap_to_char(&self.text, self.cursor);
+                self.anchor = self.anchor.map(|anchor| snap_to_char(&self.text, anchor));
+                self.preedit = None;
+                self.update_text_layout();
+                cx.request_layout(self.id());
+            }
+        }
+    }
+
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        _id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        let handled = match &event {
+            Event::PointerDown(e) if e.button.is_primary() => {
+                let index = self.index_at(e.pos - self.text_origin(cx).to_vec2());
+                match e.count {
+                    2 => {
+                        let word = get_dbl_click_selection(index, &self.text);
+                        self.anchor = Some(word.start);
+                        self.cursor = word.end;
+                    }
+                    3 => {
+                        let line = line_range(&self.text, index);
+                        self.anchor = Some(line.start);
+                        self.cursor = line.end;
+                    }
+                    _ => self.move_to(index, e.modifiers.contains(ModifiersState::SHIFT)),
+                }
+                self.selecting = e.count < 2;
+                self.preferred_x = None;
+                self.id().request_focus();
+                // Keep getting pointer events when dragging outside of the text area
+                self.id().capture_pointer();
+                true
+            }
+            Event::PointerMove(e) if self.selecting => {
+                let index = self.index_at(e.pos - self.text_origin(cx).to_vec2());
+                self.move_to(index, true);
+                true
+            }
+            Event::PointerUp(_) if self.selecting => {
+                self.selecting = false;
+                false
+            }
+            Event::KeyDown(e) if self.preedit.is_none() => self.handle_key_down(e),
+            // Key presses are consumed by the IME while composing, so they don't reach the
+            // views below either
+            Event::KeyDown(_) => return EventPropagation::Stop,
+            Event::ImePreedit { text, cursor } => {
+                if let Some(selection) = self.selection() {
+                    self.delete(selection);
+                }
+                self.preedit = (!text.is_empty()).then(|| Preedit {
+                    text: text.clone(),
+                    cursor: *cursor,
+                });
+                true
+            }
+            Event::ImeCommit(text) => {
+                self.preedit = None;
+                self.insert(text);
+                true
+            }
+            Event::ImeDisabled => {
+                self.preedit = None;
+                true
+            }
+            Event::FocusLost => {
+                self.anchor = None;
+                self.preedit = None;
+                self.selecting = false;
+                self.ime_cursor_area = None;
+                true
+            }
+            _ => false,
+        };
+
+        if handled {
+            self.update_text_layout();
+            self.scroll_to_cursor = true;
+            self.last_cursor_action_on = Instant::now();
+            cx.app_state_mut().request_layout(self.id());
+            if matches!(event, Event::KeyDown(_) | Event::PointerDown(_)) {
+                return EventPropagation::Stop;
+            }
+        }
+        EventPropagation::Continue
+    }
+
+    fn style(&mut self, cx: &mut crate::context::StyleCx<'_>) {
+        if self.font.read(cx) | self.style.read(cx) {
+            self.update_text_layout();
+            cx.app_state_mut().request_layout(self.id());
+        }
+    }
+
+    fn layout(&mut self, cx: &mut crate::context::LayoutCx) -> taffy::prelude::Node {
+        cx.layout_node(self.id(), true, |cx| {
+            if self.text_node.is_none() {
+                self.text_node = Some(
+                    cx.app_state_mut()
+                        .taffy
+                        .new_leaf(taffy::style::Style::DEFAULT)
+                        .unwrap(),
+                );
+            }
+            let text_node = self.text_node.unwrap();
+
+            let size = self.text_layout.size();
+            let style = Style::new()
+                .width(size.width as f32)
+                .height(size.height as f32)
+                .to_taffy_style();
+            let _ = cx.app_state_mut().taffy.set_style(text_node, style);
+            vec![text_node]
+        })
+    }
+
+    fn compute_layout(&mut self, cx: &mut crate::context::ComputeLayoutCx) -> Option<Rect> {
+        let layout = cx.get_layout(self.id()).unwrap();
+        let style = cx.app_state_mut().get_builtin_style(self.id());
+        let padding_left = match style.padding_left() {
+            PxPct::Px(padding) => padding as f32,
+            PxPct::Pct(pct) => pct as f32 / 100.0 * layout.size.width,
+        };
+        let padding_right = match style.padding_right() {
+            PxPct::Px(padding) => padding as f32,
+            PxPct::Pct(pct) => pct as f32 / 100.0 * layout.size.width,
+        };
+        let available_width = (layout.size.width - padding_left - padding_right).max(1.0);
+        if self.available_width != available_width {
+            self.available_width = available_width;
+            self.update_text_layout();
+            cx.app_state_mut().request_layout(self.id());
+        }
+
+        if cx.app_state.is_focused(&self.id()) {
+            let text_location = cx.app_state.taffy.layout(self.text_node?).ok()?.location;
+            let cursor_rect =
+                self.cursor_rect() + Vec2::new(text_location.x as f64, text_location.y as f64);
+            if std::mem::take(&mut self.scroll_to_cursor) {
+                self.id().scroll_to(Some(cursor_rect));
+            }
+            // Keep the IME candidate window next to the caret
+            let cursor_area = cursor_rect + cx.window_origin.to_vec2();
+            if self.ime_cursor_area != Some(cursor_area) {
+                self.ime_cursor_area = Some(cursor_area);
+                set_ime_cursor_area(cursor_area.origin(), cursor_area.size());
+            }
+        }
+
+        None
+    }
+
+    fn paint(&mut self, cx: &mut crate::context::PaintCx) {
+        let text_node = self.text_node.unwrap();
+        let location = cx.app_state.taffy.layout(text_node).unwrap().location;
+        let origin = Point::new(location.x as f64, location.y as f64);
+        let is_focused = cx.app_state.is_focused(&self.id());
+        let cursor_color = self.style.cursor_color();
+
+        if is_focused {
+            if let Some(selection) = self.selection() {
+                let color = cursor_color.unwrap_or(Color::rgba8(0, 0, 0, 60));
+                for rect in text_selection_rects(&self.text_layout, selection) {
+                    cx.fill(&(rect + origin.to_vec2()), color, 0.0);
+                }
+            }
+        }
+
+        cx.draw_text(&self.text_layout, origin);
+
+        if let Some(preedit) = &self.preedit {
+            let start = self.text_layout.hit_position(self.cursor);
+            let end = self
+                .text_layout
+                .hit_position(self.cursor + preedit.text.len());
+            let y = start.point.y + start.glyph_descent;
+            let underline = Rect::new(start.point.x, y - 1.0, end.point.x, y) + origin.to_vec2();
+            cx.fill(&underline, self.style.color().unwrap_or(Color::BLACK), 0.0);
+        }
+
+        if !is_focused {
+            return;
+        }
+
+        let is_cursor_visible = self.selection().is_none()
+            && self
+                .preedit
+                .as_ref()
+                .map_or(true, |preedit| preedit.cursor.is_some())
+            && (self.last_cursor_action_on.elapsed().as_millis()
+                / CURSOR_BLINK_INTERVAL_MS as u128)
+                % 2
+                == 0;
+        if is_cursor_visible {
+            cx.fill(
+                &(self.cursor_rect() + origin.to_vec2()),
+                cursor_color.unwrap_or(Color::BLACK),
+                0.0,
+            );
+        }
+
+        let id = self.id();
+        exec_after(
+            Duration::from_millis(CURSOR_BLINK_INTERVAL_MS),
+            Box::new(move |_| {
+                id.request_paint();
+            }),
+        );
+    }
+}
@@ -1,4 +1,5 @@
-use crate::action::exec_after;
+use crate::accessibility::Role;
+use crate::action::{exec_after, set_ime_allowed, set_ime_cursor_area};
 use crate::event::EventListener;
 use crate::keyboard::{self, KeyEvent};
 use crate::pointer::{PointerButton, PointerInputEvent};
@@ -20,16 +21,19 @@ use crate::{peniko::Color, style::Style, view::View};
 
 use std::{
     any::Any,
+    cell::Cell,
     ops::Range,
+    rc::Rc,
     time::{Duration, Instant},
 };
 
-use crate::cosmic_text::{Attrs, AttrsList, FamilyOwned, TextLayout};
+use crate::cosmic_text::{Attrs, AttrsList, TextLayout};
 use kurbo::{Point, Rect, Size};
 
 use crate::{
     context::{EventCx, UpdateCx},
     event::Event,
+    font::font_families,
     id::Id,
 };
 
@@ -94,6 +98,22 @@ pub struct TextInput {
     cursor_width: f64, // TODO: make this configurable
     is_focused: bool,
     last_cursor_action_on: Instant,
+    // The IME composition that is shown inline at the cursor but is not yet part of the buffer
+    preedit: Option<Preedit>,
+    // The caret rect in window coordinates that was last reported to the IME
+    ime_cursor_area: Option<Rect>,
+    // Whether the text is masked, which also keeps it from being copied and disables the IME
+    pub(crate) password: bool,
+    // Whether the IME is enabled when the input is focused, which is shared with the focus listener
+    pub(crate) ime_allowed: Rc<Cell<bool>>,
+    // Consulted with the text an insertion would result in, which is only made if it returns true
+    pub(crate) validate: Option<Box<dyn Fn(&str) -> bool>>,
+}
+
+struct Preedit {
+    text: String,
+    // Byte range of the IME cursor within `text`. `None` means the cursor should be hidden
+    cursor: Option<(usize, usize)>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -109,10 +129,14 @@ pub enum Direction {
     Right,
 }
 
+/// The character shown instead of each character of a password.
+const PASSWORD_MASK: char = '•';
+
 /// Text Input View
 pub fn text_input(buffer: RwSignal<String>) -> TextInput {
     let id = Id::next();
     let is_focused = create_rw_signal(false);
+    let ime_allowed = Rc::new(Cell::new(true));
 
     {
         create_effect(move |_| {
@@ -146,13 +170,21 @@ pub fn text_input(buffer: RwSignal<String>) -> TextInput {
         height: 0.0,
         is_focused: false,
         last_cursor_action_on: Instant::now(),
+        preedit: None,
+        ime_cursor_area: None,
+        password: false,
+        ime_allowed: ime_allowed.clone(),
+        validate: None,
     }
     .keyboard_navigatable()
+    .role(Role::TextInput)
     .on_event_stop(EventListener::FocusGained, move |_| {
         is_focused.set(true);
+        set_ime_allowed(ime_allowed.get());
     })
     .on_event_stop(EventListener::FocusLost, move |_| {
         is_focused.set(false);
+        set_ime_allowed(false);
     })
 }
 
@@ -222,6 +254,11 @@ const APPROX_VISIBLE_CHARS_TARGET: f32 = 10.0;
 
 impl TextInput {
     fn move_cursor(&mut self, move_kind: Movement, direction: Direction) -> bool {
+        // Moving by words would reveal where the words of a password are
+        let move_kind = match move_kind {
+            Movement::Word if self.password => Movement::Line,
+            move_kind => move_kind,
+        };
         match (move_kind, direction) {
             (Movement::Glyph, Direction::Left) => {
                 let untracked_buffer = self.buffer.get_untracked();
@@ -290,7 +327,7 @@ impl TextInput {
     fn clip_text(&mut self, node_layout: &Layout) {
         let virt_text = self.text_buf.as_ref().unwrap();
         let node_width = node_layout.size.width as f64;
-        let cursor_text_loc = Cursor::new(0, self.cursor_glyph_idx);
+        let cursor_text_loc = Cursor::new(0, self.display_cursor_idx());
         let layout_cursor = virt_text.layout_cursor(&cursor_text_loc);
         let cursor_glyph_pos = virt_text.hit_position(layout_cursor.glyph);
         let cursor_x = cursor_glyph_pos.point.x;
@@ -317,12 +354,10 @@ impl TextInput {
             .index;
 
         let new_text = self
-            .buffer
-            .get()
-            .chars()
-            .skip(clip_start)
-            .take(clip_end - clip_start)
-            .collect();
+            .display_text()
+            .get(clip_start..clip_end)
+            .unwrap_or_default()
+            .to_string();
 
         self.cursor_x -= clip_start_x;
         self.clip_start_idx = clip_start;
@@ -359,6 +394,11 @@ impl TextInput {
     }
 
     fn handle_double_click(&mut self, pos_x: f64, pos_y: f64, cx: &mut EventCx) {
+        // Selecting a word would reveal where the words of a password are
+        if self.password {
+            self.select_all(cx);
+            return;
+        }
         let clicked_glyph_idx = self.get_box_position(pos_x, pos_y, cx);
 
         self.buffer.with_untracked(|buff| {
@@ -380,7 +420,8 @@ impl TextInput {
             PxPct::Px(padding) => padding as f32,
             PxPct::Pct(pct) => pct as f32 * layout.size.width,
         };
-        self.text_buf
+        let index = self
+            .text_buf
             .as_ref()
             .unwrap()
             .hit_point(Point::new(
@@ -389,7 +430,8 @@ impl TextInput {
                 // slightly below the text
                 pos_y - padding_top as f64,
             ))
-            .index
+            .index;
+        self.buffer_idx(index)
     }
 
     fn get_selection_rect(&self, node_layout: &Layout, left_padding: f64) -> Rect {
@@ -402,12 +444,19 @@ impl TextInput {
         let virtual_text = self.text_buf.as_ref().unwrap();
         let text_height = virtual_text.size().height;
 
-        let selection_start_x =
-            virtual_text.hit_position(selection.start).point.x - self.clip_start_x;
+        let selection_start_x = virtual_text
+            .hit_position(self.display_idx(selection.start))
+            .point
+            .x
+            - self.clip_start_x;
         let selection_start_x = selection_start_x.max(node_layout.location.x as f64 - left_padding);
 
-        let selection_end_x =
-            virtual_text.hit_position(selection.end).point.x + left_padding - self.clip_start_x;
+        let selection_end_x = virtual_text
+            .hit_position(self.display_idx(selection.end))
+            .point
+            .x
+            + left_padding
+            - self.clip_start_x;
         let selection_end_x =
             selection_end_x.min(selection_start_x + self.width as f64 + left_padding);
 
@@ -446,12 +495,119 @@ impl TextInput {
         }
     }
 
+    /// The text that is displayed, which is the buffer with the IME composition inserted at the cursor.
+    /// Passwords are masked instead.
+    fn display_text(&self) -> String {
+        if self.password {
+            return self
+                .buffer
+                .with_untracked(|buff| buff.chars().map(|_| PASSWORD_MASK).collect());
+        }
+        let mut text = self.buffer.get_untracked();
+        if let Some(preedit) = &self.preedit {
+            text.insert_str(self.cursor_glyph_idx, &preedit.text);
+        }
+        text
+    }
+
+    /// The cursor position within [`Self::display_text`].
+    fn display_cursor_idx(&self) -> usize {
+        match &self.preedit {
+            Some(preedit) => {
+                let offset = preedit
+                    .cursor
+                    .map(|(start, _)| start)
+                    .unwrap_or(preedit.text.len());
+                self.cursor_glyph_idx + offset
+            }
+            None => self.display_idx(self.cursor_glyph_idx),
+        }
+    }
+
+    /// The position in [`Self::display_text`] of `idx` in the buffer, ignoring the IME
+    /// composition. This only differs for passwords.
+    fn display_idx(&self, idx: usize) -> usize {
+        if !self.password {
+            return idx;
+        }
+        self.buffer.with_untracked(|buff| {
+            buff.get(..idx).map_or(0, |text| text.chars().count()) * PASSWORD_MASK.len_utf8()
+        })
+    }
+
+    /// The position in the buffer of `idx` in [`Self::display_text`].
+    fn buffer_idx(&self, idx: usize) -> usize {
+        if !self.password {
+            return idx;
+        }
+        self.buffer.with_untracked(|buff| {
+            buff.char_indices()
+                .nth(idx / PASSWORD_MASK.len_utf8())
+                .map_or(buff.len(), |(i, _)| i)
+        })
+    }
+
+    /// The currently selected part of the buffer.
+    fn selected_text(&self) -> Option<String> {
+        let selection = self.selection.clone()?;
+        self.buffer
+            .with_untracked(|buff| buff.get(selection).map(|text| text.to_string()))
+    }
+
+    fn delete_selection(&mut self) {
+        if let Some(selection) = self.selection.take() {
+            self.buffer
+                .update(|buf| replace_range(buf, selection.clone(), None));
+            self.cursor_glyph_idx = selection.start;
+        }
+    }
+
+    fn handle_ime_preedit(&mut self, text: &str, cursor: Option<(usize, usize)>) {
+        if text.is_empty() {
+            self.preedit = None;
+            return;
+        }
+
+        self.delete_selection();
+        self.preedit = Some(Preedit {
+            text: text.to_string(),
+            cursor,
+        });
+    }
+
+    /// The range of the buffer inserted text replaces, which is the selection or the cursor.
+    fn insertion_range(&self) -> Range<usize> {
+        self.selection
+            .clone()
+            .unwrap_or(self.cursor_glyph_idx..self.cursor_glyph_idx)
+    }
+
+    /// Whether the validator accepts the text replacing `range` of the buffer with `text` results in.
+    fn accepts(&self, range: Range<usize>, text: &str) -> bool {
+        let Some(validate) = &self.validate else {
+            return true;
+        };
+        let mut candidate = self.buffer.get_untracked();
+        replace_range(&mut candidate, range, Some(text));
+        validate(&candidate)
+    }
+
+    fn handle_ime_commit(&mut self, text: &str) {
+        self.preedit = None;
+        if !self.accepts(self.insertion_range(), text) {
+            return;
+        }
+        self.delete_selection();
+        self.buffer
+            .update(|buf| buf.insert_str(self.cursor_glyph_idx, text));
+        self.cursor_glyph_idx += text.len();
+    }
+
     fn update_text_layout(&mut self) {
         let mut text_layout = TextLayout::new();
         let attrs_list = self.get_text_attrs();
 
-        self.buffer
-            .with_untracked(|buff| text_layout.set_text(buff, attrs_list.clone()));
+        text_layout.set_text(&self.display_text(), attrs_list.clone());
 
         let glyph_max_size = self.get_font_glyph_max_size();
         self.height = glyph_max_size.height as f32;
@@ -503,10 +659,7 @@ impl TextInput {
         if let Some(font_style) = self.font.style() {
             attrs = attrs.style(font_style);
         }
-        let font_family = self.font.family().as_ref().map(|font_family| {
-            let family: Vec<FamilyOwned> = FamilyOwned::parse_list(font_family).collect();
-            family
-        });
+        let font_family = font_families(self.font.family().as_deref());
         if let Some(font_family) = font_family.as_ref() {
             attrs = attrs.family(font_family);
         }
@@ -550,29 +703,19 @@ impl TextInput {
                 self.select_all(cx);
                 true
             }
+            // Passwords can't be copied
+            TextCommand::Copy | TextCommand::Cut if self.password => true,
             TextCommand::Copy => {
-                if let Some(selection) = &self.selection {
-                    let selection_txt = self
-                        .buffer
-                        .get()
-                        .chars()
-                        .skip(selection.start)
-                        .take(selection.end - selection.start)
-                        .collect();
-                    let _ = Clipboard::set_contents(selection_txt);
+                if let Some(selection_txt) = self.selected_text() {
+                    let _ = Clipboard::set_text(&selection_txt);
                 }
                 true
             }
             TextCommand::Cut => {
                 if let Some(selection) = &self.selection {
-                    let selection_txt = self
-                        .buffer
-                        .get()
-                        .chars()
-                        .skip(selection.start)
-                        .take(selection.end - selection.start)
-                        .collect();
-                    let _ = Clipboard::set_contents(selection_txt);
+                    if let Some(selection_txt) = self.selected_text() {
+                        let _ = Clipboard::set_text(&selection_txt);
+                    }
 
                     self.buffer
                         .update(|buf| replace_range(buf, selection.clone(), None));
@@ -584,13 +727,16 @@ impl TextInput {
                 true
             }
             TextCommand::Paste => {
-                let clipboard_content = match Clipboard::get_contents() {
+                let clipboard_content = match Clipboard::get_text() {
                     Ok(content) => content,
                     Err(_) => return false,
                 };
                 if clipboard_content.is_empty() {
                     return false;
                 }
+                if !self.accepts(self.insertion_range(), &clipboard_content) {
+                    return true;
+                }
 
                 if let Some(selection) = &self.selection {
                     self.buffer.update(|buf| {
@@ -622,6 +768,9 @@ impl TextInput {
                 if handled_modifier_cmd {
                     return true;
                 }
+                if !self.accepts(self.insertion_range(), ch) {
+                    return false;
+                }
 
                 let selection = self.selection.clone();
                 if let Some(selection) = selection {
@@ -641,9 +790,11 @@ impl TextInput {
                         .update(|buf| replace_range(buf, selection.clone(), None));
                     self.cursor_glyph_idx = selection.start;
                     self.selection = None;
-                } else {
+                } else if self.accepts(self.insertion_range(), " ") {
                     self.buffer
                         .update(|buf| buf.insert(self.cursor_glyph_idx, ' '));
+                } else {
+                    return false;
                 }
                 self.move_cursor(Movement::Glyph, Direction::Right)
             }
@@ -837,6 +988,31 @@ impl TextInput {
         cx.draw_text(placeholder_buff, text_start_point);
     }
 
+    fn paint_preedit_underline(
+        &self,
+        preedit: &Preedit,
+        node_layout: &Layout,
+        cx: &mut crate::context::PaintCx,
+    ) {
+        let text_buf = self.text_buf.as_ref().unwrap();
+        let start_x = text_buf.hit_position(self.cursor_glyph_idx).point.x - self.clip_start_x;
+        let end_x = text_buf
+            .hit_position(self.cursor_glyph_idx + preedit.text.len())
+            .point
+            .x
+            - self.clip_start_x;
+
+        let node_location = node_layout.location;
+        let bottom = node_location.y as f64 + self.height as f64;
+        let underline = Rect::new(
+            node_location.x as f64 + start_x.max(0.0),
+            bottom - 1.0,
+            node_location.x as f64 + end_x.min(node_layout.size.width as f64),
+            bottom,
+        );
+        cx.fill(&underline, self.style.color().unwrap_or(Color::BLACK), 0.0);
+    }
+
     fn paint_selection_rect(&self, &node_layout: &Layout, cx: &mut crate::context::PaintCx<'_>) {
         let style = cx.app_state.get_computed_style(self.id());
         let cursor_color = style.get(CursorColor);
@@ -891,7 +1067,7 @@ fn replace_range(buff: &mut String, del_range: Range<usize>, replacement: Option
     buff.push_str(&after_del_range);
 }
 
-fn get_dbl_click_selection(glyph_idx: usize, buffer: &String) -> Range<usize> {
+pub(crate) fn get_dbl_click_selection(glyph_idx: usize, buffer: &String) -> Range<usize> {
     let mut selectable_ranges: Vec<Range<usize>> = Vec::new();
     let glyph_idx = usize::min(glyph_idx, buffer.len().saturating_sub(1));
 
@@ -941,6 +1117,10 @@ impl View for TextInput {
         format!("TextInput: {:?}", self.buffer.get_untracked()).into()
     }
 
+    fn accessible_text(&self) -> Option<String> {
+        Some(self.display_text())
+    }
+
     fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn Any>) {
         if let Ok(state) = state.downcast::<(String, bool)>() {
             let (_, is_focused) = *state;
@@ -981,6 +1161,8 @@ impl View for TextInput {
 
                 if event.count == 2 {
                     self.handle_double_click(event.pos.x, event.pos.y, cx);
+                } else if event.count == 3 {
+                    self.select_all(cx);
                 } else {
                     self.cursor_glyph_idx = self.get_box_position(event.pos.x, event.pos.y, cx);
                     self.selection = None;
@@ -995,7 +1177,22 @@ impl View for TextInput {
                 }
                 false
             }
-            Event::KeyDown(event) => self.handle_key_down(cx, event),
+            Event::KeyDown(event) if self.preedit.is_none() => self.handle_key_down(cx, event),
+            // Key presses are consumed by the IME while composing, so they don't reach the
+            // views below either
+            Event::KeyDown(_) => return EventPropagation::Stop,
+            Event::ImePreedit { text, cursor } => {
+                self.handle_ime_preedit(text, *cursor);
+                true
+            }
+            Event::ImeCommit(text) => {
+                self.handle_ime_commit(text);
+                true
+            }
+            Event::ImeDisabled => {
+                self.preedit = None;
+                true
+            }
             _ => false,
         };
 
@@ -1030,6 +1227,8 @@ impl View for TextInput {
 
             if was_focused && !self.is_focused {
                 self.selection = None;
+                self.preedit = None;
+                self.ime_cursor_area = None;
             }
 
             if self.text_node.is_none() {
@@ -1118,10 +1317,19 @@ impl View for TextInput {
                 .text_buf
                 .as_ref()
                 .unwrap()
-                .hit_position(self.cursor_glyph_idx);
+                .hit_position(self.display_cursor_idx());
             self.cursor_x = hit_pos.point.x;
         }
 
+        if self.is_focused {
+            // Keep the IME candidate window next to the caret
+            let cursor_rect = self.get_cursor_rect(&node_layout) + cx.window_origin.to_vec2();
+            if self.ime_cursor_area != Some(cursor_rect) {
+                self.ime_cursor_area = Some(cursor_rect);
+                set_ime_cursor_area(cursor_rect.origin(), cursor_rect.size());
+            }
+        }
+
         None
     }
 
@@ -1150,8 +1358,16 @@ impl View for TextInput {
             cx.draw_text(self.text_buf.as_ref().unwrap(), text_start_point);
         }
 
+        if let Some(preedit) = &self.preedit {
+            self.paint_preedit_underline(preedit, &node_layout, cx);
+        }
+
         let is_cursor_visible = cx.app_state.is_focused(&self.id())
             && self.selection.is_none()
+            && self
+                .preedit
+                .as_ref()
+                .map_or(true, |preedit| preedit.cursor.is_some())
             && (self.last_cursor_action_on.elapsed().as_millis()
                 / CURSOR_BLINK_INTERVAL_MS as u128)
                 % 2
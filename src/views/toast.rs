@@ -0,0 +1,245 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use floem_reactive::{with_scope, RwSignal, Scope};
+use kurbo::Point;
+use peniko::Color;
+
+use crate::{
+    action::{add_overlay, clear_timer, exec_after, TimerToken},
+    event::EventListener,
+    id::Id,
+    style::{Background, TextColor, Transition},
+    view::View,
+    views::{dyn_stack, label, Decorators},
+    window::use_window_size,
+    window_handle::get_current_view,
+};
+
+/// How long toasts take to fade in and out, in seconds
+const FADE: f64 = 0.2;
+
+/// The severity of a [`toast`], which picks its color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ToastLevel {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl ToastLevel {
+    fn color(self) -> Color {
+        match self {
+            ToastLevel::Info => Color::rgb8(50, 50, 56),
+            ToastLevel::Success => Color::rgb8(32, 128, 64),
+            ToastLevel::Warning => Color::rgb8(184, 120, 0),
+            ToastLevel::Error => Color::rgb8(184, 40, 40),
+        }
+    }
+}
+
+thread_local! {
+    /// The toasts of each window
+    static TOAST_HOSTS: RefCell<HashMap<Id, ToastHost>> = Default::default();
+}
+
+/// The toasts shown in a window, in an overlay which is created with the first toast.
+#[derive(Clone, Copy)]
+struct ToastHost {
+    /// The scope of the toasts, which outlives the views showing them
+    scope: Scope,
+    toasts: RwSignal<Vec<Toast>>,
+}
+
+#[derive(Clone)]
+struct Toast {
+    id: u64,
+    /// The scope of the signals of the toast, which is disposed when it's removed
+    scope: Scope,
+    message: String,
+    level: ToastLevel,
+    /// This is `false` while the toast fades in and out
+    shown: RwSignal<bool>,
+    /// When the dismissal timer was started, unless it's paused
+    timer: Rc<Cell<Option<(Instant, TimerToken)>>>,
+    /// How long the toast is shown for before it's dismissed, from when the timer was started
+    remaining: Rc<Cell<Duration>>,
+}
+
+impl Toast {
+    fn resume(&self, toasts: RwSignal<Vec<Toast>>) {
+        // The signal is disposed once the toast is removed
+        if self.timer.get().is_some() || self.shown.try_get_untracked() != Some(true) {
+            return;
+        }
+        let toast = self.clone();
+        let token = exec_after(self.remaining.get(), move |_| toast.dismiss(toasts));
+        self.timer.set(Some((Instant::now(), token)));
+    }
+
+    fn pause(&self) {
+        if let Some((start, token)) = self.timer.take() {
+            clear_timer(token);
+            self.remaining
+                .set(self.remaining.get().saturating_sub(start.elapsed()));
+        }
+    }
+
+    fn dismiss(&self, toasts: RwSignal<Vec<Toast>>) {
+        self.timer.set(None);
+        self.shown.set(false);
+        let id = self.id;
+        let scope = self.scope;
+        exec_after(Duration::from_secs_f64(FADE), move |_| {
+            toasts.update(|toasts| toasts.retain(|toast| toast.id != id));
+            scope.dispose();
+        });
+    }
+}
+
+/// Show `message` in a toast at the bottom right corner of the current window for `duration`.
+///
+/// Toasts are stacked above each other, with the newest one at the bottom. They fade in and out,
+/// and are shown above all other views. The dismissal of a toast is paused while the pointer
+/// is over it.
+///
+/// This must be called while building or updating the views of a window.
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use floem::views::{toast, ToastLevel};
+/// toast("Saved", ToastLevel::Info, Duration::from_secs(3));
+/// ```
+pub fn toast(message: impl Into<String>, level: ToastLevel, duration: Duration) {
+    static TOAST_COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+    let host = toast_host(get_current_view());
+    let scope = host.scope.create_child();
+    let toast = Toast {
+        id: TOAST_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+        scope,
+        message: message.into(),
+        level,
+        shown: scope.create_rw_signal(false),
+        timer: Rc::new(Cell::new(None)),
+        remaining: Rc::new(Cell::new(duration)),
+    };
+    host.toasts.update(|toasts| toasts.push(toast));
+}
+
+fn toast_host(window: Id) -> ToastHost {
+    if let Some(host) = TOAST_HOSTS.with(|hosts| hosts.borrow().get(&window).copied()) {
+        return host;
+    }
+
+    let scope = Scope::new();
+    let host = ToastHost {
+        scope,
+        toasts: scope.create_rw_signal(Vec::new()),
+    };
+    TOAST_HOSTS.with(|hosts| hosts.borrow_mut().insert(window, host));
+    add_overlay(Point::ZERO, move |_| {
+        with_scope(scope, move || toast_stack(window, host))
+    });
+    host
+}
+
+/// The view covering the window which the toasts of `host` are stacked in.
+fn toast_stack(window: Id, host: ToastHost) -> impl View {
+    let size = use_window_size();
+    let toasts = host.toasts;
+    dyn_stack(
+        move || toasts.get(),
+        |toast| toast.id,
+        move |toast| toast_view(toast, toasts),
+    )
+    .on_cleanup(move || {
+        TOAST_HOSTS.with(|hosts| hosts.borrow_mut().remove(&window));
+        host.scope.dispose();
+    })
+    .style(move |s| {
+        let size = size.get();
+        s.flex_col()
+            .justify_end()
+            .items_end()
+            .gap(0.0, 8.0)
+            .padding(16.0)
+            .width(size.width)
+            .height(size.height)
+    })
+}
+
+fn toast_view(toast: Toast, toasts: RwSignal<Vec<Toast>>) -> impl View {
+    let shown = toast.shown;
+    let color = toast.level.color();
+    let message = toast.message.clone();
+    label(move || message.clone())
+        // The toast is shown once it's laid out so it fades in
+        .on_mounted({
+            let toast = toast.clone();
+            move || {
+                shown.set(true);
+                toast.resume(toasts);
+            }
+        })
+        .on_event_cont(EventListener::PointerEnter, {
+            let toast = toast.clone();
+            move |_| toast.pause()
+        })
+        .on_event_cont(EventListener::PointerLeave, move |_| toast.resume(toasts))
+        .style(move |s| {
+            let alpha = if shown.get() { 1.0 } else { 0.0 };
+            s.padding_horiz(16.0)
+                .padding_vert(10.0)
+                .border_radius(6.0)
+                .max_width(360.0)
+                .background(color.with_alpha_factor(alpha))
+                .color(Color::WHITE.with_alpha_factor(alpha))
+                .transition(Background, Transition::ease(FADE))
+                .transition(TextColor, Transition::ease(FADE))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use kurbo::{Point, Size};
+
+    use super::{toast, ToastLevel, TOAST_HOSTS};
+    use crate::{app_handle::fire_due_timers, views::empty, window::HeadlessWindow};
+
+    #[test]
+    fn toasts_stay_while_hovered() {
+        let mut window = HeadlessWindow::new(
+            |_| {
+                toast("Saved", ToastLevel::Info, Duration::from_secs(1));
+                empty()
+            },
+            Size::new(200.0, 100.0),
+            1.0,
+        );
+        window.render();
+        let toasts = TOAST_HOSTS.with(|hosts| hosts.borrow().values().next().unwrap().toasts);
+        let later = || Instant::now() + Duration::from_secs(2);
+
+        // The toast is in the bottom right corner, inside the padding of the stack
+        window.simulate_pointer_move(Point::new(180.0, 80.0));
+        fire_due_timers(later());
+        window.render();
+        assert_eq!(toasts.get_untracked().len(), 1);
+        assert!(toasts.get_untracked()[0].shown.get_untracked());
+
+        // The rest of the duration starts once the pointer leaves
+        window.simulate_pointer_move(Point::new(10.0, 10.0));
+        fire_due_timers(later());
+        assert!(!toasts.get_untracked()[0].shown.get_untracked());
+        fire_due_timers(later());
+        assert!(toasts.get_untracked().is_empty());
+    }
+}
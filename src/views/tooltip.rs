@@ -1,9 +1,9 @@
-use kurbo::Point;
+use kurbo::{Point, Size, Vec2};
 use std::{rc::Rc, time::Duration};
 
 use crate::{
-    action::{add_overlay, exec_after, remove_overlay, TimerToken},
-    context::{EventCx, UpdateCx},
+    action::{add_overlay, clear_timer, exec_after, remove_overlay, TimerToken},
+    context::{ComputeLayoutCx, EventCx, PaintCx, StyleCx, UpdateCx},
     event::Event,
     id::Id,
     prop, prop_extracter,
@@ -11,6 +11,7 @@ use crate::{
     EventPropagation,
 };
 
+/// How long in seconds the pointer has to rest over the view before the tooltip shows.
 prop!(pub Delay: f64 {} = 0.6);
 
 prop_extracter! {
@@ -31,6 +32,10 @@ pub struct Tooltip {
 }
 
 /// A view that displays a tooltip for its child.
+///
+/// The tooltip shows after the pointer rests over the child for the [`Delay`] style
+/// property, near the cursor and flipped to stay inside the window. It's dismissed
+/// when the pointer leaves or on a click, scroll or key press.
 pub fn tooltip<V: View + 'static, T: View + 'static>(
     child: V,
     tip: impl Fn() -> T + 'static,
@@ -74,6 +79,11 @@ impl View for Tooltip {
         "Tooltip".into()
     }
 
+    fn style(&mut self, cx: &mut StyleCx<'_>) {
+        self.style.read(cx);
+        cx.style_view(&mut self.child);
+    }
+
     fn update(&mut self, _cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
         if let Ok(token) = state.downcast::<TimerToken>() {
             if let Some(window_origin) = self.window_origin {
@@ -81,7 +91,11 @@ impl View for Tooltip {
                     let tip = self.tip.clone();
                     self.overlay = Some(add_overlay(
                         window_origin + self.hover.unwrap().0.to_vec2(),
-                        move |_| tip(),
+                        move |_| TooltipTip {
+                            data: ViewData::new(Id::next()),
+                            child: tip(),
+                            offset: Vec2::ZERO,
+                        },
                     ));
                 }
             }
@@ -97,6 +111,10 @@ impl View for Tooltip {
         match &event {
             Event::PointerMove(e) => {
                 if self.overlay.is_none() {
+                    // Restart the delay as the pointer hasn't come to rest yet
+                    if let Some((_, token)) = self.hover.take() {
+                        clear_timer(token);
+                    }
                     let id = self.id();
                     let token =
                         exec_after(Duration::from_secs_f64(self.style.delay()), move |token| {
@@ -105,12 +123,11 @@ impl View for Tooltip {
                     self.hover = Some((e.pos, token));
                 }
             }
-            Event::PointerLeave => {
-                self.hover = None;
-                if let Some(id) = self.overlay {
-                    remove_overlay(id);
-                    self.overlay = None;
-                }
+            Event::PointerLeave
+            | Event::PointerDown(_)
+            | Event::PointerWheel(_)
+            | Event::KeyDown(_) => {
+                self.dismiss();
             }
             _ => {}
         }
@@ -118,16 +135,91 @@ impl View for Tooltip {
         default_event(self, cx, id_path, event)
     }
 
-    fn compute_layout(&mut self, cx: &mut crate::context::ComputeLayoutCx) -> Option<kurbo::Rect> {
+    fn compute_layout(&mut self, cx: &mut ComputeLayoutCx) -> Option<kurbo::Rect> {
         self.window_origin = Some(cx.window_origin);
         default_compute_layout(self, cx)
     }
 }
 
+impl Tooltip {
+    /// Cancel the pending delay and hide the tooltip if it's shown.
+    fn dismiss(&mut self) {
+        if let Some((_, token)) = self.hover.take() {
+            clear_timer(token);
+        }
+        if let Some(id) = self.overlay.take() {
+            remove_overlay(id);
+        }
+    }
+}
+
 impl Drop for Tooltip {
     fn drop(&mut self) {
-        if let Some(id) = self.overlay {
-            remove_overlay(id)
+        self.dismiss();
+    }
+}
+
+/// The overlay holding a tooltip, placed at the cursor. It moves the tooltip to the
+/// other side of the cursor when it would go past the edges of the window.
+struct TooltipTip {
+    data: ViewData,
+    child: Box<dyn View>,
+    offset: Vec2,
+}
+
+impl View for TooltipTip {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn View) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn View) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn View) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Tooltip Tip".into()
+    }
+
+    fn compute_layout(&mut self, cx: &mut ComputeLayoutCx) -> Option<kurbo::Rect> {
+        let app_state = cx.app_state();
+        let size = app_state
+            .get_layout(self.child.id())
+            .map(|layout| Size::new(layout.size.width as f64, layout.size.height as f64))
+            .unwrap_or_default();
+        let window = app_state.root_size / app_state.scale;
+        let cursor = cx.window_origin;
+
+        let mut origin = cursor;
+        if origin.x + size.width > window.width {
+            origin.x = cursor.x - size.width;
         }
+        if origin.y + size.height > window.height {
+            origin.y = cursor.y - size.height;
+        }
+        self.offset = Point::new(origin.x.max(0.0), origin.y.max(0.0)) - cursor;
+
+        default_compute_layout(self, cx)
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        cx.save();
+        cx.offset((self.offset.x, self.offset.y));
+        cx.paint_view(&mut self.child);
+        cx.restore();
     }
 }
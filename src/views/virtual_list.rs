@@ -34,6 +34,12 @@ impl<T> VirtualList<T> {
         self.selection
     }
 
+    /// Build views for items up to `overscan` outside of the viewport.
+    pub fn overscan(mut self, overscan: f64) -> Self {
+        self.child = self.child.overscan(overscan);
+        self
+    }
+
     pub fn on_select(self, on_select: impl Fn(Option<usize>) + 'static) -> Self {
         create_effect(move |_| {
             let selection = self.selection.get();
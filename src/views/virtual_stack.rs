@@ -1,6 +1,9 @@
-use std::{hash::Hash, marker::PhantomData, ops::Range};
+use std::{cell::RefCell, hash::Hash, marker::PhantomData, ops::Range, rc::Rc};
 
-use floem_reactive::{as_child_of_current_scope, create_effect, create_signal, Scope, WriteSignal};
+use floem_reactive::{
+    as_child_of_current_scope, create_effect, create_rw_signal, create_signal, create_trigger,
+    RwSignal, Scope, WriteSignal,
+};
 use kurbo::{Rect, Size};
 use smallvec::SmallVec;
 use taffy::{
@@ -56,6 +59,7 @@ where
     children: Vec<Option<(V, Scope)>>,
     viewport: Rect,
     set_viewport: WriteSignal<Rect>,
+    overscan: RwSignal<f64>,
     view_fn: Box<dyn Fn(T) -> (V, Scope)>,
     phatom: PhantomData<T>,
     before_size: f64,
@@ -89,64 +93,87 @@ where
     let id = Id::next();
 
     let (viewport, set_viewport) = create_signal(Rect::ZERO);
+    let overscan = create_rw_signal(0.0);
+
+    let items_vector = Rc::new(RefCell::new(None));
+    // The start offset of each item along the main axis, followed by the total size.
+    // This is only used for `VirtualItemSize::Fn` and is rebuilt when the items change,
+    // so scrolling doesn't have to measure every item.
+    let offsets = Rc::new(RefCell::new(Vec::new()));
+    let item_size = Rc::new(item_size);
+    let items_changed = create_trigger();
+
+    create_effect({
+        let items_vector = items_vector.clone();
+        let offsets = offsets.clone();
+        let item_size = item_size.clone();
+        move |_| {
+            let mut vector = each_fn();
+            if let VirtualItemSize::Fn(size_fn) = &*item_size {
+                let total_len = vector.total_len();
+                let mut new_offsets = Vec::with_capacity(total_len + 1);
+                let mut current = 0.0;
+                for item in vector.slice(0..total_len) {
+                    new_offsets.push(current);
+                    current += size_fn(&item);
+                }
+                new_offsets.push(current);
+                *offsets.borrow_mut() = new_offsets;
+            }
+            *items_vector.borrow_mut() = Some(vector);
+            items_changed.notify();
+        }
+    });
 
     create_effect(move |prev| {
-        let mut items_vector = each_fn();
+        items_changed.track();
         let viewport = viewport.get();
+        let overscan = overscan.get();
         let min = match direction {
             VirtualDirection::Vertical => viewport.y0,
             VirtualDirection::Horizontal => viewport.x0,
-        };
+        } - overscan;
         let max = match direction {
             VirtualDirection::Vertical => viewport.height() + viewport.y0,
             VirtualDirection::Horizontal => viewport.width() + viewport.x0,
-        };
-        let mut items = Vec::new();
+        } + overscan;
 
-        let mut before_size = 0.0;
-        let mut content_size = 0.0;
-        match &item_size {
+        let mut vector_ref = items_vector.borrow_mut();
+        let vector = vector_ref.as_mut().unwrap();
+        let total_len = vector.total_len();
+
+        let (range, before_size, content_size) = match &*item_size {
             VirtualItemSize::Fixed(item_size) => {
                 let item_size = item_size();
-                let total_len = items_vector.total_len();
-                let start = if item_size > 0.0 {
-                    (min / item_size).floor() as usize
-                } else {
-                    0
-                };
-                let end = if item_size > 0.0 {
-                    ((max / item_size).ceil() as usize).min(total_len)
+                let (start, end) = if item_size > 0.0 {
+                    (
+                        (min.max(0.0) / item_size).floor() as usize,
+                        ((max / item_size).ceil() as usize).min(total_len),
+                    )
                 } else {
-                    usize::MAX
+                    (0, total_len)
                 };
-                before_size = item_size * (start.min(total_len)) as f64;
-
-                for item in items_vector.slice(start..end) {
-                    items.push(item);
-                }
-
-                content_size = item_size * total_len as f64;
+                let start = start.min(end);
+                (
+                    start..end,
+                    item_size * start as f64,
+                    item_size * total_len as f64,
+                )
             }
-            VirtualItemSize::Fn(size_fn) => {
-                let mut main_axis = 0.0;
-                let total_len = items_vector.total_len();
-                for item in items_vector.slice(0..total_len) {
-                    let item_size = size_fn(&item);
-                    content_size += item_size;
-                    if main_axis + item_size < min {
-                        main_axis += item_size;
-                        before_size += item_size;
-                        continue;
-                    }
-
-                    if main_axis <= max {
-                        main_axis += item_size;
-                        items.push(item);
-                    }
-                }
+            VirtualItemSize::Fn(_) => {
+                let offsets = offsets.borrow();
+                // Skip the items which end before `min` and the items which start after `max`
+                let start = offsets[1..].partition_point(|&end| end < min);
+                let end = offsets[..total_len]
+                    .partition_point(|&start| start <= max)
+                    .max(start);
+                (start..end, offsets[start], offsets[total_len])
             }
         };
 
+        let items = vector.slice(range).collect::<Vec<_>>();
+        drop(vector_ref);
+
         let hashed_items = items.iter().map(&key_fn).collect::<FxIndexSet<_>>();
         let (prev_before_size, prev_content_size, diff) =
             if let Some((prev_before_size, prev_content_size, HashRun(prev_hash_run))) = prev {
@@ -192,6 +219,7 @@ where
         children: Vec::new(),
         viewport: Rect::ZERO,
         set_viewport,
+        overscan,
         view_fn,
         phatom: PhantomData,
         before_size: 0.0,
@@ -201,6 +229,15 @@ where
     }
 }
 
+impl<V: View, T> VirtualStack<V, T> {
+    /// Build views for items up to `overscan` outside of the viewport, so they are
+    /// already laid out when scrolled into view.
+    pub fn overscan(self, overscan: f64) -> Self {
+        self.overscan.set(overscan);
+        self
+    }
+}
+
 impl<V: View + 'static, T> View for VirtualStack<V, T> {
     fn view_data(&self) -> &ViewData {
         &self.data
@@ -1,4 +1,5 @@
 use crate::{
+    accessibility::Role,
     style_class,
     view::View,
     views::{self, container, Decorators},
@@ -11,4 +12,5 @@ pub fn button<S: Display + 'static>(label: impl Fn() -> S + 'static) -> impl Vie
     container(views::label(label))
         .keyboard_navigatable()
         .class(ButtonClass)
+        .role(Role::Button)
 }
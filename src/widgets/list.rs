@@ -1,4 +1,5 @@
 use crate::{
+    accessibility::Role,
     style_class,
     view::View,
     views::{self, container, Decorators, List},
@@ -14,7 +15,8 @@ where
     views::list(
         iterator
             .into_iter()
-            .map(|view| container(view).class(ListItemClass)),
+            .map(|view| container(view).class(ListItemClass).role(Role::ListItem)),
     )
     .class(ListClass)
+    .role(Role::List)
 }
@@ -4,9 +4,13 @@
 //!
 
 use crate::{
+    action::ModalScrimClass,
     style::{Background, CursorStyle, Foreground, Style, Transition},
     unit::{PxPct, UnitExt},
-    views::scroll,
+    views::{
+        scroll, DropdownClass, DropdownItemClass, DropdownPopupClass, SplitDividerClass,
+        TabHeaderClass, TabHeaderStripClass, TextAreaClass,
+    },
     widgets::{self, slider::SliderClass},
 };
 use peniko::Color;
@@ -15,6 +19,9 @@ use std::rc::Rc;
 mod checkbox;
 pub use checkbox::*;
 
+mod radio_button;
+pub use radio_button::*;
+
 mod list;
 pub use list::*;
 
@@ -132,6 +139,20 @@ pub(crate) fn default_theme() -> Theme {
         })
         .apply(focus_style.clone());
 
+    let labeled_radio_button_style = Style::new()
+        .gap(padding, 0.0)
+        .hover(|s| s.background(hover_bg_color))
+        .padding(padding)
+        .transition(Background, Transition::linear(0.04))
+        .border_radius(border_radius)
+        .active(|s| s.class(RadioButtonClass, |s| s.background(active_bg_color)))
+        .disabled(|s| s.color(Color::GRAY));
+
+    let radio_group_style = Style::new()
+        .border_radius(border_radius)
+        .apply(focus_style.clone())
+        .focus(|s| s.class(RadioButtonClass, |_| focus_applied_style.clone()));
+
     const FONT_SIZE: f32 = 12.0;
 
     let input_style = Style::new()
@@ -166,8 +187,15 @@ pub(crate) fn default_theme() -> Theme {
         })
         .class(FocusClass, |_| focus_style)
         .class(LabeledCheckboxClass, |_| labeled_checkbox_style)
+        .class(RadioButtonClass, |_| {
+            checkbox_style.clone().border_radius(100.pct())
+        })
+        .class(LabeledRadioButtonClass, |_| labeled_radio_button_style)
+        .class(RadioGroupClass, |_| radio_group_style)
         .class(CheckboxClass, |_| checkbox_style)
+        .class(TextAreaClass, |_| input_style.clone().min_height(60.0))
         .class(TextInputClass, |_| input_style)
+        .class(DropdownClass, |_| button_style.clone())
         .class(ButtonClass, |_| button_style)
         .class(scroll::Handle, |s| {
             s.border_radius(4.0)
@@ -219,6 +247,42 @@ pub(crate) fn default_theme() -> Theme {
                 .box_shadow_v_offset(2.0)
                 .box_shadow_color(Color::BLACK.with_alpha_factor(0.2))
         })
+        .class(DropdownPopupClass, |s| {
+            s.border(0.5)
+                .border_color(border)
+                .border_radius(2.0)
+                .background(Color::WHITE)
+                .box_shadow_blur(2.0)
+                .box_shadow_h_offset(2.0)
+                .box_shadow_v_offset(2.0)
+                .box_shadow_color(Color::BLACK.with_alpha_factor(0.2))
+        })
+        .class(DropdownItemClass, |s| {
+            s.padding(padding)
+                .selected(|s| s.background(selected_bg_color))
+        })
+        .class(SplitDividerClass, |s| {
+            s.min_width(4.0)
+                .min_height(4.0)
+                .background(Color::rgb8(224, 224, 224))
+                .hover(|s| s.background(border))
+                .active(|s| s.background(border))
+        })
+        .class(ModalScrimClass, |s| {
+            s.background(Color::BLACK.with_alpha_factor(0.3))
+        })
+        .class(TabHeaderStripClass, |s| {
+            s.border_bottom(1.0).border_color(border)
+        })
+        .class(TabHeaderClass, |s| {
+            s.padding_horiz(padding * 2.0)
+                .padding_vert(padding)
+                .hover(|s| s.background(hover_bg_color))
+                .selected(|s| {
+                    s.background(selected_bg_color)
+                        .hover(|s| s.background(selected_hover_bg_color))
+                })
+        })
         .font_size(FONT_SIZE)
         .color(Color::BLACK);
 
@@ -0,0 +1,85 @@
+use crate::{
+    accessibility::Role,
+    event::{Event, EventListener},
+    keyboard::{Key, NamedKey},
+    style_class,
+    view::View,
+    views::{self, h_stack, svg, v_stack_from_iter, Decorators},
+    EventPropagation,
+};
+use floem_reactive::RwSignal;
+use std::fmt::Display;
+
+style_class!(pub RadioButtonClass);
+
+style_class!(pub LabeledRadioButtonClass);
+
+style_class!(pub RadioGroupClass);
+
+fn radio_button_svg(selected: impl Fn() -> bool + 'static) -> impl View {
+    const RADIO_BUTTON_SVG: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 20"><circle fill="currentColor" cx="10" cy="10" r="5" /></svg>"#;
+    let svg_str = move || if selected() { RADIO_BUTTON_SVG } else { "" }.to_string();
+    svg(svg_str).class(RadioButtonClass)
+}
+
+/// Renders a labeled radio button for each of the `options`, with the one equal to
+/// `selected` checked. Clicking an option selects it.
+///
+/// The group is focused as a whole, and the arrow keys move the selection to the
+/// previous or next option when it is.
+pub fn radio_group<T>(selected: RwSignal<T>, options: impl IntoIterator<Item = T>) -> impl View
+where
+    T: Clone + PartialEq + Display + 'static,
+{
+    let options: Vec<T> = options.into_iter().collect();
+    let buttons = options.clone().into_iter().map(move |option| {
+        let label = option.to_string();
+        let is_selected = {
+            let option = option.clone();
+            move || selected.with(|selected| *selected == option)
+        };
+        let is_checked = {
+            let option = option.clone();
+            move || selected.with(|selected| *selected == option)
+        };
+        h_stack((
+            radio_button_svg(is_selected),
+            views::label(move || label.clone()),
+        ))
+        .class(LabeledRadioButtonClass)
+        .style(|s| s.items_center())
+        .role(Role::RadioButton)
+        .accessible_checked(is_checked)
+        .on_click_stop(move |_| {
+            if selected.with_untracked(|selected| *selected != option) {
+                selected.set(option.clone());
+            }
+        })
+    });
+    v_stack_from_iter(buttons)
+        .class(RadioGroupClass)
+        .keyboard_navigatable()
+        .role(Role::RadioGroup)
+        .on_event(EventListener::KeyDown, move |e| {
+            let Event::KeyDown(key_event) = e else {
+                return EventPropagation::Continue;
+            };
+            let backwards = match key_event.key.logical_key {
+                Key::Named(NamedKey::ArrowUp | NamedKey::ArrowLeft) => true,
+                Key::Named(NamedKey::ArrowDown | NamedKey::ArrowRight) => false,
+                _ => return EventPropagation::Continue,
+            };
+            if options.is_empty() {
+                return EventPropagation::Stop;
+            }
+            let current = selected
+                .with_untracked(|selected| options.iter().position(|option| option == selected));
+            let next = match current {
+                Some(i) if backwards => (i + options.len() - 1) % options.len(),
+                Some(i) => (i + 1) % options.len(),
+                None => 0,
+            };
+            selected.set(options[next].clone());
+            EventPropagation::Stop
+        })
+}
@@ -1,21 +1,24 @@
-//! A toggle button widget. An example can be found in widget-gallery/button in the floem examples.
+//! A slider widget for picking a value in a range.
 
-use floem_reactive::create_effect;
+use std::ops::RangeInclusive;
+
+use floem_reactive::{create_effect, RwSignal};
 use floem_renderer::Renderer;
 use kurbo::{Circle, Point, RoundedRect};
 use peniko::Color;
 use winit::keyboard::{Key, NamedKey};
 
 use crate::{
-    prop,
-    prop_extracter,
+    accessibility::Role,
+    event::Event,
+    id::Id,
+    prop, prop_extracter,
     style::{Background, BorderRadius, Foreground},
     style_class,
     unit::PxPct,
     view::{View, ViewData},
     views::Decorators,
     EventPropagation,
-    // EventPropagation,
 };
 
 prop!(pub CircleRad: PxPct {} = PxPct::Pct(98.));
@@ -42,14 +45,24 @@ prop_extracter! {
     }
 }
 
+/// The fraction of the range the arrow keys move the slider by if it has no step.
+const KEY_STEP_FRACTION: f64 = 0.1;
+
 /// A slider
 pub struct Slider {
     data: ViewData,
+    value: RwSignal<f64>,
+    range: RangeInclusive<f64>,
+    step: Option<f64>,
+    vertical: bool,
+    on_change_complete: Option<Box<dyn Fn(f64)>>,
     onchangepx: Option<Box<dyn Fn(f32)>>,
     onchangepct: Option<Box<dyn Fn(f32)>>,
     held: bool,
-    position: f32,
-    prev_position: f32,
+    current: f64,
+    /// The value when the user started dragging the thumb.
+    held_from: f64,
+    prev_thumb: f64,
     base_bar_style: BarStyle,
     accent_bar_style: BarStyle,
     circle: Circle,
@@ -59,37 +72,44 @@ pub struct Slider {
     style: SliderStyle,
 }
 
-/// A reactive toggle button. When the button is toggled by clicking or dragging the widget an update will be sent to the [ToggleButton::on_toggle] handler.
-/// See also [ToggleButtonClass], [ToggleButtonSwitch] and the other toggle button styles that can be applied.
+/// A slider for picking a value in `range`, bound to the `value` signal.
 ///
-/// By default this toggle button has a style class of [ToggleButtonClass] applied with a default style provided.
+/// Dragging the thumb or clicking on the track sets `value` as the pointer moves, and
+/// the arrow keys move it when the slider is focused. Use [`Slider::step`] to limit it to
+/// discrete values and [`Slider::on_change_complete`] to find out when the user is done
+/// changing it.
 ///
-/// Styles:  
-/// background color: [style::Background]  
-/// foreground color: [style::Foreground]  
-/// inner switch inset: [ToggleButtonInset]  
-/// inner switch (circle) size/radius: [ToggleButtonCircleRad]  
-/// toggle button switch behavior: [ToggleButtonBehavior] / [ToggleButtonSwitch]
+/// Styles:
+/// thumb color: [Foreground]
+/// thumb radius: [CircleRad]
+/// bar styles: [Bar] and [AccentBar] with `background`, `border_radius` and [Thickness]
+/// whether the bar extends past the thumb at the ends: [BarExtends]
 ///
-/// An example using RwSignal
 /// ```rust
-/// let state = floem::reactive::create_rw_signal(true);
-/// floem::widgets::toggle_button(move || state.get())
-///         .on_toggle(move |new_state| state.set(new_state));
-///```
-pub fn slider(state: impl Fn() -> f32 + 'static) -> Slider {
-    let id = crate::id::Id::next();
+/// let value = floem::reactive::create_rw_signal(50.0);
+/// floem::widgets::slider::slider(value, 0.0..=100.0)
+///     .step(5.0)
+///     .on_change_complete(|value| println!("{value}"));
+/// ```
+pub fn slider(value: RwSignal<f64>, range: RangeInclusive<f64>) -> Slider {
+    let id = Id::next();
     create_effect(move |_| {
-        let state = state();
-        id.update_state(state, false);
+        let value = value.get();
+        id.update_state(value, false);
     });
     Slider {
         data: ViewData::new(id),
+        value,
+        current: value.get_untracked(),
+        range,
+        step: None,
+        vertical: false,
+        on_change_complete: None,
         onchangepx: None,
         onchangepct: None,
         held: false,
-        position: 0.0,
-        prev_position: 0.0,
+        held_from: 0.0,
+        prev_thumb: 0.0,
         circle: Default::default(),
         base_bar_style: Default::default(),
         accent_bar_style: Default::default(),
@@ -100,6 +120,7 @@ pub fn slider(state: impl Fn() -> f32 + 'static) -> Slider {
     }
     .class(SliderClass)
     .keyboard_navigatable()
+    .role(Role::Slider)
 }
 
 impl View for Slider {
@@ -111,9 +132,13 @@ impl View for Slider {
         &mut self.data
     }
 
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Slider".into()
+    }
+
     fn update(&mut self, cx: &mut crate::context::UpdateCx, state: Box<dyn std::any::Any>) {
-        if let Ok(position) = state.downcast::<f32>() {
-            self.position = *position;
+        if let Ok(value) = state.downcast::<f64>() {
+            self.current = *value;
             cx.request_layout(self.id());
         }
     }
@@ -121,46 +146,49 @@ impl View for Slider {
     fn event(
         &mut self,
         cx: &mut crate::context::EventCx,
-        _id_path: Option<&[crate::id::Id]>,
-        event: crate::event::Event,
+        _id_path: Option<&[Id]>,
+        event: Event,
     ) -> EventPropagation {
         match event {
-            crate::event::Event::PointerDown(event) => {
-                cx.update_active(self.id());
-                cx.app_state_mut().request_layout(self.id());
-                self.held = true;
-                self.position = event.pos.x as f32;
+            Event::PointerDown(event) => {
+                if event.button.is_primary() {
+                    cx.update_active(self.id());
+                    // Keep getting pointer events when dragging outside of the slider
+                    self.id().capture_pointer();
+                    self.held = true;
+                    self.held_from = self.current;
+                    self.set_value(self.value_at(event.pos));
+                }
             }
-            crate::event::Event::PointerUp(event) => {
-                cx.app_state_mut().request_layout(self.id());
-
-                // set the state based on the position of the slider
+            Event::PointerMove(event) => {
                 if self.held {
-                    self.position = event.pos.x as f32;
-                    self.update_restrict_position();
+                    self.set_value(self.value_at(event.pos));
                 }
-                self.held = false;
             }
-            crate::event::Event::PointerMove(event) => {
-                cx.app_state_mut().request_layout(self.id());
+            Event::PointerUp(event) => {
                 if self.held {
-                    self.position = event.pos.x as f32;
-                    self.update_restrict_position();
+                    self.held = false;
+                    self.set_value(self.value_at(event.pos));
+                    self.change_complete(self.held_from);
                 }
             }
-            crate::event::Event::FocusLost => {
+            Event::FocusLost => {
                 self.held = false;
             }
-            crate::event::Event::KeyDown(event) => {
-                if event.key.logical_key == Key::Named(NamedKey::ArrowLeft) {
-                    cx.app_state_mut().request_layout(self.id());
-                    self.position -= (self.size.width - self.circle.radius as f32 * 2.) * 0.1;
-                    self.update_restrict_position();
-                } else if event.key.logical_key == Key::Named(NamedKey::ArrowRight) {
-                    cx.app_state_mut().request_layout(self.id());
-                    self.position += (self.size.width - self.circle.radius as f32 * 2.) * 0.1;
-                    self.update_restrict_position();
-                }
+            Event::KeyDown(event) => {
+                let (start, end) = (*self.range.start(), *self.range.end());
+                let step = self.step.unwrap_or((end - start).abs() * KEY_STEP_FRACTION);
+                let value = match event.key.logical_key {
+                    Key::Named(NamedKey::ArrowLeft | NamedKey::ArrowDown) => self.current - step,
+                    Key::Named(NamedKey::ArrowRight | NamedKey::ArrowUp) => self.current + step,
+                    Key::Named(NamedKey::Home) => start,
+                    Key::Named(NamedKey::End) => end,
+                    _ => return EventPropagation::Continue,
+                };
+                let old = self.current;
+                self.set_value(value);
+                self.change_complete(old);
+                return EventPropagation::Stop;
             }
             _ => {}
         };
@@ -186,21 +214,31 @@ impl View for Slider {
         let layout = cx.get_layout(self.id()).unwrap();
 
         self.size = layout.size;
+        let (length, cross) = self.axes();
 
         let circle_radius = match self.style.circle_rad() {
-            PxPct::Px(px) => px as f32,
-            PxPct::Pct(pct) => self.size.width.min(self.size.height) / 2. * (pct as f32 / 100.),
+            PxPct::Px(px) => px,
+            PxPct::Pct(pct) => length.min(cross) / 2. * (pct / 100.),
         };
-        let circle_point = Point::new(self.position as f64, (self.size.height / 2.) as f64);
-        self.circle = crate::kurbo::Circle::new(circle_point, circle_radius as f64);
+        let thumb = self.thumb_offset(circle_radius);
+        self.circle = Circle::new(self.point(thumb, cross / 2.), circle_radius);
+        if thumb != self.prev_thumb {
+            if let Some(onchangepx) = &self.onchangepx {
+                onchangepx(thumb as f32);
+            }
+            if let Some(onchangepct) = &self.onchangepct {
+                onchangepct(self.fraction() as f32);
+            }
+        }
+        self.prev_thumb = thumb;
 
         let base_bar_thickness = match self.base_bar_style.thickness() {
             PxPct::Px(px) => px,
-            PxPct::Pct(pct) => self.size.height as f64 * (pct / 100.),
+            PxPct::Pct(pct) => cross * (pct / 100.),
         };
         let accent_bar_thickness = match self.accent_bar_style.thickness() {
             PxPct::Px(px) => px,
-            PxPct::Pct(pct) => self.size.height as f64 * (pct / 100.),
+            PxPct::Pct(pct) => cross * (pct / 100.),
         };
 
         let base_bar_radius = match self.base_bar_style.border_radius() {
@@ -212,46 +250,18 @@ impl View for Slider {
             PxPct::Pct(pct) => accent_bar_thickness / 2. * (pct / 100.),
         };
 
-        let mut base_bar_length = self.size.width as f64;
-        if !self.style.bar_extends() {
-            base_bar_length -= self.circle.radius * 2.;
-        }
-
-        let base_bar_y_start = self.size.height as f64 / 2. - base_bar_thickness / 2.;
-        let accent_bar_y_start = self.size.height as f64 / 2. - accent_bar_thickness / 2.;
-
-        let bar_x_start = if self.style.bar_extends() {
-            0.
+        let (bar_start, bar_end) = if self.style.bar_extends() {
+            (0., length)
         } else {
-            self.circle.radius
+            (circle_radius, length - circle_radius)
         };
 
-        self.base_bar = kurbo::Rect::new(
-            bar_x_start,
-            base_bar_y_start,
-            bar_x_start + base_bar_length,
-            base_bar_y_start + base_bar_thickness,
-        )
-        .to_rounded_rect(base_bar_radius);
-        self.accent_bar = kurbo::Rect::new(
-            bar_x_start,
-            accent_bar_y_start,
-            self.position as f64,
-            accent_bar_y_start + accent_bar_thickness,
-        )
-        .to_rounded_rect(accent_bar_radius);
-
-        if self.position != self.prev_position {
-            if let Some(onchangepx) = &self.onchangepx {
-                onchangepx(self.position);
-            }
-            if let Some(onchangepct) = &self.onchangepct {
-                onchangepct(
-                    (self.position - circle_radius) / (self.size.width - circle_radius * 2.),
-                )
-            }
-        }
-        self.prev_position = self.position;
+        self.base_bar = self
+            .bar_rect(bar_start, bar_end, cross, base_bar_thickness)
+            .to_rounded_rect(base_bar_radius);
+        self.accent_bar = self
+            .bar_rect(bar_start, thumb, cross, accent_bar_thickness)
+            .to_rounded_rect(accent_bar_radius);
 
         None
     }
@@ -262,37 +272,171 @@ impl View for Slider {
             self.base_bar_style.color().unwrap_or(Color::BLACK),
             0.,
         );
+        cx.save();
         cx.clip(&self.base_bar);
         cx.fill(
             &self.accent_bar,
             self.accent_bar_style.color().unwrap_or(Color::GREEN),
             0.,
         );
+        cx.restore();
 
         if let Some(color) = self.style.foreground() {
-            cx.clear_clip();
             cx.fill(&self.circle, color, 0.);
         }
     }
 }
+
 impl Slider {
-    fn update_restrict_position(&mut self) {
-        self.position = self
-            .position
-            .max(self.circle.radius as f32)
-            .min(self.size.width - self.circle.radius as f32);
+    /// Limit the value to multiples of `step` from the start of the range.
+    /// This is also how far the arrow keys move the slider.
+    pub fn step(mut self, step: f64) -> Self {
+        self.step = Some(step).filter(|step| *step > 0.0);
+        self
     }
 
-    /// Add an event handler to be run when the button is toggled.
+    /// Lay out the slider vertically, with the start of the range at the bottom.
+    pub fn vertical(mut self) -> Self {
+        self.vertical = true;
+        self
+    }
+
+    /// Add a handler which is called with the value when the user is done changing it,
+    /// which is when the pointer is released after dragging or on a key press. It isn't
+    /// called if the value ends up the same as before.
     ///
-    ///This does not run if the state is changed because of an outside signal.
-    /// This handler is only called if this button is clicked or switched
+    /// The bound signal is updated continuously while dragging.
+    pub fn on_change_complete(mut self, on_change_complete: impl Fn(f64) + 'static) -> Self {
+        self.on_change_complete = Some(Box::new(on_change_complete));
+        self
+    }
+
+    /// Add a handler which is called with the position of the thumb along the track as a
+    /// fraction between 0 and 1 when it moves.
+    #[deprecated(note = "read the bound signal or use `on_change_complete` instead")]
     pub fn on_change_pct(mut self, onchangepct: impl Fn(f32) + 'static) -> Self {
         self.onchangepct = Some(Box::new(onchangepct));
         self
     }
+
+    /// Add a handler which is called with the offset in pixels of the center of the thumb
+    /// from the start of the slider when it moves.
+    #[deprecated(note = "read the bound signal or use `on_change_complete` instead")]
     pub fn on_change_px(mut self, onchangepx: impl Fn(f32) + 'static) -> Self {
         self.onchangepx = Some(Box::new(onchangepx));
         self
     }
+
+    /// Call the `on_change_complete` handler if the value changed from `old`.
+    fn change_complete(&self, old: f64) {
+        if self.current == old {
+            return;
+        }
+        if let Some(on_change_complete) = &self.on_change_complete {
+            on_change_complete(self.current);
+        }
+    }
+
+    /// Clamp `value` to the range and the step and update the bound signal.
+    fn set_value(&mut self, value: f64) {
+        let (start, end) = (*self.range.start(), *self.range.end());
+        let (min, max) = (start.min(end), start.max(end));
+        let mut value = value.clamp(min, max);
+        if let Some(step) = self.step {
+            value = (start + ((value - start) / step).round() * step).clamp(min, max);
+        }
+        self.current = value;
+        if self.value.get_untracked() != value {
+            self.value.set(value);
+        }
+    }
+
+    /// The length of the slider along its axis and across it.
+    fn axes(&self) -> (f64, f64) {
+        let (width, height) = (self.size.width as f64, self.size.height as f64);
+        if self.vertical {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// The point `along` the axis of the slider from its start and `across` it.
+    fn point(&self, along: f64, across: f64) -> Point {
+        if self.vertical {
+            Point::new(across, self.size.height as f64 - along)
+        } else {
+            Point::new(along, across)
+        }
+    }
+
+    /// A bar between `from` and `to` along the axis, centered across it.
+    fn bar_rect(&self, from: f64, to: f64, cross: f64, thickness: f64) -> kurbo::Rect {
+        let across = cross / 2. - thickness / 2.;
+        kurbo::Rect::from_points(self.point(from, across), self.point(to, across + thickness))
+    }
+
+    /// The fraction of the range the slider is at.
+    fn fraction(&self) -> f64 {
+        let (start, end) = (*self.range.start(), *self.range.end());
+        if start == end {
+            0.0
+        } else {
+            ((self.current - start) / (end - start)).clamp(0.0, 1.0)
+        }
+    }
+
+    /// The offset along the axis of the center of the thumb.
+    fn thumb_offset(&self, circle_radius: f64) -> f64 {
+        let (length, _) = self.axes();
+        circle_radius + self.fraction() * (length - circle_radius * 2.).max(0.0)
+    }
+
+    /// The value for a pointer at `pos`.
+    fn value_at(&self, pos: Point) -> f64 {
+        let (length, _) = self.axes();
+        let along = if self.vertical {
+            self.size.height as f64 - pos.y
+        } else {
+            pos.x
+        };
+        let radius = self.circle.radius;
+        let track = length - radius * 2.;
+        let fraction = if track > 0.0 {
+            ((along - radius) / track).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let (start, end) = (*self.range.start(), *self.range.end());
+        start + fraction * (end - start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use floem_reactive::create_rw_signal;
+    use kurbo::{Point, Size};
+
+    use super::slider;
+    use crate::{views::Decorators, window::HeadlessWindow};
+
+    #[test]
+    fn change_complete_is_only_called_for_new_values() {
+        let value = create_rw_signal(0.0);
+        let completed = create_rw_signal(Vec::new());
+        let mut window = HeadlessWindow::new(
+            move |_| {
+                slider(value, 0.0..=100.0)
+                    .step(10.0)
+                    .on_change_complete(move |value| completed.update(|c| c.push(value)))
+                    .style(|s| s.size(100.0, 20.0))
+            },
+            Size::new(100.0, 20.0),
+            1.0,
+        );
+        window.simulate_click(Point::new(50.0, 10.0));
+        assert_eq!(value.get_untracked(), 50.0);
+        window.simulate_click(Point::new(51.0, 10.0));
+        assert_eq!(completed.get_untracked(), [50.0]);
+    }
 }
@@ -2,7 +2,7 @@ use crate::{
     style_class,
     views::{self, Decorators, TextInput},
 };
-use floem_reactive::RwSignal;
+use floem_reactive::{create_effect, create_rw_signal, RwSignal};
 
 style_class!(pub TextInputClass);
 style_class!(pub PlaceholderTextClass);
@@ -11,9 +11,110 @@ pub fn text_input(buffer: RwSignal<String>) -> TextInput {
     views::text_input(buffer).class(TextInputClass)
 }
 
+/// A text input which only accepts numbers, with an optional minus sign and decimal point.
+/// `value` is the number which is entered, or `None` if there's none, like when the input is
+/// empty. Setting `value` updates the text.
+///
+/// ```rust
+/// let price = floem::reactive::create_rw_signal(Some(9.5));
+/// floem::widgets::numeric_input(price);
+/// ```
+pub fn numeric_input(value: RwSignal<Option<f64>>) -> TextInput {
+    let buffer = create_rw_signal(
+        value
+            .get_untracked()
+            .map(|value| value.to_string())
+            .unwrap_or_default(),
+    );
+    create_effect(move |_| {
+        let parsed = buffer.with(|text| parse_number(text));
+        if value.get_untracked() != parsed {
+            value.set(parsed);
+        }
+    });
+    create_effect(move |_| {
+        let new_value = value.get();
+        // Keep the text as it is if it means the same number, like `1.` for 1
+        if buffer.with_untracked(|text| parse_number(text)) != new_value {
+            buffer.set(new_value.map(|value| value.to_string()).unwrap_or_default());
+        }
+    });
+    text_input(buffer).on_validate(is_partial_number)
+}
+
+fn parse_number(text: &str) -> Option<f64> {
+    text.parse().ok()
+}
+
+/// Whether `text` is a number or the start of one, like `-`, `1.` or `1e-`.
+fn is_partial_number(text: &str) -> bool {
+    let is_digits = |text: &str| text.chars().all(|c| c.is_ascii_digit());
+    let text = text.strip_prefix('-').unwrap_or(text);
+    let (mantissa, exponent) = match text.find(['e', 'E']) {
+        Some(i) => (&text[..i], Some(&text[i + 1..])),
+        None => (text, None),
+    };
+    if !mantissa.splitn(2, '.').all(is_digits) {
+        return false;
+    }
+    match exponent {
+        // The exponent needs digits in front of it
+        Some(exponent) => {
+            let digits = exponent.strip_prefix(['-', '+']).unwrap_or(exponent);
+            mantissa.chars().any(|c| c.is_ascii_digit()) && is_digits(digits)
+        }
+        None => true,
+    }
+}
+
 impl TextInput {
     pub fn placeholder(mut self, text: impl Into<String>) -> Self {
         self.placeholder_text = Some(text.into());
         self
     }
+
+    /// Only accept insertions which result in text for which `validate` returns true. This
+    /// applies to typed text, pasted text and text from the IME, but not to deleting text.
+    ///
+    /// ```rust
+    /// let code = floem::reactive::create_rw_signal(String::new());
+    /// floem::widgets::text_input(code)
+    ///     .on_validate(|text| text.len() <= 6 && text.chars().all(|c| c.is_ascii_alphanumeric()));
+    /// ```
+    pub fn on_validate(mut self, validate: impl Fn(&str) -> bool + 'static) -> Self {
+        self.validate = Some(Box::new(validate));
+        self
+    }
+
+    /// Mask the text, for entering passwords. The text can't be copied or cut, and the IME is
+    /// disabled. The bound signal still holds the text that was entered.
+    pub fn password(mut self) -> Self {
+        self.password = true;
+        self.ime_allowed.set(false);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_partial_number, parse_number};
+
+    #[test]
+    fn partial_numbers_are_accepted() {
+        for text in [
+            "", "-", ".", "-.", "1.", "-1.5", "1e", "1e-", "1E+", "2.5e10",
+        ] {
+            assert!(is_partial_number(text), "{text:?}");
+        }
+        assert_eq!(parse_number("2.5e10"), Some(2.5e10));
+    }
+
+    #[test]
+    fn invalid_numbers_are_rejected() {
+        for text in [
+            "a", "--1", "1-", "1.2.3", "e", "-e", ".e1", "1e5e", "1e2.5", "1 ",
+        ] {
+            assert!(!is_partial_number(text), "{text:?}");
+        }
+    }
 }
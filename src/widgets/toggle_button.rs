@@ -6,6 +6,7 @@ use kurbo::{Point, Size};
 use winit::keyboard::{Key, NamedKey};
 
 use crate::{
+    accessibility::{AccessUpdate, Role},
     prop, prop_extracter,
     style::{self, Foreground},
     style_class,
@@ -81,6 +82,7 @@ pub fn toggle_button(state: impl Fn() -> bool + 'static) -> ToggleButton {
     let id = crate::id::Id::next();
     create_effect(move |_| {
         let state = state();
+        id.update_accessibility(AccessUpdate::Checked(state));
         id.update_state(state, false);
     });
 
@@ -96,6 +98,7 @@ pub fn toggle_button(state: impl Fn() -> bool + 'static) -> ToggleButton {
     }
     .class(ToggleButtonClass)
     .keyboard_navigatable()
+    .role(Role::Switch)
 }
 
 impl View for ToggleButton {
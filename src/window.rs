@@ -1,29 +1,74 @@
+use std::{collections::VecDeque, time::Duration};
+
 use kurbo::{Point, Size};
 pub use winit::window::Fullscreen;
+pub use winit::window::Icon;
 pub use winit::window::ResizeDirection;
 pub use winit::window::Theme;
 pub use winit::window::WindowButtons;
 pub use winit::window::WindowId;
 pub use winit::window::WindowLevel;
 
+pub use winit::dpi::PhysicalPosition;
+pub use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+use floem_reactive::{create_memo, create_rw_signal, Memo, ReadSignal};
+
 use crate::{
     app::{add_app_update_event, AppUpdateEvent},
+    app_handle::WINDOW_IDS,
+    id::Id,
+    keyboard::{Key, KeyInput},
     view::View,
+    window_handle::{get_current_view, window_signals, WindowHandle},
 };
 
+/// The size and position of a window in logical pixels, which can be saved and later
+/// restored with [`WindowConfig::geometry`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowGeometry {
+    /// The outer position of the window.
+    pub position: Point,
+    /// The inner size of the window.
+    pub size: Size,
+    pub maximized: bool,
+}
+
+/// The response of a close requested handler registered with [`on_close_requested`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseResponse {
+    /// Let the window close.
+    Close,
+    /// Keep the window open.
+    Prevent,
+}
+
 #[derive(Default, Debug)]
 pub struct WindowConfig {
     pub(crate) size: Option<Size>,
+    pub(crate) min_size: Option<Size>,
+    pub(crate) max_size: Option<Size>,
     pub(crate) position: Option<Point>,
+    pub(crate) centered: bool,
     pub(crate) show_titlebar: Option<bool>,
     pub(crate) transparent: Option<bool>,
+    pub(crate) blur: Option<bool>,
     pub(crate) fullscreen: Option<Fullscreen>,
-    pub(crate) window_icon: Option<bool>,
+    pub(crate) window_icon: Option<Icon>,
     pub(crate) title: Option<String>,
     pub(crate) enabled_buttons: Option<WindowButtons>,
     pub(crate) resizable: Option<bool>,
     pub(crate) window_level: Option<WindowLevel>,
+    pub(crate) always_on_top: Option<bool>,
     pub(crate) themed: Option<bool>,
+    pub(crate) fallback_theme: Option<Theme>,
+    pub(crate) scroll_line_height: Option<f64>,
+    pub(crate) multi_click_interval: Option<Duration>,
+    pub(crate) maximized: Option<bool>,
+    pub(crate) show_frame_timing: bool,
+    /// Only use `position` if it's on one of the available monitors
+    pub(crate) validate_position: bool,
 }
 
 impl WindowConfig {
@@ -32,11 +77,30 @@ impl WindowConfig {
         self
     }
 
+    /// Set the minimum inner size the window can be resized to.
+    pub fn min_size(mut self, size: impl Into<Size>) -> Self {
+        self.min_size = Some(size.into());
+        self
+    }
+
+    /// Set the maximum inner size the window can be resized to.
+    pub fn max_size(mut self, size: impl Into<Size>) -> Self {
+        self.max_size = Some(size.into());
+        self
+    }
+
     pub fn position(mut self, position: Point) -> Self {
         self.position = Some(position);
         self
     }
 
+    /// Open the window centered on the monitor of the focused window, or on the primary
+    /// monitor if no window is focused. This is ignored if `position` is set.
+    pub fn centered(mut self, centered: bool) -> Self {
+        self.centered = centered;
+        self
+    }
+
     pub fn show_titlebar(mut self, show_titlebar: bool) -> Self {
         self.show_titlebar = Some(show_titlebar);
         self
@@ -47,13 +111,28 @@ impl WindowConfig {
         self
     }
 
+    /// Request the background behind a transparent window to be blurred.
+    ///
+    /// This is supported on macOS, and on Wayland and X11 with KDE.
+    pub fn with_blur(mut self, blur: bool) -> Self {
+        self.blur = Some(blur);
+        self
+    }
+
     pub fn fullscreen(mut self, fullscreen: Fullscreen) -> Self {
         self.fullscreen = Some(fullscreen);
         self
     }
 
-    pub fn window_icon(mut self, window_icon: bool) -> Self {
-        self.window_icon = Some(window_icon);
+    /// Set the window icon from decoded RGBA pixels, 4 bytes per pixel in row-major order.
+    ///
+    /// Invalid icon data is ignored with a warning. On macOS the icon is taken from the
+    /// application bundle instead, so this has no effect there.
+    pub fn window_icon(mut self, rgba: Vec<u8>, width: u32, height: u32) -> Self {
+        match Icon::from_rgba(rgba, width, height) {
+            Ok(icon) => self.window_icon = Some(icon),
+            Err(err) => eprintln!("invalid window icon: {err}"),
+        }
         self
     }
 
@@ -67,6 +146,8 @@ impl WindowConfig {
         self
     }
 
+    /// Set whether the window can be resized by the user. This also disables the maximize
+    /// button of non-resizable windows.
     pub fn resizable(mut self, resizable: bool) -> Self {
         self.resizable = Some(resizable);
         self
@@ -77,6 +158,57 @@ impl WindowConfig {
         self
     }
 
+    /// Keep the window above all other windows. This takes priority over `window_level`.
+    pub fn always_on_top(mut self, always_on_top: bool) -> Self {
+        self.always_on_top = Some(always_on_top);
+        self
+    }
+
+    /// Set the system theme to assume on platforms which don't report one. This defaults to
+    /// [`Theme::Light`].
+    pub fn fallback_theme(mut self, theme: Theme) -> Self {
+        self.fallback_theme = Some(theme);
+        self
+    }
+
+    /// Set the number of pixels to scroll for each line reported by a mouse wheel. This defaults
+    /// to 60.
+    pub fn scroll_line_height(mut self, scroll_line_height: f64) -> Self {
+        self.scroll_line_height = Some(scroll_line_height);
+        self
+    }
+
+    /// Set the maximum time between clicks for them to count as a double or triple click.
+    /// This defaults to 500 milliseconds.
+    pub fn multi_click_interval(mut self, interval: Duration) -> Self {
+        self.multi_click_interval = Some(interval);
+        self
+    }
+
+    /// Show the timing of the last frame in the top left corner of the window, to help finding
+    /// slow frames. See [`on_frame_timing`] to record the timings instead.
+    pub fn show_frame_timing(mut self, show_frame_timing: bool) -> Self {
+        self.show_frame_timing = show_frame_timing;
+        self
+    }
+
+    pub fn maximized(mut self, maximized: bool) -> Self {
+        self.maximized = Some(maximized);
+        self
+    }
+
+    /// Restore a window geometry saved with [`get_window_geometry`].
+    ///
+    /// If the saved position is no longer on any of the available monitors, the window is placed
+    /// by the platform instead so it doesn't open off-screen.
+    pub fn geometry(mut self, geometry: WindowGeometry) -> Self {
+        self.size = Some(geometry.size);
+        self.position = Some(geometry.position);
+        self.maximized = Some(geometry.maximized);
+        self.validate_position = true;
+        self
+    }
+
     pub fn themed(mut self, themed: bool) -> Self {
         self.themed = Some(themed);
         self
@@ -99,3 +231,862 @@ pub fn new_window<V: View + 'static>(
 pub fn close_window(window_id: WindowId) {
     add_app_update_event(AppUpdateEvent::CloseWindow { window_id });
 }
+
+/// request the window to be brought to the front and focused. This does nothing if the window
+/// has been closed.
+pub fn focus_window(window_id: WindowId) {
+    add_app_update_event(AppUpdateEvent::FocusWindow { window_id });
+}
+
+/// Get the ids of the open windows, in the order they were opened.
+pub fn window_ids() -> Vec<WindowId> {
+    WINDOW_IDS.with(|ids| ids.borrow().clone())
+}
+
+/// request the window to be minimized
+pub fn minimize_window(window_id: WindowId) {
+    add_app_update_event(AppUpdateEvent::MinimizeWindow { window_id });
+}
+
+/// request the window to be maximized
+pub fn maximize_window(window_id: WindowId) {
+    add_app_update_event(AppUpdateEvent::MaximizeWindow { window_id });
+}
+
+/// request the window to be restored from being minimized, or maximized if it isn't minimized
+pub fn restore_window(window_id: WindowId) {
+    add_app_update_event(AppUpdateEvent::RestoreWindow { window_id });
+}
+
+/// request the window to enter or exit fullscreen. Use `None` to exit fullscreen.
+///
+/// `Fullscreen::Borderless(None)` uses the monitor the window is currently on. The window
+/// receives `Event::WindowFullscreenChanged` once the change has been applied.
+pub fn set_fullscreen(window_id: WindowId, fullscreen: Option<Fullscreen>) {
+    add_app_update_event(AppUpdateEvent::SetFullscreen {
+        window_id,
+        fullscreen,
+    });
+}
+
+/// request the window to exit fullscreen if it's fullscreen, or otherwise enter
+/// borderless fullscreen on the monitor the window is currently on
+pub fn toggle_fullscreen(window_id: WindowId) {
+    add_app_update_event(AppUpdateEvent::ToggleFullscreen { window_id });
+}
+
+/// request the window to be kept above other windows, or to be returned to the normal level.
+///
+/// The level is kept when the window enters or exits fullscreen.
+pub fn set_always_on_top(window_id: WindowId, always_on_top: bool) {
+    add_app_update_event(AppUpdateEvent::SetWindowLevel {
+        window_id,
+        window_level: if always_on_top {
+            WindowLevel::AlwaysOnTop
+        } else {
+            WindowLevel::Normal
+        },
+    });
+}
+
+/// request the minimum inner size of the window to be changed. Use `None` to remove the constraint.
+pub fn set_window_min_size(window_id: WindowId, size: Option<Size>) {
+    add_app_update_event(AppUpdateEvent::SetWindowMinSize { window_id, size });
+}
+
+/// request the maximum inner size of the window to be changed. Use `None` to remove the constraint.
+///
+/// Setting the same minimum and maximum size locks the window to that size.
+pub fn set_window_max_size(window_id: WindowId, size: Option<Size>) {
+    add_app_update_event(AppUpdateEvent::SetWindowMaxSize { window_id, size });
+}
+
+/// request the window to be made resizable or not. This also enables or disables the
+/// maximize button.
+pub fn set_window_resizable(window_id: WindowId, resizable: bool) {
+    add_app_update_event(AppUpdateEvent::SetWindowResizable {
+        window_id,
+        resizable,
+    });
+}
+
+/// request the current geometry of the window. The callback receives `None` if the window
+/// doesn't exist.
+pub fn get_window_geometry(
+    window_id: WindowId,
+    callback: impl FnOnce(Option<WindowGeometry>) + 'static,
+) {
+    add_app_update_event(AppUpdateEvent::GetWindowGeometry {
+        window_id,
+        callback: Box::new(callback),
+    });
+}
+
+/// Set a handler which is called when the user asks to close the window, for example by
+/// clicking the close button. Returning [`CloseResponse::Prevent`] keeps the window open.
+///
+/// This replaces any previously set handler. It doesn't affect [`close_window`], which always
+/// closes the window.
+pub fn on_close_requested(window_id: WindowId, handler: impl Fn() -> CloseResponse + 'static) {
+    add_app_update_event(AppUpdateEvent::SetCloseRequestedHandler {
+        window_id,
+        handler: Box::new(handler),
+    });
+}
+
+/// A window which isn't shown on screen, for testing views. Events are injected with
+/// [`HeadlessWindow::window_event`] as if the platform reported them, or with the `simulate_*`
+/// methods, and frames are rendered into a buffer with [`HeadlessWindow::render`].
+///
+/// It's not part of the running [`Application`](crate::Application), so timers and app update
+/// events like [`close_window`] have no effect on it.
+///
+/// ```rust,no_run
+/// # use floem::{kurbo::{Point, Size}, reactive::create_rw_signal, views::{empty, Decorators}};
+/// # use floem::window::HeadlessWindow;
+/// let clicked = create_rw_signal(false);
+/// let mut window = HeadlessWindow::new(
+///     move |_| {
+///         empty()
+///             .style(|s| s.size(100.0, 100.0))
+///             .on_click_stop(move |_| clicked.set(true))
+///     },
+///     Size::new(200.0, 200.0),
+///     1.0,
+/// );
+/// window.simulate_click(Point::new(50.0, 50.0));
+/// assert!(clicked.get());
+/// ```
+pub struct HeadlessWindow {
+    handle: WindowHandle,
+}
+
+impl HeadlessWindow {
+    /// Creates a window with the inner size `size` in logical pixels and the scale factor
+    /// `scale`. The view is styled and laid out, so it's ready for events.
+    pub fn new<V: View + 'static>(
+        app_view: impl FnOnce(WindowId) -> V + 'static,
+        size: Size,
+        scale: f64,
+    ) -> Self {
+        let mut handle =
+            WindowHandle::new_headless(move |window_id| Box::new(app_view(window_id)), size, scale);
+        handle.process_update_no_paint();
+        Self { handle }
+    }
+
+    /// The id views of this window see, for example in the `app_view` function.
+    pub fn id(&self) -> WindowId {
+        self.handle.window_id
+    }
+
+    /// Handles `event` as if the platform reported it for the window. Positions and sizes in
+    /// the event are in physical pixels. Requests to close the window are ignored.
+    pub fn window_event(&mut self, event: WindowEvent) {
+        self.handle.window_event(event);
+    }
+
+    /// Moves the pointer to `point`, in logical pixels relative to the top left corner of the
+    /// window.
+    pub fn simulate_pointer_move(&mut self, point: Point) {
+        self.handle.pointer_move(point);
+    }
+
+    /// Moves the pointer to `point`, in logical pixels, and presses and releases the primary
+    /// mouse button there.
+    pub fn simulate_click(&mut self, point: Point) {
+        self.handle.pointer_move(point);
+        for state in [ElementState::Pressed, ElementState::Released] {
+            self.handle.mouse_input(MouseButton::Left, state);
+        }
+    }
+
+    /// Presses and releases `key`, which goes to the focused view or runs a shortcut like a
+    /// key the platform reported. The modifiers are the ones last sent with
+    /// [`WindowEvent::ModifiersChanged`].
+    pub fn simulate_key(&mut self, key: Key) {
+        for state in [ElementState::Pressed, ElementState::Released] {
+            self.handle.key_event(KeyInput::new(key.clone(), state));
+        }
+    }
+
+    /// Runs any pending style and layout updates and renders a frame. The pixels are returned
+    /// as unpremultiplied RGBA8 values, row by row, for an image of the window size times the
+    /// scale in physical pixels, rounded down.
+    pub fn render(&mut self) -> Vec<u8> {
+        self.handle.process_update_no_paint();
+        let image = self
+            .handle
+            .paint()
+            .expect("a headless renderer returns the frame it painted");
+        image.into_rgba8().into_raw()
+    }
+}
+
+impl Drop for HeadlessWindow {
+    fn drop(&mut self) {
+        self.handle.destroy();
+    }
+}
+
+/// How long the work for a frame of a window took, as reported to the handler set with
+/// [`on_frame_timing`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameTiming {
+    /// The time spent styling views since the last frame.
+    pub style: Duration,
+    /// The time spent laying out views since the last frame.
+    pub layout: Duration,
+    /// The time spent painting the frame, including handing it to the renderer.
+    pub paint: Duration,
+    /// The time between the start of the last frame and this one. This is `None` for the first
+    /// frame of the window.
+    pub since_last_frame: Option<Duration>,
+}
+
+impl FrameTiming {
+    /// The time spent on styling, layout and painting for the frame.
+    pub fn total(&self) -> Duration {
+        self.style + self.layout + self.paint
+    }
+}
+
+/// The durations of a number of recent frames, to find the slow ones.
+///
+/// ```rust
+/// # use std::{cell::RefCell, time::Duration};
+/// # use floem::window::{on_frame_timing, FrameStats, WindowId};
+/// # fn record(window_id: WindowId) {
+/// let stats = RefCell::new(FrameStats::new(600));
+/// on_frame_timing(window_id, move |timing| {
+///     let mut stats = stats.borrow_mut();
+///     stats.push(timing.total());
+///     if stats.len() == 600 {
+///         println!("p95: {:?}, p99: {:?}", stats.percentile(95.0), stats.percentile(99.0));
+///         stats.clear();
+///     }
+/// });
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FrameStats {
+    durations: VecDeque<Duration>,
+    capacity: usize,
+}
+
+impl FrameStats {
+    /// Keeps the durations of the last `capacity` frames.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            durations: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Record the duration of a frame, forgetting the oldest one if there are already
+    /// `capacity` durations.
+    pub fn push(&mut self, duration: Duration) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.durations.len() == self.capacity {
+            self.durations.pop_front();
+        }
+        self.durations.push_back(duration);
+    }
+
+    pub fn len(&self) -> usize {
+        self.durations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.durations.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.durations.clear();
+    }
+
+    /// The duration which `percentile` percent of the recorded frames didn't exceed, like `95.0`
+    /// for the p95 frame time. This is `None` if no frames were recorded.
+    pub fn percentile(&self, percentile: f64) -> Option<Duration> {
+        if self.durations.is_empty() {
+            return None;
+        }
+        let mut durations: Vec<_> = self.durations.iter().copied().collect();
+        durations.sort_unstable();
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * durations.len() as f64).ceil() as usize;
+        Some(durations[rank.saturating_sub(1)])
+    }
+}
+
+/// Set a handler which is called after each frame of the window is painted, with the time spent
+/// on the frame. This can be used to find slow frames, for example with [`FrameStats`].
+///
+/// This replaces any previously set handler.
+pub fn on_frame_timing(window_id: WindowId, handler: impl Fn(&FrameTiming) + 'static) {
+    add_app_update_event(AppUpdateEvent::SetFrameTimingHandler {
+        window_id,
+        handler: Box::new(handler),
+    });
+}
+
+/// Get a signal tracking if the current window has focus.
+///
+/// This must be called while building or updating the views of a window. Outside of a window
+/// the signal is always `false`.
+pub fn use_window_focus() -> ReadSignal<bool> {
+    window_signals(get_current_view())
+        .map(|signals| signals.focused.read_only())
+        .unwrap_or_else(|| create_rw_signal(false).read_only())
+}
+
+/// Get a signal tracking if the current window is hidden from view, for example by being
+/// minimized or covered by other windows. Not all platforms report this.
+///
+/// This must be called while building or updating the views of a window. Outside of a window
+/// the signal is always `false`.
+pub fn use_window_occluded() -> ReadSignal<bool> {
+    window_signals(get_current_view())
+        .map(|signals| signals.occluded.read_only())
+        .unwrap_or_else(|| create_rw_signal(false).read_only())
+}
+
+/// Get a signal tracking the view of the current window which has keyboard focus.
+///
+/// This must be called while building or updating the views of a window. Outside of a window
+/// the signal is always `None`.
+pub fn use_focused_view() -> ReadSignal<Option<Id>> {
+    window_signals(get_current_view())
+        .map(|signals| signals.focused_view.read_only())
+        .unwrap_or_else(|| create_rw_signal(None).read_only())
+}
+
+/// Get a signal tracking the scale factor of the current window, as reported by the platform.
+///
+/// This changes when the system scale factor changes or the window is moved to a monitor with a
+/// different scale factor. It doesn't include the scale set by `update_window_scale`.
+pub fn use_window_scale() -> ReadSignal<f64> {
+    window_signals(get_current_view())
+        .map(|signals| signals.scale.read_only())
+        .unwrap_or_else(|| create_rw_signal(1.0).read_only())
+}
+
+/// Get a signal tracking the inner size of the current window in logical pixels.
+///
+/// The signal is updated when the window is resized, before the window is styled and laid out
+/// again, so changes made in response to it are shown in the same frame.
+///
+/// This must be called while building or updating the views of a window. Outside of a window
+/// the signal is always zero.
+pub fn use_window_size() -> ReadSignal<Size> {
+    window_signals(get_current_view())
+        .map(|signals| signals.size.read_only())
+        .unwrap_or_else(|| create_rw_signal(Size::ZERO).read_only())
+}
+
+/// Get a memo of the breakpoint `f` picks for the width of the current window, which only
+/// changes when `f` returns a different value. This is used to change the style or views when
+/// the window crosses some widths.
+///
+/// ```rust
+/// # use floem::{views::{empty, Decorators}, window::use_breakpoint};
+/// let compact = use_breakpoint(|width| width < 600.0);
+/// let sidebar = empty().style(move |s| s.width(200.0).apply_if(compact.get(), |s| s.hide()));
+/// ```
+pub fn use_breakpoint<T: PartialEq + 'static>(f: impl Fn(f64) -> T + 'static) -> Memo<T> {
+    let size = use_window_size();
+    create_memo(move |_| f(size.get().width))
+}
+
+/// Get a signal tracking the refresh rate in Hz of the monitor the current window is on.
+///
+/// This is `None` if the platform doesn't report the refresh rate.
+pub fn use_monitor_refresh_rate() -> ReadSignal<Option<f64>> {
+    window_signals(get_current_view())
+        .map(|signals| signals.refresh_rate.read_only())
+        .unwrap_or_else(|| create_rw_signal(None).read_only())
+}
+
+/// Get a signal tracking the system theme of the current window.
+///
+/// To react to theme changes for the whole application, see
+/// [`Application::on_system_theme_changed`](crate::Application::on_system_theme_changed).
+pub fn use_system_theme() -> ReadSignal<Theme> {
+    window_signals(get_current_view())
+        .map(|signals| signals.system_theme.read_only())
+        .unwrap_or_else(|| create_rw_signal(Theme::Light).read_only())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc, time::Duration};
+
+    use floem_reactive::{create_rw_signal, provide_context, use_context};
+    use kurbo::{Point, Rect, Size};
+    use peniko::Color;
+
+    use super::{
+        DeviceId, ElementState, FrameStats, HeadlessWindow, MouseButton, PhysicalPosition,
+        WindowEvent,
+    };
+    use crate::{
+        action::register_shortcut,
+        context::{EventPropagation, PaintCx},
+        event::{Event, EventListener},
+        id::Id,
+        keyboard::{Key, KeyCombination, KeyInput, ModifiersState},
+        menu::{Menu, MenuItem},
+        renderer::render_to_buffer,
+        style::Overflow,
+        view::{View, ViewData},
+        views::{container, empty, h_stack, Decorators},
+    };
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Theme(&'static str);
+
+    #[test]
+    fn window_events_reach_views() {
+        let clicked = create_rw_signal(false);
+        let mut window = HeadlessWindow::new(
+            move |_| {
+                empty()
+                    .style(|s| s.size(100.0, 100.0))
+                    .on_click_stop(move |_| clicked.set(true))
+            },
+            Size::new(200.0, 200.0),
+            2.0,
+        );
+        // SAFETY: The device id is only compared with other device ids.
+        let device_id = unsafe { DeviceId::dummy() };
+        let mut click = |x, y| {
+            window.window_event(WindowEvent::CursorMoved {
+                device_id,
+                position: PhysicalPosition::new(x, y),
+            });
+            for state in [ElementState::Pressed, ElementState::Released] {
+                window.window_event(WindowEvent::MouseInput {
+                    device_id,
+                    state,
+                    button: MouseButton::Left,
+                });
+            }
+        };
+        // Positions are in physical pixels, so this is outside of the view
+        click(250.0, 250.0);
+        assert!(!clicked.get_untracked());
+        click(150.0, 150.0);
+        assert!(clicked.get_untracked());
+    }
+
+    #[test]
+    fn simulated_keys_go_through_the_key_dispatch() {
+        let keys = create_rw_signal(Vec::new());
+        let saved = create_rw_signal(false);
+        let save = KeyCombination::new(Key::Character("s".into()), ModifiersState::CONTROL);
+        let mut window = HeadlessWindow::new(
+            move |_| {
+                register_shortcut(save, move || saved.set(true));
+                let input = empty().keyboard_navigatable().on_event_stop(
+                    EventListener::KeyDown,
+                    move |event| {
+                        if let Event::KeyDown(event) = event {
+                            keys.update(|keys| keys.push(event.key.logical_key.clone()));
+                        }
+                    },
+                );
+                input.id().request_focus();
+                input
+            },
+            Size::new(100.0, 100.0),
+            1.0,
+        );
+        window.simulate_key(Key::Character("s".into()));
+        assert_eq!(keys.get_untracked(), [Key::Character("s".into())]);
+        assert!(!saved.get_untracked());
+
+        // Shortcuts with Control take precedence over the focused view
+        window.window_event(WindowEvent::ModifiersChanged(
+            ModifiersState::CONTROL.into(),
+        ));
+        window.simulate_key(Key::Character("s".into()));
+        assert_eq!(keys.get_untracked().len(), 1);
+        assert!(saved.get_untracked());
+    }
+
+    #[test]
+    fn unhandled_keys_go_to_the_ancestors_of_the_focused_view() {
+        let keys = create_rw_signal(Vec::new());
+        let listener = move |name: &'static str, handles: &'static str| {
+            move |event: &Event| {
+                let Event::KeyDown(event) = event else {
+                    return EventPropagation::Continue;
+                };
+                keys.update(|keys| keys.push(name));
+                if event.key.logical_key == Key::Character(handles.into()) {
+                    EventPropagation::Stop
+                } else {
+                    EventPropagation::Continue
+                }
+            }
+        };
+        let mut window = HeadlessWindow::new(
+            move |_| {
+                let focused = empty()
+                    .keyboard_navigatable()
+                    .on_event(EventListener::KeyDown, listener("focused", "a"));
+                focused.id().request_focus();
+                container(
+                    container(focused).on_event(EventListener::KeyDown, listener("inner", "b")),
+                )
+                .on_event(EventListener::KeyDown, listener("outer", "c"))
+            },
+            Size::new(100.0, 100.0),
+            1.0,
+        );
+        window.simulate_key(Key::Character("a".into()));
+        assert_eq!(keys.get_untracked(), ["focused"]);
+
+        // The innermost ancestor handling the key stops it
+        keys.set(Vec::new());
+        window.simulate_key(Key::Character("b".into()));
+        assert_eq!(keys.get_untracked(), ["focused", "inner"]);
+
+        keys.set(Vec::new());
+        window.simulate_key(Key::Character("d".into()));
+        assert_eq!(keys.get_untracked(), ["focused", "inner", "outer"]);
+    }
+
+    #[test]
+    fn access_keys_ignore_the_characters_alt_composes() {
+        let clicked = create_rw_signal(false);
+        let mut window = HeadlessWindow::new(
+            move |_| {
+                empty()
+                    .access_key('f')
+                    .on_click_stop(move |_| clicked.set(true))
+            },
+            Size::new(100.0, 100.0),
+            1.0,
+        );
+        window.window_event(WindowEvent::ModifiersChanged(ModifiersState::ALT.into()));
+        // Option+F types ƒ on macOS
+        for state in [ElementState::Pressed, ElementState::Released] {
+            let key = KeyInput::new(Key::Character("ƒ".into()), state)
+                .with_key_without_modifiers(Key::Character("f".into()));
+            window.handle.key_event(key);
+        }
+        assert!(clicked.get_untracked());
+    }
+
+    #[test]
+    fn focus_is_lost_before_it_is_gained() {
+        let events = create_rw_signal(Vec::new());
+        let view = move |name: &'static str| {
+            empty()
+                .style(|s| s.size(50.0, 50.0))
+                .keyboard_navigatable()
+                .on_event_cont(EventListener::FocusGained, move |_| {
+                    events.update(|events| events.push((name, "gained")))
+                })
+                .on_event_cont(EventListener::FocusLost, move |_| {
+                    events.update(|events| events.push((name, "lost")))
+                })
+        };
+        let mut window = HeadlessWindow::new(
+            move |_| h_stack((view("a"), view("b"))),
+            Size::new(200.0, 100.0),
+            1.0,
+        );
+        window.simulate_click(Point::new(25.0, 25.0));
+        window.simulate_click(Point::new(75.0, 25.0));
+        assert_eq!(
+            events.get_untracked(),
+            [("a", "gained"), ("a", "lost"), ("b", "gained")]
+        );
+    }
+
+    #[test]
+    fn disabled_focus_wins_over_keyboard_navigation() {
+        let focused = create_rw_signal(false);
+        let mut window = HeadlessWindow::new(
+            move |_| {
+                empty()
+                    .style(|s| s.size(50.0, 50.0))
+                    .disable_focus()
+                    .keyboard_navigatable()
+                    .on_event_cont(EventListener::FocusGained, move |_| focused.set(true))
+            },
+            Size::new(100.0, 100.0),
+            1.0,
+        );
+        window.simulate_click(Point::new(25.0, 25.0));
+        assert!(!focused.get_untracked());
+    }
+
+    #[test]
+    fn handlers_see_contexts_of_the_window() {
+        let seen = create_rw_signal(None);
+        let mut window = HeadlessWindow::new(
+            move |_| {
+                provide_context(Theme("dark"));
+                empty()
+                    .style(|s| s.size(100.0, 100.0))
+                    .on_click_stop(move |_| seen.set(use_context::<Theme>()))
+            },
+            Size::new(200.0, 200.0),
+            1.0,
+        );
+        window.simulate_click(Point::new(50.0, 50.0));
+        assert_eq!(seen.get_untracked(), Some(Theme("dark")));
+        // The value is only provided for the views of the window
+        assert_eq!(use_context::<Theme>(), None);
+    }
+
+    #[test]
+    fn children_are_clipped_to_the_content_box() {
+        let clicked = create_rw_signal(0);
+        let child = empty()
+            .style(|s| {
+                s.absolute()
+                    .inset_left(-20.0)
+                    .inset_top(-20.0)
+                    .size(100.0, 100.0)
+                    .background(Color::RED)
+            })
+            .on_click_stop(move |_| clicked.update(|clicked| *clicked += 1));
+        let parent = container(child).style(|s| {
+            s.size(60.0, 60.0)
+                .border(10.0)
+                .padding(10.0)
+                .overflow(Overflow::Hidden)
+        });
+        let mut window = HeadlessWindow::new(move |_| parent, Size::new(100.0, 100.0), 1.0);
+        let pixel = |frame: &[u8], x: usize, y: usize| frame[(y * 100 + x) * 4..][..4].to_vec();
+        let frame = window.render();
+        assert_eq!(pixel(&frame, 30, 30), [255, 0, 0, 255]);
+        assert_ne!(pixel(&frame, 15, 15), [255, 0, 0, 255]);
+        assert_ne!(pixel(&frame, 5, 5), [255, 0, 0, 255]);
+
+        // The border and the padding don't hit the child
+        window.simulate_click(Point::new(5.0, 5.0));
+        window.simulate_click(Point::new(15.0, 15.0));
+        assert_eq!(clicked.get_untracked(), 0);
+        window.simulate_click(Point::new(30.0, 30.0));
+        assert_eq!(clicked.get_untracked(), 1);
+    }
+
+    #[test]
+    fn mounted_listeners_run_once() {
+        let mounted = create_rw_signal(Vec::new());
+        let mut window = HeadlessWindow::new(
+            move |_| {
+                // The listener owns the name, which it can give away as it's only called once
+                let name = String::from("view");
+                empty().on_mounted(move || mounted.update(|mounted| mounted.push(name)))
+            },
+            Size::new(100.0, 100.0),
+            1.0,
+        );
+        window.render();
+        window.render();
+        assert_eq!(mounted.get_untracked(), ["view"]);
+    }
+
+    /// Fills its 20 by 20 pixels with the color of the cell.
+    struct Swatch {
+        data: ViewData,
+        color: Rc<Cell<Color>>,
+    }
+
+    impl View for Swatch {
+        fn view_data(&self) -> &ViewData {
+            &self.data
+        }
+
+        fn view_data_mut(&mut self) -> &mut ViewData {
+            &mut self.data
+        }
+
+        fn paint(&mut self, cx: &mut PaintCx) {
+            cx.fill(&Rect::new(0.0, 0.0, 20.0, 20.0), self.color.get(), 0.0);
+        }
+    }
+
+    #[test]
+    fn repaint_includes_overflowing_children() {
+        let color = Rc::new(Cell::new(Color::RED));
+        let swatch = Swatch {
+            data: ViewData::new(Id::next()),
+            color: color.clone(),
+        }
+        .style(|s| {
+            s.absolute()
+                .inset_left(30.0)
+                .inset_top(30.0)
+                .size(20.0, 20.0)
+        });
+        let parent = container(swatch).style(|s| s.size(20.0, 20.0));
+        let parent_id = parent.id();
+        let mut window = HeadlessWindow::new(move |_| parent, Size::new(100.0, 100.0), 1.0);
+        let pixel = |frame: &[u8]| frame[(40 * 100 + 40) * 4..][..4].to_vec();
+        assert_eq!(pixel(&window.render()), [255, 0, 0, 255]);
+
+        // Only the area painted by the parent and the swatch is repainted
+        color.set(Color::BLUE);
+        parent_id.request_paint();
+        assert_eq!(pixel(&window.render()), [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn repaint_includes_what_views_paint_outside_of_their_box() {
+        let swatch = |color: &Rc<Cell<Color>>| {
+            Swatch {
+                data: ViewData::new(Id::next()),
+                color: color.clone(),
+            }
+            .style(|s| s.size(10.0, 10.0))
+        };
+        let color = Rc::new(Cell::new(Color::RED));
+        let view = swatch(&color);
+        let id = view.id();
+        let mut window = HeadlessWindow::new(move |_| view, Size::new(40.0, 40.0), 1.0);
+        window.render();
+
+        // The swatch paints 20 by 20 pixels, so that's repainted and not just its box
+        color.set(Color::BLUE);
+        id.request_paint();
+        let expected = render_to_buffer(
+            swatch(&Rc::new(Cell::new(Color::BLUE))),
+            Size::new(40.0, 40.0),
+            1.0,
+        );
+        assert!(window.render() == expected);
+    }
+
+    /// Fills a rect as wide as the cell and 30 pixels high.
+    struct Stripe {
+        data: ViewData,
+        width: Rc<Cell<f64>>,
+    }
+
+    impl View for Stripe {
+        fn view_data(&self) -> &ViewData {
+            &self.data
+        }
+
+        fn view_data_mut(&mut self) -> &mut ViewData {
+            &mut self.data
+        }
+
+        fn paint(&mut self, cx: &mut PaintCx) {
+            cx.fill(
+                &Rect::new(0.0, 0.0, self.width.get(), 30.0),
+                Color::RED,
+                0.0,
+            );
+        }
+    }
+
+    #[test]
+    fn faded_views_are_painted_outside_of_their_last_layer() {
+        let width = Rc::new(Cell::new(30.0));
+        let stripe = Stripe {
+            data: ViewData::new(Id::next()),
+            width: width.clone(),
+        };
+        let stripe_id = stripe.id();
+        let faded = container(stripe).style(|s| s.size(30.0, 30.0).opacity(0.5));
+        let mut window = HeadlessWindow::new(move |_| faded, Size::new(40.0, 40.0), 1.0);
+        let pixel = |frame: &[u8], x: usize| frame[(10 * 40 + x) * 4..][..4].to_vec();
+        let frame = window.render();
+        assert_ne!(pixel(&frame, 20), [255, 255, 255, 255]);
+        assert_eq!(pixel(&frame, 35), [255, 255, 255, 255]);
+
+        // The layer of the faded view covers where it painted on the last frame, so what's
+        // painted outside of it now is painted with a larger layer
+        width.set(40.0);
+        stripe_id.request_paint();
+        let frame = window.render();
+        assert_eq!(pixel(&frame, 35), pixel(&frame, 20));
+    }
+
+    #[test]
+    fn layers_are_above_what_is_painted_before_them() {
+        let raised = empty().style(|s| s.size(20.0, 20.0).z_index(10).background(Color::RED));
+        let faded = empty().style(|s| {
+            s.absolute()
+                .inset_left(0.0)
+                .inset_top(0.0)
+                .size(20.0, 20.0)
+                .background(Color::BLUE)
+                .opacity(0.5)
+        });
+        let mut window = HeadlessWindow::new(
+            move |_| h_stack((container(raised), faded)),
+            Size::new(40.0, 40.0),
+            1.0,
+        );
+        // The z-index of the red view only orders it among its siblings, so the faded view
+        // painted after it is above it
+        let frame = window.render();
+        let pixel = &frame[(10 * 40 + 10) * 4..][..4];
+        let (red, green, blue) = (pixel[0], pixel[1], pixel[2]);
+        assert!(red < 192 && green < 64 && blue > 64, "{red} {green} {blue}");
+    }
+
+    #[test]
+    fn window_menu_shortcuts_are_replaced_with_the_menu() {
+        let key = |c: &str| KeyCombination::new(Key::Character(c.into()), ModifiersState::CONTROL);
+        let save = create_rw_signal(key("s"));
+        let mut window = HeadlessWindow::new(
+            move |_| {
+                register_shortcut(key("o"), || {});
+                empty().window_menu(move || {
+                    Menu::new("").entry(
+                        Menu::new("File")
+                            .entry(MenuItem::new("Save").shortcut(save.get()).action(|| {}))
+                            .entry(MenuItem::new("Quit").shortcut(key("q")).enabled(false)),
+                    )
+                })
+            },
+            Size::new(100.0, 100.0),
+            1.0,
+        );
+        // The shortcuts registered by views take precedence over the ones of the menu
+        assert_eq!(window.handle.shortcuts(), [key("o"), key("s")]);
+
+        save.set(key("w"));
+        window.render();
+        assert_eq!(window.handle.shortcuts(), [key("o"), key("w")]);
+    }
+
+    #[test]
+    fn frame_stats_percentiles() {
+        let mut stats = FrameStats::new(100);
+        assert_eq!(stats.percentile(50.0), None);
+        for ms in (1..=100).rev() {
+            stats.push(Duration::from_millis(ms));
+        }
+        assert_eq!(stats.percentile(0.0), Some(Duration::from_millis(1)));
+        assert_eq!(stats.percentile(50.0), Some(Duration::from_millis(50)));
+        assert_eq!(stats.percentile(95.0), Some(Duration::from_millis(95)));
+        assert_eq!(stats.percentile(99.5), Some(Duration::from_millis(100)));
+        assert_eq!(stats.percentile(100.0), Some(Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn frame_stats_forget_the_oldest_frames() {
+        let mut stats = FrameStats::new(3);
+        for ms in [50, 1, 2, 3] {
+            stats.push(Duration::from_millis(ms));
+        }
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats.percentile(100.0), Some(Duration::from_millis(3)));
+
+        let mut stats = FrameStats::new(0);
+        stats.push(Duration::from_millis(1));
+        assert!(stats.is_empty());
+    }
+}
@@ -1,26 +1,35 @@
 use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
     mem,
+    path::PathBuf,
     rc::Rc,
     time::{Duration, Instant},
 };
 
 use floem_reactive::{with_scope, RwSignal, Scope};
-use floem_renderer::Renderer;
+use floem_renderer::{
+    cosmic_text::{Attrs, AttrsList, TextLayout},
+    Renderer,
+};
 use image::DynamicImage;
 use indexmap::IndexMap;
-use kurbo::{Affine, Point, Rect, Size, Vec2};
+use kurbo::{Affine, Point, Rect, Shape, Size, Vec2};
 use winit::{
     dpi::{LogicalPosition, LogicalSize},
-    event::{ElementState, Ime, MouseButton, MouseScrollDelta},
+    event::{ElementState, Ime, MouseButton, MouseScrollDelta, Touch, TouchPhase, WindowEvent},
     keyboard::{Key, ModifiersState, NamedKey},
-    window::{CursorIcon, WindowId},
+    window::{CursorGrabMode, CursorIcon, Fullscreen, WindowButtons, WindowId, WindowLevel},
 };
 
+#[cfg(feature = "accesskit")]
+use crate::accessibility::AccessibilityAdapter;
 #[cfg(target_os = "linux")]
 use crate::unit::UnitExt;
 #[cfg(target_os = "linux")]
 use crate::views::{container_box, stack, Decorators};
 use crate::{
+    action::{cancel_timers_with, ModalScrimClass},
     animate::{AnimPropKind, AnimUpdateMsg, AnimValue, AnimatedProp, SizeUnit},
     context::{
         AppState, ComputeLayoutCx, EventCx, FrameUpdate, LayoutCx, MoveListener, PaintCx,
@@ -29,20 +38,22 @@ use crate::{
     event::{Event, EventListener},
     id::{Id, IdPath, ID_PATHS},
     inspector::{self, Capture, CaptureState, CapturedView},
-    keyboard::KeyEvent,
+    keyboard::{KeyCombination, KeyEvent, KeyInput, ShortcutId},
     menu::Menu,
     nav::view_arrow_navigation,
-    pointer::{PointerButton, PointerInputEvent, PointerMoveEvent, PointerWheelEvent},
+    pointer::{PointerButton, PointerInputEvent, PointerMoveEvent, PointerWheelEvent, TouchEvent},
     profiler::Profile,
-    style::{CursorStyle, Style, StyleSelector},
+    style::{CursorStyle, Style, StyleClass, StyleSelector},
     update::{
         UpdateMessage, ANIM_UPDATE_MESSAGES, CENTRAL_DEFERRED_UPDATE_MESSAGES,
         CENTRAL_UPDATE_MESSAGES, CURRENT_RUNNING_VIEW_HANDLE, DEFERRED_UPDATE_MESSAGES,
         UPDATE_MESSAGES,
     },
-    view::{view_children_set_parent_id, view_tab_navigation, View, ViewData},
+    view::{default_event, view_children_set_parent_id, view_tab_navigation, View, ViewData},
     view_data::{update_data, ChangeFlags},
     widgets::{default_theme, Theme},
+    window::{CloseResponse, FrameTiming, WindowGeometry},
+    EventPropagation,
 };
 
 /// The top-level window handle that owns the winit Window.
@@ -53,7 +64,7 @@ use crate::{
 /// - requesting a new animation frame from the backend
 pub(crate) struct WindowHandle {
     pub(crate) window: Option<winit::window::Window>,
-    window_id: WindowId,
+    pub(crate) window_id: WindowId,
     id: Id,
     /// Reactive Scope for this WindowHandle
     scope: Scope,
@@ -63,16 +74,48 @@ pub(crate) struct WindowHandle {
     size: RwSignal<Size>,
     theme: Option<Theme>,
     pub(crate) profile: Option<Profile>,
-    os_theme: RwSignal<Option<winit::window::Theme>>,
     is_maximized: bool,
+    is_fullscreen: bool,
+    window_level: WindowLevel,
     transparent: bool,
     pub(crate) scale: f64,
     pub(crate) modifiers: ModifiersState,
+    /// The number of pixels scrolled for each line of a mouse wheel
+    pub(crate) scroll_line_height: f64,
+    /// The maximum time between clicks which counts as a double or triple click
+    pub(crate) multi_click_interval: Duration,
     pub(crate) cursor_position: Point,
     pub(crate) window_position: Point,
     pub(crate) last_pointer_down: Option<(u8, Point, Instant)>,
+    /// The view that received the last `HoveredFile` event
+    file_hover_target: Option<Id>,
+    pub(crate) close_requested_handler: Option<Box<dyn Fn() -> CloseResponse>>,
+    pub(crate) frame_timing_handler: Option<Box<dyn Fn(&FrameTiming)>>,
+    /// Whether the inspector overlay is shown over the view under the cursor
+    inspector_overlay: bool,
+    /// Whether the timing of the last frame is shown in the window
+    pub(crate) show_frame_timing: bool,
+    /// The timing of the frame which is being prepared
+    frame_timing: FrameTiming,
+    /// The timing of the last painted frame
+    last_frame_timing: Option<FrameTiming>,
+    /// When the last frame started painting
+    last_frame: Option<Instant>,
+    /// Keyboard shortcuts of the window, later ones take precedence
+    shortcuts: Vec<(ShortcutId, KeyCombination, Rc<dyn Fn()>)>,
+    /// The shortcuts registered for the items of the window menu, which are removed when the
+    /// menu changes
+    menu_shortcuts: Vec<ShortcutId>,
+    /// The touch which is emulating the primary mouse button
+    primary_touch: Option<u64>,
+    /// Whether something changed which needs to be painted on the next frame
+    needs_paint: bool,
+    /// Whether the next redraw was requested by the window handle, instead of the platform
+    redraw_requested: bool,
     #[cfg(target_os = "linux")]
     pub(crate) context_menu: RwSignal<Option<(Menu, Point)>>,
+    #[cfg(feature = "accesskit")]
+    accessibility: Option<AccessibilityAdapter>,
 }
 
 impl WindowHandle {
@@ -81,16 +124,94 @@ impl WindowHandle {
         view_fn: impl FnOnce(winit::window::WindowId) -> Box<dyn View> + 'static,
         transparent: bool,
         themed: bool,
+        window_level: WindowLevel,
+        fallback_theme: winit::window::Theme,
     ) -> Self {
-        let scope = Scope::new();
-        let window_id = window.id();
-        let id = Id::next();
         let scale = window.scale_factor();
         let size: LogicalSize<f64> = window.inner_size().to_logical(scale);
         let size = Size::new(size.width, size.height);
-        let size = scope.create_rw_signal(Size::new(size.width, size.height));
-        let theme = scope.create_rw_signal(window.theme());
-        let is_maximized = window.is_maximized();
+        let platform = PlatformState {
+            window_id: window.id(),
+            scale,
+            size,
+            is_maximized: window.is_maximized(),
+            is_fullscreen: window.fullscreen().is_some(),
+            focused: window.has_focus(),
+            refresh_rate: monitor_refresh_rate(&window),
+            system_theme: window.theme().unwrap_or(fallback_theme),
+        };
+        let paint_state = PaintState::new(&window, scale, size * scale, transparent);
+        #[allow(unused_mut)]
+        let mut window_handle = Self::with_platform_state(
+            Some(window),
+            platform,
+            paint_state,
+            view_fn,
+            transparent,
+            themed,
+            window_level,
+        );
+        #[cfg(feature = "accesskit")]
+        {
+            window_handle.accessibility = window_handle
+                .window
+                .as_ref()
+                .and_then(|window| AccessibilityAdapter::new(window, window_handle.id));
+        }
+        window_handle
+    }
+
+    /// Creates a window handle without a window, which paints into a buffer returned by
+    /// [`WindowHandle::paint`].
+    pub(crate) fn new_headless(
+        view_fn: impl FnOnce(winit::window::WindowId) -> Box<dyn View> + 'static,
+        size: Size,
+        scale: f64,
+    ) -> Self {
+        let platform = PlatformState {
+            // SAFETY: The dummy id is only guaranteed to be equal to itself, so it may be the id
+            // of a real window. It's never passed to winit, and headless windows aren't added to
+            // the application, so Floem doesn't look up windows by it either. Views could still
+            // use it with functions like `close_window` when an application is running, which
+            // is why headless windows are meant for tests.
+            window_id: unsafe { WindowId::dummy() },
+            scale,
+            size,
+            is_maximized: false,
+            is_fullscreen: false,
+            focused: true,
+            refresh_rate: None,
+            system_theme: winit::window::Theme::Light,
+        };
+        let paint_state = PaintState::new_headless(scale, size * scale);
+        Self::with_platform_state(
+            None,
+            platform,
+            paint_state,
+            view_fn,
+            false,
+            true,
+            WindowLevel::Normal,
+        )
+    }
+
+    fn with_platform_state(
+        window: Option<winit::window::Window>,
+        platform: PlatformState,
+        paint_state: PaintState,
+        view_fn: impl FnOnce(winit::window::WindowId) -> Box<dyn View> + 'static,
+        transparent: bool,
+        themed: bool,
+        window_level: WindowLevel,
+    ) -> Self {
+        let scope = Scope::new();
+        cancel_timers_with(scope);
+        let window_id = platform.window_id;
+        let id = Id::next();
+        let scale = platform.scale;
+        let size = scope.create_rw_signal(platform.size);
+        let is_maximized = platform.is_maximized;
+        let is_fullscreen = platform.is_fullscreen;
 
         set_current_view(id);
 
@@ -98,6 +219,19 @@ impl WindowHandle {
             id_paths.borrow_mut().insert(id, IdPath(vec![id]));
         });
 
+        let signals = WindowSignals {
+            focused: scope.create_rw_signal(platform.focused),
+            occluded: scope.create_rw_signal(false),
+            focused_view: scope.create_rw_signal(None),
+            scale: scope.create_rw_signal(scale),
+            size,
+            refresh_rate: scope.create_rw_signal(platform.refresh_rate),
+            system_theme: scope.create_rw_signal(platform.system_theme),
+        };
+        WINDOW_SIGNALS.with(|window_signals| {
+            window_signals.borrow_mut().insert(id, signals);
+        });
+
         #[cfg(target_os = "linux")]
         let context_menu = scope.create_rw_signal(None);
 
@@ -124,9 +258,8 @@ impl WindowHandle {
             overlays: Default::default(),
         };
 
-        let paint_state = PaintState::new(&window, scale, size.get_untracked() * scale);
         let mut window_handle = Self {
-            window: Some(window),
+            window,
             window_id,
             id,
             scope,
@@ -135,22 +268,123 @@ impl WindowHandle {
             paint_state,
             size,
             theme: themed.then(default_theme),
-            os_theme: theme,
             is_maximized,
+            is_fullscreen,
+            window_level,
             transparent,
             profile: None,
             scale,
             modifiers: ModifiersState::default(),
+            scroll_line_height: 60.0,
+            multi_click_interval: Duration::from_millis(500),
             cursor_position: Point::ZERO,
             window_position: Point::ZERO,
             #[cfg(target_os = "linux")]
             context_menu,
             last_pointer_down: None,
+            file_hover_target: None,
+            primary_touch: None,
+            needs_paint: true,
+            redraw_requested: false,
+            close_requested_handler: None,
+            frame_timing_handler: None,
+            inspector_overlay: false,
+            show_frame_timing: false,
+            frame_timing: FrameTiming::default(),
+            last_frame_timing: None,
+            last_frame: None,
+            shortcuts: Vec::new(),
+            menu_shortcuts: Vec::new(),
+            #[cfg(feature = "accesskit")]
+            accessibility: None,
         };
         window_handle.app_state.set_root_size(size.get_untracked());
         window_handle
     }
 
+    /// Handles an event the platform reported for the window, except for closing it, which is
+    /// handled by the application.
+    pub(crate) fn window_event(&mut self, event: WindowEvent) {
+        match event {
+            WindowEvent::ActivationTokenDone { .. } => {}
+            WindowEvent::Resized(size) => {
+                let size: LogicalSize<f64> = size.to_logical(self.scale);
+                let size = Size::new(size.width, size.height);
+                self.size(size);
+            }
+            WindowEvent::Moved(position) => {
+                let position: LogicalPosition<f64> = position.to_logical(self.scale);
+                let point = Point::new(position.x, position.y);
+                self.position(point);
+            }
+            // Closing the window is up to the application
+            WindowEvent::CloseRequested | WindowEvent::Destroyed => {}
+            WindowEvent::DroppedFile(path) => {
+                self.dropped_file(path);
+            }
+            WindowEvent::HoveredFile(path) => {
+                self.hovered_file(Some(path));
+            }
+            WindowEvent::HoveredFileCancelled => {
+                self.hovered_file(None);
+            }
+            WindowEvent::Focused(focused) => {
+                self.focused(focused);
+            }
+            WindowEvent::KeyboardInput { event, .. } => {
+                self.key_event(event.into());
+            }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers_changed(modifiers.state());
+            }
+            WindowEvent::Ime(ime) => {
+                self.ime(ime);
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                let position: LogicalPosition<f64> = position.to_logical(self.scale);
+                let point = Point::new(position.x, position.y);
+                self.pointer_move(point);
+            }
+            WindowEvent::CursorEntered { .. } => {}
+            WindowEvent::CursorLeft { .. } => {
+                self.pointer_leave();
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                self.mouse_wheel(delta);
+            }
+            WindowEvent::MouseInput { state, button, .. } => {
+                self.mouse_input(button, state);
+            }
+            WindowEvent::TouchpadMagnify { delta, phase, .. } => {
+                self.pinch_gesture(delta, phase);
+            }
+            WindowEvent::SmartMagnify { .. } => {
+                self.smart_magnify();
+            }
+            WindowEvent::TouchpadRotate { .. } => {}
+            WindowEvent::TouchpadPressure { .. } => {}
+            WindowEvent::AxisMotion { .. } => {}
+            WindowEvent::Touch(touch) => {
+                self.touch(touch);
+            }
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                self.scale(scale_factor);
+            }
+            WindowEvent::ThemeChanged(theme) => {
+                self.os_theme_changed(theme);
+            }
+            WindowEvent::Occluded(occluded) => {
+                self.occluded(occluded);
+            }
+            WindowEvent::MenuAction(id) => {
+                self.menu_action(id);
+            }
+            WindowEvent::RedrawRequested => {
+                self.render_frame();
+            }
+        }
+    }
+
     pub fn event(&mut self, event: Event) {
         set_current_view(self.id);
         let event = event.scale(self.app_state.scale);
@@ -160,15 +394,20 @@ impl WindowHandle {
         };
 
         let is_pointer_move = matches!(&event, Event::PointerMove(_));
-        let (was_hovered, was_dragging_over) = if is_pointer_move {
-            cx.app_state.cursor = None;
+        let (was_hovered, was_dragging_over, was_cursor) = if is_pointer_move {
+            let was_cursor = cx.app_state.cursor.take();
             let was_hovered = std::mem::take(&mut cx.app_state.hovered);
             let was_dragging_over = std::mem::take(&mut cx.app_state.dragging_over);
+            if let Some(dragging) = cx.app_state.dragging.as_mut() {
+                dragging.target_accepts = None;
+            }
 
-            (Some(was_hovered), Some(was_dragging_over))
+            (Some(was_hovered), Some(was_dragging_over), was_cursor)
         } else {
-            (None, None)
+            (None, None, None)
         };
+        // The view getting the pointer events regardless of where the pointer is
+        let capture = cx.app_state.pointer_capture.or(cx.app_state.active);
 
         let is_pointer_down = matches!(&event, Event::PointerDown(_));
         let was_focused = if is_pointer_down {
@@ -180,73 +419,119 @@ impl WindowHandle {
 
         if event.needs_focus() {
             let mut processed = false;
-
-            if !processed {
-                if let Some(id) = cx.app_state.focus {
-                    let id_path = ID_PATHS.with(|paths| paths.borrow().get(&id).cloned());
-                    if let Some(id_path) = id_path {
-                        processed |= cx
-                            .unconditional_view_event(
-                                &mut self.view,
-                                Some(id_path.dispatch()),
-                                event.clone(),
-                            )
-                            .is_processed();
-                    } else {
-                        cx.app_state.focus = None;
-                    }
+            let modal = self.view.modal().map(|modal| modal.id());
+            let focus_scope = self.view.focus_scope(cx.app_state);
+
+            let mut ancestors = Vec::new();
+            if let Some(id) = cx.app_state.focus {
+                let id_path = ID_PATHS.with(|paths| paths.borrow().get(&id).cloned());
+                // Views outside of the modal don't get key events
+                let id_path = id_path.filter(|id_path| {
+                    modal.map_or(true, |modal| id_path.dispatch().contains(&modal))
+                });
+                if let Some(id_path) = id_path {
+                    processed |= cx
+                        .unconditional_view_event(
+                            &mut self.view,
+                            Some(id_path.dispatch()),
+                            event.clone(),
+                        )
+                        .is_processed();
+                    let dispatch = id_path.dispatch();
+                    let dispatch = &dispatch[..dispatch.len() - 1];
+                    // The views containing the modal are outside of it
+                    let start = match modal {
+                        Some(modal) => dispatch
+                            .iter()
+                            .position(|id| *id == modal)
+                            .unwrap_or(dispatch.len()),
+                        None => 0,
+                    };
+                    ancestors.extend(dispatch[start..].iter().rev());
+                } else {
+                    cx.app_state.focus = None;
                 }
+            }
 
-                if !processed {
-                    if let Some(listener) = event.listener() {
-                        if let Some(action) = cx.get_event_listener(self.view.main.id(), &listener)
-                        {
-                            processed |= (*action)(&event).is_processed();
+            // Keys the focused view doesn't handle go to the listeners of its ancestors,
+            // innermost first, and then to the main view
+            if !processed {
+                if let Some(listener) = event.listener() {
+                    let main = Some(self.view.main.id())
+                        .filter(|main| modal.is_none() && !ancestors.contains(main));
+                    for id in ancestors.into_iter().chain(main) {
+                        if cx.app_state.is_hidden(id) || cx.app_state.is_disabled(&id) {
+                            continue;
+                        }
+                        if let Some(action) = cx.get_event_listener(id, &listener) {
+                            if (*action)(&event).is_processed() {
+                                processed = true;
+                                break;
+                            }
                         }
                     }
                 }
+            }
 
-                if !processed {
-                    if let Event::KeyDown(KeyEvent { key, modifiers }) = &event {
-                        if key.logical_key == Key::Named(NamedKey::Tab)
-                            && (modifiers.is_empty() || *modifiers == ModifiersState::SHIFT)
+            if !processed {
+                if let Event::KeyDown(KeyEvent { key, modifiers }) = &event {
+                    if key.logical_key == Key::Named(NamedKey::Tab)
+                        && (modifiers.is_empty() || *modifiers == ModifiersState::SHIFT)
+                    {
+                        let backwards = modifiers.contains(ModifiersState::SHIFT);
+                        view_tab_navigation(&self.view, cx.app_state, backwards, focus_scope);
+                        // view_debug_tree(&self.view);
+                    } else if key.logical_key == Key::Named(NamedKey::Escape) && modal.is_some() {
+                        if let Some(modal) = self.view.modal().and_then(|view| view.modal.as_ref())
                         {
-                            let backwards = modifiers.contains(ModifiersState::SHIFT);
-                            view_tab_navigation(&self.view, cx.app_state, backwards);
+                            (modal.on_dismiss)();
+                        }
+                    } else if let Key::Character(character) = &key.logical_key {
+                        // 'I' displays some debug information
+                        if character.eq_ignore_ascii_case("i") {
                             // view_debug_tree(&self.view);
-                        } else if let Key::Character(character) = &key.logical_key {
-                            // 'I' displays some debug information
-                            if character.eq_ignore_ascii_case("i") {
-                                // view_debug_tree(&self.view);
-                            }
-                        } else if *modifiers == ModifiersState::ALT {
-                            if let Key::Named(
-                                name @ (NamedKey::ArrowUp
-                                | NamedKey::ArrowDown
-                                | NamedKey::ArrowLeft
-                                | NamedKey::ArrowRight),
-                            ) = key.logical_key
-                            {
-                                view_arrow_navigation(name, cx.app_state, &self.view);
-                            }
+                        }
+                    } else if *modifiers == ModifiersState::ALT && modal.is_none() {
+                        if let Key::Named(
+                            name @ (NamedKey::ArrowUp
+                            | NamedKey::ArrowDown
+                            | NamedKey::ArrowLeft
+                            | NamedKey::ArrowRight),
+                        ) = key.logical_key
+                        {
+                            view_arrow_navigation(name, cx.app_state, &self.view);
                         }
                     }
+                }
 
-                    let keyboard_trigger_end = cx.app_state.keyboard_navigation
-                        && event.is_keyboard_trigger()
-                        && matches!(event, Event::KeyUp(_));
-                    if keyboard_trigger_end {
-                        if let Some(id) = cx.app_state.active {
-                            // To remove the styles applied by the Active selector
-                            if cx.app_state.has_style_for_sel(id, StyleSelector::Active) {
-                                cx.app_state.request_style_recursive(id);
-                            }
-
-                            cx.app_state.active = None;
+                let keyboard_trigger_end = cx.app_state.keyboard_navigation
+                    && event.is_keyboard_trigger()
+                    && matches!(event, Event::KeyUp(_));
+                if keyboard_trigger_end {
+                    if let Some(id) = cx.app_state.active {
+                        // To remove the styles applied by the Active selector
+                        if cx.app_state.has_style_for_sel(id, StyleSelector::Active) {
+                            cx.app_state.request_style_recursive(id);
                         }
+
+                        cx.app_state.active = None;
                     }
                 }
             }
+        } else if let Some(id) = cx.app_state.pointer_capture.filter(|_| event.is_pointer()) {
+            let id_path = ID_PATHS.with(|paths| paths.borrow().get(&id).cloned());
+            if let Some(id_path) = id_path {
+                cx.unconditional_view_event(
+                    &mut self.view,
+                    Some(id_path.dispatch()),
+                    event.clone(),
+                );
+            }
+            if let Event::PointerUp(_) = &event {
+                // The view can capture the pointer again while handling the event,
+                // which is applied when the update messages are processed.
+                cx.app_state.pointer_capture = None;
+            }
         } else if cx.app_state.active.is_some() && event.is_pointer() {
             if cx.app_state.is_dragging() {
                 cx.unconditional_view_event(&mut self.view, None, event.clone());
@@ -275,8 +560,29 @@ impl WindowHandle {
 
         if let Event::PointerUp(_) = &event {
             cx.app_state.drag_start = None;
+            // The drag is over, so the payload has left the targets it wasn't dropped on
+            cx.app_state.leave_drop_targets(&HashSet::new());
         }
         if is_pointer_move {
+            if let Some(id) = capture {
+                let rejected = cx
+                    .app_state
+                    .dragging
+                    .as_ref()
+                    .map(|dragging| dragging.target_accepts == Some(false))
+                    .unwrap_or(false);
+                cx.app_state.cursor = if rejected {
+                    // Show that the payload being dragged can't be dropped on the target
+                    Some(CursorStyle::NotAllowed)
+                } else {
+                    // The cursor of the capturing view wins over the views below the pointer.
+                    // Without one the cursor stays as it was when the pointer was captured.
+                    cx.app_state
+                        .get_builtin_style(id)
+                        .cursor()
+                        .or(was_cursor.filter(|cursor| *cursor != CursorStyle::NotAllowed))
+                };
+            }
             let hovered = &cx.app_state.hovered.clone();
             for id in was_hovered.unwrap().symmetric_difference(hovered) {
                 let view_state = cx.app_state.view_state(*id);
@@ -302,6 +608,7 @@ impl WindowHandle {
                 }
             }
             let dragging_over = &cx.app_state.dragging_over.clone();
+            cx.app_state.leave_drop_targets(dragging_over);
             for id in was_dragging_over
                 .unwrap()
                 .symmetric_difference(dragging_over)
@@ -316,7 +623,8 @@ impl WindowHandle {
             }
         }
         if was_focused != cx.app_state.focus {
-            cx.app_state.focus_changed(was_focused, cx.app_state.focus);
+            let focus = cx.app_state.focus;
+            focus_changed(&mut self.view, cx.app_state, was_focused, focus);
         }
 
         if is_pointer_down {
@@ -338,15 +646,72 @@ impl WindowHandle {
         self.process_update();
     }
 
+    /// Let the accessibility adapter see `event`, which it needs to know when the window is
+    /// focused and resized.
+    #[cfg(feature = "accesskit")]
+    pub(crate) fn accessibility_event(&self, event: &winit::event::WindowEvent) {
+        if let (Some(accessibility), Some(window)) =
+            (self.accessibility.as_ref(), self.window.as_ref())
+        {
+            accessibility.process_event(window, event);
+        }
+    }
+
+    /// Perform an action requested by assistive technology.
+    #[cfg(feature = "accesskit")]
+    pub(crate) fn accessibility_action(&mut self, request: accesskit::ActionRequest) {
+        set_current_view(self.id);
+        let id = Id::from_raw(request.target.0);
+        match request.action {
+            accesskit::Action::Focus => id.request_focus(),
+            accesskit::Action::Default => {
+                // Click the view like pressing Enter on it would
+                let view_state = self.app_state.view_states.get(&id);
+                let click = view_state
+                    .and_then(|view_state| view_state.event_listeners.get(&EventListener::Click));
+                if let (Some(view_state), Some(click)) = (view_state, click) {
+                    let event = Event::PointerUp(PointerInputEvent {
+                        pos: view_state.layout_rect.size().to_rect().center(),
+                        button: PointerButton::Primary,
+                        modifiers: ModifiersState::default(),
+                        count: 1,
+                    });
+                    click(&event);
+                }
+            }
+            _ => {}
+        }
+        self.process_update();
+    }
+
     pub(crate) fn scale(&mut self, scale: f64) {
         self.scale = scale;
+        self.update_monitor_signals();
         let scale = self.scale * self.app_state.scale;
         self.paint_state.set_scale(scale);
         self.schedule_repaint();
     }
 
+    fn update_monitor_signals(&self) {
+        if let Some(signals) = window_signals(self.id) {
+            if signals.scale.get_untracked() != self.scale {
+                signals.scale.set(self.scale);
+            }
+            if let Some(window) = self.window.as_ref() {
+                let refresh_rate = monitor_refresh_rate(window);
+                if signals.refresh_rate.get_untracked() != refresh_rate {
+                    signals.refresh_rate.set(refresh_rate);
+                }
+            }
+        }
+    }
+
     pub(crate) fn os_theme_changed(&mut self, theme: winit::window::Theme) {
-        self.os_theme.set(Some(theme));
+        set_current_view(self.id);
+        if let Some(signals) = window_signals(self.id) {
+            signals.system_theme.set(theme);
+        }
+        self.event(Event::ThemeChanged(theme));
     }
 
     pub(crate) fn size(&mut self, size: Size) {
@@ -365,22 +730,157 @@ impl WindowHandle {
             }
         }
 
+        if let Some(window) = self.window.as_ref() {
+            let is_fullscreen = window.fullscreen().is_some();
+            if is_fullscreen != self.is_fullscreen {
+                self.is_fullscreen = is_fullscreen;
+                self.event(Event::WindowFullscreenChanged(is_fullscreen));
+            }
+        }
+
         self.style();
         self.layout();
         self.process_update();
         self.schedule_repaint();
     }
 
+    /// Unminimize the window if needed and give it focus.
+    pub(crate) fn bring_to_front(&self) {
+        if let Some(window) = self.window.as_ref() {
+            if window.is_minimized().unwrap_or(false) {
+                window.set_minimized(false);
+            }
+            window.set_visible(true);
+            window.focus_window();
+        }
+    }
+
+    pub(crate) fn minimize(&self) {
+        if let Some(window) = self.window.as_ref() {
+            window.set_minimized(true);
+        }
+    }
+
+    pub(crate) fn maximize(&self) {
+        if let Some(window) = self.window.as_ref() {
+            window.set_maximized(true);
+        }
+    }
+
+    pub(crate) fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        if let Some(window) = self.window.as_ref() {
+            window.set_fullscreen(fullscreen);
+            // Some platforms reset the window level when changing fullscreen state
+            window.set_window_level(self.window_level);
+        }
+    }
+
+    /// Exit fullscreen if the window is fullscreen, otherwise enter borderless fullscreen
+    /// on the monitor the window is currently on.
+    pub(crate) fn toggle_fullscreen(&self) {
+        if let Some(window) = self.window.as_ref() {
+            let fullscreen = if window.fullscreen().is_some() {
+                None
+            } else {
+                Some(Fullscreen::Borderless(None))
+            };
+            self.set_fullscreen(fullscreen);
+        }
+    }
+
+    pub(crate) fn set_window_level(&mut self, window_level: WindowLevel) {
+        self.window_level = window_level;
+        if let Some(window) = self.window.as_ref() {
+            window.set_window_level(window_level);
+        }
+    }
+
+    /// Unminimize the window if it's minimized, otherwise unmaximize it.
+    pub(crate) fn restore(&self) {
+        if let Some(window) = self.window.as_ref() {
+            if window.is_minimized().unwrap_or(false) {
+                window.set_minimized(false);
+            } else {
+                window.set_maximized(false);
+            }
+        }
+    }
+
+    pub(crate) fn close_requested(&mut self) -> CloseResponse {
+        set_current_view(self.id);
+        let response = self
+            .close_requested_handler
+            .as_ref()
+            .map(|handler| handler())
+            .unwrap_or(CloseResponse::Close);
+        self.process_update();
+        response
+    }
+
+    pub(crate) fn geometry(&self) -> Option<WindowGeometry> {
+        let window = self.window.as_ref()?;
+        let position: LogicalPosition<f64> = window.outer_position().ok()?.to_logical(self.scale);
+        let size: LogicalSize<f64> = window.inner_size().to_logical(self.scale);
+        Some(WindowGeometry {
+            position: Point::new(position.x, position.y),
+            size: Size::new(size.width, size.height),
+            maximized: window.is_maximized(),
+        })
+    }
+
+    pub(crate) fn set_resizable(&self, resizable: bool) {
+        if let Some(window) = self.window.as_ref() {
+            window.set_resizable(resizable);
+            let mut buttons = window.enabled_buttons();
+            buttons.set(WindowButtons::MAXIMIZE, resizable);
+            window.set_enabled_buttons(buttons);
+        }
+    }
+
+    pub(crate) fn set_min_size(&mut self, size: Option<Size>) {
+        if let Some(window) = self.window.as_ref() {
+            window.set_min_inner_size(size.map(|size| LogicalSize::new(size.width, size.height)));
+        }
+        self.sync_size();
+    }
+
+    pub(crate) fn set_max_size(&mut self, size: Option<Size>) {
+        if let Some(window) = self.window.as_ref() {
+            window.set_max_inner_size(size.map(|size| LogicalSize::new(size.width, size.height)));
+        }
+        self.sync_size();
+    }
+
+    /// Update the layout if the window was resized without a `Resized` event being sent,
+    /// which can happen when the size constraints change.
+    fn sync_size(&mut self) {
+        if let Some(window) = self.window.as_ref() {
+            let size: LogicalSize<f64> = window.inner_size().to_logical(self.scale);
+            let size = Size::new(size.width, size.height);
+            if size != self.size.get_untracked() {
+                self.size(size);
+            }
+        }
+    }
+
     pub(crate) fn position(&mut self, point: Point) {
         self.window_position = point;
+        // The window may have been moved to a different monitor
+        self.update_monitor_signals();
         self.event(Event::WindowMoved(point));
     }
 
-    pub(crate) fn key_event(&mut self, key_event: winit::event::KeyEvent) {
+    pub(crate) fn key_event(&mut self, key_event: KeyInput) {
         let event = KeyEvent {
             key: key_event,
             modifiers: self.modifiers,
         };
+        if event.key.state.is_pressed()
+            && (self.run_shortcut(&event) || self.run_access_key(&event))
+        {
+            self.process_update();
+            return;
+        }
         if event.key.state.is_pressed() {
             self.event(Event::KeyDown(event));
         } else {
@@ -388,9 +888,106 @@ impl WindowHandle {
         }
     }
 
+    /// Run the shortcut matching the key event. Shortcuts without Control, Alt or Super
+    /// are only run when no view has keyboard focus, so they don't steal keys from text inputs,
+    /// and none are run while a modal is open.
+    fn run_shortcut(&mut self, event: &KeyEvent) -> bool {
+        // Shortcuts don't reach the content behind a modal
+        if self.view.modal().is_some() {
+            return false;
+        }
+        let has_focus = self.app_state.focus.is_some();
+        let shortcut = self.shortcuts.iter().rev().find(|(_, combination, _)| {
+            combination.matches(event) && (combination.is_command() || !has_focus)
+        });
+        if let Some((_, _, action)) = shortcut {
+            set_current_view(self.id);
+            action();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Click the view with the access key pressed with Alt in `event`, or move the focus
+    /// between the views with it if there are several. Only views in a modal are considered
+    /// while one is open.
+    fn run_access_key(&mut self, event: &KeyEvent) -> bool {
+        let modifiers = event.modifiers;
+        if !modifiers.alt_key() || modifiers.control_key() || modifiers.super_key() {
+            return false;
+        }
+        // Option composes other characters on macOS, like ƒ for Option+F
+        let Key::Character(text) = event.key.key_without_modifiers() else {
+            return false;
+        };
+        let Some(key) = text.chars().next().and_then(|c| c.to_lowercase().next()) else {
+            return false;
+        };
+        let scope = self.view.focus_scope(&self.app_state);
+        let app_state = &self.app_state;
+        let views: Vec<Id> = app_state
+            .access_keys
+            .iter()
+            .filter(|(id, view_key)| {
+                **view_key == key
+                    && !app_state.is_disabled_recursive(**id)
+                    && id.id_path().map_or(false, |id_path| {
+                        scope.map_or(true, |scope| id_path.dispatch().contains(&scope))
+                    })
+                    && !app_state.is_hidden_recursive(**id)
+            })
+            .map(|(id, _)| *id)
+            .collect();
+
+        let Some(&first) = views.first() else {
+            return false;
+        };
+        set_current_view(self.id);
+        let focusable: Vec<Id> = views
+            .iter()
+            .copied()
+            .filter(|id| self.app_state.can_focus(*id))
+            .collect();
+        if views.len() == 1 || focusable.is_empty() {
+            if self.app_state.can_focus(first) {
+                first.request_focus();
+            }
+            let click = self
+                .app_state
+                .view_states
+                .get(&first)
+                .and_then(|view_state| view_state.event_listeners.get(&EventListener::Click));
+            if let Some(click) = click {
+                click(&Event::KeyDown(event.clone()));
+            }
+        } else {
+            let focused = self
+                .app_state
+                .focus
+                .and_then(|focus| focusable.iter().position(|id| *id == focus));
+            let next = focused.map_or(0, |i| (i + 1) % focusable.len());
+            focusable[next].request_focus();
+        }
+        true
+    }
+
+    /// Update the modifier keys, and underline the access keys while Alt is held.
+    pub(crate) fn modifiers_changed(&mut self, modifiers: ModifiersState) {
+        self.modifiers = modifiers;
+        let show_access_keys = modifiers.alt_key() && !self.app_state.access_keys.is_empty();
+        if show_access_keys != self.app_state.show_access_keys {
+            self.app_state.show_access_keys = show_access_keys;
+            self.schedule_repaint();
+        }
+    }
+
     pub(crate) fn pointer_move(&mut self, pos: Point) {
         if self.cursor_position != pos {
             self.cursor_position = pos;
+            if self.inspector_overlay {
+                self.schedule_repaint();
+            }
             let event = PointerMoveEvent {
                 pos,
                 modifiers: self.modifiers,
@@ -405,6 +1002,7 @@ impl WindowHandle {
             app_state: &mut self.app_state,
         };
         let was_hovered = std::mem::take(&mut cx.app_state.hovered);
+        let pointer_capture = cx.app_state.pointer_capture;
         for id in was_hovered {
             let view_state = cx.app_state.view_state(id);
             if view_state.has_style_selectors.has(StyleSelector::Hover)
@@ -413,6 +1011,9 @@ impl WindowHandle {
             {
                 cx.app_state.request_style_recursive(id);
             }
+            if pointer_capture.is_some() {
+                continue;
+            }
             let id_path = ID_PATHS.with(|paths| paths.borrow().get(&id).cloned());
             if let Some(id_path) = id_path {
                 cx.unconditional_view_event(
@@ -422,12 +1023,172 @@ impl WindowHandle {
                 );
             }
         }
+        // When the pointer is captured only the capturing view is told it left
+        if let Some(id) = pointer_capture {
+            let id_path = ID_PATHS.with(|paths| paths.borrow().get(&id).cloned());
+            if let Some(id_path) = id_path {
+                cx.unconditional_view_event(
+                    &mut self.view,
+                    Some(id_path.dispatch()),
+                    Event::PointerLeave,
+                );
+            }
+        }
+        self.process_update();
+    }
+
+    pub(crate) fn dropped_file(&mut self, path: PathBuf) {
+        let hover_target = self.file_hover_target.take();
+        if let Some(id) = self.listener_target_at_cursor(&EventListener::DroppedFile) {
+            self.dispatch_to(id, Event::DroppedFile(path));
+        }
+        // No cancel event is sent by the platform after a drop, so clear the hover state here
+        if let Some(id) = hover_target {
+            self.dispatch_to(id, Event::HoveredFile(None));
+        }
+        self.process_update();
+    }
+
+    pub(crate) fn hovered_file(&mut self, path: Option<PathBuf>) {
+        match path {
+            Some(path) => {
+                let target = self.listener_target_at_cursor(&EventListener::HoveredFile);
+                if let Some(id) = self.file_hover_target.filter(|id| Some(*id) != target) {
+                    self.dispatch_to(id, Event::HoveredFile(None));
+                }
+                self.file_hover_target = target;
+                if let Some(id) = target {
+                    self.dispatch_to(id, Event::HoveredFile(Some(path)));
+                }
+            }
+            None => {
+                if let Some(id) = self.file_hover_target.take() {
+                    self.dispatch_to(id, Event::HoveredFile(None));
+                }
+            }
+        }
         self.process_update();
     }
 
+    /// Find the deepest view under the last known cursor position which has a handler for `listener`.
+    fn listener_target_at_cursor(&self, listener: &EventListener) -> Option<Id> {
+        fn hit_test(
+            view: &dyn View,
+            app_state: &AppState,
+            pos: Point,
+            listener: &EventListener,
+        ) -> Option<Id> {
+            let id = view.id();
+            let view_state = app_state.view_states.get(&id)?;
+            if app_state.is_hidden(id)
+                || app_state.is_disabled(&id)
+                || app_state.is_transparent(id)
+                || !view_state.layout_rect.contains(pos)
+            {
+                return None;
+            }
+            // A view clipping its children has a layout rect of just itself
+            let local = pos - view_state.layout_rect.origin().to_vec2();
+            if let Some(border_box) = app_state.overflow_border_box(id) {
+                if !border_box.contains(local) {
+                    return None;
+                }
+            }
+            // Children painted on top take priority, which are the ones with the highest
+            // z-index, and then the last declared
+            let mut target = None;
+            let mut target_z_index = i32::MIN;
+            view.for_each_child(&mut |child| {
+                // Children aren't hit outside of the content box of a view clipping them
+                if let Some(clip) = app_state.overflow_clip(id) {
+                    if !clip.contains(local) {
+                        return true;
+                    }
+                }
+                let z_index = app_state.z_index(child.id());
+                if z_index >= target_z_index {
+                    if let Some(id) = hit_test(child, app_state, pos, listener) {
+                        target = Some(id);
+                        target_z_index = z_index;
+                    }
+                }
+                false
+            });
+            target.or_else(|| {
+                view_state
+                    .event_listeners
+                    .contains_key(listener)
+                    .then_some(id)
+            })
+        }
+
+        let pos = Point::new(
+            self.cursor_position.x / self.app_state.scale,
+            self.cursor_position.y / self.app_state.scale,
+        );
+        hit_test(&self.view, &self.app_state, pos, listener)
+    }
+
+    pub(crate) fn pinch_gesture(&mut self, delta: f64, phase: TouchPhase) {
+        if let Some(id) = self.listener_target_at_cursor(&EventListener::PinchGesture) {
+            self.dispatch_to(id, Event::PinchGesture { delta, phase });
+        }
+        self.process_update();
+    }
+
+    pub(crate) fn smart_magnify(&mut self) {
+        if let Some(id) = self.listener_target_at_cursor(&EventListener::SmartMagnify) {
+            self.dispatch_to(id, Event::SmartMagnify);
+        }
+        self.process_update();
+    }
+
+    /// Relative mouse motion goes to the view which captured the pointer, or else to the view
+    /// under the cursor listening for it.
+    pub(crate) fn pointer_motion(&mut self, delta: Vec2) {
+        let target = self
+            .app_state
+            .pointer_capture
+            .or_else(|| self.listener_target_at_cursor(&EventListener::PointerMotion));
+        if let Some(id) = target {
+            self.dispatch_to(id, Event::PointerMotion(delta));
+        }
+        self.process_update();
+    }
+
+    pub(crate) fn has_focus(&self) -> bool {
+        self.window
+            .as_ref()
+            .is_some_and(|window| window.has_focus())
+    }
+
+    fn dispatch_to(&mut self, id: Id, event: Event) {
+        set_current_view(self.id);
+        let id_path = ID_PATHS.with(|paths| paths.borrow().get(&id).cloned());
+        if let Some(id_path) = id_path {
+            let mut cx = EventCx {
+                app_state: &mut self.app_state,
+            };
+            cx.unconditional_view_event(&mut self.view, Some(id_path.dispatch()), event);
+        }
+    }
+
     pub(crate) fn mouse_wheel(&mut self, delta: MouseScrollDelta) {
         let delta = match delta {
-            MouseScrollDelta::LineDelta(x, y) => Vec2::new(-x as f64 * 60.0, -y as f64 * 60.0),
+            MouseScrollDelta::LineDelta(x, y) => {
+                let (x, y) = (-x as f64, -y as f64);
+                // Shift turns a vertical mouse wheel into horizontal scrolling.
+                // macOS already does this for us.
+                let (x, y) = if cfg!(not(target_os = "macos"))
+                    && self.modifiers.contains(ModifiersState::SHIFT)
+                    && x == 0.0
+                {
+                    (y, 0.0)
+                } else {
+                    (x, y)
+                };
+                Vec2::new(x, y) * self.scroll_line_height
+            }
             MouseScrollDelta::PixelDelta(delta) => {
                 let position: LogicalPosition<f64> = delta.to_logical(self.scale);
                 Vec2::new(-position.x, -position.y)
@@ -447,7 +1208,7 @@ impl WindowHandle {
             if let Some((count, last_pos, instant)) = self.last_pointer_down.as_mut() {
                 if *count == 4 {
                     *count = 1;
-                } else if instant.elapsed().as_millis() < 500
+                } else if instant.elapsed() < self.multi_click_interval
                     && last_pos.distance(self.cursor_position) < 4.0
                 {
                     *count += 1;
@@ -461,6 +1222,11 @@ impl WindowHandle {
                 self.last_pointer_down = Some((1, self.cursor_position, Instant::now()));
                 1
             }
+        } else if button.is_primary() {
+            // The release of a click has the same count as the press
+            self.last_pointer_down
+                .map(|(count, _, _)| count)
+                .unwrap_or(0)
         } else {
             0
         };
@@ -480,14 +1246,67 @@ impl WindowHandle {
         }
     }
 
+    pub(crate) fn touch(&mut self, touch: Touch) {
+        let position: LogicalPosition<f64> = touch.location.to_logical(self.scale);
+        let pos = Point::new(position.x, position.y);
+        self.event(Event::Touch(TouchEvent {
+            id: touch.id,
+            phase: touch.phase,
+            pos,
+            modifiers: self.modifiers,
+        }));
+
+        // The first finger down behaves like the left mouse button
+        match touch.phase {
+            TouchPhase::Started if self.primary_touch.is_none() => {
+                self.primary_touch = Some(touch.id);
+                self.pointer_move(pos);
+                self.mouse_input(MouseButton::Left, ElementState::Pressed);
+            }
+            TouchPhase::Moved if self.primary_touch == Some(touch.id) => {
+                self.pointer_move(pos);
+            }
+            TouchPhase::Ended if self.primary_touch == Some(touch.id) => {
+                self.primary_touch = None;
+                self.pointer_move(pos);
+                self.mouse_input(MouseButton::Left, ElementState::Released);
+                self.pointer_leave();
+            }
+            TouchPhase::Cancelled if self.primary_touch == Some(touch.id) => {
+                // Don't send a pointer up so the cancelled touch doesn't register as a click
+                self.primary_touch = None;
+                self.app_state.active = None;
+                self.app_state.drag_start = None;
+                self.app_state.clicking.clear();
+                self.pointer_leave();
+            }
+            _ => {}
+        }
+    }
+
     pub(crate) fn focused(&mut self, focused: bool) {
+        if let Some(signals) = window_signals(self.id) {
+            signals.focused.set(focused);
+        }
         if focused {
             self.event(Event::WindowGotFocus);
         } else {
+            // Clicking outside the window dismisses the context menu, like a platform menu would
+            #[cfg(target_os = "linux")]
+            self.context_menu.set(None);
             self.event(Event::WindowLostFocus);
         }
     }
 
+    pub(crate) fn occluded(&mut self, occluded: bool) {
+        if let Some(signals) = window_signals(self.id) {
+            signals.occluded.set(occluded);
+        }
+        if !occluded {
+            self.schedule_repaint();
+        }
+    }
+
     fn style(&mut self) {
         let mut cx = StyleCx::new(&mut self.app_state, self.view.id());
         if let Some(style) = self.theme.as_ref().map(|theme| theme.style.clone()) {
@@ -527,16 +1346,54 @@ impl WindowHandle {
             }
         }
 
-        self.process_update_no_paint();
-        self.paint();
+        // Redraws the platform asks for are painted fully as the window contents may have been
+        // lost, but frames requested for scheduled updates which didn't change anything are
+        // skipped.
+        if !mem::take(&mut self.redraw_requested) {
+            self.needs_paint = true;
+        }
+        if self.process_update_no_paint() {
+            self.paint();
+        }
 
         // Request a new frame if there's any scheduled updates.
         if !self.app_state.scheduled_updates.is_empty() {
-            self.schedule_repaint();
+            self.request_frame();
         }
     }
 
+    /// The window area to repaint, or `None` if the whole window has to be painted.
+    fn take_damage(&mut self) -> Option<Rect> {
+        let full = mem::take(&mut self.needs_paint) | mem::take(&mut self.app_state.full_repaint);
+        let damage = self.app_state.damage.take();
+        self.app_state.request_paint = false;
+        if full
+            || self.inspector_overlay
+            || self.show_frame_timing
+            || self.app_state.capture.is_some()
+            || self.app_state.dragging.is_some()
+        {
+            return None;
+        }
+        // Views blurring their backdrop are repainted with everything they sample when
+        // something behind them changes.
+        let damage = damage.map(|mut damage| {
+            while let Some(backdrop) = self.app_state.backdrops.iter().find(|backdrop| {
+                !backdrop.intersect(damage).is_empty() && damage.union(**backdrop) != damage
+            }) {
+                damage = damage.union(*backdrop);
+            }
+            damage
+        });
+        // Repainting most of the window in parts isn't worth it.
+        let window = self.size.get_untracked() / self.app_state.scale;
+        damage.filter(|damage| damage.area() <= window.width * window.height / 2.0)
+    }
+
     pub fn paint(&mut self) -> Option<DynamicImage> {
+        let start = Instant::now();
+        let damage = self.take_damage();
+        self.app_state.backdrops.clear();
         let mut cx = PaintCx {
             app_state: &mut self.app_state,
             paint_state: &mut self.paint_state,
@@ -546,36 +1403,85 @@ impl WindowHandle {
             saved_transforms: Vec::new(),
             saved_clips: Vec::new(),
             saved_z_indexes: Vec::new(),
+            damage: None,
+            painted: None,
+            painted_outside_damage: false,
+            painted_outside_layer: false,
+        };
+        let damaged = damage.map_or(false, |damage| {
+            cx.paint_state.renderer.begin_damaged(damage)
+        });
+        if damaged {
+            cx.damage = damage;
+        } else {
+            cx.paint_state
+                .renderer
+                .begin(cx.app_state.capture.is_some());
+        }
+        let mut paint_views = |cx: &mut PaintCx| {
+            if !self.transparent {
+                let scale = cx.app_state.scale;
+                let color = self
+                    .theme
+                    .as_ref()
+                    .map(|theme| theme.background)
+                    .unwrap_or(peniko::Color::WHITE);
+                // fill window with default white background if it's not transparent
+                cx.fill(
+                    &self
+                        .size
+                        .get_untracked()
+                        .to_rect()
+                        .scale_from_origin(1.0 / scale)
+                        .expand(),
+                    color,
+                    0.0,
+                );
+            }
+            cx.paint_view(&mut self.view);
         };
-        cx.paint_state
-            .renderer
-            .begin(cx.app_state.capture.is_some());
-        if !self.transparent {
+        paint_views(&mut cx);
+        // What's painted where nothing was painted on the last frame isn't known until it's
+        // painted, so the whole frame is painted again if it's not in the repainted area or in
+        // the layer of a view.
+        if cx.painted_outside_damage || cx.painted_outside_layer {
+            cx.damage = None;
+            cx.paint_state.renderer.begin(false);
+            paint_views(&mut cx);
+        }
+        if self.inspector_overlay {
             let scale = cx.app_state.scale;
-            let color = self
-                .theme
-                .as_ref()
-                .map(|theme| theme.background)
-                .unwrap_or(peniko::Color::WHITE);
-            // fill window with default white background if it's not transparent
-            cx.fill(
-                &self
-                    .size
-                    .get_untracked()
-                    .to_rect()
-                    .scale_from_origin(1.0 / scale)
-                    .expand(),
-                color,
-                0.0,
+            inspector::paint_overlay(
+                &mut cx,
+                &self.view,
+                (self.cursor_position.to_vec2() / scale).to_point(),
+                self.size.get_untracked() / scale,
             );
         }
-        cx.paint_view(&mut self.view);
+        if self.show_frame_timing {
+            if let Some(timing) = self.last_frame_timing {
+                paint_frame_timing(&mut cx, &timing);
+            }
+        }
         if let Some(window) = self.window.as_ref() {
             if cx.app_state.capture.is_none() {
                 window.pre_present_notify();
             }
         }
-        cx.paint_state.renderer.finish()
+        let image = cx.paint_state.renderer.finish();
+
+        // Frames painted for the inspector aren't shown, so they aren't reported.
+        if self.app_state.capture.is_none() {
+            let mut timing = mem::take(&mut self.frame_timing);
+            timing.paint = start.elapsed();
+            timing.since_last_frame = self.last_frame.replace(start).map(|last| start - last);
+            if let Some(handler) = self.frame_timing_handler.as_ref() {
+                handler(&timing);
+            }
+            self.last_frame_timing = Some(timing);
+        }
+
+        image
     }
 
     pub(crate) fn capture(&mut self) -> Capture {
@@ -595,6 +1501,7 @@ impl WindowHandle {
         self.app_state.view_states.values_mut().for_each(|state| {
             state.requested_changes = ChangeFlags::all();
         });
+        self.app_state.full_layout = true;
 
         fn get_taffy_depth(taffy: &taffy::Taffy, root: taffy::node::Node) -> usize {
             let children = taffy.children(root).unwrap();
@@ -642,14 +1549,13 @@ impl WindowHandle {
 
     pub(crate) fn process_update(&mut self) {
         if self.process_update_no_paint() {
-            self.schedule_repaint();
+            self.request_frame();
         }
     }
 
     /// Processes updates and runs style and layout if needed.
     /// Returns `true` if painting is required.
     pub(crate) fn process_update_no_paint(&mut self) -> bool {
-        let mut paint = false;
         loop {
             self.process_update_messages();
             if !self.needs_layout()
@@ -657,32 +1563,56 @@ impl WindowHandle {
                 && !self.has_deferred_update_messages()
                 && !self.has_anim_update_messages()
                 && !self.app_state.request_compute_layout
+                && self.app_state.mounted_listeners.is_empty()
             {
                 break;
             }
 
+            // Listeners registered while this pass runs are for views it may not lay out, so
+            // they're left for the next pass
+            let mounted_listeners = std::mem::take(&mut self.app_state.mounted_listeners);
+
             if self.needs_style() {
-                paint = true;
+                self.needs_paint = true;
+                let start = Instant::now();
                 self.style();
+                self.frame_timing.style += start.elapsed();
             }
 
+            let start = Instant::now();
             if self.needs_layout() {
-                paint = true;
+                self.needs_paint = true;
                 self.layout();
             }
 
             if self.app_state.request_compute_layout {
+                // Views may have moved
+                self.needs_paint = true;
                 self.compute_layout();
             }
+            self.frame_timing.layout += start.elapsed();
 
             self.process_deferred_update_messages();
             self.process_anim_update_messages();
+
+            // The views are laid out now, and the updates of the listeners are processed by
+            // the next iteration
+            for (id, action) in mounted_listeners {
+                if id.has_id_path() {
+                    action();
+                }
+            }
         }
 
         self.set_cursor();
 
-        // TODO: This should only use `self.app_state.request_paint)`
-        paint || mem::take(&mut self.app_state.request_paint)
+        #[cfg(feature = "accesskit")]
+        if let Some(accessibility) = self.accessibility.as_mut() {
+            let scale = self.scale * self.app_state.scale;
+            accessibility.update(&self.view, &self.app_state, scale);
+        }
+
+        self.needs_paint || self.app_state.request_paint
     }
 
     fn process_central_messages(&self) {
@@ -738,19 +1668,44 @@ impl WindowHandle {
                             cx.app_state.request_layout(id);
                         }
                     }
-                    UpdateMessage::RequestPaint => {
-                        cx.app_state.request_paint = true;
+                    UpdateMessage::RequestPaint(id) => {
+                        cx.app_state.request_paint(id);
                     }
                     UpdateMessage::Focus(id) => {
-                        if cx.app_state.focus != Some(id) {
+                        if !cx.app_state.keyboard_navigable.contains(&id)
+                            || cx.app_state.is_disabled_recursive(id)
+                        {
+                            eprintln!(
+                                "Ignoring the focus request for {id:?}, which can't be focused"
+                            );
+                        } else if cx.app_state.focus != Some(id) {
                             let old = cx.app_state.focus;
                             cx.app_state.focus = Some(id);
-                            cx.app_state.focus_changed(old, cx.app_state.focus);
+                            focus_changed(&mut self.view, cx.app_state, old, Some(id));
                         }
                     }
                     UpdateMessage::ClearFocus(id) => {
                         cx.app_state.clear_focus();
-                        cx.app_state.focus_changed(Some(id), None);
+                        focus_changed(&mut self.view, cx.app_state, Some(id), None);
+                    }
+                    UpdateMessage::RegisterShortcut {
+                        id,
+                        combination,
+                        action,
+                    } => {
+                        self.shortcuts.push((id, combination, Rc::from(action)));
+                    }
+                    UpdateMessage::UnregisterShortcut(id) => {
+                        self.shortcuts
+                            .retain(|(shortcut_id, _, _)| *shortcut_id != id);
+                    }
+                    UpdateMessage::CapturePointer(id) => {
+                        cx.app_state.pointer_capture = Some(id);
+                    }
+                    UpdateMessage::ReleasePointer(id) => {
+                        if cx.app_state.pointer_capture == Some(id) {
+                            cx.app_state.pointer_capture = None;
+                        }
                     }
                     UpdateMessage::Active(id) => {
                         let old = cx.app_state.active;
@@ -770,6 +1725,9 @@ impl WindowHandle {
                             cx.app_state.request_style_recursive(id);
                         }
                     }
+                    UpdateMessage::Accessibility { id, update } => {
+                        cx.app_state.view_state(id).accessibility.update(update);
+                    }
                     UpdateMessage::ScrollTo { id, rect } => {
                         self.view.scroll_to(cx.app_state, id, rect);
                     }
@@ -777,6 +1735,15 @@ impl WindowHandle {
                         if is_disabled {
                             cx.app_state.disabled.insert(id);
                             cx.app_state.hovered.remove(&id);
+                            // The focus can't stay in a disabled view
+                            if let Some(focus) = cx.app_state.focus.filter(|focus| {
+                                focus
+                                    .id_path()
+                                    .map_or(false, |id_path| id_path.dispatch().contains(&id))
+                            }) {
+                                cx.app_state.clear_focus();
+                                focus_changed(&mut self.view, cx.app_state, Some(focus), None);
+                            }
                         } else {
                             cx.app_state.disabled.remove(&id);
                         }
@@ -818,11 +1785,49 @@ impl WindowHandle {
                         cx.request_style(id);
                     }
                     UpdateMessage::KeyboardNavigable { id } => {
-                        cx.app_state.keyboard_navigable.insert(id);
+                        if !cx.app_state.focus_disabled.contains(&id) {
+                            cx.app_state.keyboard_navigable.insert(id);
+                        }
+                    }
+                    UpdateMessage::DisableFocus { id } => {
+                        cx.app_state.focus_disabled.insert(id);
+                        cx.app_state.keyboard_navigable.remove(&id);
+                        if cx.app_state.focus == Some(id) {
+                            cx.app_state.clear_focus();
+                            focus_changed(&mut self.view, cx.app_state, Some(id), None);
+                        }
+                    }
+                    UpdateMessage::AccessKey { id, key } => {
+                        cx.app_state.access_keys.insert(id, key);
+                    }
+                    UpdateMessage::TabIndex { id, index } => {
+                        cx.app_state.tab_index.insert(id, index);
+                    }
+                    UpdateMessage::FocusTrap { id } => {
+                        cx.app_state.focus_traps.insert(id);
+                    }
+                    UpdateMessage::TabNavigation { backwards } => {
+                        let old = cx.app_state.focus;
+                        let within = self.view.focus_scope(cx.app_state);
+                        view_tab_navigation(&self.view, cx.app_state, backwards, within);
+                        let new = cx.app_state.focus;
+                        if old != new {
+                            focus_changed(&mut self.view, cx.app_state, old, new);
+                        }
+                    }
+                    UpdateMessage::DisabledAbsorbsPointer { id } => {
+                        cx.app_state.disabled_absorbing.insert(id);
                     }
                     UpdateMessage::Draggable { id } => {
                         cx.app_state.draggable.insert(id);
                     }
+                    UpdateMessage::DragPayload { id, payload } => {
+                        cx.app_state.draggable.insert(id);
+                        cx.app_state.view_state(id).drag_payload = Some(payload);
+                    }
+                    UpdateMessage::DropTarget { id, target } => {
+                        cx.app_state.view_state(id).drop_target = Some(target);
+                    }
                     UpdateMessage::DragWindow => {
                         if let Some(window) = self.window.as_ref() {
                             let _ = window.drag_window();
@@ -885,7 +1890,13 @@ impl WindowHandle {
                     }
                     UpdateMessage::CleanupListener { id, action } => {
                         let state = cx.app_state.view_state(id);
-                        state.cleanup_listener = Some(action);
+                        state.cleanup_listeners.push(action);
+                    }
+                    UpdateMessage::MountedListener { id, action } => {
+                        cx.app_state.mounted_listeners.push((id, action));
+                    }
+                    UpdateMessage::OwnScope { id, scope } => {
+                        cx.app_state.view_state(id).scopes.push(scope);
                     }
                     UpdateMessage::Animation { id, animation } => {
                         let view_state = cx.app_state.view_state(id);
@@ -906,7 +1917,8 @@ impl WindowHandle {
                         let state = cx.app_state.view_state(id);
                         state.popout_menu = Some(menu);
                     }
-                    UpdateMessage::ShowContextMenu { menu, pos } => {
+                    UpdateMessage::ShowContextMenu { mut menu, pos } => {
+                        use_registered_shortcuts(&self.shortcuts, &mut menu);
                         let mut menu = menu.popup();
                         let platform_menu = menu.platform_menu();
                         cx.app_state.context_menu.clear();
@@ -933,6 +1945,16 @@ impl WindowHandle {
                             window.set_ime_allowed(allowed);
                         }
                     }
+                    UpdateMessage::SetCursorVisible(visible) => {
+                        if let Some(window) = self.window.as_ref() {
+                            window.set_cursor_visible(visible);
+                        }
+                    }
+                    UpdateMessage::SetCursorGrab(mode) => {
+                        if let Some(window) = self.window.as_ref() {
+                            set_cursor_grab(window, mode);
+                        }
+                    }
                     UpdateMessage::SetImeCursorArea { position, size } => {
                         if let Some(window) = self.window.as_ref() {
                             window.set_ime_cursor_area(
@@ -950,15 +1972,44 @@ impl WindowHandle {
                     UpdateMessage::Inspect => {
                         inspector::capture(self.window_id);
                     }
-                    UpdateMessage::AddOverlay { id, position, view } => {
+                    UpdateMessage::FileDialog(show) => {
+                        show(self.window.as_ref());
+                    }
+                    UpdateMessage::ToggleInspectorOverlay => {
+                        self.inspector_overlay = !self.inspector_overlay;
+                        self.schedule_repaint();
+                    }
+                    UpdateMessage::AddOverlay {
+                        id,
+                        position,
+                        view,
+                        on_dismiss,
+                    } => {
                         let scope = self.scope.create_child();
+                        cancel_timers_with(scope);
 
                         let view = with_scope(scope, view);
 
+                        let modal = on_dismiss.map(|on_dismiss| {
+                            id.update_class(ModalScrimClass::class_ref());
+                            Modal {
+                                on_dismiss,
+                                previous_focus: cx.app_state.focus,
+                            }
+                        });
+                        // Move the focus into the modal
+                        if let Some(previous_focus) =
+                            modal.as_ref().and_then(|modal| modal.previous_focus)
+                        {
+                            cx.app_state.clear_focus();
+                            focus_changed(&mut self.view, cx.app_state, Some(previous_focus), None);
+                        }
+
                         let view = OverlayView {
                             data: ViewData::new(id),
                             position,
                             scope,
+                            modal,
                             child: view,
                         };
 
@@ -969,9 +2020,26 @@ impl WindowHandle {
                         cx.app_state.request_all(self.id);
                     }
                     UpdateMessage::RemoveOverlay { id } => {
-                        let mut overlay = self.view.overlays.remove(&id).unwrap();
+                        let mut overlay = self.view.overlays.shift_remove(&id).unwrap();
                         cx.app_state.remove_view(&mut overlay);
                         overlay.scope.dispose();
+                        if let Some(previous_focus) = overlay
+                            .modal
+                            .and_then(|modal| modal.previous_focus)
+                            .filter(|id| id.id_path().is_some())
+                        {
+                            let old = cx.app_state.focus;
+                            cx.app_state.clear_focus();
+                            cx.app_state.update_focus(previous_focus, false);
+                            if old != Some(previous_focus) {
+                                focus_changed(
+                                    &mut self.view,
+                                    cx.app_state,
+                                    old,
+                                    Some(previous_focus),
+                                );
+                            }
+                        }
                         cx.app_state.request_all(self.id);
                     }
                 }
@@ -1089,23 +2157,46 @@ impl WindowHandle {
         ANIM_UPDATE_MESSAGES.with(|m| !m.borrow().is_empty())
     }
 
-    fn update_window_menu(&mut self, _menu: Menu) {
-        // if let Some(action) = menu.item.action.take() {
-        //     self.window_menu.insert(menu.item.id as u32, action);
-        // }
-        // for child in menu.children {
-        //     match child {
-        //         crate::menu::MenuEntry::Separator => {}
-        //         crate::menu::MenuEntry::Item(mut item) => {
-        //             if let Some(action) = item.action.take() {
-        //                 self.window_menu.insert(item.id as u32, action);
-        //             }
-        //         }
-        //         crate::menu::MenuEntry::SubMenu(m) => {
-        //             self.update_window_menu(m);
-        //         }
-        //     }
-        // }
+    /// Take the actions of the items in the window menu, which are run when an item is
+    /// selected or its shortcut is pressed. The shortcuts are registered like the ones of
+    /// [`register_shortcut`](crate::action::register_shortcut), but only take precedence over
+    /// the shortcuts of previous menus.
+    fn update_window_menu(&mut self, menu: Menu) {
+        fn add_items(
+            menu: Menu,
+            window_menu: &mut HashMap<usize, Box<dyn Fn()>>,
+            shortcuts: &mut Vec<(ShortcutId, KeyCombination, Rc<dyn Fn()>)>,
+        ) {
+            for child in menu.children {
+                match child {
+                    crate::menu::MenuEntry::Separator => {}
+                    crate::menu::MenuEntry::Item(item) => {
+                        let Some(action) = item.action else {
+                            continue;
+                        };
+                        let action: Rc<dyn Fn()> = Rc::from(action);
+                        // Disabled items can't be selected with their shortcut either
+                        if let Some(shortcut) = item.shortcut.filter(|_| item.enabled) {
+                            shortcuts.push((ShortcutId::next(), shortcut, action.clone()));
+                        }
+                        window_menu.insert(item.id as usize, Box::new(move || action()));
+                    }
+                    crate::menu::MenuEntry::SubMenu(menu) => {
+                        add_items(menu, window_menu, shortcuts);
+                    }
+                }
+            }
+        }
+
+        let old_shortcuts = mem::take(&mut self.menu_shortcuts);
+        self.shortcuts
+            .retain(|(id, _, _)| !old_shortcuts.contains(id));
+        self.app_state.window_menu.clear();
+        let mut shortcuts = Vec::new();
+        add_items(menu, &mut self.app_state.window_menu, &mut shortcuts);
+        self.menu_shortcuts = shortcuts.iter().map(|(id, _, _)| *id).collect();
+        // They go first, so the shortcuts registered by views take precedence
+        self.shortcuts.splice(0..0, shortcuts);
     }
 
     fn set_cursor(&mut self) {
@@ -1125,6 +2216,7 @@ impl WindowHandle {
             Some(CursorStyle::NResize) => CursorIcon::NResize,
             Some(CursorStyle::NeswResize) => CursorIcon::NeswResize,
             Some(CursorStyle::NwseResize) => CursorIcon::NwseResize,
+            Some(CursorStyle::NotAllowed) => CursorIcon::NotAllowed,
             None => CursorIcon::Default,
         };
         if cursor != self.app_state.last_cursor {
@@ -1135,14 +2227,42 @@ impl WindowHandle {
         }
     }
 
-    fn schedule_repaint(&self) {
+    /// Paint the window on the next frame.
+    fn schedule_repaint(&mut self) {
+        self.needs_paint = true;
+        self.request_frame();
+    }
+
+    /// Request a frame, which is only painted if something changed by then.
+    fn request_frame(&mut self) {
+        self.redraw_requested = true;
         if let Some(window) = self.window.as_ref() {
             window.request_redraw();
         }
     }
 
+    #[cfg(test)]
+    pub(crate) fn app_state(&self) -> &AppState {
+        &self.app_state
+    }
+
+    /// The combinations of the shortcuts of the window, in the order they're looked up
+    #[cfg(test)]
+    pub(crate) fn shortcuts(&self) -> Vec<KeyCombination> {
+        self.shortcuts
+            .iter()
+            .rev()
+            .map(|(_, combination, _)| combination.clone())
+            .collect()
+    }
+
     pub(crate) fn destroy(&mut self) {
         self.event(Event::WindowClosed);
+        // Removing the views runs their cleanup listeners, children before their parents
+        self.app_state.remove_view(&mut self.view);
+        WINDOW_SIGNALS.with(|window_signals| {
+            window_signals.borrow_mut().remove(&self.id);
+        });
         self.scope.dispose();
     }
 
@@ -1219,6 +2339,122 @@ impl WindowHandle {
     }
 }
 
+/// Grab the cursor with `mode`, falling back to the other grabbing mode if the platform
+/// doesn't support it.
+fn set_cursor_grab(window: &winit::window::Window, mode: CursorGrabMode) {
+    let fallback = match mode {
+        CursorGrabMode::None => None,
+        CursorGrabMode::Confined => Some(CursorGrabMode::Locked),
+        CursorGrabMode::Locked => Some(CursorGrabMode::Confined),
+    };
+    if let Err(err) = window.set_cursor_grab(mode) {
+        let fallback_err = match fallback {
+            Some(fallback) => window.set_cursor_grab(fallback).err(),
+            None => Some(err),
+        };
+        if let Some(err) = fallback_err {
+            eprintln!("Failed to grab the cursor: {err}");
+        }
+    }
+}
+
+/// Restyle the views which gained and lost the focus, send them the focus events and update
+/// the focused view signal of the window.
+fn focus_changed(
+    view: &mut WindowView,
+    app_state: &mut AppState,
+    old: Option<Id>,
+    new: Option<Id>,
+) {
+    app_state.focus_changed(old, new);
+
+    let mut cx = EventCx { app_state };
+    // The old view loses the focus before the new one gains it
+    for (id, event) in [(old, Event::FocusLost), (new, Event::FocusGained)] {
+        let id_path = id.and_then(|id| ID_PATHS.with(|paths| paths.borrow().get(&id).cloned()));
+        if let Some(id_path) = id_path {
+            cx.unconditional_view_event(view, Some(id_path.dispatch()), event);
+        }
+    }
+
+    if let Some(signals) = window_signals(view.id()) {
+        if signals.focused_view.get_untracked() != new {
+            signals.focused_view.set(new);
+        }
+    }
+}
+
+/// The state of a window reported by the platform, used to create a [`WindowHandle`]
+struct PlatformState {
+    window_id: WindowId,
+    scale: f64,
+    /// The inner size in logical pixels
+    size: Size,
+    is_maximized: bool,
+    is_fullscreen: bool,
+    focused: bool,
+    refresh_rate: Option<f64>,
+    system_theme: winit::window::Theme,
+}
+
+/// Reactive state of a window which views can subscribe to
+#[derive(Clone, Copy)]
+pub(crate) struct WindowSignals {
+    pub(crate) focused: RwSignal<bool>,
+    pub(crate) occluded: RwSignal<bool>,
+    pub(crate) focused_view: RwSignal<Option<Id>>,
+    pub(crate) scale: RwSignal<f64>,
+    pub(crate) size: RwSignal<Size>,
+    pub(crate) refresh_rate: RwSignal<Option<f64>>,
+    pub(crate) system_theme: RwSignal<winit::window::Theme>,
+}
+
+/// The refresh rate in Hz of the monitor the window is on
+fn monitor_refresh_rate(window: &winit::window::Window) -> Option<f64> {
+    window
+        .current_monitor()?
+        .refresh_rate_millihertz()
+        .map(|rate| rate as f64 / 1000.0)
+}
+
+thread_local! {
+    /// The signals of each window, keyed by the `Id` of the window's root view
+    static WINDOW_SIGNALS: RefCell<HashMap<Id, WindowSignals>> = Default::default();
+}
+
+/// Get the signals of the window with the root view `id`
+pub(crate) fn window_signals(id: Id) -> Option<WindowSignals> {
+    WINDOW_SIGNALS.with(|window_signals| window_signals.borrow().get(&id).copied())
+}
+
+/// Paints the timing of a frame in the top left corner of the window.
+fn paint_frame_timing(cx: &mut PaintCx, timing: &FrameTiming) {
+    let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+    let mut text = format!(
+        "style {:.1} ms, layout {:.1} ms, paint {:.1} ms",
+        ms(timing.style),
+        ms(timing.layout),
+        ms(timing.paint)
+    );
+    if let Some(since_last_frame) = timing.since_last_frame {
+        text += &format!(", {:.1} ms since the last frame", ms(since_last_frame));
+    }
+    let mut text_layout = TextLayout::new();
+    text_layout.set_text(
+        &text,
+        AttrsList::new(Attrs::new().color(peniko::Color::WHITE).font_size(12.0)),
+    );
+    let size = text_layout.size();
+
+    cx.clear_transform();
+    cx.fill(
+        &Rect::new(0.0, 0.0, size.width + 8.0, size.height + 4.0),
+        peniko::Color::BLACK.with_alpha_factor(0.7),
+        0.0,
+    );
+    cx.draw_text(&text_layout, Point::new(4.0, 2.0));
+}
+
 pub(crate) fn get_current_view() -> Id {
     CURRENT_RUNNING_VIEW_HANDLE.with(|running| *running.borrow())
 }
@@ -1229,6 +2465,38 @@ pub(crate) fn set_current_view(id: Id) {
     });
 }
 
+/// Only show the shortcuts of the items of a context menu which are registered for the window,
+/// and let items without an action run the action of their shortcut.
+fn use_registered_shortcuts(
+    shortcuts: &[(ShortcutId, KeyCombination, Rc<dyn Fn()>)],
+    menu: &mut Menu,
+) {
+    for child in menu.children.iter_mut() {
+        match child {
+            crate::menu::MenuEntry::Separator => {}
+            crate::menu::MenuEntry::Item(item) => {
+                let Some(shortcut) = item.shortcut.as_ref() else {
+                    continue;
+                };
+                let registered = shortcuts
+                    .iter()
+                    .rev()
+                    .find(|(_, combination, _)| combination == shortcut);
+                match registered {
+                    Some((_, _, action)) => {
+                        if item.action.is_none() {
+                            let action = action.clone();
+                            item.action = Some(Box::new(move || action()));
+                        }
+                    }
+                    None => item.shortcut = None,
+                }
+            }
+            crate::menu::MenuEntry::SubMenu(menu) => use_registered_shortcuts(shortcuts, menu),
+        }
+    }
+}
+
 #[cfg(target_os = "linux")]
 fn context_menu_view(
     cx: Scope,
@@ -1248,7 +2516,9 @@ fn context_menu_view(
     struct MenuDisplay {
         id: Option<u64>,
         enabled: bool,
+        selected: Option<bool>,
         title: String,
+        shortcut: Option<String>,
         children: Option<Vec<Option<MenuDisplay>>>,
     }
 
@@ -1260,13 +2530,17 @@ fn context_menu_view(
                 crate::menu::MenuEntry::Item(i) => Some(MenuDisplay {
                     id: Some(i.id),
                     enabled: i.enabled,
+                    selected: i.selected,
                     title: i.title.clone(),
+                    shortcut: i.shortcut.as_ref().map(|shortcut| shortcut.label()),
                     children: None,
                 }),
                 crate::menu::MenuEntry::SubMenu(m) => Some(MenuDisplay {
                     id: None,
                     enabled: m.item.enabled,
+                    selected: None,
                     title: m.item.title.clone(),
+                    shortcut: None,
                     children: Some(format_menu(m)),
                 }),
             })
@@ -1295,11 +2569,31 @@ fn context_menu_view(
             let on_submenu = create_rw_signal(false);
             let on_child_submenu = create_rw_signal(false);
             let has_submenu = menu.children.is_some();
+            let is_checkable = menu.selected.is_some();
+            let is_checked = menu.selected == Some(true);
+            let has_shortcut = menu.shortcut.is_some();
+            let shortcut = menu.shortcut.unwrap_or_default();
+            let check_svg = r#"<svg width="16" height="16" viewBox="0 0 16 16" xmlns="http://www.w3.org/2000/svg" fill="currentColor"><path fill-rule="evenodd" clip-rule="evenodd" d="M14.431 3.323l-8.47 10-.79-.036-3.35-4.77.818-.574 2.978 4.24 8.051-9.506.764.646z"/></svg>"#;
             let submenu_svg = r#"<svg width="16" height="16" viewBox="0 0 16 16" xmlns="http://www.w3.org/2000/svg" fill="currentColor"><path fill-rule="evenodd" clip-rule="evenodd" d="M10.072 8.024L5.715 3.667l.618-.62L11 7.716v.618L6.333 13l-.618-.619 4.357-4.357z"/></svg>"#;
             container_box(
                 stack((
                     stack((
-                        text(menu.title),
+                        stack((
+                            svg(|| check_svg.to_string()).style(move |s| {
+                                s.size(16.0, 16.0)
+                                    .margin_right(5.0)
+                                    .apply_if(!is_checked, |s| s.color(Color::TRANSPARENT))
+                                    .apply_if(!is_checkable, |s| s.hide())
+                            }),
+                            text(menu.title),
+                        ))
+                        // The shortcut goes to the right edge of the menu
+                        .style(|s| s.items_center().flex_grow(1.0)),
+                        text(shortcut).style(move |s| {
+                            s.margin_left(20.0)
+                                .color(Color::rgb8(160, 160, 160))
+                                .apply_if(!has_shortcut, |s| s.hide())
+                        }),
                         svg(|| submenu_svg.to_string()).style(move |s| {
                             s.size(20.0, 20.0)
                                 .color(Color::rgb8(201, 201, 201))
@@ -1504,10 +2798,18 @@ fn context_menu_view(
     view
 }
 
+struct Modal {
+    on_dismiss: Box<dyn Fn()>,
+    /// The view which had the focus when the modal was opened.
+    previous_focus: Option<Id>,
+}
+
 struct OverlayView {
     data: ViewData,
     scope: Scope,
     position: Point,
+    /// Set for a modal, which is centered over a scrim covering the window.
+    modal: Option<Modal>,
     child: Box<dyn View>,
 }
 
@@ -1521,6 +2823,15 @@ impl View for OverlayView {
     }
 
     fn view_style(&self) -> Option<crate::style::Style> {
+        if self.modal.is_some() {
+            return Some(
+                Style::new()
+                    .absolute()
+                    .inset(0.0)
+                    .items_center()
+                    .justify_center(),
+            );
+        }
         Some(
             Style::new()
                 .absolute()
@@ -1545,7 +2856,28 @@ impl View for OverlayView {
     }
 
     fn debug_name(&self) -> std::borrow::Cow<'static, str> {
-        "Overlay".into()
+        if self.modal.is_some() {
+            "Modal".into()
+        } else {
+            "Overlay".into()
+        }
+    }
+
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        if default_event(self, cx, id_path, event.clone()).is_processed() {
+            return EventPropagation::Stop;
+        }
+        // The scrim of a modal keeps pointer events from reaching the views below it
+        if self.modal.is_some() && event.is_pointer() {
+            EventPropagation::Stop
+        } else {
+            EventPropagation::Continue
+        }
     }
 }
 
@@ -1597,3 +2929,21 @@ impl View for WindowView {
         "Window".into()
     }
 }
+
+impl WindowView {
+    /// The topmost modal, which gets all key events.
+    fn modal(&self) -> Option<&OverlayView> {
+        self.overlays
+            .values()
+            .rev()
+            .find(|overlay| overlay.modal.is_some())
+    }
+
+    /// The view Tab keeps the focus within, which is the topmost modal or the focus trap
+    /// the focused view is in.
+    fn focus_scope(&self, app_state: &AppState) -> Option<Id> {
+        self.modal()
+            .map(|modal| modal.id())
+            .or_else(|| app_state.focus_trap())
+    }
+}
@@ -1,19 +1,21 @@
 use anyhow::{anyhow, Result};
 use floem_renderer::cosmic_text::{CacheKey, SubpixelBin, SwashCache, SwashContent, TextLayout};
 use floem_renderer::tiny_skia::{
-    self, FillRule, FilterQuality, GradientStop, LinearGradient, Mask, MaskType, Paint, Path,
-    PathBuilder, Pattern, Pixmap, RadialGradient, Shader, SpreadMode, Stroke, Transform,
+    self, BlendMode, FillRule, FilterQuality, GradientStop, LinearGradient, Mask, MaskType, Paint,
+    Path, PathBuilder, Pattern, Pixmap, PixmapPaint, RadialGradient, Shader, SpreadMode, Stroke,
+    Transform,
 };
 use floem_renderer::Img;
 use floem_renderer::Renderer;
-use image::DynamicImage;
+use image::{DynamicImage, RgbaImage};
 use peniko::kurbo::PathEl;
 use peniko::{
-    kurbo::{Affine, Point, Rect, Shape},
+    kurbo::{Affine, Point, Rect, Shape, Vec2},
     BrushRef, Color, GradientKind,
 };
 use softbuffer::{Context, Surface};
 use std::collections::HashMap;
+use std::mem;
 use std::num::NonZeroU32;
 use std::rc::Rc;
 
@@ -36,15 +38,50 @@ struct Glyph {
 #[derive(PartialEq, Clone, Copy)]
 struct CacheColor(bool);
 
-pub struct TinySkiaRenderer {
+/// The full frame, set aside while a damaged region of it is painted.
+struct Damage {
+    pixmap: Pixmap,
+    mask: Mask,
+    /// The position of the region in the frame
+    x: u32,
+    y: u32,
+}
+
+/// A layer which is being painted, with what was set aside to paint it.
+struct Layer {
+    /// What's painted into before the layer
+    parent: Pixmap,
+    parent_mask: Mask,
+    parent_clip: Option<Rect>,
+    alpha: f32,
+    /// Maps the paint coordinates to the layer, in logical pixels
+    to_layer: Affine,
+    /// Maps the pixels of the layer to the pixels of the parent
+    to_parent: Affine,
+}
+
+struct WindowSurface {
     #[allow(unused)]
     context: Context,
     surface: Surface,
+}
+
+pub struct TinySkiaRenderer {
+    /// The surface frames are presented to, or `None` if frames are only returned by `finish`
+    surface: Option<WindowSurface>,
     pixmap: Pixmap,
     mask: Mask,
     scale: f64,
     transform: Affine,
     clip: Option<Rect>,
+    transparent: bool,
+
+    /// Whether `pixmap` holds the last presented frame, so parts of it can be repainted.
+    presented: bool,
+    /// Set while a damaged region is painted into `pixmap` instead of the full frame.
+    damage: Option<Damage>,
+    /// The layers which are being painted, `pixmap` being the last of them
+    layers: Vec<Layer>,
 
     /// The cache color value set for cache entries accessed this frame.
     cache_color: CacheColor,
@@ -62,6 +99,7 @@ impl TinySkiaRenderer {
         width: u32,
         height: u32,
         scale: f64,
+        transparent: bool,
     ) -> Result<Self> {
         let context = unsafe {
             Context::new(&window).map_err(|err| anyhow!("unable to create context: {}", err))?
@@ -70,20 +108,30 @@ impl TinySkiaRenderer {
             Surface::new(&context, &window)
                 .map_err(|err| anyhow!("unable to create surface: {}", err))?
         };
+        let mut renderer = Self::new_headless(width, height, scale, transparent)?;
+        renderer.surface = Some(WindowSurface { context, surface });
+        Ok(renderer)
+    }
 
+    /// Creates a renderer without a window. Each frame is returned by `finish` instead of being
+    /// presented, which is useful for rendering views in tests.
+    pub fn new_headless(width: u32, height: u32, scale: f64, transparent: bool) -> Result<Self> {
         let pixmap =
             Pixmap::new(width, height).ok_or_else(|| anyhow!("unable to create pixmap"))?;
 
         let mask = Mask::new(width, height).ok_or_else(|| anyhow!("unable to create mask"))?;
 
         Ok(Self {
-            context,
-            surface,
+            surface: None,
             pixmap,
             mask,
             scale,
             transform: Affine::IDENTITY,
             clip: None,
+            transparent,
+            presented: false,
+            damage: None,
+            layers: Vec::new(),
             cache_color: CacheColor(false),
             image_cache: Default::default(),
             glyph_cache: Default::default(),
@@ -92,19 +140,26 @@ impl TinySkiaRenderer {
 
     pub fn resize(&mut self, width: u32, height: u32, scale: f64) {
         if width != self.pixmap.width() || height != self.pixmap.width() {
-            self.surface
-                .resize(
-                    NonZeroU32::new(width).unwrap_or(NonZeroU32::new(1).unwrap()),
-                    NonZeroU32::new(height).unwrap_or(NonZeroU32::new(1).unwrap()),
-                )
-                .expect("failed to resize surface");
+            if let Some(surface) = self.surface.as_mut() {
+                surface
+                    .surface
+                    .resize(
+                        NonZeroU32::new(width).unwrap_or(NonZeroU32::new(1).unwrap()),
+                        NonZeroU32::new(height).unwrap_or(NonZeroU32::new(1).unwrap()),
+                    )
+                    .expect("failed to resize surface");
+            }
             self.pixmap = Pixmap::new(width, height).expect("unable to create pixmap");
             self.mask = Mask::new(width, height).expect("unable to create mask");
+            self.presented = false;
         }
-        self.scale = scale;
+        self.set_scale(scale);
     }
 
     pub fn set_scale(&mut self, scale: f64) {
+        if scale != self.scale {
+            self.presented = false;
+        }
         self.scale = scale;
     }
 }
@@ -117,6 +172,13 @@ fn to_point(point: Point) -> tiny_skia::Point {
     tiny_skia::Point::from_xy(point.x as f32, point.y as f32)
 }
 
+fn to_transform(affine: Affine) -> Transform {
+    let [sx, ky, kx, sy, tx, ty] = affine.as_coeffs();
+    Transform::from_row(
+        sx as f32, ky as f32, kx as f32, sy as f32, tx as f32, ty as f32,
+    )
+}
+
 impl TinySkiaRenderer {
     fn shape_to_path(&self, shape: &impl Shape) -> Option<Path> {
         let mut builder = PathBuilder::new();
@@ -202,12 +264,15 @@ impl TinySkiaRenderer {
         } else {
             return Some(rect);
         };
-        let clip = self.rect(clip.scale_from_origin(self.scale))?;
+        let (x, y) = self.offset();
+        let clip = self.rect(clip.scale_from_origin(self.scale) - Vec2::new(x as f64, y as f64))?;
         clip.intersect(&rect)
     }
 
     /// Renders the pixmap at the position without transforming it.
     fn render_pixmap_direct(&mut self, pixmap: &Pixmap, x: f32, y: f32) {
+        let (offset_x, offset_y) = self.offset();
+        let (x, y) = (x - offset_x, y - offset_y);
         let rect = try_ret!(tiny_skia::Rect::from_xywh(
             x,
             y,
@@ -286,17 +351,28 @@ impl TinySkiaRenderer {
     }
 
     fn current_transform(&self) -> Transform {
-        let transfrom = self.transform.as_coeffs();
         let scale = self.scale as f32;
-        Transform::from_row(
-            transfrom[0] as f32,
-            transfrom[1] as f32,
-            transfrom[2] as f32,
-            transfrom[3] as f32,
-            transfrom[4] as f32,
-            transfrom[5] as f32,
-        )
-        .post_scale(scale, scale)
+        to_transform(self.local_transform())
+            .post_scale(scale, scale)
+            .post_translate(-self.offset().0, -self.offset().1)
+    }
+
+    /// The transform from the paint coordinates to `pixmap`, in logical pixels.
+    fn local_transform(&self) -> Affine {
+        match self.layers.last() {
+            Some(layer) => layer.to_layer * self.transform,
+            None => self.transform,
+        }
+    }
+
+    /// The position of `pixmap` in the frame, in physical pixels.
+    fn offset(&self) -> (f32, f32) {
+        if !self.layers.is_empty() {
+            return (0.0, 0.0);
+        }
+        self.damage
+            .as_ref()
+            .map_or((0.0, 0.0), |damage| (damage.x as f32, damage.y as f32))
     }
 
     fn cache_glyph(&mut self, cache_key: CacheKey, color: Color) -> Option<Rc<Glyph>> {
@@ -340,11 +416,49 @@ impl TinySkiaRenderer {
 
 impl Renderer for TinySkiaRenderer {
     fn begin(&mut self, _capture: bool) {
+        // The whole frame is painted instead of the damaged region
+        if let Some(damage) = self.damage.take() {
+            self.pixmap = damage.pixmap;
+            self.mask = damage.mask;
+        }
         self.transform = Affine::IDENTITY;
-        self.pixmap.fill(tiny_skia::Color::WHITE);
+        self.pixmap.fill(if self.transparent {
+            tiny_skia::Color::TRANSPARENT
+        } else {
+            tiny_skia::Color::WHITE
+        });
         self.clip = None;
     }
 
+    fn begin_damaged(&mut self, damage: Rect) -> bool {
+        if !self.presented {
+            return false;
+        }
+        let frame = Rect::new(
+            0.0,
+            0.0,
+            self.pixmap.width() as f64,
+            self.pixmap.height() as f64,
+        );
+        let damage = damage
+            .scale_from_origin(self.scale)
+            .expand()
+            .intersect(frame);
+        let (width, height) = (damage.width() as u32, damage.height() as u32);
+        let (Some(pixmap), Some(mask)) = (Pixmap::new(width, height), Mask::new(width, height))
+        else {
+            return false;
+        };
+        self.damage = Some(Damage {
+            pixmap: mem::replace(&mut self.pixmap, pixmap),
+            mask: mem::replace(&mut self.mask, mask),
+            x: damage.x0 as u32,
+            y: damage.y0 as u32,
+        });
+        self.begin(false);
+        true
+    }
+
     fn stroke<'b>(&mut self, shape: &impl Shape, brush: impl Into<BrushRef<'b>>, width: f64) {
         let paint = try_ret!(self.brush_to_paint(brush));
         let path = try_ret!(self.shape_to_path(shape));
@@ -381,7 +495,7 @@ impl Renderer for TinySkiaRenderer {
     }
 
     fn draw_text(&mut self, layout: &TextLayout, pos: impl Into<Point>) {
-        let offset = self.transform.translation();
+        let offset = self.local_transform().translation();
         let pos: Point = pos.into();
         let clip = self.clip;
         for line in layout.layout_runs() {
@@ -471,7 +585,12 @@ impl Renderer for TinySkiaRenderer {
 
         let paint = brush.and_then(|brush| self.brush_to_paint(brush));
 
-        if let Some((color, pixmap)) = self.image_cache.get_mut(svg.hash) {
+        // The size is part of the key so the svg is rasterized again when the scale changes
+        let mut key = svg.hash.to_owned();
+        key.extend_from_slice(&width.to_le_bytes());
+        key.extend_from_slice(&height.to_le_bytes());
+
+        if let Some((color, pixmap)) = self.image_cache.get_mut(&key) {
             *color = self.cache_color;
             let pixmap = pixmap.clone();
             self.render_pixmap_paint(&pixmap, rect, paint);
@@ -489,7 +608,7 @@ impl Renderer for TinySkiaRenderer {
         self.render_pixmap_paint(&pixmap, rect, paint);
 
         self.image_cache
-            .insert(svg.hash.to_owned(), (self.cache_color, Rc::new(pixmap)));
+            .insert(key, (self.cache_color, Rc::new(pixmap)));
     }
 
     fn transform(&mut self, transform: Affine) {
@@ -509,7 +628,7 @@ impl Renderer for TinySkiaRenderer {
             shape.bounding_box()
         };
 
-        let offset = self.transform.translation();
+        let offset = self.local_transform().translation();
         self.clip = Some(rect + offset);
 
         self.mask.clear();
@@ -522,6 +641,123 @@ impl Renderer for TinySkiaRenderer {
         self.clip = None;
     }
 
+    fn push_layer(&mut self, alpha: f32, bounds: Rect) {
+        // Only the part of the layer which isn't clipped away is painted
+        let (x, y) = self.offset();
+        let mut visible = Rect::new(
+            0.0,
+            0.0,
+            self.pixmap.width() as f64,
+            self.pixmap.height() as f64,
+        );
+        if let Some(clip) = self.clip {
+            let clip = clip.scale_from_origin(self.scale) - Vec2::new(x as f64, y as f64);
+            visible = visible.intersect(clip);
+        }
+
+        // The layer is painted in the current coordinates, so it's transformed as a whole
+        let to_parent = Affine::translate((-x as f64, -y as f64))
+            * Affine::scale(self.scale)
+            * self.local_transform()
+            * Affine::scale(self.scale.recip());
+        let bounds = to_parent
+            .inverse()
+            .transform_rect_bbox(visible)
+            .intersect(bounds.scale_from_origin(self.scale))
+            .expand();
+        let bounds = if bounds.area().is_finite() {
+            bounds
+        } else {
+            Rect::ZERO
+        };
+        let origin = bounds.origin().to_vec2();
+
+        let (width, height) = (bounds.width().max(1.0), bounds.height().max(1.0));
+        let pixmap = Pixmap::new(width as u32, height as u32).expect("unable to create pixmap");
+        let mask = Mask::new(width as u32, height as u32).expect("unable to create mask");
+        self.layers.push(Layer {
+            parent: mem::replace(&mut self.pixmap, pixmap),
+            parent_mask: mem::replace(&mut self.mask, mask),
+            parent_clip: self.clip.take(),
+            alpha,
+            to_layer: Affine::translate(-origin / self.scale) * self.transform.inverse(),
+            to_parent: to_parent * Affine::translate(origin),
+        });
+    }
+
+    fn pop_layer(&mut self) {
+        let layer = try_ret!(self.layers.pop());
+        let pixmap = mem::replace(&mut self.pixmap, layer.parent);
+        self.mask = layer.parent_mask;
+        self.clip = layer.parent_clip;
+
+        let [sx, ky, kx, sy, tx, ty] = layer.to_parent.as_coeffs();
+        let aligned =
+            (sx, ky, kx, sy) == (1.0, 0.0, 0.0, 1.0) && tx.fract() == 0.0 && ty.fract() == 0.0;
+        self.pixmap.draw_pixmap(
+            0,
+            0,
+            pixmap.as_ref(),
+            &PixmapPaint {
+                opacity: layer.alpha,
+                quality: if aligned {
+                    FilterQuality::Nearest
+                } else {
+                    FilterQuality::Bilinear
+                },
+                ..Default::default()
+            },
+            to_transform(layer.to_parent),
+            self.clip.is_some().then_some(&self.mask),
+        );
+    }
+
+    fn blur_backdrop(&mut self, shape: &impl Shape, radius: f64) {
+        let radius = (radius * self.scale).round() as i32;
+        if radius <= 0 {
+            return;
+        }
+        let path = try_ret!(self.shape_to_path(shape));
+        let transform = self.current_transform();
+        let bounds = try_ret!(path.clone().transform(transform)).bounds();
+
+        // The blurred region, including the pixels sampled around the shape. Nothing is done
+        // if the shape isn't in the repainted region of the frame.
+        let extent = radius * 3;
+        let region = try_ret!(tiny_skia::IntRect::from_ltrb(
+            (bounds.left().floor() as i32 - extent).max(0),
+            (bounds.top().floor() as i32 - extent).max(0),
+            (bounds.right().ceil() as i32 + extent).min(self.pixmap.width() as i32),
+            (bounds.bottom().ceil() as i32 + extent).min(self.pixmap.height() as i32),
+        ));
+        let mut blurred = try_ret!(self.pixmap.clone_rect(region));
+        let (width, height) = (blurred.width() as usize, blurred.height() as usize);
+        // Three box blurs are close to a gaussian blur
+        for _ in 0..3 {
+            box_blur(blurred.data_mut(), width, height, radius as usize, true);
+            box_blur(blurred.data_mut(), width, height, radius as usize, false);
+        }
+
+        let paint = Paint {
+            shader: Pattern::new(
+                blurred.as_ref(),
+                SpreadMode::Pad,
+                FilterQuality::Nearest,
+                1.0,
+                try_ret!(transform.invert()).pre_translate(region.x() as f32, region.y() as f32),
+            ),
+            blend_mode: BlendMode::Source,
+            ..Default::default()
+        };
+        self.pixmap.fill_path(
+            &path,
+            &paint,
+            FillRule::Winding,
+            transform,
+            self.clip.is_some().then_some(&self.mask),
+        );
+    }
+
     fn finish(&mut self) -> Option<DynamicImage> {
         // Remove cache entries which were not accessed.
         self.image_cache.retain(|_, (c, _)| *c == self.cache_color);
@@ -530,21 +766,145 @@ impl Renderer for TinySkiaRenderer {
         // Swap the cache color.
         self.cache_color = CacheColor(!self.cache_color.0);
 
-        let mut buffer = self
+        // Put the repainted region back into the frame.
+        let damage = self.damage.take().map(|damage| {
+            let region = mem::replace(&mut self.pixmap, damage.pixmap);
+            self.mask = damage.mask;
+            self.pixmap.draw_pixmap(
+                damage.x as i32,
+                damage.y as i32,
+                region.as_ref(),
+                &PixmapPaint {
+                    blend_mode: BlendMode::Source,
+                    ..Default::default()
+                },
+                Transform::identity(),
+                None,
+            );
+            softbuffer::Rect {
+                x: damage.x,
+                y: damage.y,
+                width: NonZeroU32::new(region.width()).unwrap(),
+                height: NonZeroU32::new(region.height()).unwrap(),
+            }
+        });
+
+        let Some(surface) = self.surface.as_mut() else {
+            let data = self
+                .pixmap
+                .pixels()
+                .iter()
+                .flat_map(|pixel| {
+                    let pixel = pixel.demultiply();
+                    [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()]
+                })
+                .collect();
+            let image = RgbaImage::from_raw(self.pixmap.width(), self.pixmap.height(), data)?;
+            self.presented = true;
+            return Some(DynamicImage::ImageRgba8(image));
+        };
+
+        let mut buffer = surface
             .surface
             .buffer_mut()
             .expect("failed to get the surface buffer");
 
         // Copy from `tiny_skia::Pixmap` to the format specified by `softbuffer::Buffer`.
+        // The whole frame is copied as the buffer may not hold the previous frame.
         for (out_pixel, pixel) in (buffer.iter_mut()).zip(self.pixmap.pixels().iter()) {
             *out_pixel =
                 (pixel.red() as u32) << 16 | (pixel.green() as u32) << 8 | (pixel.blue() as u32);
         }
 
-        buffer
-            .present()
-            .expect("failed to present the surface buffer");
+        match damage {
+            Some(damage) => buffer.present_with_damage(&[damage]),
+            None => buffer.present(),
+        }
+        .expect("failed to present the surface buffer");
+        self.presented = true;
 
         None
     }
 }
+
+/// Blur the premultiplied RGBA pixels in `data`, of an image which is `width` by `height` pixels
+/// large, with a box of `radius` pixels around each pixel, along the rows if `horizontal` is set
+/// and otherwise along the columns. Pixels outside of the image repeat the edge.
+fn box_blur(data: &mut [u8], width: usize, height: usize, radius: usize, horizontal: bool) {
+    let (lines, len, step, line_step) = if horizontal {
+        (height, width, 4, width * 4)
+    } else {
+        (width, height, width * 4, 4)
+    };
+    if len == 0 {
+        return;
+    }
+    let window = 2 * radius as u32 + 1;
+    let mut line = vec![[0u8; 4]; len];
+    for l in 0..lines {
+        let start = l * line_step;
+        for (i, pixel) in line.iter_mut().enumerate() {
+            let at = start + i * step;
+            pixel.copy_from_slice(&data[at..at + 4]);
+        }
+        let get = |i: isize| line[i.clamp(0, len as isize - 1) as usize];
+
+        let mut sum = [0u32; 4];
+        for i in -(radius as isize)..=radius as isize {
+            for (sum, value) in sum.iter_mut().zip(get(i)) {
+                *sum += value as u32;
+            }
+        }
+        for i in 0..len {
+            let at = start + i * step;
+            for (out, sum) in data[at..at + 4].iter_mut().zip(sum) {
+                *out = ((sum + window / 2) / window) as u8;
+            }
+            let i = i as isize;
+            let (add, remove) = (get(i + radius as isize + 1), get(i - radius as isize));
+            for ((sum, add), remove) in sum.iter_mut().zip(add).zip(remove) {
+                *sum = *sum + add as u32 - remove as u32;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::box_blur;
+
+    fn pixels(values: &[u8]) -> Vec<u8> {
+        values.iter().flat_map(|&v| [v; 4]).collect()
+    }
+
+    #[test]
+    fn box_blur_spreads_pixels() {
+        let mut data = pixels(&[0, 0, 255, 0, 0]);
+        box_blur(&mut data, 5, 1, 1, true);
+        assert_eq!(data, pixels(&[0, 85, 85, 85, 0]));
+
+        // Columns are blurred the same way
+        let mut data = pixels(&[0, 0, 255, 0, 0]);
+        box_blur(&mut data, 1, 5, 1, false);
+        assert_eq!(data, pixels(&[0, 85, 85, 85, 0]));
+    }
+
+    #[test]
+    fn box_blur_repeats_edges() {
+        let mut data = pixels(&[90, 0, 0]);
+        box_blur(&mut data, 3, 1, 1, true);
+        assert_eq!(data, pixels(&[60, 30, 0]));
+    }
+
+    #[test]
+    fn box_blur_keeps_lines_apart() {
+        // Blurring rows doesn't mix them, and blurring columns doesn't mix columns
+        let mut data = pixels(&[255, 255, 0, 0]);
+        box_blur(&mut data, 2, 2, 4, true);
+        assert_eq!(data, pixels(&[255, 255, 0, 0]));
+
+        let mut data = pixels(&[255, 0, 255, 0]);
+        box_blur(&mut data, 2, 2, 4, false);
+        assert_eq!(data, pixels(&[255, 0, 255, 0]));
+    }
+}
@@ -0,0 +1,294 @@
+use std::borrow::Cow;
+
+use peniko::kurbo::{Point, Rect, Vec2};
+use wgpu::util::DeviceExt;
+use wgpu::{Device, Queue, StoreOp, TextureFormat};
+
+/// The usages of the textures layers are painted into.
+pub(crate) const LAYER_USAGES: wgpu::TextureUsages = wgpu::TextureUsages::RENDER_ATTACHMENT
+    .union(wgpu::TextureUsages::TEXTURE_BINDING)
+    .union(wgpu::TextureUsages::COPY_SRC)
+    .union(wgpu::TextureUsages::COPY_DST);
+
+/// A textured quad drawn by the [`Compositor`].
+pub(crate) struct Quad {
+    /// The corners in the target, in physical pixels, in the order top left, top right,
+    /// bottom left and bottom right of the texture.
+    pub(crate) corners: [Point; 4],
+    /// The texture coordinates of the corners.
+    pub(crate) uvs: [Point; 4],
+    pub(crate) alpha: f32,
+    pub(crate) mask: Option<Mask>,
+    /// The part of the target which is drawn to, in physical pixels.
+    pub(crate) scissor: Option<Rect>,
+}
+
+/// A rounded rectangle a [`Quad`] is masked to.
+pub(crate) struct Mask {
+    /// The corners of the quad in the coordinates of the mask.
+    pub(crate) corners: [Point; 4],
+    pub(crate) rect: Rect,
+    pub(crate) radius: f64,
+    /// The physical pixels per unit of the mask, so its edge is antialiased.
+    pub(crate) scale: f64,
+}
+
+/// Draws textures into other textures, which Vger can't do. This is used to composite layers
+/// and blur backdrops.
+pub(crate) struct Compositor {
+    layout: wgpu::BindGroupLayout,
+    composite: wgpu::RenderPipeline,
+    blur: wgpu::RenderPipeline,
+    sampler: wgpu::Sampler,
+}
+
+impl Compositor {
+    pub(crate) fn new(device: &Device, format: TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("compositor"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("compositor.wgsl"))),
+        });
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("compositor"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("compositor"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = |entry_point: &str, blend: Option<wgpu::BlendState>| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("compositor"),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vs_main",
+                    buffers: &[],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point,
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleStrip,
+                    ..Default::default()
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+            })
+        };
+        let composite = pipeline(
+            "fs_composite",
+            Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+        );
+        let blur = pipeline("fs_blur", None);
+        // Clamping repeats the edges of blurred textures
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("compositor"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        Self {
+            layout,
+            composite,
+            blur,
+            sampler,
+        }
+    }
+
+    /// Blend `quad` of `source` onto `target`, which is `size` physical pixels large.
+    pub(crate) fn composite(
+        &self,
+        device: &Device,
+        queue: &Queue,
+        source: &wgpu::TextureView,
+        target: &wgpu::TextureView,
+        size: (u32, u32),
+        quad: &Quad,
+    ) {
+        self.draw(
+            device,
+            queue,
+            &self.composite,
+            source,
+            target,
+            size,
+            quad,
+            None,
+        );
+    }
+
+    /// Blur all of `source` into `target` along one direction, both being `size` physical
+    /// pixels large, with a gaussian blur whose standard deviation is `sigma` pixels.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn blur(
+        &self,
+        device: &Device,
+        queue: &Queue,
+        source: &wgpu::TextureView,
+        target: &wgpu::TextureView,
+        size: (u32, u32),
+        sigma: f64,
+        horizontal: bool,
+    ) {
+        let (width, height) = (size.0 as f64, size.1 as f64);
+        let quad = Quad {
+            corners: [
+                Point::new(0.0, 0.0),
+                Point::new(width, 0.0),
+                Point::new(0.0, height),
+                Point::new(width, height),
+            ],
+            uvs: [
+                Point::new(0.0, 0.0),
+                Point::new(1.0, 0.0),
+                Point::new(0.0, 1.0),
+                Point::new(1.0, 1.0),
+            ],
+            alpha: 1.0,
+            mask: None,
+            scissor: None,
+        };
+        let step = if horizontal {
+            Vec2::new(width.recip(), 0.0)
+        } else {
+            Vec2::new(0.0, height.recip())
+        };
+        let blur = Some((step, sigma));
+        self.draw(device, queue, &self.blur, source, target, size, &quad, blur);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn draw(
+        &self,
+        device: &Device,
+        queue: &Queue,
+        pipeline: &wgpu::RenderPipeline,
+        source: &wgpu::TextureView,
+        target: &wgpu::TextureView,
+        size: (u32, u32),
+        quad: &Quad,
+        blur: Option<(Vec2, f64)>,
+    ) {
+        let (width, height) = (size.0 as f64, size.1 as f64);
+        let scissor = quad
+            .scissor
+            .unwrap_or(Rect::new(0.0, 0.0, width, height))
+            .intersect(Rect::new(0.0, 0.0, width, height))
+            .round();
+        if scissor.width() <= 0.0 || scissor.height() <= 0.0 {
+            return;
+        }
+
+        let mut uniforms = Vec::with_capacity(44);
+        for (corner, uv) in quad.corners.iter().zip(quad.uvs) {
+            uniforms.extend([
+                (corner.x / width * 2.0 - 1.0) as f32,
+                (1.0 - corner.y / height * 2.0) as f32,
+                uv.x as f32,
+                uv.y as f32,
+            ]);
+        }
+        // Without a mask everything is covered
+        let (corners, rect, radius, scale) = match &quad.mask {
+            Some(mask) => (mask.corners, mask.rect, mask.radius, mask.scale),
+            None => ([Point::ZERO; 4], Rect::new(-1.0, -1.0, 1.0, 1.0), 0.0, 1.0),
+        };
+        for corner in corners {
+            uniforms.extend([corner.x as f32, corner.y as f32, 0.0, 0.0]);
+        }
+        uniforms.extend([rect.x0, rect.y0, rect.x1, rect.y1].map(|v| v as f32));
+        let (step, sigma) = blur.unwrap_or((Vec2::ZERO, 0.0));
+        uniforms.extend([quad.alpha, radius as f32, scale as f32, sigma as f32]);
+        uniforms.extend([step.x as f32, step.y as f32, 0.0, 0.0]);
+        let contents: Vec<u8> = uniforms.iter().flat_map(|v| v.to_ne_bytes()).collect();
+
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("compositor"),
+            contents: &contents,
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("compositor"),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(source),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+        });
+
+        let mut encoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("compositor"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.set_scissor_rect(
+                scissor.x0 as u32,
+                scissor.y0 as u32,
+                scissor.width() as u32,
+                scissor.height() as u32,
+            );
+            pass.draw(0..4, 0..1);
+        }
+        queue.submit(Some(encoder.finish()));
+    }
+}
@@ -0,0 +1,69 @@
+struct Uniforms {
+    // The corners of the drawn quad, with the position in clip space in `xy` and the
+    // texture coordinates in `zw`
+    corners: array<vec4<f32>, 4>,
+    // The corners of the quad in the coordinates of the mask, in `xy`
+    mask_corners: array<vec4<f32>, 4>,
+    // The rounded rectangle the quad is masked to, as `x0`, `y0`, `x1` and `y1`
+    mask_rect: vec4<f32>,
+    // The alpha, the corner radius of the mask, the physical pixels per unit of the mask and
+    // the standard deviation of the blur in texels
+    params: vec4<f32>,
+    // The distance between the texels which are blurred together in `xy`, in texture
+    // coordinates
+    blur_step: vec4<f32>,
+}
+
+@group(0) @binding(0) var<uniform> uniforms: Uniforms;
+@group(0) @binding(1) var source: texture_2d<f32>;
+@group(0) @binding(2) var source_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) mask_position: vec2<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let corner = uniforms.corners[index];
+    var out: VertexOutput;
+    out.position = vec4<f32>(corner.xy, 0.0, 1.0);
+    out.uv = corner.zw;
+    out.mask_position = uniforms.mask_corners[index].xy;
+    return out;
+}
+
+fn coverage(position: vec2<f32>) -> f32 {
+    let rect = uniforms.mask_rect;
+    let radius = uniforms.params.y;
+    let center = (rect.xy + rect.zw) * 0.5;
+    let half_size = (rect.zw - rect.xy) * 0.5;
+    let q = abs(position - center) - half_size + vec2<f32>(radius);
+    let distance = length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - radius;
+    return clamp(0.5 - distance * uniforms.params.z, 0.0, 1.0);
+}
+
+// The textures hold premultiplied colors, so they're faded by scaling all channels
+@fragment
+fn fs_composite(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSampleLevel(source, source_sampler, in.uv, 0.0);
+    return color * uniforms.params.x * coverage(in.mask_position);
+}
+
+// One direction of a gaussian blur, sampling up to 3 standard deviations away
+@fragment
+fn fs_blur(in: VertexOutput) -> @location(0) vec4<f32> {
+    let sigma = uniforms.params.w;
+    let taps = i32(ceil(sigma * 3.0));
+    var sum = vec4<f32>(0.0);
+    var total = 0.0;
+    for (var i = -taps; i <= taps; i = i + 1) {
+        let x = f32(i);
+        let weight = exp(-(x * x) / (2.0 * sigma * sigma));
+        let uv = in.uv + uniforms.blur_step.xy * x;
+        sum = sum + textureSampleLevel(source, source_sampler, uv, 0.0) * weight;
+        total = total + weight;
+    }
+    return sum / total;
+}
@@ -1,3 +1,5 @@
+mod compositor;
+
 use std::mem;
 use std::sync::mpsc::sync_channel;
 use std::sync::Arc;
@@ -13,6 +15,60 @@ use peniko::{
 use vger::{Image, PaintIndex, PixelFormat, Vger};
 use wgpu::{Device, DeviceType, Queue, StoreOp, Surface, SurfaceConfiguration, TextureFormat};
 
+use compositor::{Compositor, Mask, Quad, LAYER_USAGES};
+
+/// What a frame is painted into.
+enum FrameTexture {
+    Surface(wgpu::SurfaceTexture),
+    Capture(wgpu::Texture),
+}
+
+/// A texture which is painted into by multiple scenes.
+struct Target {
+    view: wgpu::TextureView,
+    width: u32,
+    height: u32,
+    /// Whether the texture has to be cleared before the next scene is painted into it
+    clear: bool,
+}
+
+impl Target {
+    fn new(texture: &wgpu::Texture) -> Self {
+        Self {
+            view: texture.create_view(&wgpu::TextureViewDescriptor::default()),
+            width: texture.width(),
+            height: texture.height(),
+            clear: true,
+        }
+    }
+}
+
+/// A region of the frame which was blurred, in physical pixels.
+struct Blurred {
+    region: Rect,
+    sigma: f64,
+    texture: wgpu::Texture,
+}
+
+/// A layer which is being painted. Vger can only paint one scene into a texture at a time,
+/// so the layer is painted into its own texture and composited onto its parent when it's
+/// popped.
+struct Layer {
+    texture: wgpu::Texture,
+    target: Target,
+    /// The size of the layer in the texture, in physical pixels
+    width: u32,
+    height: u32,
+    alpha: f32,
+    /// Maps the paint coordinates to the layer, in logical pixels
+    to_layer: Affine,
+    /// Maps the physical pixels of the layer to the physical pixels of the parent
+    to_parent: Affine,
+    /// The clip of the parent, which is restored when the layer is popped
+    parent_clip: Option<Rect>,
+    parent_scissor: Option<(Rect, f32)>,
+}
+
 pub struct VgerRenderer {
     device: Arc<Device>,
     #[allow(unused)]
@@ -24,9 +80,28 @@ pub struct VgerRenderer {
     scale: f64,
     transform: Affine,
     clip: Option<Rect>,
+    /// The scissor of the scene in physical pixels, and its corner radius
+    scissor: Option<(Rect, f32)>,
+    z_index: i32,
     capture: bool,
+    frame: Option<(FrameTexture, Target)>,
+    layers: Vec<Layer>,
+    /// Textures of popped layers, which are reused for the next layers
+    spare_textures: Vec<wgpu::Texture>,
+    /// The area given to `begin_damaged`, which is all that changed since the last frame
+    damage: Option<Rect>,
+    /// The backdrops blurred on this frame
+    blurred: Vec<Blurred>,
+    /// The backdrops blurred on the last frame which nothing changed behind, so their blur
+    /// is reused
+    last_blurred: Vec<Blurred>,
+    compositor: Option<Compositor>,
+    alt_compositor: Option<Compositor>,
 }
 
+/// How many textures are kept around to paint layers and blurs into.
+const MAX_SPARE_TEXTURES: usize = 16;
+
 const CLEAR_COLOR: wgpu::Color = wgpu::Color {
     r: 0.0,
     g: 0.0,
@@ -42,6 +117,7 @@ impl VgerRenderer {
         width: u32,
         height: u32,
         scale: f64,
+        transparent: bool,
     ) -> Result<Self> {
         let instance = wgpu::Instance::default();
 
@@ -90,13 +166,34 @@ impl VgerRenderer {
             .find(|it| matches!(it, TextureFormat::Rgba8Unorm | TextureFormat::Bgra8Unorm))
             .ok_or_else(|| anyhow::anyhow!("surface should support Rgba8Unorm or Bgra8Unorm"))?;
 
+        // `Auto` prefers `Opaque`, which would discard the alpha of transparent windows
+        let alpha_mode = if transparent {
+            surface_caps
+                .alpha_modes
+                .iter()
+                .copied()
+                .find(|mode| {
+                    matches!(
+                        mode,
+                        wgpu::CompositeAlphaMode::PostMultiplied
+                            | wgpu::CompositeAlphaMode::PreMultiplied
+                            | wgpu::CompositeAlphaMode::Inherit
+                    )
+                })
+                .unwrap_or(wgpu::CompositeAlphaMode::Auto)
+        } else {
+            wgpu::CompositeAlphaMode::Auto
+        };
+
+        // Frames are copied from to blur their backdrops, if the surface allows it
         let config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | (surface_caps.usages & wgpu::TextureUsages::COPY_SRC),
             format: texture_format,
             width,
             height,
             present_mode: wgpu::PresentMode::Fifo,
-            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            alpha_mode,
             view_formats: vec![],
         };
         surface.configure(&device, &config);
@@ -113,7 +210,17 @@ impl VgerRenderer {
             config,
             transform: Affine::IDENTITY,
             clip: None,
+            scissor: None,
+            z_index: 0,
             capture: false,
+            frame: None,
+            layers: Vec::new(),
+            spare_textures: Vec::new(),
+            damage: None,
+            blurred: Vec::new(),
+            last_blurred: Vec::new(),
+            compositor: None,
+            alt_compositor: None,
         })
     }
 
@@ -122,6 +229,9 @@ impl VgerRenderer {
             self.config.width = width;
             self.config.height = height;
             self.surface.configure(&self.device, &self.config);
+            self.spare_textures.clear();
+            self.blurred.clear();
+            self.last_blurred.clear();
         }
         self.scale = scale;
     }
@@ -155,9 +265,85 @@ impl VgerRenderer {
         Some(paint)
     }
 
+    /// The transform from the paint coordinates to the texture painted into, in logical
+    /// pixels.
+    fn local_transform(&self) -> Affine {
+        match self.layers.last() {
+            Some(layer) => layer.to_layer * self.transform,
+            None => self.transform,
+        }
+    }
+
+    /// The texture painted into and its target.
+    fn target(&self) -> Option<(&wgpu::Texture, &Target)> {
+        match self.layers.last() {
+            Some(layer) => Some((&layer.texture, &layer.target)),
+            None => self.frame.as_ref().map(|(texture, target)| {
+                let texture = match texture {
+                    FrameTexture::Surface(frame) => &frame.texture,
+                    FrameTexture::Capture(texture) => texture,
+                };
+                (texture, target)
+            }),
+        }
+    }
+
+    fn target_format(&self) -> TextureFormat {
+        if self.capture {
+            TextureFormat::Rgba8Unorm
+        } else {
+            self.config.format
+        }
+    }
+
+    /// Start a new scene for the texture painted into, with the current clip and z-index.
+    fn begin_scene(&mut self) {
+        let (width, height) = self
+            .target()
+            .map_or((self.config.width, self.config.height), |(_, target)| {
+                (target.width, target.height)
+            });
+        self.vger
+            .begin(width as f32, height as f32, self.scale as f32);
+        self.vger.set_z_index(self.z_index);
+        if let Some((rect, radius)) = self.scissor {
+            self.vger.scissor(local_rect(rect), radius);
+        }
+    }
+
+    /// Paint the scene into the texture painted into. A new scene has to be started after this.
+    fn encode(&mut self) {
+        let target = match self.layers.last_mut() {
+            Some(layer) => &mut layer.target,
+            None => match self.frame.as_mut() {
+                Some((_, target)) => target,
+                None => return,
+            },
+        };
+        let load = if mem::take(&mut target.clear) {
+            wgpu::LoadOp::Clear(CLEAR_COLOR)
+        } else {
+            wgpu::LoadOp::Load
+        };
+        let desc = wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &target.view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load,
+                    store: StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        };
+        self.vger.encode(&desc);
+    }
+
     fn vger_point(&self, point: Point) -> vger::defs::LocalPoint {
-        let coeffs = self.transform.as_coeffs();
-        let point = point + Vec2::new(coeffs[4], coeffs[5]);
+        let point = self.local_transform() * point;
         vger::defs::LocalPoint::new(
             (point.x * self.scale).round() as f32,
             (point.y * self.scale).round() as f32,
@@ -175,39 +361,196 @@ impl VgerRenderer {
         vger::defs::LocalRect::new(origin, size)
     }
 
-    fn render_image(&mut self) -> Option<DynamicImage> {
-        let width_align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT - 1;
-        let width = (self.config.width + width_align) & !width_align;
-        let height = self.config.height;
-        let texture_desc = wgpu::TextureDescriptor {
+    fn create_texture(
+        &self,
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+        usage: wgpu::TextureUsages,
+    ) -> wgpu::Texture {
+        self.device.create_texture(&wgpu::TextureDescriptor {
             size: wgpu::Extent3d {
-                width: self.config.width,
+                width,
                 height,
                 depth_or_array_layers: 1,
             },
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8Unorm,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
-            label: Some("render_texture"),
-            view_formats: &[wgpu::TextureFormat::Rgba8Unorm],
+            format,
+            usage,
+            label: None,
+            view_formats: &[],
+        })
+    }
+
+    /// Blur the target inside `shape` with a gaussian blur whose standard deviation is `sigma`
+    /// physical pixels. This does nothing if the target can't be copied from.
+    fn blur_target(&mut self, shape: &impl Shape, sigma: f64) -> Option<()> {
+        if self.compositor.is_none() {
+            self.compositor = Some(Compositor::new(&self.device, self.target_format()));
+        }
+        let (texture, target) = self.target()?;
+        if !texture.usage().contains(wgpu::TextureUsages::COPY_SRC) {
+            return None;
+        }
+
+        // The blurred region, including the pixels sampled around the shape
+        let to_target = Affine::scale(self.scale) * self.local_transform();
+        let region = to_target
+            .transform_rect_bbox(shape.bounding_box())
+            .inflate(sigma * 3.0, sigma * 3.0)
+            .expand()
+            .intersect(Rect::new(
+                0.0,
+                0.0,
+                target.width as f64,
+                target.height as f64,
+            ));
+        if region.width() < 1.0 || region.height() < 1.0 {
+            return None;
+        }
+        let (width, height) = (region.width() as u32, region.height() as u32);
+
+        // Only the blurs of the frame are kept, as layers are painted again
+        let reused = self.layers.is_empty().then(|| {
+            self.last_blurred
+                .iter()
+                .position(|blurred| blurred.region == region && blurred.sigma == sigma)
+        });
+        let blurred = match reused.flatten() {
+            Some(i) => self.last_blurred.swap_remove(i).texture,
+            None => {
+                let blurred = self.spare_texture(width, height);
+                self.blur_region(&blurred, region, sigma)?;
+                blurred
+            }
         };
-        let texture = self.device.create_texture(&texture_desc);
-        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-        let desc = wgpu::RenderPassDescriptor {
-            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: &view,
-                resolve_target: None,
-                ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(CLEAR_COLOR),
-                    store: StoreOp::Store,
-                },
-            })],
-            ..Default::default()
+        let view = blurred.create_view(&wgpu::TextureViewDescriptor::default());
+        let compositor = self.compositor.as_ref()?;
+        let (_, target) = self.target()?;
+
+        // The blurred region replaces the target inside the shape
+        let (rect, radius) = if let Some(rect) = shape.as_rounded_rect() {
+            (rect.rect(), rect.radii().as_single_radius().unwrap_or(0.0))
+        } else {
+            (shape.bounding_box(), 0.0)
         };
+        let corners = [
+            Point::new(region.x0, region.y0),
+            Point::new(region.x1, region.y0),
+            Point::new(region.x0, region.y1),
+            Point::new(region.x1, region.y1),
+        ];
+        let from_target = to_target.inverse();
+        let quad = Quad {
+            corners,
+            uvs: [
+                Point::new(0.0, 0.0),
+                Point::new(1.0, 0.0),
+                Point::new(0.0, 1.0),
+                Point::new(1.0, 1.0),
+            ],
+            alpha: 1.0,
+            mask: Some(Mask {
+                corners: corners.map(|corner| from_target * corner),
+                rect,
+                radius,
+                scale: to_target.determinant().abs().sqrt(),
+            }),
+            scissor: self.scissor.map(|(rect, _)| rect),
+        };
+        compositor.composite(
+            &self.device,
+            &self.queue,
+            &view,
+            &target.view,
+            (target.width, target.height),
+            &quad,
+        );
+        if self.layers.is_empty() {
+            self.blurred.push(Blurred {
+                region,
+                sigma,
+                texture: blurred,
+            });
+        } else {
+            self.spare_textures.push(blurred);
+        }
+        Some(())
+    }
 
-        self.vger.encode(&desc);
+    /// A texture of exactly `width` by `height` for blurring, reusing a spare one if there is
+    /// one. The blur samples past the edges of the region, so a larger one can't be used.
+    fn spare_texture(&mut self, width: u32, height: u32) -> wgpu::Texture {
+        let format = self.target_format();
+        match self.spare_textures.iter().position(|texture| {
+            (texture.width(), texture.height()) == (width, height) && texture.format() == format
+        }) {
+            Some(i) => self.spare_textures.swap_remove(i),
+            None => self.create_texture(width, height, format, LAYER_USAGES),
+        }
+    }
+
+    /// Blur `region` of the target, in physical pixels, into `blurred`.
+    fn blur_region(&mut self, blurred: &wgpu::Texture, region: Rect, sigma: f64) -> Option<()> {
+        let (width, height) = (blurred.width(), blurred.height());
+        let scratch = self.spare_texture(width, height);
+        let compositor = self.compositor.as_ref()?;
+        let (texture, _) = self.target()?;
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        encoder.copy_texture_to_texture(
+            wgpu::ImageCopyTexture {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: region.x0 as u32,
+                    y: region.y0 as u32,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            blurred.as_image_copy(),
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.queue.submit(Some(encoder.finish()));
+
+        let blurred_view = blurred.create_view(&wgpu::TextureViewDescriptor::default());
+        let scratch_view = scratch.create_view(&wgpu::TextureViewDescriptor::default());
+        let size = (width, height);
+        let (device, queue) = (&self.device, &self.queue);
+        compositor.blur(
+            device,
+            queue,
+            &blurred_view,
+            &scratch_view,
+            size,
+            sigma,
+            true,
+        );
+        compositor.blur(
+            device,
+            queue,
+            &scratch_view,
+            &blurred_view,
+            size,
+            sigma,
+            false,
+        );
+        self.spare_textures.push(scratch);
+        Some(())
+    }
+
+    fn render_image(&mut self, texture: &wgpu::Texture) -> Option<DynamicImage> {
+        let width_align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT - 1;
+        let width = (self.config.width + width_align) & !width_align;
+        let height = self.config.height;
 
         let bytes_per_pixel = 4;
         let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
@@ -232,7 +575,7 @@ impl VgerRenderer {
                     rows_per_image: None,
                 },
             },
-            texture_desc.size,
+            texture.size(),
         );
         let command_buffer = encoder.finish();
         self.queue.submit(Some(command_buffer));
@@ -278,15 +621,65 @@ impl Renderer for VgerRenderer {
                     TextureFormat::Rgba8Unorm,
                 ));
             }
-            mem::swap(&mut self.vger, self.alt_vger.as_mut().unwrap())
+            mem::swap(&mut self.vger, self.alt_vger.as_mut().unwrap());
+            mem::swap(&mut self.compositor, &mut self.alt_compositor);
+            self.spare_textures.clear();
+            self.blurred.clear();
+            self.last_blurred.clear();
         }
 
+        // The blurs of the last frame are kept if nothing changed in the region they sampled
+        let damage = self
+            .damage
+            .take()
+            .map(|damage| damage.scale_from_origin(self.scale).expand());
+        let unused = mem::take(&mut self.last_blurred);
+        self.spare_textures
+            .extend(unused.into_iter().map(|blurred| blurred.texture));
+        for blurred in mem::take(&mut self.blurred) {
+            match damage {
+                Some(damage) if blurred.region.intersect(damage).is_empty() => {
+                    self.last_blurred.push(blurred)
+                }
+                _ => self.spare_textures.push(blurred.texture),
+            }
+        }
+        // The oldest spare textures are dropped, as the sizes in use change
+        let excess = self.spare_textures.len().saturating_sub(MAX_SPARE_TEXTURES);
+        self.spare_textures.drain(..excess);
+
         self.transform = Affine::IDENTITY;
-        self.vger.begin(
-            self.config.width as f32,
-            self.config.height as f32,
-            self.scale as f32,
-        );
+        self.clip = None;
+        self.scissor = None;
+        self.z_index = 0;
+        self.layers.clear();
+        let texture = if self.capture {
+            Some(FrameTexture::Capture(self.create_texture(
+                self.config.width,
+                self.config.height,
+                TextureFormat::Rgba8Unorm,
+                LAYER_USAGES,
+            )))
+        } else {
+            self.surface
+                .get_current_texture()
+                .ok()
+                .map(FrameTexture::Surface)
+        };
+        self.frame = texture.map(|texture| {
+            let target = match &texture {
+                FrameTexture::Surface(frame) => Target::new(&frame.texture),
+                FrameTexture::Capture(texture) => Target::new(texture),
+            };
+            (texture, target)
+        });
+        self.begin_scene();
+    }
+
+    fn begin_damaged(&mut self, damage: Rect) -> bool {
+        // The frame is painted in full, but the blurs outside of `damage` are reused
+        self.damage = Some(damage);
+        false
     }
 
     fn stroke<'b>(&mut self, shape: &impl Shape, brush: impl Into<BrushRef<'b>>, width: f64) {
@@ -305,7 +698,10 @@ impl Renderer for VgerRenderer {
                 width,
                 paint,
             );
-        } else if let Some(rect) = shape.as_rounded_rect() {
+        } else if let Some(rect) = shape
+            .as_rounded_rect()
+            .filter(|rect| rect.radii().as_single_radius().is_some())
+        {
             let min = rect.origin();
             let max = min + rect.rect().size().to_vec2();
             let radius = (rect.radii().top_left * self.scale) as f32;
@@ -326,7 +722,14 @@ impl Renderer for VgerRenderer {
         } else {
             for segment in shape.path_segments(0.0) {
                 match segment {
-                    peniko::kurbo::PathSeg::Line(_) => todo!(),
+                    peniko::kurbo::PathSeg::Line(line) => {
+                        self.vger.stroke_segment(
+                            self.vger_point(line.p0),
+                            self.vger_point(line.p1),
+                            width,
+                            paint,
+                        );
+                    }
                     peniko::kurbo::PathSeg::Quad(bez) => {
                         self.vger.stroke_bezier(
                             self.vger_point(bez.p0),
@@ -336,7 +739,17 @@ impl Renderer for VgerRenderer {
                             paint,
                         );
                     }
-                    peniko::kurbo::PathSeg::Cubic(_) => todo!(),
+                    peniko::kurbo::PathSeg::Cubic(cubic) => {
+                        for (_, _, bez) in cubic.to_quads(0.1) {
+                            self.vger.stroke_bezier(
+                                self.vger_point(bez.p0),
+                                self.vger_point(bez.p1),
+                                self.vger_point(bez.p2),
+                                width,
+                                paint,
+                            );
+                        }
+                    }
                 }
             }
         }
@@ -354,7 +767,10 @@ impl Renderer for VgerRenderer {
                 paint,
                 (blur_radius * self.scale) as f32,
             );
-        } else if let Some(rect) = path.as_rounded_rect() {
+        } else if let Some(rect) = path
+            .as_rounded_rect()
+            .filter(|rect| rect.radii().as_single_radius().is_some())
+        {
             self.vger.fill_rect(
                 self.vger_rect(rect.rect()),
                 (rect.radii().top_left * self.scale) as f32,
@@ -387,7 +803,16 @@ impl Renderer for VgerRenderer {
                         self.vger
                             .quad_to(self.vger_point(quad.p1), self.vger_point(quad.p2));
                     }
-                    peniko::kurbo::PathSeg::Cubic(_) => {}
+                    peniko::kurbo::PathSeg::Cubic(cubic) => {
+                        if first {
+                            first = false;
+                            self.vger.move_to(self.vger_point(cubic.p0));
+                        }
+                        for (_, _, quad) in cubic.to_quads(0.1) {
+                            self.vger
+                                .quad_to(self.vger_point(quad.p1), self.vger_point(quad.p2));
+                        }
+                    }
                 }
             }
             self.vger.fill(paint);
@@ -396,7 +821,7 @@ impl Renderer for VgerRenderer {
 
     fn draw_text(&mut self, layout: &TextLayout, pos: impl Into<Point>) {
         let mut swash_cache = SwashCache::new();
-        let transform = self.transform.as_coeffs();
+        let transform = self.local_transform().as_coeffs();
         let offset = Vec2::new(transform[4], transform[5]);
         let pos: Point = pos.into();
         let clip = self.clip;
@@ -459,7 +884,7 @@ impl Renderer for VgerRenderer {
     }
 
     fn draw_img(&mut self, img: Img<'_>, rect: Rect) {
-        let transform = self.transform.as_coeffs();
+        let transform = self.local_transform().as_coeffs();
         let width = (rect.width() * self.scale).round() as u32;
         let height = (rect.height() * self.scale).round() as u32;
         let width = width.max(1);
@@ -488,7 +913,7 @@ impl Renderer for VgerRenderer {
         rect: Rect,
         brush: Option<impl Into<BrushRef<'b>>>,
     ) {
-        let transform = self.transform.as_coeffs();
+        let transform = self.local_transform().as_coeffs();
         let width = (rect.width() * self.scale).round() as u32;
         let height = (rect.height() * self.scale).round() as u32;
         let width = width.max(1);
@@ -498,10 +923,14 @@ impl Renderer for VgerRenderer {
         let y = ((origin.y + transform[5]) * self.scale).round() as f32;
 
         let paint = brush.and_then(|brush| self.brush_to_paint(brush));
+        // The size is part of the key so the svg is rasterized again when the scale changes
+        let mut key = svg.hash.to_owned();
+        key.extend_from_slice(&width.to_le_bytes());
+        key.extend_from_slice(&height.to_le_bytes());
         self.vger.render_svg(
             x,
             y,
-            svg.hash,
+            &key,
             width,
             height,
             || {
@@ -522,6 +951,7 @@ impl Renderer for VgerRenderer {
     }
 
     fn set_z_index(&mut self, z_index: i32) {
+        self.z_index = z_index;
         self.vger.set_z_index(z_index);
     }
 
@@ -529,55 +959,160 @@ impl Renderer for VgerRenderer {
         let (rect, radius) = if let Some(rect) = shape.as_rect() {
             (rect, 0.0)
         } else if let Some(rect) = shape.as_rounded_rect() {
-            (rect.rect(), rect.radii().top_left)
+            // The scissor only supports a single radius, so clip corners with
+            // different radii as a plain rect.
+            (rect.rect(), rect.radii().as_single_radius().unwrap_or(0.0))
         } else {
             (shape.bounding_box(), 0.0)
         };
 
-        self.vger
-            .scissor(self.vger_rect(rect), (radius * self.scale) as f32);
+        let scissor = self.vger_rect(rect);
+        let radius = (radius * self.scale) as f32;
+        self.vger.scissor(scissor, radius);
+        self.scissor = Some((
+            Rect::from_origin_size(
+                (scissor.origin.x as f64, scissor.origin.y as f64),
+                (scissor.size.width as f64, scissor.size.height as f64),
+            ),
+            radius,
+        ));
 
-        let transform = self.transform.as_coeffs();
-        let offset = Vec2::new(transform[4], transform[5]);
+        let offset = self.local_transform().translation();
         self.clip = Some(rect + offset);
     }
 
     fn clear_clip(&mut self) {
         self.vger.reset_scissor();
         self.clip = None;
+        self.scissor = None;
     }
 
-    fn finish(&mut self) -> Option<DynamicImage> {
-        if self.capture {
-            self.render_image()
+    fn push_layer(&mut self, alpha: f32, bounds: Rect) {
+        // What's below the layer is painted first, as it's composited onto it
+        self.encode();
+
+        // Only the part of the layer which isn't clipped away is painted
+        let (parent_width, parent_height) = self
+            .target()
+            .map_or((self.config.width, self.config.height), |(_, target)| {
+                (target.width, target.height)
+            });
+        let mut visible = Rect::new(0.0, 0.0, parent_width as f64, parent_height as f64);
+        if let Some((scissor, _)) = self.scissor {
+            visible = visible.intersect(scissor);
+        }
+
+        // The layer is painted in the current coordinates, so it's transformed as a whole
+        let to_parent =
+            Affine::scale(self.scale) * self.local_transform() * Affine::scale(self.scale.recip());
+        let bounds = to_parent
+            .inverse()
+            .transform_rect_bbox(visible)
+            .intersect(bounds.scale_from_origin(self.scale))
+            .expand();
+        let bounds = if bounds.area().is_finite() {
+            bounds
         } else {
-            if let Ok(frame) = self.surface.get_current_texture() {
-                let texture_view = frame
-                    .texture
-                    .create_view(&wgpu::TextureViewDescriptor::default());
-                let desc = wgpu::RenderPassDescriptor {
-                    label: None,
-                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                        view: &texture_view,
-                        resolve_target: None,
-                        ops: wgpu::Operations {
-                            load: wgpu::LoadOp::Clear(CLEAR_COLOR),
-                            store: StoreOp::Store,
-                        },
-                    })],
-                    depth_stencil_attachment: None,
-                    timestamp_writes: None,
-                    occlusion_query_set: None,
-                };
+            Rect::ZERO
+        };
+        let origin = bounds.origin().to_vec2();
+        let max = self.device.limits().max_texture_dimension_2d as f64;
+        let width = bounds.width().clamp(1.0, max) as u32;
+        let height = bounds.height().clamp(1.0, max) as u32;
 
-                self.vger.encode(&desc);
+        let format = self.target_format();
+        let texture = match self
+            .spare_textures
+            .iter()
+            .position(|texture| texture.width() >= width && texture.height() >= height)
+        {
+            Some(i) => self.spare_textures.swap_remove(i),
+            None => self.create_texture(width, height, format, LAYER_USAGES),
+        };
+        let target = Target::new(&texture);
+        self.layers.push(Layer {
+            texture,
+            target,
+            width,
+            height,
+            alpha,
+            to_layer: Affine::translate(-origin / self.scale) * self.transform.inverse(),
+            to_parent: to_parent * Affine::translate(origin),
+            parent_clip: self.clip.take(),
+            parent_scissor: self.scissor.take(),
+        });
+        self.begin_scene();
+    }
+
+    fn pop_layer(&mut self) {
+        if self.layers.is_empty() {
+            return;
+        }
+        self.encode();
+        let layer = self.layers.pop().unwrap();
+        self.clip = layer.parent_clip;
+        self.scissor = layer.parent_scissor;
+
+        if self.compositor.is_none() {
+            self.compositor = Some(Compositor::new(&self.device, self.target_format()));
+        }
+        if let (Some((_, target)), Some(compositor)) = (self.target(), self.compositor.as_ref()) {
+            let (width, height) = (layer.width as f64, layer.height as f64);
+            let (texture_width, texture_height) =
+                (layer.target.width as f64, layer.target.height as f64);
+            let corners = [(0.0, 0.0), (width, 0.0), (0.0, height), (width, height)];
+            let quad = Quad {
+                corners: corners.map(|corner| layer.to_parent * Point::from(corner)),
+                uvs: corners.map(|(x, y)| Point::new(x / texture_width, y / texture_height)),
+                alpha: layer.alpha,
+                mask: None,
+                scissor: self.scissor.map(|(rect, _)| rect),
+            };
+            compositor.composite(
+                &self.device,
+                &self.queue,
+                &layer.target.view,
+                &target.view,
+                (target.width, target.height),
+                &quad,
+            );
+        }
+        self.spare_textures.push(layer.texture);
+        self.begin_scene();
+    }
+
+    fn blur_backdrop(&mut self, shape: &impl Shape, radius: f64) {
+        let sigma = radius * self.scale;
+        if sigma <= 0.0 {
+            return;
+        }
+        // What's painted so far is blurred, so it has to be in the target
+        self.encode();
+        self.blur_target(shape, sigma);
+        self.begin_scene();
+    }
+
+    fn finish(&mut self) -> Option<DynamicImage> {
+        while !self.layers.is_empty() {
+            self.pop_layer();
+        }
+        self.encode();
+        match self.frame.take()?.0 {
+            FrameTexture::Surface(frame) => {
                 frame.present();
+                None
             }
-            None
+            FrameTexture::Capture(texture) => self.render_image(&texture),
         }
     }
 }
 
+fn local_rect(rect: Rect) -> vger::defs::LocalRect {
+    let origin = vger::defs::LocalPoint::new(rect.x0 as f32, rect.y0 as f32);
+    let end = vger::defs::LocalPoint::new(rect.x1 as f32, rect.y1 as f32);
+    vger::defs::LocalRect::new(origin, (end - origin).to_size())
+}
+
 fn vger_color(color: Color) -> vger::Color {
     vger::Color {
         r: color.r as f32 / 255.0,